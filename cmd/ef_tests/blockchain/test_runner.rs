@@ -9,6 +9,7 @@ use ethrex_blockchain::{
     Blockchain, BlockchainType,
     error::{ChainError, InvalidBlockError},
     fork_choice::apply_fork_choice,
+    vm::StoreVmDatabase,
 };
 use ethrex_common::{
     constants::EMPTY_KECCACK_HASH,
@@ -19,15 +20,34 @@ use ethrex_common::{
 };
 use ethrex_rlp::decode::RLPDecode;
 use ethrex_storage::{EngineType, Store};
-use ethrex_vm::{EvmEngine, EvmError};
+use ethrex_vm::{Evm, EvmEngine, EvmError};
 use regex::Regex;
 use zkvm_interface::io::ProgramInput;
 
+/// Runs every test in the file, checking before each block is added that executing it in
+/// parallel yields exactly the same receipts, requests and state transitions as executing it sequentially.
+pub fn parse_and_execute_parallel_differential(
+    path: &Path,
+    skipped_tests: Option<&[&str]>,
+) -> datatest_stable::Result<()> {
+    run_tests(path, EvmEngine::LEVM, skipped_tests, false, true)
+}
+
 pub fn parse_and_execute(
     path: &Path,
     evm: EvmEngine,
     skipped_tests: Option<&[&str]>,
     re_run_stateless: bool,
+) -> datatest_stable::Result<()> {
+    run_tests(path, evm, skipped_tests, re_run_stateless, false)
+}
+
+fn run_tests(
+    path: &Path,
+    evm: EvmEngine,
+    skipped_tests: Option<&[&str]>,
+    re_run_stateless: bool,
+    parallel_differential: bool,
 ) -> datatest_stable::Result<()> {
     let rt = tokio::runtime::Runtime::new().unwrap();
     let tests = parse_tests(path);
@@ -44,7 +64,11 @@ pub fn parse_and_execute(
             continue;
         }
 
-        let result = rt.block_on(run_ef_test(&test_key, &test, evm, re_run_stateless));
+        let result = if parallel_differential {
+            rt.block_on(run_ef_test_parallel_differential(&test_key, &test))
+        } else {
+            rt.block_on(run_ef_test(&test_key, &test, evm, re_run_stateless))
+        };
 
         if let Err(e) = result {
             eprintln!("Test {test_key} failed: {e:?}");
@@ -127,6 +151,82 @@ pub async fn run_ef_test(
     Ok(())
 }
 
+/// Same as [run_ef_test] with LEVM, but importing blocks with parallel execution and comparing
+/// each block's execution against the sequential one.
+pub async fn run_ef_test_parallel_differential(
+    test_key: &str,
+    test: &TestUnit,
+) -> Result<(), String> {
+    let store = build_store_for_test(test).await;
+    let blockchain = Blockchain::new(EvmEngine::LEVM, store.clone(), BlockchainType::L1)
+        .with_parallel_execution(true);
+
+    for block_fixture in test.blocks.iter() {
+        if exception_in_rlp_decoding(block_fixture) {
+            return Ok(());
+        }
+        let block: &CoreBlock = &block_fixture.block().unwrap().clone().into();
+        let hash = block.hash();
+
+        compare_parallel_execution(test_key, &store, block)?;
+
+        // Invalid blocks were already compared, there's nothing left to import
+        if blockchain.add_block(block).await.is_err() {
+            if block_fixture.expect_exception.is_none() {
+                return Err(format!(
+                    "Block {hash:#x} unexpectedly failed with parallel execution on test: {test_key}"
+                ));
+            }
+            break;
+        }
+        apply_fork_choice(&store, hash, hash, hash).await.unwrap();
+    }
+    check_poststate_against_db(test_key, test, &store).await;
+    Ok(())
+}
+
+fn compare_parallel_execution(
+    test_key: &str,
+    store: &Store,
+    block: &CoreBlock,
+) -> Result<(), String> {
+    // Blocks whose parent wasn't imported can't be executed
+    if store
+        .get_block_header_by_hash(block.header.parent_hash)
+        .ok()
+        .flatten()
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    let execute = |parallel: bool| {
+        let vm_db = StoreVmDatabase::new(store.clone(), block.header.parent_hash);
+        let mut vm = Evm::new_for_l1(EvmEngine::LEVM, vm_db);
+        let result = if parallel {
+            vm.execute_block_parallel(block)
+        } else {
+            vm.execute_block(block)
+        };
+        result
+            .and_then(|result| {
+                let requests: Vec<_> = result.requests.iter().map(|r| r.encode().0).collect();
+                Ok((result.receipts, requests, vm.get_state_transitions()?))
+            })
+            .map_err(|error| error.to_string())
+    };
+
+    let sequential = execute(false);
+    let parallel = execute(true);
+    if sequential != parallel {
+        return Err(format!(
+            "Parallel execution of block {:#x} differs from the sequential one on test: {test_key}\nsequential: {sequential:?}\nparallel: {parallel:?}",
+            block.hash()
+        ));
+    }
+    Ok(())
+}
+
 fn exception_is_expected(
     expected_exceptions: Vec<BlockChainExpectedException>,
     returned_error: &ChainError,
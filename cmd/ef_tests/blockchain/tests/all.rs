@@ -1,4 +1,6 @@
-use ef_tests_blockchain::test_runner::parse_and_execute;
+use ef_tests_blockchain::test_runner::{
+    parse_and_execute, parse_and_execute_parallel_differential,
+};
 use ethrex_vm::EvmEngine;
 use std::path::Path;
 
@@ -18,6 +20,11 @@ fn parse_and_execute_runner(path: &Path) -> datatest_stable::Result<()> {
 fn parse_and_execute_stateless_runner(path: &Path) -> datatest_stable::Result<()> {
     parse_and_execute(path, EvmEngine::LEVM, None, true)
 }
+#[cfg(feature = "levm")]
+fn parse_and_execute_parallel_runner(path: &Path) -> datatest_stable::Result<()> {
+    parse_and_execute_parallel_differential(path, None)
+}
+
 #[cfg(feature = "levm")]
 datatest_stable::harness!(
     parse_and_execute_runner,
@@ -25,6 +32,9 @@ datatest_stable::harness!(
     r".*",
     parse_and_execute_stateless_runner,
     TEST_FOLDER,
+    r".*",
+    parse_and_execute_parallel_runner,
+    TEST_FOLDER,
     r".*"
 );
 #[cfg(not(feature = "levm"))]
@@ -209,6 +209,7 @@ pub fn prepare_vm_for_tx<'a>(
             tx_nonce: test_tx.nonce,
             block_gas_limit: test.env.current_gas_limit,
             is_privileged: false,
+            l1_fee_config: None,
         },
         db,
         &tx,
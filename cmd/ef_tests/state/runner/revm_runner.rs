@@ -575,6 +575,7 @@ pub async fn _ensure_post_state_revm(
                             gas_refunded: 42,
                             logs: vec![],
                             output: Bytes::new(),
+                            state_access: None,
                         }),
                         //TODO: This is not a TransactionReport because it is REVM
                         error_reason,
@@ -597,6 +598,7 @@ pub async fn _ensure_post_state_revm(
                                 gas_refunded: 42,
                                 logs: vec![],
                                 output: Bytes::new(),
+                                state_access: None,
                             }),
                             //TODO: This is not a TransactionReport because it is REVM
                             format!("Post-state root mismatch on REVM runner, line: {}", line!())
@@ -14,7 +14,7 @@ pub async fn main() -> Result<(), RunnerError> {
     let tests = parse_tests(&mut runner_options)?;
 
     println!("\nFinished parsing. Executing tests...");
-    run_tests(tests).await?;
+    run_tests(tests, runner_options.trace).await?;
     println!(
         "\nTests finished running.
     Find successful tests (if any) report at: './success_report.txt'.
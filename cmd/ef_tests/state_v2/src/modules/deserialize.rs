@@ -65,6 +65,9 @@ where
                     "TransactionException.INSUFFICIENT_MAX_FEE_PER_BLOB_GAS" => {
                         TransactionExpectedException::InsufficientMaxFeePerBlobGas
                     }
+                    "TransactionException.GAS_LIMIT_EXCEEDS_MAXIMUM" => {
+                        TransactionExpectedException::GasLimitExceedsMaximum
+                    }
                     _other => TransactionExpectedException::Other, //TODO: Support exceptions that enter here.
                 }
             })
@@ -144,6 +147,7 @@ where
             "Shanghai" => Fork::Shanghai,
             "Cancun" => Fork::Cancun,
             "Prague" => Fork::Prague,
+            "Osaka" => Fork::Osaka,
             "Byzantium" => Fork::Byzantium,
             "EIP158" => Fork::SpuriousDragon,
             "EIP150" => Fork::Tangerine,
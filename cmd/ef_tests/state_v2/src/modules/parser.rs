@@ -20,6 +20,9 @@ pub struct RunnerOptions {
     /// For skipping certain .json files
     #[arg(long, value_name = "SKIP_FILES", value_delimiter = ',')]
     pub skip_files: Vec<PathBuf>,
+    /// For writing an EIP-3155 trace of each failing test case to the `./traces` directory.
+    #[arg(long, default_value_t = false)]
+    pub trace: bool,
 }
 
 //TODO: Use this constant, improve it.
@@ -219,6 +219,12 @@ fn exception_matches_expected(
             ) | (
                 TransactionExpectedException::Type4TxContractCreation,
                 VMError::TxValidation(TxValidationError::Type4TxContractCreation)
+            ) | (
+                TransactionExpectedException::GasLimitExceedsMaximum,
+                VMError::TxValidation(TxValidationError::TxMaxGasLimitExceeded {
+                    tx_gas_limit: _,
+                    max_gas_limit: _
+                })
             ) | (
                 TransactionExpectedException::Other,
                 VMError::TxValidation(_) //TODO: Decide whether to support more specific errors, I think this is enough.
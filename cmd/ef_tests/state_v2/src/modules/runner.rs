@@ -1,30 +1,46 @@
 use colored::Colorize;
 use std::{
-    fs::{self, OpenOptions},
-    io::Write,
+    cell::RefCell,
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, Write},
     path::PathBuf,
+    rc::Rc,
 };
 
 use ethrex_common::{
     U256,
     types::{EIP1559Transaction, EIP7702Transaction, Transaction, TxKind},
 };
-use ethrex_levm::{EVMConfig, Environment, tracing::LevmCallTracer, vm::VM, vm::VMType};
+use ethrex_levm::{
+    EVMConfig, Environment, native_tracers::Eip3155Tracer, tracing::LevmCallTracer, vm::VM,
+    vm::VMType,
+};
+use ethrex_vm::backends::levm::LEVM;
+use keccak_hash::keccak;
 
 use crate::modules::{
     error::RunnerError,
     report::add_test_to_report,
-    result_check::check_test_case_results,
+    result_check::{check_test_case_results, post_state_root},
     types::{Env, Test, TestCase},
     utils::{effective_gas_price, load_initial_state},
 };
 
+/// Directory where the traces of the failing test cases are written.
+const TRACES_DIR: &str = "./traces";
+
 /// Runs all the tests that have been parsed.
-pub async fn run_tests(tests: Vec<Test>) -> Result<(), RunnerError> {
+/// If `trace` is set, failing test cases are run again with an EIP-3155 tracer.
+pub async fn run_tests(tests: Vec<Test>, trace: bool) -> Result<(), RunnerError> {
     // Remove previous report if it exists.
     let successful_report_path = PathBuf::from("./success_report.txt");
     let _ = fs::remove_file(&successful_report_path);
     let _ = fs::remove_file("./failure_report.txt");
+    if trace {
+        let _ = fs::remove_dir_all(TRACES_DIR);
+        fs::create_dir_all(TRACES_DIR)
+            .map_err(|err| RunnerError::FailedToWriteTrace(err.to_string()))?;
+    }
 
     let mut success_report = OpenOptions::new()
         .append(true)
@@ -44,6 +60,7 @@ pub async fn run_tests(tests: Vec<Test>) -> Result<(), RunnerError> {
             &mut passing_tests,
             &mut failing_tests,
             &mut total_run,
+            trace,
         )
         .await?;
     }
@@ -56,6 +73,7 @@ pub async fn run_test(
     passing_tests: &mut usize,
     failing_tests: &mut usize,
     total_run: &mut usize,
+    trace: bool,
 ) -> Result<(), RunnerError> {
     let mut failing_test_cases = Vec::new();
     for test_case in &test.test_cases {
@@ -83,6 +101,12 @@ pub async fn run_test(
 
         // If test case did not pass the checks, add it to failing test cases record (for future reporting)
         if !checks_result.passed {
+            if trace {
+                // A trace that can't be written doesn't change the result of the run.
+                if let Err(err) = trace_test_case(test, test_case, checks_result.passed).await {
+                    eprintln!("\nFailed to trace test case of {}: {err:?}", test.name);
+                }
+            }
             failing_test_cases.push(checks_result);
             *failing_tests += 1;
         } else {
@@ -102,6 +126,68 @@ pub async fn run_test(
     Ok(())
 }
 
+/// Executes a test case again, writing the EIP-3155 trace of its transaction to
+/// `<TRACES_DIR>/<trace_file_name>`. The summary line holds the post-state root and whether the
+/// test case passed its checks.
+pub async fn trace_test_case(
+    test: &Test,
+    test_case: &TestCase,
+    passed: bool,
+) -> Result<(), RunnerError> {
+    let trace_path = PathBuf::from(TRACES_DIR).join(trace_file_name(&test.name, test_case));
+    let trace_file =
+        File::create(trace_path).map_err(|err| RunnerError::FailedToWriteTrace(err.to_string()))?;
+
+    let (mut db, initial_block_hash, storage, _) = load_initial_state(test).await;
+    let env = get_vm_env_for_test(test.env, test_case)?;
+    let tx = get_tx_from_test_case(test_case)?;
+    let mut vm = VM::new(env, &mut db, &tx, LevmCallTracer::disabled(), VMType::L1)
+        .map_err(RunnerError::VMError)?;
+    let tracer = Rc::new(RefCell::new(Eip3155Tracer::new(Box::new(BufWriter::new(
+        trace_file,
+    )))));
+    vm.set_tracer(tracer.clone());
+
+    // Execution errors are already part of the test case report, the trace is all we want here.
+    let _ = vm.execute();
+
+    let account_updates = LEVM::get_state_transitions(&mut vm.db.clone())
+        .map_err(|e| RunnerError::FailedToGetAccountsUpdates(e.to_string()))?;
+    let state_root = post_state_root(&account_updates, initial_block_hash, storage).await;
+    tracer.borrow_mut().finish(state_root, passed);
+    Ok(())
+}
+
+/// Name of the trace file of a test case: `<test name>_<fork>_d<data>g<gas>v<value>.jsonl`.
+/// Test names (e.g. `tests/prague/eip7702/test_x.py::test_y[fork_Prague-state_test]`) are
+/// turned into a single path component, and long ones are shortened with a hash of the full
+/// name to stay within file name limits while keeping them unique.
+pub fn trace_file_name(test_name: &str, test_case: &TestCase) -> String {
+    const MAX_NAME_LEN: usize = 150;
+
+    let mut name: String = test_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.len() > MAX_NAME_LEN {
+        let hash = keccak(test_name.as_bytes());
+        name.truncate(MAX_NAME_LEN);
+        name.push_str(&format!("_{}", hex::encode(&hash.0[..4])));
+    }
+
+    let (data_index, gas_index, value_index) = test_case.vector;
+    format!(
+        "{name}_{:?}_d{data_index}g{gas_index}v{value_index}.jsonl",
+        test_case.fork
+    )
+}
+
 /// Gets the enviroment needed to prepare the VM for a transaction.
 pub fn get_vm_env_for_test(
     test_env: Env,
@@ -131,6 +217,7 @@ pub fn get_vm_env_for_test(
         tx_nonce: test_case.nonce,
         block_gas_limit: test_env.current_gas_limit,
         is_privileged: false,
+        l1_fee_config: None,
     })
 }
 
@@ -176,3 +263,64 @@ pub fn get_tx_from_test_case(test_case: &TestCase) -> Result<Transaction, Runner
     };
     Ok(tx)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::types::Post;
+    use ethrex_common::{
+        Address, H256,
+        types::{Fork, TxKind},
+    };
+
+    fn test_case() -> TestCase {
+        TestCase {
+            vector: (1, 2, 3),
+            data: Default::default(),
+            gas: 0,
+            value: U256::zero(),
+            tx_bytes: Default::default(),
+            gas_price: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            max_fee_per_blob_gas: None,
+            nonce: 0,
+            secret_key: H256::zero(),
+            sender: Address::zero(),
+            to: TxKind::Create,
+            fork: Fork::Prague,
+            post: Post {
+                hash: H256::zero(),
+                logs: H256::zero(),
+                state: None,
+                expected_exceptions: None,
+            },
+            blob_versioned_hashes: vec![],
+            access_list: vec![],
+            authorization_list: None,
+        }
+    }
+
+    #[test]
+    fn trace_file_names_are_valid_file_names() {
+        let short = "tests/prague/eip7702_set_code_tx/test_set_code_txs.py::test_set_code_to_sstore[fork_Prague-state_test]";
+        let long = format!("{short}::{}", "x".repeat(300));
+        let other_long = format!("{short}::{}", "y".repeat(300));
+
+        let short_name = trace_file_name(short, &test_case());
+        assert_eq!(
+            short_name,
+            "tests_prague_eip7702_set_code_tx_test_set_code_txs.py__test_set_code_to_sstore_fork_Prague-state_test__Prague_d1g2v3.jsonl"
+        );
+        let long_name = trace_file_name(&long, &test_case());
+        assert!(long_name.len() < 255);
+        assert_ne!(long_name, trace_file_name(&other_long, &test_case()));
+
+        let dir = std::env::temp_dir().join("ef_tests_state_v2_trace_file_names");
+        fs::create_dir_all(&dir).unwrap();
+        for name in [short_name, long_name] {
+            File::create(dir.join(&name)).unwrap();
+        }
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -7,8 +7,11 @@ use std::{
 
 use clap::{ArgAction, Parser as ClapParser, Subcommand as ClapSubcommand};
 use ethrex_blockchain::{BlockchainType, error::ChainError};
-use ethrex_common::types::{Block, Genesis};
-use ethrex_p2p::{sync::SyncMode, types::Node};
+use ethrex_common::{
+    H256,
+    types::{Block, Genesis},
+};
+use ethrex_p2p::{nat::NatConfig, sync::SyncMode, types::Node};
 use ethrex_rlp::encode::RLPEncode;
 use ethrex_storage::error::StoreError;
 use ethrex_vm::EvmEngine;
@@ -108,6 +111,15 @@ pub struct Options {
         help_heading = "Node options",
         env = "ETHREX_EVM")]
     pub evm: EvmEngine,
+    #[arg(
+        long = "parallel-execution",
+        action = ArgAction::SetTrue,
+        help = "Execute block transactions optimistically in parallel",
+        long_help = "Speculatively executes the transactions of imported blocks on several threads, re-executing the ones that conflict. The resulting state is the same as with sequential execution. Only supported with LEVM.",
+        help_heading = "Node options",
+        env = "ETHREX_PARALLEL_EXECUTION"
+    )]
+    pub parallel_execution: bool,
     #[arg(
         long = "log.level",
         default_value_t = Level::INFO,
@@ -190,6 +202,45 @@ pub struct Options {
         help_heading = "P2P options"
     )]
     pub discovery_port: String,
+    #[arg(
+        long = "nat",
+        default_value = "none",
+        value_name = "NAT_MECHANISM",
+        value_parser = clap::value_parser!(NatConfig),
+        help = "How the external address of the node is determined.",
+        long_help = "Can be `none`, `extip:<IP>`, `upnp` or `pmp[:<GATEWAY_IP>]`. With `none` the external IP is learned from the endpoint our discovery peers see us at. `extip` always advertises the given IP, while `upnp` and `pmp` map the P2P ports in the gateway and advertise the IP it reports.",
+        help_heading = "P2P options",
+        env = "ETHREX_NAT"
+    )]
+    pub nat: NatConfig,
+    #[arg(
+        long = "p2p.block-gossip",
+        action = ArgAction::SetTrue,
+        help = "Gossip new blocks to peers over the eth protocol.",
+        long_help = "Only meant for private and dev networks without a consensus client. Blocks added to the chain are sent to peers with `NewBlock`/`NewBlockHashes` and the ones received are validated and imported. Every node of the network has to enable it.",
+        help_heading = "P2P options",
+        env = "ETHREX_P2P_BLOCK_GOSSIP"
+    )]
+    pub p2p_block_gossip: bool,
+    #[arg(
+        long = "sync.target",
+        value_name = "BLOCK_HASH",
+        conflicts_with = "sync_target_rpc",
+        help = "Trusted block hash to sync to without a consensus client.",
+        long_help = "The node syncs from its peers to the block with the given hash, verifying the chain of headers backwards from it. Once it's reached the node is considered synced, a consensus client may still be attached to follow the chain afterwards.",
+        help_heading = "P2P options",
+        env = "ETHREX_SYNC_TARGET"
+    )]
+    pub sync_target: Option<H256>,
+    #[arg(
+        long = "sync.target-rpc",
+        value_name = "URL",
+        help = "Trusted RPC endpoint to take the sync target from.",
+        long_help = "Like `--sync.target`, but the target is the finalized block of the given execution client RPC endpoint at startup.",
+        help_heading = "P2P options",
+        env = "ETHREX_SYNC_TARGET_RPC"
+    )]
+    pub sync_target_rpc: Option<String>,
 }
 
 impl Options {
@@ -249,6 +300,10 @@ impl Default for Options {
             p2p_port: Default::default(),
             discovery_addr: Default::default(),
             discovery_port: Default::default(),
+            nat: Default::default(),
+            p2p_block_gossip: false,
+            sync_target: None,
+            sync_target_rpc: None,
             network: Default::default(),
             bootnodes: Default::default(),
             datadir: Default::default(),
@@ -258,6 +313,7 @@ impl Default for Options {
             metrics_enabled: Default::default(),
             dev: Default::default(),
             evm: Default::default(),
+            parallel_execution: false,
             force: false,
         }
     }
@@ -403,7 +459,7 @@ pub async fn import_blocks(
 ) -> Result<(), ChainError> {
     let data_dir = init_datadir(data_dir);
     let store = init_store(&data_dir, genesis).await;
-    let blockchain = init_blockchain(evm, store.clone(), blockchain_type);
+    let blockchain = init_blockchain(evm, store.clone(), blockchain_type, false);
     let path_metadata = metadata(path).expect("Failed to read path");
 
     // If it's an .rlp file it will be just one chain, but if it's a directory there can be multiple chains.
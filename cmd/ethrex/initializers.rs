@@ -6,7 +6,7 @@ use crate::{
     },
 };
 use ethrex_blockchain::{Blockchain, BlockchainType};
-use ethrex_common::types::Genesis;
+use ethrex_common::{H256, types::Genesis};
 use ethrex_config::networks::Network;
 
 use ethrex_metrics::profiling::{FunctionProfilingLayer, initialize_block_processing_profile};
@@ -19,6 +19,10 @@ use ethrex_p2p::{
     sync_manager::SyncManager,
     types::{Node, NodeRecord},
 };
+use ethrex_rpc::{
+    EthClient,
+    types::block_identifier::{BlockIdentifier, BlockTag},
+};
 use ethrex_storage::{EngineType, Store};
 use ethrex_vm::EvmEngine;
 use local_ip_address::local_ip;
@@ -115,22 +119,26 @@ pub fn init_blockchain(
     evm_engine: EvmEngine,
     store: Store,
     blockchain_type: BlockchainType,
+    parallel_execution: bool,
 ) -> Arc<Blockchain> {
     info!("Initiating blockchain with EVM: {}", evm_engine);
-    Blockchain::new(evm_engine, store, blockchain_type).into()
+    Blockchain::new(evm_engine, store, blockchain_type)
+        .with_parallel_execution(parallel_execution)
+        .into()
 }
 
+/// Starts the RPC API, returns the SyncManager it uses so syncing can be started later on
 #[allow(clippy::too_many_arguments)]
 pub async fn init_rpc_api(
     opts: &Options,
     peer_table: Arc<Mutex<KademliaTable>>,
     local_p2p_node: Node,
-    local_node_record: NodeRecord,
+    local_node_record: Arc<Mutex<NodeRecord>>,
     store: Store,
     blockchain: Arc<Blockchain>,
     cancel_token: CancellationToken,
     tracker: TaskTracker,
-) {
+) -> SyncManager {
     let peer_handler = PeerHandler::new(peer_table);
 
     // Create SyncManager
@@ -151,12 +159,39 @@ pub async fn init_rpc_api(
         read_jwtsecret_file(&opts.authrpc_jwtsecret),
         local_p2p_node,
         local_node_record,
-        syncer,
+        syncer.clone(),
         peer_handler,
         get_client_version(),
     );
 
     tracker.spawn(rpc_api);
+    syncer
+}
+
+/// Starts syncing to the trusted block given by `--sync.target` or `--sync.target-rpc`, if any
+/// The headers are requested from peers, so this should be called once the P2P network is up
+pub async fn init_sync_target(opts: &Options, syncer: &SyncManager) {
+    let sync_target = match (opts.sync_target, &opts.sync_target_rpc) {
+        (Some(block_hash), _) => block_hash,
+        (None, Some(url)) => match get_finalized_block_hash(url).await {
+            Ok(block_hash) => block_hash,
+            Err(error) => {
+                error!("Failed to get the sync target from {url}: {error}");
+                return;
+            }
+        },
+        (None, None) => return,
+    };
+    info!("Syncing to trusted block {sync_target:#x}");
+    syncer.sync_to_checkpoint(sync_target);
+}
+
+async fn get_finalized_block_hash(url: &str) -> eyre::Result<H256> {
+    let client = EthClient::new(url)?;
+    let block = client
+        .get_block_by_number(BlockIdentifier::Tag(BlockTag::Finalized))
+        .await?;
+    Ok(block.header.hash())
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -193,6 +228,8 @@ pub async fn init_network(
         blockchain,
         get_client_version(),
         based_context,
+        opts.nat.clone(),
+        opts.p2p_block_gossip,
     );
 
     context.set_fork_id().await.expect("Set fork id");
@@ -387,7 +424,12 @@ pub async fn init_l1(
     #[cfg(feature = "sync-test")]
     set_sync_block(&store).await;
 
-    let blockchain = init_blockchain(opts.evm, store.clone(), BlockchainType::L1);
+    let blockchain = init_blockchain(
+        opts.evm,
+        store.clone(),
+        BlockchainType::L1,
+        opts.parallel_execution,
+    );
 
     let signer = get_signer(&data_dir);
 
@@ -406,11 +448,11 @@ pub async fn init_l1(
 
     let cancel_token = tokio_util::sync::CancellationToken::new();
 
-    init_rpc_api(
+    let syncer = init_rpc_api(
         &opts,
         peer_table.clone(),
         local_p2p_node.clone(),
-        local_node_record.lock().await.clone(),
+        local_node_record.clone(),
         store.clone(),
         blockchain.clone(),
         cancel_token.clone(),
@@ -440,6 +482,7 @@ pub async fn init_l1(
             None,
         )
         .await;
+        init_sync_target(&opts, &syncer).await;
     } else {
         info!("P2P is disabled");
     }
@@ -22,11 +22,11 @@ use ethrex_rpc::{
 use ethrex_storage::{EngineType, Store, UpdateBatch};
 use ethrex_storage_rollup::StoreRollup;
 use eyre::OptionExt;
-use itertools::Itertools;
 use keccak_hash::keccak;
 use reqwest::Url;
 use secp256k1::SecretKey;
 use std::{
+    collections::BTreeMap,
     fs::{create_dir_all, read_dir},
     path::PathBuf,
     time::Duration,
@@ -83,6 +83,11 @@ impl L2Command {
                 .on_chain_proposer_address = Some(contract_addresses.on_chain_proposer_address);
             l2_options.sequencer_opts.watcher_opts.bridge_address =
                 Some(contract_addresses.bridge_address);
+            // The dev prover runs locally without an authentication key
+            l2_options
+                .sequencer_opts
+                .proof_coordinator_opts
+                .allow_any_prover = true;
             println!("Initializing L2");
         }
         l2::init_l2(l2_options).await?;
@@ -241,8 +246,11 @@ impl Command {
                             beacon_client.get_block_by_hash(parent_beacon_hash).await?;
                         let target_slot = parent_beacon_block.message.slot + 1;
 
-                        // Get versioned hashes from transactions
-                        let mut l2_blob_hashes = vec![];
+                        // Get blobs from block's slot
+                        let blobs = beacon_client.get_blobs_by_slot(target_slot).await?;
+
+                        // Only keep L2 commitment's blobs. A batch's state diff can span several
+                        // blobs, so they are saved as <batch number>-<index in the commitment>.blob
                         for log in logs {
                             let tx = eth_client
                                 .get_transaction_by_hash(log.transaction_hash)
@@ -251,22 +259,26 @@ impl Command {
                                     "Transaction {:#x} not found",
                                     log.transaction_hash
                                 ))?;
-                            l2_blob_hashes.extend(tx.blob_versioned_hashes.ok_or_eyre(format!(
+                            let batch_number =
+                                U256::from_big_endian(tx.data.get(4..36).ok_or_eyre(format!(
+                                    "Batch number not found in transaction {:#x}",
+                                    log.transaction_hash
+                                ))?);
+                            let blob_hashes = tx.blob_versioned_hashes.ok_or_eyre(format!(
                                 "Blobs not found in transaction {:#x}",
                                 log.transaction_hash
-                            ))?);
-                        }
-
-                        // Get blobs from block's slot and only keep L2 commitment's blobs
-                        for blob in beacon_client
-                            .get_blobs_by_slot(target_slot)
-                            .await?
-                            .into_iter()
-                            .filter(|blob| l2_blob_hashes.contains(&blob.versioned_hash()))
-                        {
-                            let blob_path =
-                                data_dir.join(format!("{target_slot}-{}.blob", blob.index));
-                            std::fs::write(blob_path, blob.blob)?;
+                            ))?;
+                            for (blob_index, blob_hash) in blob_hashes.iter().enumerate() {
+                                let blob = blobs
+                                    .iter()
+                                    .find(|blob| blob.versioned_hash() == *blob_hash)
+                                    .ok_or_eyre(format!(
+                                        "Blob {blob_hash:#x} not found in slot {target_slot}"
+                                    ))?;
+                                let blob_path =
+                                    data_dir.join(format!("{batch_number}-{blob_index}.blob"));
+                                std::fs::write(blob_path, &blob.blob)?;
+                            }
                         }
 
                         println!("Saved blobs for slot {target_slot}");
@@ -328,23 +340,42 @@ impl Command {
                 let mut last_block_number = 0;
                 let mut new_canonical_blocks = vec![];
 
-                // Iterate over each blob
-                let files: Vec<std::fs::DirEntry> = read_dir(blobs_dir)?.try_collect()?;
-                for (file_number, file) in files
-                    .into_iter()
-                    .sorted_by_key(|f| f.file_name())
-                    .enumerate()
-                {
-                    let batch_number = file_number as u64 + 1;
-                    let blob = std::fs::read(file.path())?;
+                // Group the blobs by batch, as a batch's state diff can span several of them
+                let mut batches_blobs: BTreeMap<u64, BTreeMap<u64, PathBuf>> = BTreeMap::new();
+                for file in read_dir(blobs_dir)? {
+                    let path = file?.path();
+                    let (batch_number, blob_index) = path
+                        .file_stem()
+                        .and_then(|stem| stem.to_str())
+                        .and_then(|stem| stem.split_once('-'))
+                        .and_then(|(batch_number, blob_index)| {
+                            Some((batch_number.parse().ok()?, blob_index.parse().ok()?))
+                        })
+                        .ok_or_eyre(format!(
+                            "Invalid blob file name {}, expected <batch number>-<index>.blob",
+                            path.display()
+                        ))?;
+                    batches_blobs
+                        .entry(batch_number)
+                        .or_default()
+                        .insert(blob_index, path);
+                }
+
+                // Iterate over each batch
+                for (batch_number, blob_paths) in batches_blobs {
+                    let mut state_diff_bytes = Vec::new();
+                    for blob_path in blob_paths.into_values() {
+                        let blob = std::fs::read(blob_path)?;
+
+                        if blob.len() != BYTES_PER_BLOB {
+                            panic!("Invalid blob size");
+                        }
 
-                    if blob.len() != BYTES_PER_BLOB {
-                        panic!("Invalid blob size");
+                        state_diff_bytes.extend(bytes_from_blob(blob.into()));
                     }
 
-                    // Decode state diff from blob
-                    let blob = bytes_from_blob(blob.into());
-                    let state_diff = StateDiff::decode(&blob)?;
+                    // Decode state diff from the batch blobs
+                    let state_diff = StateDiff::decode(&state_diff_bytes)?;
 
                     // Apply all account updates to trie
                     let account_updates = state_diff.to_account_updates(&new_trie)?;
@@ -410,7 +441,7 @@ impl Command {
                         .await?;
                     new_canonical_blocks.push((state_diff.last_header.number, new_block_hash));
                     println!(
-                        "Stored last block of blob. Block {}. State root {}",
+                        "Stored last block of batch {batch_number}. Block {}. State root {}",
                         new_block.number, new_block.state_root
                     );
 
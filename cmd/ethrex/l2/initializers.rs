@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::fs::read_to_string;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -6,6 +7,13 @@ use std::time::Duration;
 use ethrex_blockchain::{Blockchain, BlockchainType};
 use ethrex_common::Address;
 use ethrex_l2::SequencerConfig;
+use ethrex_l2_rpc::{
+    l2::{
+        preconfirmation::Preconfirmations, sponsorship::SponsorshipPolicy,
+        user_operation::UserOperationPool,
+    },
+    signer::{LocalSigner, Signer},
+};
 use ethrex_p2p::kademlia::KademliaTable;
 use ethrex_p2p::network::peer_table;
 use ethrex_p2p::peer_handler::PeerHandler;
@@ -28,7 +36,7 @@ use tui_logger::{LevelFilter, TuiTracingSubscriberLayer};
 use crate::cli::Options as L1Options;
 use crate::initializers::{
     self, get_authrpc_socket_addr, get_http_socket_addr, get_local_node_record, get_local_p2p_node,
-    get_network, get_signer, init_blockchain, init_network, init_store,
+    get_network, get_signer, init_blockchain, init_network, init_store, init_sync_target,
 };
 use crate::l2::L2Options;
 use crate::utils::{
@@ -38,16 +46,20 @@ use crate::utils::{
 #[allow(clippy::too_many_arguments)]
 async fn init_rpc_api(
     opts: &L1Options,
-    l2_opts: &L2Options,
+    sponsorship_policy: SponsorshipPolicy,
+    sponsor_pk: SecretKey,
+    preconfirmations: Preconfirmations,
+    sequencer_signer: Signer,
+    user_operations: Option<UserOperationPool>,
     peer_table: Arc<Mutex<KademliaTable>>,
     local_p2p_node: Node,
-    local_node_record: NodeRecord,
+    local_node_record: Arc<Mutex<NodeRecord>>,
     store: Store,
     blockchain: Arc<Blockchain>,
     cancel_token: CancellationToken,
     tracker: TaskTracker,
     rollup_store: StoreRollup,
-) {
+) -> SyncManager {
     let peer_handler = PeerHandler::new(peer_table);
 
     // Create SyncManager
@@ -68,33 +80,65 @@ async fn init_rpc_api(
         read_jwtsecret_file(&opts.authrpc_jwtsecret),
         local_p2p_node,
         local_node_record,
-        syncer,
+        syncer.clone(),
         peer_handler,
         get_client_version(),
-        get_valid_delegation_addresses(l2_opts),
-        l2_opts.sponsor_private_key,
+        sponsorship_policy,
+        sponsor_pk,
         rollup_store,
+        preconfirmations,
+        sequencer_signer,
+        user_operations,
     );
 
     tracker.spawn(rpc_api);
+    syncer
 }
 
-fn get_valid_delegation_addresses(l2_opts: &L2Options) -> Vec<Address> {
-    let Some(ref path) = l2_opts.sponsorable_addresses_file_path else {
-        warn!("No valid addresses provided, ethrex_SendTransaction will always fail");
-        return Vec::new();
+fn get_sponsorship_policy(l2_opts: &L2Options) -> SponsorshipPolicy {
+    let allowed_targets = match l2_opts.sponsorable_addresses_file_path {
+        Some(ref path) => read_to_string(path)
+            .unwrap_or_else(|_| panic!("Failed to load file {path}"))
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| {
+                parse_sponsorable_address(line)
+                    .inspect_err(|err| warn!("Ignoring sponsorable address {line}: {err}"))
+                    .ok()
+            })
+            .collect(),
+        None => HashMap::new(),
     };
-    let addresses: Vec<Address> = read_to_string(path)
-        .unwrap_or_else(|_| panic!("Failed to load file {path}"))
-        .lines()
-        .filter(|line| !line.trim().is_empty())
-        .map(|line| line.to_string().parse::<Address>())
-        .filter_map(Result::ok)
-        .collect();
-    if addresses.is_empty() {
+    if allowed_targets.is_empty() {
         warn!("No valid addresses provided, ethrex_SendTransaction will always fail");
     }
-    addresses
+    SponsorshipPolicy::new(
+        allowed_targets,
+        l2_opts.sponsor_max_gas_per_tx,
+        l2_opts.sponsor_max_txs_per_account,
+        l2_opts.sponsor_max_gas_per_account,
+        l2_opts.sponsor_daily_spend_cap.into(),
+    )
+}
+
+/// Parses a line of the sponsorable addresses file: an address optionally followed by the
+/// selectors that can be called on it, e.g. `0x1234...abcd 0xb61d27f6 0x47e1da2a`
+fn parse_sponsorable_address(line: &str) -> Result<(Address, Vec<[u8; 4]>), String> {
+    let mut words = line.split_whitespace();
+    let address = words
+        .next()
+        .unwrap_or_default()
+        .parse::<Address>()
+        .map_err(|err| err.to_string())?;
+    let selectors = words
+        .map(|selector| -> Result<[u8; 4], String> {
+            hex::decode(selector.trim_start_matches("0x"))
+                .map_err(|err| err.to_string())?
+                .try_into()
+                .map_err(|_| format!("invalid selector {selector}"))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((address, selectors))
 }
 
 pub async fn init_rollup_store(data_dir: &str) -> StoreRollup {
@@ -157,7 +201,12 @@ pub async fn init_l2(opts: L2Options) -> eyre::Result<()> {
     let store = init_store(&data_dir, genesis).await;
     let rollup_store = init_rollup_store(&rollup_store_dir).await;
 
-    let blockchain = init_blockchain(opts.node_opts.evm, store.clone(), BlockchainType::L2);
+    let blockchain = init_blockchain(
+        opts.node_opts.evm,
+        store.clone(),
+        BlockchainType::L2,
+        opts.node_opts.parallel_execution,
+    );
 
     let signer = get_signer(&data_dir);
 
@@ -177,12 +226,34 @@ pub async fn init_l2(opts: L2Options) -> eyre::Result<()> {
 
     let cancel_token = tokio_util::sync::CancellationToken::new();
 
-    init_rpc_api(
+    let sponsorship_policy = get_sponsorship_policy(&opts);
+    let block_producer_opts = &opts.sequencer_opts.block_producer_opts;
+    let user_operations = block_producer_opts
+        .entry_point_address
+        .zip(block_producer_opts.bundler_private_key)
+        .map(|(entry_point, bundler_private_key)| {
+            UserOperationPool::new(
+                entry_point,
+                LocalSigner::new(bundler_private_key).into(),
+                block_producer_opts.max_pending_user_operations,
+                block_producer_opts.user_operation_max_pending_blocks,
+            )
+        });
+    let l2_sequencer_cfg = SequencerConfig::try_from(opts.sequencer_opts).inspect_err(|err| {
+        error!("{err}");
+    })?;
+    let preconfirmations = Preconfirmations::new();
+
+    let syncer = init_rpc_api(
         &opts.node_opts,
-        &opts,
+        sponsorship_policy,
+        opts.sponsor_private_key,
+        preconfirmations.clone(),
+        l2_sequencer_cfg.l1_committer.signer.clone(),
+        user_operations.clone(),
         peer_table.clone(),
         local_p2p_node.clone(),
-        local_node_record.lock().await.clone(),
+        local_node_record.clone(),
         store.clone(),
         blockchain.clone(),
         cancel_token.clone(),
@@ -196,9 +267,6 @@ pub async fn init_l2(opts: L2Options) -> eyre::Result<()> {
         init_metrics(&opts.node_opts, tracker.clone());
     }
 
-    let l2_sequencer_cfg = SequencerConfig::try_from(opts.sequencer_opts).inspect_err(|err| {
-        error!("{err}");
-    })?;
     let cancellation_token = CancellationToken::new();
 
     // TODO: This should be handled differently, the current problem
@@ -235,6 +303,7 @@ pub async fn init_l2(opts: L2Options) -> eyre::Result<()> {
             }),
         )
         .await;
+        init_sync_target(&opts.node_opts, &syncer).await;
     } else {
         info!("P2P is disabled");
     }
@@ -245,6 +314,8 @@ pub async fn init_l2(opts: L2Options) -> eyre::Result<()> {
         blockchain,
         l2_sequencer_cfg,
         cancellation_token.clone(),
+        preconfirmations,
+        user_operations,
         #[cfg(feature = "metrics")]
         format!(
             "http://{}:{}",
@@ -3,7 +3,7 @@ use crate::{
     utils::{self},
 };
 use clap::Parser;
-use ethrex_common::Address;
+use ethrex_common::{Address, types::Fork};
 use ethrex_l2::{
     BasedConfig, BlockFetcherConfig, BlockProducerConfig, CommitterConfig, EthConfig,
     L1WatcherConfig, ProofCoordinatorConfig, SequencerConfig, StateUpdaterConfig,
@@ -35,10 +35,46 @@ pub struct Options {
     #[arg(
         long = "sponsorable-addresses",
         value_name = "SPONSORABLE_ADDRESSES_PATH",
-        help = "Path to a file containing addresses of contracts to which ethrex_SendTransaction should sponsor txs",
+        help = "Path to a file containing addresses of contracts to which ethrex_SendTransaction should sponsor txs. Each address can be followed by the function selectors that can be called on it, otherwise any selector is allowed.",
         help_heading = "L2 options"
     )]
     pub sponsorable_addresses_file_path: Option<String>,
+    #[arg(
+        long = "sponsor.max-gas-per-tx",
+        default_value = "200000",
+        value_name = "GAS",
+        env = "ETHREX_SPONSOR_MAX_GAS_PER_TX",
+        help = "Max gas limit of a transaction sponsored by ethrex_SendTransaction.",
+        help_heading = "L2 options"
+    )]
+    pub sponsor_max_gas_per_tx: u64,
+    #[arg(
+        long = "sponsor.max-txs-per-account",
+        default_value = "100",
+        value_name = "UINT64",
+        env = "ETHREX_SPONSOR_MAX_TXS_PER_ACCOUNT",
+        help = "Max transactions sponsored per account and day.",
+        help_heading = "L2 options"
+    )]
+    pub sponsor_max_txs_per_account: u64,
+    #[arg(
+        long = "sponsor.max-gas-per-account",
+        default_value = "5000000",
+        value_name = "GAS",
+        env = "ETHREX_SPONSOR_MAX_GAS_PER_ACCOUNT",
+        help = "Max gas sponsored per account and day.",
+        help_heading = "L2 options"
+    )]
+    pub sponsor_max_gas_per_account: u64,
+    #[arg(
+        long = "sponsor.daily-spend-cap",
+        default_value = "1000000000000000000",
+        value_name = "WEI",
+        env = "ETHREX_SPONSOR_DAILY_SPEND_CAP",
+        help = "Max fees the sponsor pays per day, in wei.",
+        help_heading = "L2 options"
+    )]
+    pub sponsor_daily_spend_cap: u128,
     //TODO: make optional when the the sponsored feature is complete
     #[arg(long, default_value = "0xffd790338a2798b648806fc8635ac7bf14af15425fed0c8f25bcc5febaa9b192", value_parser = utils::parse_private_key, env = "SPONSOR_PRIVATE_KEY", help = "The private key of ethrex L2 transactions sponsor.", help_heading = "L2 options")]
     pub sponsor_private_key: SecretKey,
@@ -50,6 +86,10 @@ impl Default for Options {
             node_opts: NodeOptions::default(),
             sequencer_opts: SequencerOptions::default(),
             sponsorable_addresses_file_path: None,
+            sponsor_max_gas_per_tx: 200000,
+            sponsor_max_txs_per_account: 100,
+            sponsor_max_gas_per_account: 5000000,
+            sponsor_daily_spend_cap: 1000000000000000000,
             sponsor_private_key: utils::parse_private_key(
                 "0xffd790338a2798b648806fc8635ac7bf14af15425fed0c8f25bcc5febaa9b192",
             )
@@ -159,6 +199,7 @@ impl TryFrom<SequencerOptions> for SequencerConfig {
                     .coinbase_address
                     .ok_or(SequencerOptionsError::NoCoinbaseAddress)?,
                 elasticity_multiplier: opts.block_producer_opts.elasticity_multiplier,
+                l1_fee_vault_address: opts.block_producer_opts.l1_fee_vault_address,
             },
             l1_committer: CommitterConfig {
                 on_chain_proposer_address: opts
@@ -169,6 +210,7 @@ impl TryFrom<SequencerOptions> for SequencerConfig {
                 arbitrary_base_blob_gas_price: opts.committer_opts.arbitrary_base_blob_gas_price,
                 signer: committer_signer,
                 validium: opts.validium,
+                l1_fork: opts.committer_opts.l1_fork,
             },
             eth: EthConfig {
                 rpc_url: opts.eth_opts.rpc_url,
@@ -194,6 +236,9 @@ impl TryFrom<SequencerOptions> for SequencerConfig {
                 listen_ip: opts.proof_coordinator_opts.listen_ip,
                 listen_port: opts.proof_coordinator_opts.listen_port,
                 proof_send_interval_ms: opts.proof_coordinator_opts.proof_send_interval_ms,
+                lease_timeout_ms: opts.proof_coordinator_opts.lease_timeout_ms,
+                prover_allowlist: opts.proof_coordinator_opts.prover_allowlist,
+                allow_any_prover: opts.proof_coordinator_opts.allow_any_prover,
                 signer: proof_coordinator_signer,
                 tdx_private_key: opts
                     .proof_coordinator_opts
@@ -385,6 +430,51 @@ pub struct BlockProducerOptions {
         help_heading = "Proposer options"
     )]
     pub elasticity_multiplier: u64,
+    #[arg(
+        long = "block-producer.l1-fee-vault-address",
+        value_name = "ADDRESS",
+        env = "ETHREX_BLOCK_PRODUCER_L1_FEE_VAULT_ADDRESS",
+        help_heading = "Block producer options",
+        help = "Address that receives the L1 data fee paid by L2 transactions. If not set, the L1 data fee is not charged."
+    )]
+    pub l1_fee_vault_address: Option<Address>,
+    #[arg(
+        long = "block-producer.entry-point-address",
+        value_name = "ADDRESS",
+        env = "ETHREX_BLOCK_PRODUCER_ENTRY_POINT_ADDRESS",
+        help_heading = "Block producer options",
+        help = "Address of the ERC-4337 v0.7 entry point to bundle user operations for. If not set, the eth_*UserOperation* methods are disabled.",
+        requires = "bundler_private_key"
+    )]
+    pub entry_point_address: Option<Address>,
+    #[arg(
+        long = "block-producer.bundler-private-key",
+        value_name = "PRIVATE_KEY",
+        value_parser = utils::parse_private_key,
+        env = "ETHREX_BLOCK_PRODUCER_BUNDLER_PRIVATE_KEY",
+        help_heading = "Block producer options",
+        help = "Private key of the account that sends the handleOps transactions and collects their fees.",
+        requires = "entry_point_address"
+    )]
+    pub bundler_private_key: Option<SecretKey>,
+    #[arg(
+        long = "block-producer.max-pending-user-operations",
+        default_value = "4096",
+        value_name = "UINT64",
+        env = "ETHREX_BLOCK_PRODUCER_MAX_PENDING_USER_OPERATIONS",
+        help_heading = "Block producer options",
+        help = "Max amount of user operations waiting to be bundled. New operations are rejected while the pool is full."
+    )]
+    pub max_pending_user_operations: usize,
+    #[arg(
+        long = "block-producer.user-operation-max-pending-blocks",
+        default_value = "64",
+        value_name = "UINT64",
+        env = "ETHREX_BLOCK_PRODUCER_USER_OPERATION_MAX_PENDING_BLOCKS",
+        help_heading = "Block producer options",
+        help = "Blocks after which a user operation that can't be bundled, because it pays less than the base fee or the L1 fee or doesn't fit in a block, is dropped."
+    )]
+    pub user_operation_max_pending_blocks: u64,
 }
 
 impl Default for BlockProducerOptions {
@@ -397,6 +487,11 @@ impl Default for BlockProducerOptions {
                     .unwrap(),
             ),
             elasticity_multiplier: 2,
+            l1_fee_vault_address: None,
+            entry_point_address: None,
+            bundler_private_key: None,
+            max_pending_user_operations: 4096,
+            user_operation_max_pending_blocks: 64,
         }
     }
 }
@@ -461,6 +556,16 @@ pub struct CommitterOptions {
         help_heading = "L1 Committer options"
     )]
     pub arbitrary_base_blob_gas_price: u64,
+    #[arg(
+        long = "committer.l1-fork",
+        default_value = "prague",
+        value_name = "FORK",
+        value_parser = utils::parse_l1_fork,
+        env = "ETHREX_COMMITTER_L1_FORK",
+        help_heading = "L1 Committer options",
+        help = "Fork of the L1, either prague or osaka. It sets how many blobs a commit transaction can carry and their proofs."
+    )]
+    pub l1_fork: Fork,
 }
 
 impl Default for CommitterOptions {
@@ -473,6 +578,7 @@ impl Default for CommitterOptions {
             on_chain_proposer_address: None,
             commit_time_ms: 60000,
             arbitrary_base_blob_gas_price: 1_000_000_000,
+            l1_fork: Fork::Prague,
             committer_remote_signer_url: None,
             committer_remote_signer_public_key: None,
         }
@@ -549,6 +655,35 @@ pub struct ProofCoordinatorOptions {
         help_heading = "Proof coordinator options"
     )]
     pub proof_send_interval_ms: u64,
+    #[arg(
+        long = "proof-coordinator.lease-timeout",
+        default_value = "3600000",
+        value_name = "UINT64",
+        env = "ETHREX_PROOF_COORDINATOR_LEASE_TIMEOUT",
+        help = "How long a prover can work on a batch, in milliseconds, before it is handed to another prover.",
+        help_heading = "Proof coordinator options"
+    )]
+    pub lease_timeout_ms: u64,
+    #[arg(
+        long = "proof-coordinator.prover-allowlist",
+        value_name = "ADDRESSES",
+        value_delimiter = ',',
+        num_args = 1..,
+        env = "ETHREX_PROOF_COORDINATOR_PROVER_ALLOWLIST",
+        help = "Addresses of the keys provers must sign their requests with. Required unless --proof-coordinator.allow-any-prover is set.",
+        help_heading = "Proof coordinator options"
+    )]
+    pub prover_allowlist: Vec<Address>,
+    #[arg(
+        long = "proof-coordinator.allow-any-prover",
+        action = clap::ArgAction::SetTrue,
+        default_value = "false",
+        conflicts_with = "prover_allowlist",
+        env = "ETHREX_PROOF_COORDINATOR_ALLOW_ANY_PROVER",
+        help = "Accept unauthenticated requests from any prover instead of requiring a prover allowlist. Only meant for local and trusted networks.",
+        help_heading = "Proof coordinator options"
+    )]
+    pub allow_any_prover: bool,
 }
 
 impl Default for ProofCoordinatorOptions {
@@ -564,6 +699,9 @@ impl Default for ProofCoordinatorOptions {
             listen_ip: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
             listen_port: 3900,
             proof_send_interval_ms: 5000,
+            lease_timeout_ms: 3600000,
+            prover_allowlist: Vec::new(),
+            allow_any_prover: false,
             proof_coordinator_tdx_private_key: None,
         }
     }
@@ -770,6 +908,15 @@ pub struct ProverClientOptions {
         help_heading = "Prover client options"
     )]
     pub aligned: bool,
+    #[arg(
+        long = "auth-private-key",
+        value_name = "PRIVATE_KEY",
+        value_parser = utils::parse_private_key,
+        env = "PROVER_CLIENT_AUTH_PRIVATE_KEY",
+        help = "Key to sign the requests to the proof coordinator with, its address must be in the coordinator's prover allowlist",
+        help_heading = "Prover client options"
+    )]
+    pub auth_private_key: Option<SecretKey>,
 }
 
 impl From<ProverClientOptions> for ProverConfig {
@@ -779,6 +926,7 @@ impl From<ProverClientOptions> for ProverConfig {
             proof_coordinator: config.proof_coordinator_endpoint,
             proving_time_ms: config.proving_time_ms,
             aligned_mode: config.aligned,
+            auth_private_key: config.auth_private_key,
         }
     }
 }
@@ -791,6 +939,7 @@ impl Default for ProverClientOptions {
             log_level: Level::INFO,
             aligned: false,
             backend: Backend::Exec,
+            auth_private_key: None,
         }
     }
 }
@@ -1,7 +1,7 @@
 use crate::decode;
 use bytes::Bytes;
 use directories::ProjectDirs;
-use ethrex_common::types::Block;
+use ethrex_common::types::{Block, Fork};
 use ethrex_p2p::{
     kademlia::KademliaTable,
     sync::SyncMode,
@@ -94,6 +94,16 @@ pub fn parse_sync_mode(s: &str) -> eyre::Result<SyncMode> {
     }
 }
 
+pub fn parse_l1_fork(s: &str) -> eyre::Result<Fork> {
+    match s {
+        "prague" => Ok(Fork::Prague),
+        "osaka" => Ok(Fork::Osaka),
+        other => Err(eyre::eyre!(
+            "Invalid L1 fork {other:?} expected either prague or osaka",
+        )),
+    }
+}
+
 pub fn parse_socket_addr(addr: &str, port: &str) -> io::Result<SocketAddr> {
     // NOTE: this blocks until hostname can be resolved
     format!("{addr}:{port}")
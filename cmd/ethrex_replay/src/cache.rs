@@ -10,10 +10,10 @@ use std::{
 #[serde_as]
 #[derive(Serialize, Deserialize)]
 pub struct L2Fields {
-    #[serde_as(as = "[_; 48]")]
-    pub blob_commitment: blobs_bundle::Commitment,
-    #[serde_as(as = "[_; 48]")]
-    pub blob_proof: blobs_bundle::Proof,
+    #[serde_as(as = "Vec<[_; 48]>")]
+    pub blob_commitments: Vec<blobs_bundle::Commitment>,
+    #[serde_as(as = "Vec<[_; 48]>")]
+    pub blob_proofs: Vec<blobs_bundle::Proof>,
 }
 
 #[derive(Serialize, Deserialize)]
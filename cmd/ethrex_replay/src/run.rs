@@ -87,7 +87,7 @@ fn get_input(cache: Cache) -> eyre::Result<ProgramInput> {
             blocks,
             db,
             elasticity_multiplier: ELASTICITY_MULTIPLIER,
-            // The L2 specific fields (blob_commitment, blob_proof)
+            // The L2 specific fields (blob_commitments, blob_proofs)
             // will be filled by Default::default() if the 'l2' feature of
             // 'zkvm_interface' is active (due to workspace compilation).
             // If 'zkvm_interface' is compiled without 'l2' (e.g. standalone build),
@@ -107,8 +107,8 @@ fn get_input(cache: Cache) -> eyre::Result<ProgramInput> {
             blocks,
             db,
             elasticity_multiplier: ELASTICITY_MULTIPLIER,
-            blob_commitment: l2_fields.blob_commitment,
-            blob_proof: l2_fields.blob_proof,
+            blob_commitments: l2_fields.blob_commitments,
+            blob_proofs: l2_fields.blob_proofs,
         })
     }
 }
@@ -65,6 +65,8 @@ pub struct Blockchain {
     /// This does not reflect whether there is an ongoing sync process
     is_synced: AtomicBool,
     pub r#type: BlockchainType,
+    /// Whether block transactions are executed optimistically in parallel
+    parallel_execution: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -93,9 +95,16 @@ impl Blockchain {
             mempool: Mempool::new(),
             is_synced: AtomicBool::new(false),
             r#type: blockchain_type,
+            parallel_execution: false,
         }
     }
 
+    /// Enables optimistic parallel execution of the block transactions when importing blocks.
+    pub fn with_parallel_execution(mut self, enabled: bool) -> Self {
+        self.parallel_execution = enabled;
+        self
+    }
+
     pub fn default_with_store(store: Store) -> Self {
         Self {
             evm_engine: EvmEngine::default(),
@@ -103,6 +112,7 @@ impl Blockchain {
             mempool: Mempool::new(),
             is_synced: AtomicBool::new(false),
             r#type: BlockchainType::default(),
+            parallel_execution: false,
         }
     }
 
@@ -126,7 +136,7 @@ impl Blockchain {
         let vm_db = StoreVmDatabase::new(self.storage.clone(), block.header.parent_hash);
         let mut vm = self.new_evm(vm_db)?;
 
-        let execution_result = vm.execute_block(block)?;
+        let execution_result = self.run_block(&mut vm, block)?;
         let account_updates = vm.get_state_transitions()?;
 
         // Validate execution went alright
@@ -137,6 +147,15 @@ impl Blockchain {
         Ok((execution_result, account_updates))
     }
 
+    /// Runs the block transactions on the given vm, in parallel if enabled
+    fn run_block(&self, vm: &mut Evm, block: &Block) -> Result<BlockExecutionResult, ChainError> {
+        if self.parallel_execution {
+            Ok(vm.execute_block_parallel(block)?)
+        } else {
+            Ok(vm.execute_block(block)?)
+        }
+    }
+
     /// Executes a block from a given vm instance an does not clear its state
     fn execute_block_from_state(
         &self,
@@ -147,7 +166,7 @@ impl Blockchain {
     ) -> Result<BlockExecutionResult, ChainError> {
         // Validate the block pre-execution
         validate_block(block, parent_header, chain_config, ELASTICITY_MULTIPLIER)?;
-        let execution_result = vm.execute_block(block)?;
+        let execution_result = self.run_block(vm, block)?;
         // Validate execution went alright
         validate_gas_used(&execution_result.receipts, &block.header)?;
         validate_receipts_root(&block.header, &execution_result.receipts)?;
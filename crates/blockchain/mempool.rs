@@ -803,6 +803,7 @@ mod tests {
                 blobs: blobs.to_vec(),
                 commitments: commitments.to_vec(),
                 proofs: proofs.to_vec(),
+                ..Default::default()
             };
             mempool.add_blobs_bundle(H256::random(), bundle).unwrap();
         }
@@ -2,7 +2,10 @@ use axum::{Router, routing::get};
 
 use crate::profiling::gather_profiling_metrics;
 
-use crate::{MetricsApiError, metrics_blocks::METRICS_BLOCKS, metrics_transactions::METRICS_TX};
+use crate::{
+    MetricsApiError, metrics_blocks::METRICS_BLOCKS, metrics_sync::METRICS_SYNC,
+    metrics_transactions::METRICS_TX,
+};
 
 pub async fn start_prometheus_metrics_api(
     address: String,
@@ -47,5 +50,14 @@ pub(crate) async fn get_metrics() -> String {
         }
     }
 
+    ret_string.push('\n');
+    match METRICS_SYNC.gather_metrics() {
+        Ok(string) => ret_string.push_str(&string),
+        Err(_) => {
+            tracing::error!("Failed to register METRICS_SYNC");
+            return String::new();
+        }
+    }
+
     ret_string
 }
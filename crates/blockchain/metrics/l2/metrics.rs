@@ -1,4 +1,6 @@
-use prometheus::{Encoder, Gauge, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder};
+use prometheus::{
+    Encoder, Gauge, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder,
+};
 use std::sync::LazyLock;
 
 use crate::MetricsError;
@@ -11,6 +13,7 @@ pub struct Metrics {
     l1_gas_price: IntGauge,
     l2_gas_price: IntGauge,
     blob_usage: Gauge,
+    rejected_prover_requests: IntCounterVec,
 }
 
 impl Default for Metrics {
@@ -45,6 +48,14 @@ impl Metrics {
                 "Keeps track of the percentage of blob usage for a batch commitment",
             )
             .unwrap(),
+            rejected_prover_requests: IntCounterVec::new(
+                Opts::new(
+                    "l2_rejected_prover_requests",
+                    "Keeps track of the prover requests rejected by the proof coordinator",
+                ),
+                &["reason"],
+            )
+            .unwrap(),
         }
     }
 
@@ -91,6 +102,14 @@ impl Metrics {
         self.blob_usage.set(usage);
     }
 
+    pub fn inc_rejected_prover_requests(&self, reason: &str) -> Result<(), MetricsError> {
+        self.rejected_prover_requests
+            .get_metric_with_label_values(&[reason])
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?
+            .inc();
+        Ok(())
+    }
+
     pub fn gather_metrics(&self) -> Result<String, MetricsError> {
         let r = Registry::new();
 
@@ -104,6 +123,8 @@ impl Metrics {
             .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
         r.register(Box::new(self.blob_usage.clone()))
             .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.rejected_prover_requests.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
 
         let encoder = TextEncoder::new();
         let metric_families = r.gather();
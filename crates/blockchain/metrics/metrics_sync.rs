@@ -0,0 +1,144 @@
+use prometheus::{Encoder, IntGauge, Registry, TextEncoder};
+use std::sync::LazyLock;
+
+use crate::MetricsError;
+
+pub static METRICS_SYNC: LazyLock<MetricsSync> = LazyLock::new(MetricsSync::default);
+
+#[derive(Debug, Clone)]
+pub struct MetricsSync {
+    starting_block: IntGauge,
+    current_block: IntGauge,
+    highest_block: IntGauge,
+    synced_accounts: IntGauge,
+    synced_storage_slots: IntGauge,
+    synced_bytecodes: IntGauge,
+    healed_trie_nodes: IntGauge,
+    healing_pending: IntGauge,
+}
+
+impl Default for MetricsSync {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsSync {
+    pub fn new() -> Self {
+        MetricsSync {
+            starting_block: IntGauge::new(
+                "sync_starting_block",
+                "Block the node was at when the current sync cycle started",
+            )
+            .unwrap(),
+            current_block: IntGauge::new(
+                "sync_current_block",
+                "Latest block fully processed by the sync",
+            )
+            .unwrap(),
+            highest_block: IntGauge::new("sync_highest_block", "Highest block known to the sync")
+                .unwrap(),
+            synced_accounts: IntGauge::new(
+                "snap_synced_accounts",
+                "Amount of accounts downloaded by snap sync",
+            )
+            .unwrap(),
+            synced_storage_slots: IntGauge::new(
+                "snap_synced_storage_slots",
+                "Amount of storage slots downloaded by snap sync",
+            )
+            .unwrap(),
+            synced_bytecodes: IntGauge::new(
+                "snap_synced_bytecodes",
+                "Amount of bytecodes downloaded by snap sync",
+            )
+            .unwrap(),
+            healed_trie_nodes: IntGauge::new(
+                "snap_healed_trie_nodes",
+                "Amount of trie nodes downloaded during snap sync healing",
+            )
+            .unwrap(),
+            healing_pending: IntGauge::new(
+                "snap_healing_pending",
+                "Amount of trie paths still pending to be healed",
+            )
+            .unwrap(),
+        }
+    }
+
+    pub fn set_starting_block(&self, value: u64) {
+        self.starting_block
+            .set(value.try_into().unwrap_or(i64::MAX));
+    }
+
+    pub fn set_current_block(&self, value: u64) {
+        self.current_block.set(value.try_into().unwrap_or(i64::MAX));
+    }
+
+    pub fn set_highest_block(&self, value: u64) {
+        self.highest_block.set(value.try_into().unwrap_or(i64::MAX));
+    }
+
+    pub fn set_synced_accounts(&self, value: u64) {
+        self.synced_accounts
+            .set(value.try_into().unwrap_or(i64::MAX));
+    }
+
+    pub fn set_synced_storage_slots(&self, value: u64) {
+        self.synced_storage_slots
+            .set(value.try_into().unwrap_or(i64::MAX));
+    }
+
+    pub fn set_synced_bytecodes(&self, value: u64) {
+        self.synced_bytecodes
+            .set(value.try_into().unwrap_or(i64::MAX));
+    }
+
+    pub fn set_healed_trie_nodes(&self, value: u64) {
+        self.healed_trie_nodes
+            .set(value.try_into().unwrap_or(i64::MAX));
+    }
+
+    pub fn set_healing_pending(&self, value: u64) {
+        self.healing_pending
+            .set(value.try_into().unwrap_or(i64::MAX));
+    }
+
+    pub fn gather_metrics(&self) -> Result<String, MetricsError> {
+        // Nothing to report if the node never synced
+        if self.highest_block.get() <= 0 {
+            return Ok(String::new());
+        }
+
+        let r = Registry::new();
+
+        r.register(Box::new(self.starting_block.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.current_block.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.highest_block.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.synced_accounts.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.synced_storage_slots.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.synced_bytecodes.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.healed_trie_nodes.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.healing_pending.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+
+        let encoder = TextEncoder::new();
+        let metric_families = r.gather();
+
+        let mut buffer = Vec::new();
+        encoder
+            .encode(&metric_families, &mut buffer)
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+
+        let res = String::from_utf8(buffer)?;
+
+        Ok(res)
+    }
+}
@@ -9,10 +9,11 @@ use ethrex_common::{
     Address, Bloom, Bytes, H256, U256,
     constants::{DEFAULT_OMMERS_HASH, DEFAULT_REQUESTS_HASH, GAS_PER_BLOB},
     types::{
-        AccountUpdate, BlobsBundle, Block, BlockBody, BlockHash, BlockHeader, BlockNumber,
-        ChainConfig, MempoolTransaction, Receipt, Transaction, TxType, Withdrawal, bloom_from_logs,
-        calc_excess_blob_gas, calculate_base_fee_per_blob_gas, calculate_base_fee_per_gas,
-        compute_receipts_root, compute_transactions_root, compute_withdrawals_root,
+        AccountUpdate, BLOB_WRAPPER_VERSION_4844, BLOB_WRAPPER_VERSION_7594, BlobsBundle, Block,
+        BlockBody, BlockHash, BlockHeader, BlockNumber, ChainConfig, MempoolTransaction, Receipt,
+        Transaction, TxType, Withdrawal, bloom_from_logs, calc_excess_blob_gas,
+        calculate_base_fee_per_blob_gas, calculate_base_fee_per_gas, compute_receipts_root,
+        compute_transactions_root, compute_withdrawals_root,
         requests::{EncodedRequests, compute_requests_hash},
     },
 };
@@ -91,9 +92,9 @@ pub fn create_payload(args: &BuildPayloadArgs, storage: &Store) -> Result<Block,
         .get_fork_blob_schedule(args.timestamp)
         .map(|schedule| {
             calc_excess_blob_gas(
-                parent_block.excess_blob_gas.unwrap_or_default(),
-                parent_block.blob_gas_used.unwrap_or_default(),
-                schedule.target,
+                &parent_block,
+                schedule,
+                chain_config.get_fork(args.timestamp),
             )
         });
 
@@ -201,6 +202,13 @@ impl PayloadBuildContext {
                 .unwrap_or_default(),
         );
 
+        // From Osaka onwards the payload's blobs bundle carries cell proofs
+        let blobs_bundle_version = if config.is_osaka_activated(payload.header.timestamp) {
+            BLOB_WRAPPER_VERSION_7594
+        } else {
+            BLOB_WRAPPER_VERSION_4844
+        };
+
         let vm_db = StoreVmDatabase::new(storage.clone(), payload.header.parent_hash);
         let vm = match blockchain_type {
             BlockchainType::L1 => Evm::new_for_l1(evm_engine, vm_db),
@@ -216,7 +224,10 @@ impl PayloadBuildContext {
             block_value: U256::zero(),
             base_fee_per_blob_gas: U256::from(base_fee_per_blob_gas),
             payload,
-            blobs_bundle: BlobsBundle::default(),
+            blobs_bundle: BlobsBundle {
+                version: blobs_bundle_version,
+                ..Default::default()
+            },
             store: storage.clone(),
             vm,
             account_updates: Vec::new(),
@@ -488,6 +499,10 @@ impl Blockchain {
                 StoreError::Custom(format!("No blobs bundle found for blob tx {tx_hash}")).into(),
             );
         };
+        if blobs_bundle.version != context.blobs_bundle.version {
+            // Bundles built before the fork transition can't be included in the payload
+            return Err(EvmError::Custom("blobs bundle version mismatch".to_string()).into());
+        }
         if context.blobs_bundle.blobs.len() + blobs_bundle.blobs.len() > max_blob_number_per_block {
             // This error will only be used for debug tracing
             return Err(EvmError::Custom("max data blobs reached".to_string()).into());
@@ -3,23 +3,25 @@ use std::{
     time::Duration,
 };
 
-use ethrex_common::{H256, tracing::CallTrace, types::Block};
+use ethrex_common::{H256, types::Block};
 use ethrex_storage::Store;
-use ethrex_vm::{Evm, EvmError};
+use ethrex_vm::{
+    Evm, EvmError,
+    tracing::{NativeTracer, StateAccess, TraceResult},
+};
 
 use crate::{Blockchain, error::ChainError, vm::StoreVmDatabase};
 
 impl Blockchain {
-    /// Outputs the call trace for the given transaction
+    /// Outputs the result of running the given tracer over the transaction
     /// May need to re-execute blocks in order to rebuild the transaction's prestate, up to the amount given by `reexec`
-    pub async fn trace_transaction_calls(
+    pub async fn trace_transaction(
         &self,
         tx_hash: H256,
         reexec: u32,
         timeout: Duration,
-        only_top_call: bool,
-        with_log: bool,
-    ) -> Result<CallTrace, ChainError> {
+        tracer: NativeTracer,
+    ) -> Result<TraceResult, ChainError> {
         // Fetch the transaction's location and the block it is contained in
         let Some((_, block_hash, tx_index)) =
             self.storage.get_transaction_location(tx_hash).await?
@@ -37,24 +39,20 @@ impl Blockchain {
         // Run the block until the transaction we want to trace
         vm.rerun_block(&block, Some(tx_index))?;
         // Trace the transaction
-        timeout_trace_operation(timeout, move || {
-            vm.trace_tx_calls(&block, tx_index, only_top_call, with_log)
-        })
-        .await
+        timeout_trace_operation(timeout, move || vm.trace_tx(&block, tx_index, &tracer)).await
     }
 
-    /// Outputs the call trace for each transaction in the block along with the transaction's hash
+    /// Outputs the result of running the given tracer over each transaction in the block along with the transaction's hash
     /// May need to re-execute blocks in order to rebuild the transaction's prestate, up to the amount given by `reexec`
-    /// Returns transaction call traces from oldest to newest
-    pub async fn trace_block_calls(
+    /// Returns transaction traces from oldest to newest
+    pub async fn trace_block(
         &self,
         // We receive the block instead of its hash/number to support multiple potential endpoints
         block: Block,
         reexec: u32,
         timeout: Duration,
-        only_top_call: bool,
-        with_log: bool,
-    ) -> Result<Vec<(H256, CallTrace)>, ChainError> {
+        tracer: NativeTracer,
+    ) -> Result<Vec<(H256, TraceResult)>, ChainError> {
         // Obtain the block's parent state
         let mut vm = self
             .rebuild_parent_state(block.header.parent_hash, reexec)
@@ -65,21 +63,52 @@ impl Blockchain {
         // We need to do this in order to pass ownership of block & evm to a blocking process without cloning
         let vm = Arc::new(Mutex::new(vm));
         let block = Arc::new(block);
-        let mut call_traces = vec![];
+        let tracer = Arc::new(tracer);
+        let mut traces = vec![];
         for index in 0..block.body.transactions.len() {
             // We are cloning the `Arc`s here, not the structs themselves
             let block = block.clone();
             let vm = vm.clone();
+            let tracer = tracer.clone();
             let tx_hash = block.as_ref().body.transactions[index].hash();
-            let call_trace = timeout_trace_operation(timeout, move || {
+            let trace = timeout_trace_operation(timeout, move || {
                 vm.lock()
                     .map_err(|_| EvmError::Custom("Unexpected Runtime Error".to_string()))?
-                    .trace_tx_calls(block.as_ref(), index, only_top_call, with_log)
+                    .trace_tx(block.as_ref(), index, tracer.as_ref())
             })
             .await?;
-            call_traces.push((tx_hash, call_trace));
+            traces.push((tx_hash, trace));
         }
-        Ok(call_traces)
+        Ok(traces)
+    }
+
+    /// Outputs the accounts and storage slots read and written by each transaction in the block, along with the values of the written ones before and after it
+    /// May need to re-execute blocks in order to rebuild the transaction's prestate, up to the amount given by `reexec`
+    /// Returns transaction state accesses from oldest to newest
+    pub async fn trace_block_state_access(
+        &self,
+        block: Block,
+        reexec: u32,
+        timeout: Duration,
+    ) -> Result<Vec<(H256, StateAccess)>, ChainError> {
+        // Obtain the block's parent state
+        let mut vm = self
+            .rebuild_parent_state(block.header.parent_hash, reexec)
+            .await?;
+        // Run anything necessary before executing the block's transactions (system calls, etc)
+        vm.rerun_block(&block, Some(0))?;
+        // Trace each transaction, the whole block shares the same timeout
+        let state_accesses = timeout_trace_operation(timeout, move || {
+            block
+                .body
+                .transactions
+                .iter()
+                .enumerate()
+                .map(|(index, tx)| Ok((tx.hash(), vm.trace_tx_state_access(&block, index)?)))
+                .collect::<Result<Vec<_>, EvmError>>()
+        })
+        .await?;
+        Ok(state_accesses)
     }
 
     /// Rebuild the parent state for a block given its parent hash, returning an `Evm` instance with all changes cached
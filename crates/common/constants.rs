@@ -57,3 +57,8 @@ pub const GAS_PER_BLOB: u32 = 1 << 17;
 
 // Minimum base fee per blob
 pub const MIN_BASE_FEE_PER_BLOB_GAS: u64 = 1;
+
+// === EIP-7918 constants ===
+
+/// Execution gas a blob is priced at at least, used as a reserve price for the blob base fee
+pub const BLOB_BASE_COST: u64 = 1 << 13;
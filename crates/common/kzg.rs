@@ -7,6 +7,11 @@ pub enum KzgError {
     CKzg(#[from] c_kzg::Error),
     #[error("kzg-rs error: {0}")]
     KzgRs(kzg_rs::KzgError),
+    #[cfg(feature = "rust-eth-kzg")]
+    #[error("rust-eth-kzg error: {0:?}")]
+    EthKzg(rust_eth_kzg::Error),
+    #[error("Cell proofs are not supported without the rust-eth-kzg feature")]
+    CellProofsNotSupported,
 }
 
 impl From<kzg_rs::KzgError> for KzgError {
@@ -15,6 +20,19 @@ impl From<kzg_rs::KzgError> for KzgError {
     }
 }
 
+#[cfg(feature = "rust-eth-kzg")]
+impl From<rust_eth_kzg::Error> for KzgError {
+    fn from(value: rust_eth_kzg::Error) -> Self {
+        KzgError::EthKzg(value)
+    }
+}
+
+#[cfg(feature = "rust-eth-kzg")]
+fn das_context() -> &'static rust_eth_kzg::DASContext {
+    static CONTEXT: std::sync::OnceLock<rust_eth_kzg::DASContext> = std::sync::OnceLock::new();
+    CONTEXT.get_or_init(rust_eth_kzg::DASContext::default)
+}
+
 /// Verifies a KZG proof for blob committed data, using a Fiat-Shamir protocol
 /// as defined by c-kzg-4844.
 pub fn verify_blob_kzg_proof(
@@ -93,3 +111,57 @@ pub fn blob_to_kzg_commitment_and_proof(blob: &Blob) -> Result<(Commitment, Proo
 
     Ok((commitment_bytes.into_inner(), proof_bytes.into_inner()))
 }
+
+/// Verifies the cell proofs of a set of blobs as defined by EIP-7594.
+/// `cell_proofs` holds `CELLS_PER_EXT_BLOB` proofs per blob, in blob order.
+pub fn verify_cell_kzg_proof_batch(
+    blobs: &[Blob],
+    commitments: &[Commitment],
+    cell_proofs: &[Proof],
+) -> Result<bool, KzgError> {
+    #[cfg(not(feature = "rust-eth-kzg"))]
+    {
+        let _ = (blobs, commitments, cell_proofs);
+        Err(KzgError::CellProofsNotSupported)
+    }
+    #[cfg(feature = "rust-eth-kzg")]
+    {
+        use crate::types::CELLS_PER_EXT_BLOB;
+
+        let context = das_context();
+        let mut cells = Vec::with_capacity(blobs.len() * CELLS_PER_EXT_BLOB);
+        for blob in blobs {
+            let (blob_cells, _) = context.compute_cells_and_kzg_proofs(blob)?;
+            cells.extend(blob_cells);
+        }
+        let cell_commitments = commitments
+            .iter()
+            .flat_map(|commitment| std::iter::repeat_n(commitment, CELLS_PER_EXT_BLOB))
+            .collect();
+        let cell_indices: Vec<u64> = (0..blobs.len())
+            .flat_map(|_| 0..CELLS_PER_EXT_BLOB as u64)
+            .collect();
+
+        match context.verify_cell_kzg_proof_batch(
+            cell_commitments,
+            &cell_indices,
+            cells.iter().map(|cell| cell.as_ref()).collect(),
+            cell_proofs.iter().collect(),
+        ) {
+            Ok(()) => Ok(true),
+            Err(err) if err.is_proof_invalid() => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// Computes the commitment and the `CELLS_PER_EXT_BLOB` cell proofs of a blob as defined by EIP-7594.
+#[cfg(feature = "rust-eth-kzg")]
+pub fn blob_to_kzg_commitment_and_cell_proofs(
+    blob: &Blob,
+) -> Result<(Commitment, Vec<Proof>), KzgError> {
+    let context = das_context();
+    let commitment = context.blob_to_kzg_commitment(blob)?;
+    let (_, proofs) = context.compute_cells_and_kzg_proofs(blob)?;
+    Ok((commitment, proofs.to_vec()))
+}
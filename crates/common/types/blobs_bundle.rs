@@ -18,7 +18,10 @@ use ethrex_rlp::{
 };
 use serde::{Deserialize, Serialize};
 
-use super::{BYTES_PER_BLOB, SAFE_BYTES_PER_BLOB};
+use super::{
+    BLOB_WRAPPER_VERSION_4844, BLOB_WRAPPER_VERSION_7594, BYTES_PER_BLOB, CELLS_PER_EXT_BLOB,
+    SAFE_BYTES_PER_BLOB,
+};
 
 pub type Bytes48 = [u8; 48];
 pub type Blob = [u8; BYTES_PER_BLOB];
@@ -33,8 +36,12 @@ pub struct BlobsBundle {
     pub blobs: Vec<Blob>,
     #[serde(with = "serde_utils::bytes48::vec")]
     pub commitments: Vec<Commitment>,
+    /// One KZG proof per blob for version 0 bundles, `CELLS_PER_EXT_BLOB` cell proofs per blob for version 1.
     #[serde(with = "serde_utils::bytes48::vec")]
     pub proofs: Vec<Proof>,
+    /// Network wrapper version, see [EIP-7594](https://eips.ethereum.org/EIPS/eip-7594)
+    #[serde(skip)]
+    pub version: u8,
 }
 
 pub fn blob_from_bytes(bytes: Bytes) -> Result<Blob, BlobsBundleError> {
@@ -57,6 +64,17 @@ pub fn blob_from_bytes(bytes: Bytes) -> Result<Blob, BlobsBundleError> {
     Ok(buf)
 }
 
+/// Splits the bytes across as many blobs as needed to hold them, see [blob_from_bytes].
+pub fn blobs_from_bytes(bytes: Bytes) -> Result<Vec<Blob>, BlobsBundleError> {
+    if bytes.is_empty() {
+        return Ok(vec![blob_from_bytes(bytes)?]);
+    }
+    bytes
+        .chunks(SAFE_BYTES_PER_BLOB)
+        .map(|chunk| blob_from_bytes(Bytes::copy_from_slice(chunk)))
+        .collect()
+}
+
 pub fn bytes_from_blob(blob: Bytes) -> [u8; SAFE_BYTES_PER_BLOB] {
     let mut buf = [0u8; SAFE_BYTES_PER_BLOB];
     buf.copy_from_slice(
@@ -101,6 +119,29 @@ impl BlobsBundle {
             blobs: blobs.clone(),
             commitments,
             proofs,
+            version: BLOB_WRAPPER_VERSION_4844,
+        })
+    }
+
+    /// Same as `create_from_blobs` but computing EIP-7594 cell proofs, as required from Osaka onwards.
+    #[cfg(feature = "rust-eth-kzg")]
+    pub fn create_from_blobs_with_cell_proofs(blobs: &[Blob]) -> Result<Self, BlobsBundleError> {
+        use crate::kzg::blob_to_kzg_commitment_and_cell_proofs;
+
+        let mut commitments = Vec::new();
+        let mut proofs = Vec::new();
+
+        for blob in blobs {
+            let (commitment, cell_proofs) = blob_to_kzg_commitment_and_cell_proofs(blob)?;
+            commitments.push(commitment);
+            proofs.extend(cell_proofs);
+        }
+
+        Ok(Self {
+            blobs: blobs.to_vec(),
+            commitments,
+            proofs,
+            version: BLOB_WRAPPER_VERSION_7594,
         })
     }
 
@@ -125,9 +166,24 @@ impl BlobsBundle {
             return Err(BlobsBundleError::BlobBundleEmptyError);
         }
 
+        // From Osaka onwards only cell proofs are accepted, before it only blob proofs
+        let expected_version = if fork >= Fork::Osaka {
+            BLOB_WRAPPER_VERSION_7594
+        } else {
+            BLOB_WRAPPER_VERSION_4844
+        };
+        if self.version != expected_version {
+            return Err(BlobsBundleError::InvalidWrapperVersion(self.version));
+        }
+        let proofs_per_blob = if self.version == BLOB_WRAPPER_VERSION_7594 {
+            CELLS_PER_EXT_BLOB
+        } else {
+            1
+        };
+
         // Check if the blob versioned hashes and blobs bundle content length mismatch
         if blob_count != self.commitments.len()
-            || blob_count != self.proofs.len()
+            || blob_count * proofs_per_blob != self.proofs.len()
             || blob_count != tx.blob_versioned_hashes.len()
         {
             return Err(BlobsBundleError::BlobsBundleWrongLen);
@@ -142,6 +198,15 @@ impl BlobsBundle {
             }
         }
 
+        if self.version == BLOB_WRAPPER_VERSION_7594 {
+            use crate::kzg::verify_cell_kzg_proof_batch;
+
+            if !verify_cell_kzg_proof_batch(&self.blobs, &self.commitments, &self.proofs)? {
+                return Err(BlobsBundleError::BlobToCommitmentAndProofError);
+            }
+            return Ok(());
+        }
+
         // Validate the blobs with the commitments and proofs
         for ((blob, commitment), proof) in self
             .blobs
@@ -182,6 +247,7 @@ impl RLPDecode for BlobsBundle {
                 blobs,
                 commitments,
                 proofs,
+                version: BLOB_WRAPPER_VERSION_4844,
             },
             decoder.finish()?,
         ))
@@ -198,6 +264,8 @@ impl AddAssign for BlobsBundle {
 
 const MAX_BLOB_COUNT: u64 = 6;
 const MAX_BLOB_COUNT_ELECTRA: u64 = 9;
+/// See [EIP-7594](https://eips.ethereum.org/EIPS/eip-7594)
+const MAX_BLOBS_PER_TX_FUSAKA: u64 = 6;
 
 fn max_blobs_per_block(fork: Fork) -> u64 {
     if fork >= Fork::Prague {
@@ -207,6 +275,15 @@ fn max_blobs_per_block(fork: Fork) -> u64 {
     }
 }
 
+/// Maximum amount of blobs a single transaction can carry.
+pub fn max_blobs_per_tx(fork: Fork) -> u64 {
+    if fork >= Fork::Osaka {
+        MAX_BLOBS_PER_TX_FUSAKA
+    } else {
+        max_blobs_per_block(fork)
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum BlobsBundleError {
     #[error("Blob data has an invalid length")]
@@ -221,6 +298,8 @@ pub enum BlobsBundleError {
     BlobToCommitmentAndProofError,
     #[error("Max blobs per block exceeded")]
     MaxBlobsExceeded,
+    #[error("Blobs bundle wrapper version {0} is not valid for the current fork")]
+    InvalidWrapperVersion(u8),
     #[error("KZG related error: {0}")]
     Kzg(#[from] KzgError),
 }
@@ -290,7 +369,8 @@ mod tests {
                             .map(|s| {
                                 shared::convert_str_to_bytes48(s)
                             })
-                            .collect()
+                            .collect(),
+            ..Default::default()
         };
 
         let tx = EIP4844Transaction {
@@ -341,7 +421,8 @@ mod tests {
                               .map(|s| {
                                 shared::convert_str_to_bytes48(s)
                               })
-                              .collect()
+                              .collect(),
+            ..Default::default()
         };
 
         let tx = EIP4844Transaction {
@@ -405,4 +486,71 @@ mod tests {
             Err(BlobsBundleError::MaxBlobsExceeded)
         ));
     }
+
+    #[test]
+    #[cfg(feature = "c-kzg")]
+    fn transaction_with_blob_proofs_should_fail_after_osaka() {
+        let blobs = vec![
+            blobs_bundle::blob_from_bytes("Hello, world!".as_bytes().into())
+                .expect("Failed to create blob"),
+        ];
+
+        let blobs_bundle =
+            BlobsBundle::create_from_blobs(&blobs).expect("Failed to create blobs bundle");
+
+        let tx = EIP4844Transaction {
+            blob_versioned_hashes: blobs_bundle.generate_versioned_hashes(),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            blobs_bundle.validate(&tx, Fork::Osaka),
+            Err(BlobsBundleError::InvalidWrapperVersion(0))
+        ));
+    }
+
+    #[test]
+    #[cfg(all(feature = "c-kzg", feature = "rust-eth-kzg"))]
+    fn transaction_with_valid_cell_proofs_should_pass() {
+        let blobs = vec![
+            blobs_bundle::blob_from_bytes("Hello, world!".as_bytes().into())
+                .expect("Failed to create blob"),
+        ];
+
+        let mut blobs_bundle = BlobsBundle::create_from_blobs_with_cell_proofs(&blobs)
+            .expect("Failed to create blobs bundle");
+        assert_eq!(blobs_bundle.proofs.len(), CELLS_PER_EXT_BLOB);
+
+        let tx = EIP4844Transaction {
+            blob_versioned_hashes: blobs_bundle.generate_versioned_hashes(),
+            ..Default::default()
+        };
+
+        assert!(matches!(blobs_bundle.validate(&tx, Fork::Osaka), Ok(())));
+        assert!(matches!(
+            blobs_bundle.validate(&tx, Fork::Prague),
+            Err(BlobsBundleError::InvalidWrapperVersion(1))
+        ));
+
+        blobs_bundle.proofs.swap(0, 1);
+        assert!(matches!(
+            blobs_bundle.validate(&tx, Fork::Osaka),
+            Err(BlobsBundleError::BlobToCommitmentAndProofError)
+        ));
+    }
+
+    #[test]
+    fn bytes_are_split_across_blobs() {
+        let data: Vec<u8> = (0..SAFE_BYTES_PER_BLOB + 100).map(|i| i as u8).collect();
+
+        let blobs = blobs_bundle::blobs_from_bytes(data.clone().into()).unwrap();
+        assert_eq!(blobs.len(), 2);
+
+        let decoded: Vec<u8> = blobs
+            .iter()
+            .flat_map(|blob| blobs_bundle::bytes_from_blob(Bytes::copy_from_slice(blob)))
+            .collect();
+        assert_eq!(decoded[..data.len()], data[..]);
+        assert!(decoded[data.len()..].iter().all(|byte| *byte == 0));
+    }
 }
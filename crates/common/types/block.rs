@@ -1,11 +1,12 @@
 use super::{
-    BASE_FEE_MAX_CHANGE_DENOMINATOR, ChainConfig, GAS_LIMIT_ADJUSTMENT_FACTOR, GAS_LIMIT_MINIMUM,
-    INITIAL_BASE_FEE,
+    BASE_FEE_MAX_CHANGE_DENOMINATOR, ChainConfig, Fork, ForkBlobSchedule,
+    GAS_LIMIT_ADJUSTMENT_FACTOR, GAS_LIMIT_MINIMUM, INITIAL_BASE_FEE,
 };
 use crate::{
     Address, H256, U256,
     constants::{
-        DEFAULT_OMMERS_HASH, EMPTY_WITHDRAWALS_HASH, GAS_PER_BLOB, MIN_BASE_FEE_PER_BLOB_GAS,
+        BLOB_BASE_COST, DEFAULT_OMMERS_HASH, EMPTY_WITHDRAWALS_HASH, GAS_PER_BLOB,
+        MIN_BASE_FEE_PER_BLOB_GAS,
     },
     types::{Receipt, Transaction},
 };
@@ -720,9 +721,9 @@ fn validate_excess_blob_gas(
         .get_fork_blob_schedule(header.timestamp)
         .map(|schedule| {
             calc_excess_blob_gas(
-                parent_header.excess_blob_gas.unwrap_or_default(),
-                parent_header.blob_gas_used.unwrap_or_default(),
-                schedule.target,
+                parent_header,
+                schedule,
+                chain_config.get_fork(header.timestamp),
             )
         })
         .unwrap_or_default();
@@ -735,14 +736,34 @@ fn validate_excess_blob_gas(
     Ok(())
 }
 
-pub fn calc_excess_blob_gas(
-    parent_excess_blob_gas: u64,
-    parent_blob_gas_used: u64,
-    target: u32,
-) -> u64 {
+/// Calculates the excess blob gas of the block following `parent`, `fork` is the block's fork
+pub fn calc_excess_blob_gas(parent: &BlockHeader, schedule: ForkBlobSchedule, fork: Fork) -> u64 {
+    let parent_excess_blob_gas = parent.excess_blob_gas.unwrap_or_default();
+    let parent_blob_gas_used = parent.blob_gas_used.unwrap_or_default();
     let excess_blob_gas = parent_excess_blob_gas + parent_blob_gas_used;
-    let target_blob_gas_per_block = target * GAS_PER_BLOB;
-    excess_blob_gas.saturating_sub(target_blob_gas_per_block.into())
+    let target_blob_gas_per_block = u64::from(schedule.target * GAS_PER_BLOB);
+    if excess_blob_gas < target_blob_gas_per_block {
+        return 0;
+    }
+
+    // EIP-7918: while the blob base fee is below the execution cost of a blob, the excess only
+    // grows with the blob gas used, so the blob fee can't get stuck at the minimum
+    if fork >= Fork::Osaka {
+        let execution_cost =
+            u128::from(BLOB_BASE_COST) * u128::from(parent.base_fee_per_gas.unwrap_or_default());
+        let blob_cost = u128::from(GAS_PER_BLOB)
+            * u128::from(calculate_base_fee_per_blob_gas(
+                parent_excess_blob_gas,
+                schedule.base_fee_update_fraction,
+            ));
+        if execution_cost > blob_cost {
+            let max = u64::from(schedule.max);
+            let target = u64::from(schedule.target);
+            return parent_excess_blob_gas + parent_blob_gas_used * (max - target) / max;
+        }
+    }
+
+    excess_blob_gas - target_blob_gas_per_block
 }
 
 #[cfg(test)]
@@ -913,4 +934,37 @@ mod test {
         );
         assert_eq!(calc_base_fee, expected_base_fee)
     }
+
+    #[test]
+    fn excess_blob_gas_has_a_reserve_price_from_osaka() {
+        let schedule = ForkBlobSchedule {
+            target: 6,
+            max: 9,
+            base_fee_update_fraction: 5_007_716,
+        };
+        let target_blob_gas = u64::from(schedule.target * GAS_PER_BLOB);
+        // At this excess the blob base fee is 1, so a blob costs GAS_PER_BLOB and its execution
+        // cost reaches it with a base fee of GAS_PER_BLOB / BLOB_BASE_COST = 16
+        let parent = |base_fee_per_gas| BlockHeader {
+            base_fee_per_gas: Some(base_fee_per_gas),
+            blob_gas_used: Some(target_blob_gas),
+            excess_blob_gas: Some(target_blob_gas),
+            ..Default::default()
+        };
+
+        // While blobs cost at least their execution cost the excess doesn't change at the target
+        assert_eq!(
+            calc_excess_blob_gas(&parent(16), schedule, Fork::Osaka),
+            target_blob_gas
+        );
+        // Otherwise it grows with the blob gas used
+        assert_eq!(
+            calc_excess_blob_gas(&parent(17), schedule, Fork::Osaka),
+            target_blob_gas + target_blob_gas * 3 / 9
+        );
+        assert_eq!(
+            calc_excess_blob_gas(&parent(17), schedule, Fork::Prague),
+            target_blob_gas
+        );
+    }
 }
@@ -21,3 +21,12 @@ pub const BYTES_PER_BLOB_F64: f64 = BYTES_PER_BLOB as f64;
 /// The maximum number of bytes that can be "safely" stored in a blob. This is, prepend
 /// a zero byte for every 32 bytes of data to ensure they not exceed the field modulus.
 pub const SAFE_BYTES_PER_BLOB: usize = BYTES_PER_BLOB * 31 / 32;
+
+// Blob cells related
+// Defined in [EIP-7594](https://eips.ethereum.org/EIPS/eip-7594)
+pub const CELLS_PER_EXT_BLOB: usize = 128;
+pub const BYTES_PER_CELL: usize = 2048;
+/// Blob transaction network wrapper carrying one KZG proof per blob.
+pub const BLOB_WRAPPER_VERSION_4844: u8 = 0;
+/// Blob transaction network wrapper carrying `CELLS_PER_EXT_BLOB` cell proofs per blob.
+pub const BLOB_WRAPPER_VERSION_7594: u8 = 1;
@@ -0,0 +1,98 @@
+use crate::{Address, U256, constants::GAS_PER_BLOB};
+
+use super::SAFE_BYTES_PER_BLOB;
+
+/// Version byte prefixed to the [`L1FeeConfig`] encoded in an L2 block header's `extra_data`.
+pub const L1_FEE_CONFIG_VERSION: u8 = 0x01;
+/// version (u8) + vault (Address) + l1_fee_per_blob_gas (u64)
+pub const L1_FEE_CONFIG_LEN: usize = 1 + 20 + 8;
+
+/// Parameters used to charge L2 transactions for the blob space their state diffs take on L1.
+///
+/// The sequencer writes them into the `extra_data` of every L2 block it produces, so every node
+/// (and the prover) charges the same fee when executing the block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct L1FeeConfig {
+    /// Account credited with the L1 data fee.
+    pub l1_fee_vault: Address,
+    /// L1 blob base fee observed by the sequencer when the block was built.
+    pub l1_fee_per_blob_gas: u64,
+}
+
+impl L1FeeConfig {
+    pub fn encode_extra_data(&self) -> Vec<u8> {
+        let mut encoded = Vec::with_capacity(L1_FEE_CONFIG_LEN);
+        encoded.push(L1_FEE_CONFIG_VERSION);
+        encoded.extend_from_slice(self.l1_fee_vault.as_bytes());
+        encoded.extend_from_slice(&self.l1_fee_per_blob_gas.to_be_bytes());
+        encoded
+    }
+
+    /// Returns `None` if the `extra_data` doesn't hold an encoded [`L1FeeConfig`].
+    pub fn from_extra_data(extra_data: &[u8]) -> Option<Self> {
+        if extra_data.len() != L1_FEE_CONFIG_LEN {
+            return None;
+        }
+        let (version, rest) = extra_data.split_first()?;
+        if *version != L1_FEE_CONFIG_VERSION {
+            return None;
+        }
+        let (vault, fee) = rest.split_at_checked(20)?;
+        Some(Self {
+            l1_fee_vault: Address::from_slice(vault),
+            l1_fee_per_blob_gas: u64::from_be_bytes(fee.try_into().ok()?),
+        })
+    }
+
+    /// Fee in wei for posting `state_diff_size` bytes to L1.
+    /// Blobs only fit [`SAFE_BYTES_PER_BLOB`] bytes of data, so each byte costs slightly more than
+    /// one unit of blob gas.
+    pub fn l1_fee(&self, state_diff_size: u64) -> U256 {
+        let numerator = U256::from(state_diff_size)
+            .saturating_mul(U256::from(GAS_PER_BLOB))
+            .saturating_mul(U256::from(self.l1_fee_per_blob_gas));
+        let (fee, remainder) = numerator.div_mod(U256::from(SAFE_BYTES_PER_BLOB));
+        if remainder.is_zero() {
+            fee
+        } else {
+            fee.saturating_add(U256::one())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extra_data_roundtrip() {
+        let config = L1FeeConfig {
+            l1_fee_vault: Address::repeat_byte(0xfe),
+            l1_fee_per_blob_gas: 12345,
+        };
+        let encoded = config.encode_extra_data();
+        assert_eq!(encoded.len(), L1_FEE_CONFIG_LEN);
+        assert_eq!(L1FeeConfig::from_extra_data(&encoded), Some(config));
+    }
+
+    #[test]
+    fn unrelated_extra_data_is_ignored() {
+        assert_eq!(L1FeeConfig::from_extra_data(&[]), None);
+        assert_eq!(L1FeeConfig::from_extra_data(b"ethrex"), None);
+        let mut encoded = L1FeeConfig::default().encode_extra_data();
+        encoded[0] = 0x02;
+        assert_eq!(L1FeeConfig::from_extra_data(&encoded), None);
+    }
+
+    #[test]
+    fn l1_fee_rounds_up() {
+        let config = L1FeeConfig {
+            l1_fee_vault: Address::zero(),
+            l1_fee_per_blob_gas: 1,
+        };
+        assert_eq!(config.l1_fee(0), U256::zero());
+        // 31 bytes of data take 32 bytes of blob space
+        assert_eq!(config.l1_fee(31), U256::from(32));
+        assert_eq!(config.l1_fee(1), U256::from(2));
+    }
+}
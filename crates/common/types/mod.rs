@@ -7,6 +7,7 @@ pub mod block_execution_witness;
 mod constants;
 mod fork_id;
 mod genesis;
+mod l1_fee;
 pub mod payload;
 mod receipt;
 pub mod requests;
@@ -20,6 +21,7 @@ pub use block::*;
 pub use constants::*;
 pub use fork_id::*;
 pub use genesis::*;
+pub use l1_fee::*;
 pub use receipt::*;
 pub use transaction::*;
 pub use tx_fields::*;
@@ -18,7 +18,7 @@ use ethrex_rlp::{
     structs::{Decoder, Encoder},
 };
 
-use crate::types::{AccessList, AuthorizationList, BlobsBundle};
+use crate::types::{AccessList, AuthorizationList, BLOB_WRAPPER_VERSION_4844, BlobsBundle};
 use once_cell::sync::OnceCell;
 
 // The `#[serde(untagged)]` attribute allows the `Transaction` enum to be serialized without
@@ -125,9 +125,12 @@ pub struct WrappedEIP4844Transaction {
 
 impl RLPEncode for WrappedEIP4844Transaction {
     fn encode(&self, buf: &mut dyn bytes::BufMut) {
-        let encoder = Encoder::new(buf);
+        let mut encoder = Encoder::new(buf).encode_field(&self.tx);
+        // EIP-7594 wrappers include their version, legacy wrappers don't
+        if self.blobs_bundle.version != BLOB_WRAPPER_VERSION_4844 {
+            encoder = encoder.encode_field(&self.blobs_bundle.version);
+        }
         encoder
-            .encode_field(&self.tx)
             .encode_field(&self.blobs_bundle.blobs)
             .encode_field(&self.blobs_bundle.commitments)
             .encode_field(&self.blobs_bundle.proofs)
@@ -139,6 +142,8 @@ impl RLPDecode for WrappedEIP4844Transaction {
     fn decode_unfinished(rlp: &[u8]) -> Result<(WrappedEIP4844Transaction, &[u8]), RLPDecodeError> {
         let decoder = Decoder::new(rlp)?;
         let (tx, decoder) = decoder.decode_field("tx")?;
+        // The wrapper version is a single byte while blobs are a list, so this only succeeds for EIP-7594 wrappers
+        let (version, decoder) = decoder.decode_optional_field::<u8>();
         let (blobs, decoder) = decoder.decode_field("blobs")?;
         let (commitments, decoder) = decoder.decode_field("commitments")?;
         let (proofs, decoder) = decoder.decode_field("proofs")?;
@@ -149,6 +154,7 @@ impl RLPDecode for WrappedEIP4844Transaction {
                 blobs,
                 commitments,
                 proofs,
+                version: version.unwrap_or(BLOB_WRAPPER_VERSION_4844),
             },
         };
         Ok((wrapped, decoder.finish()?))
@@ -3124,4 +3130,28 @@ mod tests {
         assert_eq!(generic_tx.access_list[0].address, access_list[0].0);
         assert_eq!(generic_tx.access_list[0].storage_keys, access_list[0].1);
     }
+
+    #[test]
+    fn wrapped_eip4844_transaction_rlp_roundtrip_keeps_wrapper_version() {
+        for version in [
+            BLOB_WRAPPER_VERSION_4844,
+            crate::types::BLOB_WRAPPER_VERSION_7594,
+        ] {
+            let wrapped = WrappedEIP4844Transaction {
+                tx: EIP4844Transaction {
+                    blob_versioned_hashes: vec![H256::random()],
+                    ..Default::default()
+                },
+                blobs_bundle: BlobsBundle {
+                    blobs: vec![[1; crate::types::BYTES_PER_BLOB]],
+                    commitments: vec![[2; 48]],
+                    proofs: vec![[3; 48]; 2],
+                    version,
+                },
+            };
+            let encoded = wrapped.encode_to_vec();
+            let decoded = WrappedEIP4844Transaction::decode(&encoded).unwrap();
+            assert_eq!(decoded, wrapped);
+        }
+    }
 }
@@ -4,13 +4,14 @@ use ethrex_blockchain::{Blockchain, fork_choice::apply_fork_choice, vm::StoreVmD
 use ethrex_common::{
     Address, H160, H256, U256,
     types::{
-        AccountUpdate, Block, BlockNumber, PrivilegedL2Transaction, Transaction, batch::Batch,
+        AccountUpdate, BlobsBundle, Block, BlockNumber, Fork, L1FeeConfig, PrivilegedL2Transaction,
+        Transaction, batch::Batch,
     },
 };
 use ethrex_l2_common::{
     l1_messages::{L1Message, get_block_l1_messages, get_l1_message_hash},
     privileged_transactions::compute_privileged_transactions_hash,
-    state_diff::prepare_state_diff,
+    state_diff::{COMPRESSED_STATE_DIFF_VERSION, RAW_STATE_DIFF_VERSION, prepare_state_diff},
 };
 use ethrex_rlp::decode::RLPDecode;
 use ethrex_rpc::{EthClient, types::receipt::RpcLog};
@@ -66,6 +67,8 @@ pub enum BlockFetcherError {
     GenServerError(GenServerError),
     #[error("Tried to store an empty batch")]
     EmptyBatchError,
+    #[error("Block {0} doesn't charge the L1 data fee to the configured vault")]
+    WrongL1FeeVault(u64),
 }
 
 #[derive(Clone)]
@@ -88,6 +91,8 @@ pub struct BlockFetcher {
     fetch_interval_ms: u64,
     last_l1_block_fetched: U256,
     fetch_block_step: U256,
+    l1_fee_vault_address: Option<Address>,
+    l1_fork: Fork,
 }
 
 impl BlockFetcher {
@@ -113,6 +118,8 @@ impl BlockFetcher {
             fetch_interval_ms: cfg.based.block_fetcher.fetch_interval_ms,
             last_l1_block_fetched,
             fetch_block_step: cfg.based.block_fetcher.fetch_block_step.into(),
+            l1_fee_vault_address: cfg.block_producer.l1_fee_vault_address,
+            l1_fork: cfg.l1_committer.l1_fork,
         })
     }
 
@@ -244,28 +251,39 @@ impl BlockFetcher {
         missing_batches_logs.sort_by_key(|(_log, batch_number)| *batch_number);
 
         for (batch_committed_log, batch_number) in missing_batches_logs {
-            let batch_commit_tx_calldata = self
+            let batch_commit_tx = self
                 .eth_client
                 .get_transaction_by_hash(batch_committed_log.transaction_hash)
                 .await?
                 .ok_or(BlockFetcherError::InternalError(format!(
                     "Failed to get the receipt for transaction {:x}",
                     batch_committed_log.transaction_hash
-                )))?
-                .data;
+                )))?;
 
-            let batch = decode_batch_from_calldata(&batch_commit_tx_calldata)?;
+            let batch = decode_batch_from_calldata(&batch_commit_tx.data)?;
 
             self.store_batch(&batch).await?;
 
-            self.seal_batch(&batch, batch_number, batch_committed_log.transaction_hash)
-                .await?;
+            self.seal_batch(
+                &batch,
+                batch_number,
+                batch_committed_log.transaction_hash,
+                &batch_commit_tx.blob_versioned_hashes.unwrap_or_default(),
+            )
+            .await?;
         }
         Ok(())
     }
 
     async fn store_batch(&mut self, batch: &[Block]) -> Result<(), BlockFetcherError> {
         for block in batch.iter() {
+            // Every sequencer must credit the L1 data fee to the same vault
+            let l1_fee_vault = L1FeeConfig::from_extra_data(&block.header.extra_data)
+                .map(|l1_fee_config| l1_fee_config.l1_fee_vault);
+            if l1_fee_vault != self.l1_fee_vault_address {
+                return Err(BlockFetcherError::WrongL1FeeVault(block.header.number));
+            }
+
             self.blockchain.add_block(block).await?;
 
             let block_hash = block.hash();
@@ -295,8 +313,11 @@ impl BlockFetcher {
         batch: &[Block],
         batch_number: U256,
         commit_tx: H256,
+        committed_versioned_hashes: &[H256],
     ) -> Result<(), BlockFetcherError> {
-        let batch = self.get_batch(batch, batch_number, commit_tx).await?;
+        let batch = self
+            .get_batch(batch, batch_number, commit_tx, committed_versioned_hashes)
+            .await?;
 
         self.rollup_store.seal_batch(batch).await?;
 
@@ -310,6 +331,7 @@ impl BlockFetcher {
         batch: &[Block],
         batch_number: U256,
         commit_tx: H256,
+        committed_versioned_hashes: &[H256],
     ) -> Result<Batch, BlockFetcherError> {
         let privileged_transactions: Vec<PrivilegedL2Transaction> = batch
             .iter()
@@ -376,7 +398,7 @@ impl BlockFetcher {
 
         let parent_db = StoreVmDatabase::new(self.store.clone(), parent_block_hash);
 
-        let state_diff = prepare_state_diff(
+        let mut state_diff = prepare_state_diff(
             last_block.header.clone(),
             &parent_db,
             &messages,
@@ -385,8 +407,24 @@ impl BlockFetcher {
         )
         .map_err(|_| BlockFetcherError::BlobBundleError)?;
 
-        let (blobs_bundle, _) =
-            generate_blobs_bundle(&state_diff).map_err(|_| BlockFetcherError::BlobBundleError)?;
+        // The state diff may span several blobs, same as when the batch was committed, and is
+        // encoded with the version it was committed with: the one whose blobs match the
+        // versioned hashes of the commit transaction. A validium commits no blobs at all.
+        let mut blobs_bundle = committed_versioned_hashes
+            .is_empty()
+            .then(BlobsBundle::default);
+        for version in [COMPRESSED_STATE_DIFF_VERSION, RAW_STATE_DIFF_VERSION] {
+            if blobs_bundle.is_some() {
+                break;
+            }
+            state_diff.version = version;
+            let (bundle, _) = generate_blobs_bundle(&state_diff, self.l1_fork)
+                .map_err(|_| BlockFetcherError::BlobBundleError)?;
+            if bundle.generate_versioned_hashes() == committed_versioned_hashes {
+                blobs_bundle = Some(bundle);
+            }
+        }
+        let blobs_bundle = blobs_bundle.ok_or(BlockFetcherError::BlobBundleError)?;
 
         Ok(Batch {
             number: batch_number.as_u64(),
@@ -1,6 +1,8 @@
 pub mod calldata;
 pub mod l1_messages;
 pub mod merkle_tree;
+pub mod preconfirmation;
 pub mod privileged_transactions;
 pub mod prover;
 pub mod state_diff;
+pub mod user_operation;
@@ -0,0 +1,142 @@
+use bytes::Bytes;
+use ethereum_types::{Address, H256, Signature};
+use ethrex_common::{
+    serde_utils,
+    types::{Block, BlockHeader, compute_transactions_root, recover_address_from_message},
+};
+use serde::{Deserialize, Serialize};
+
+/// Prefix of the message signed by the sequencer, so a preconfirmation signature can't be
+/// mistaken for any other signature of the committer key.
+pub const PRECONFIRMATION_DOMAIN: &[u8] = b"ethrex preconfirmation";
+
+/// Promise from the sequencer that a transaction will be included at `index` in the L2 block
+/// `block_number`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Preconfirmation {
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub chain_id: u64,
+    pub tx_hash: H256,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub block_number: u64,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub index: u64,
+}
+
+/// A [`Preconfirmation`] signed with the committer key, as returned by
+/// `ethrex_sendRawTransactionConditional`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedPreconfirmation {
+    #[serde(flatten)]
+    pub preconfirmation: Preconfirmation,
+    pub signature: Signature,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum PreconfirmationError {
+    #[error("Preconfirmation signature is invalid")]
+    InvalidSignature,
+    #[error("Preconfirmation was not signed by the sequencer")]
+    WrongSigner,
+    #[error("Block {0} is not the preconfirmed block")]
+    WrongBlock(u64),
+    #[error("Block transactions don't match the header")]
+    InvalidBlockBody,
+    #[error("Blocks are not part of the committed batch")]
+    NotCommitted,
+    #[error("Preconfirmation was honoured")]
+    Honoured,
+}
+
+impl Preconfirmation {
+    /// Message signed by the sequencer:
+    /// domain || chain_id (32 bytes) || tx_hash || block_number (32 bytes) || index (32 bytes)
+    pub fn encode(&self) -> Bytes {
+        let mut encoded = PRECONFIRMATION_DOMAIN.to_vec();
+        for value in [
+            H256::from_low_u64_be(self.chain_id),
+            self.tx_hash,
+            H256::from_low_u64_be(self.block_number),
+            H256::from_low_u64_be(self.index),
+        ] {
+            encoded.extend_from_slice(value.as_bytes());
+        }
+        encoded.into()
+    }
+
+    /// Whether the block places the preconfirmed transaction where it was promised.
+    pub fn is_honoured_by(&self, block: &Block) -> bool {
+        block.header.number == self.block_number
+            && usize::try_from(self.index)
+                .ok()
+                .and_then(|index| block.body.transactions.get(index))
+                .is_some_and(|tx| tx.hash() == self.tx_hash)
+    }
+}
+
+impl SignedPreconfirmation {
+    pub fn signer(&self) -> Result<Address, PreconfirmationError> {
+        let mut signature = self.signature;
+        // Remote signers may return the recovery id as 27/28
+        if let Some(v) = signature.0.last_mut() {
+            if *v >= 27 {
+                *v -= 27;
+            }
+        }
+        recover_address_from_message(signature, &self.preconfirmation.encode())
+            .map_err(|_| PreconfirmationError::InvalidSignature)
+    }
+}
+
+/// Evidence that the sequencer broke a preconfirmation: the preconfirmed block, with its
+/// descendants up to the last block of the batch committed on L1 that includes it.
+#[derive(Debug, Clone)]
+pub struct PreconfirmationViolation {
+    pub preconfirmation: SignedPreconfirmation,
+    pub block: Block,
+    pub descendants: Vec<BlockHeader>,
+}
+
+impl PreconfirmationViolation {
+    /// Checks the evidence against the sequencer address and the `lastBlockHash` the
+    /// `OnChainProposer` stored for the committed batch.
+    pub fn verify(
+        &self,
+        sequencer: Address,
+        committed_last_block_hash: H256,
+    ) -> Result<(), PreconfirmationError> {
+        if self.preconfirmation.signer()? != sequencer {
+            return Err(PreconfirmationError::WrongSigner);
+        }
+
+        let preconfirmation = &self.preconfirmation.preconfirmation;
+        if self.block.header.number != preconfirmation.block_number {
+            return Err(PreconfirmationError::WrongBlock(self.block.header.number));
+        }
+        if compute_transactions_root(&self.block.body.transactions)
+            != self.block.header.transactions_root
+        {
+            return Err(PreconfirmationError::InvalidBlockBody);
+        }
+
+        let mut last_header = &self.block.header;
+        for header in &self.descendants {
+            if header.parent_hash != last_header.hash()
+                || Some(header.number) != last_header.number.checked_add(1)
+            {
+                return Err(PreconfirmationError::NotCommitted);
+            }
+            last_header = header;
+        }
+        if last_header.hash() != committed_last_block_hash {
+            return Err(PreconfirmationError::NotCommitted);
+        }
+
+        if preconfirmation.is_honoured_by(&self.block) {
+            return Err(PreconfirmationError::Honoured);
+        }
+        Ok(())
+    }
+}
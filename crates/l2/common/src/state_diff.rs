@@ -1,10 +1,13 @@
-use std::collections::{BTreeMap, HashMap};
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{Cursor, Read},
+};
 
 use bytes::Bytes;
 use ethereum_types::{Address, H256, U256};
 use ethrex_common::types::{
-    AccountInfo, AccountState, AccountUpdate, BlockHeader, PrivilegedL2Transaction, TxKind,
-    code_hash,
+    AccountInfo, AccountState, AccountUpdate, BlockHeader, PrivilegedL2Transaction,
+    SAFE_BYTES_PER_BLOB, TxKind, code_hash,
 };
 use ethrex_rlp::decode::RLPDecode;
 use ethrex_storage::{error::StoreError, hash_address};
@@ -23,10 +26,39 @@ pub const PRIVILEGED_TX_LOG_LEN: u64 = 52;
 /// The serialized lenght of a default block header
 pub const BLOCK_HEADER_LEN: u64 = 136;
 
+/// Version of the plain encoding, where the state diff is written as is.
+pub const RAW_STATE_DIFF_VERSION: u8 = 1;
+
+/// Version of the compressed encoding: the version byte, the length of the compressed
+/// payload as a big endian u32, and the plain encoding (without its version byte) compressed
+/// with brotli.
+pub const COMPRESSED_STATE_DIFF_VERSION: u8 = 2;
+
+/// Brotli quality used for compressed state diffs. The guest program compresses the state diff
+/// it computes to check it against the blobs, so this is a tradeoff between the size of the
+/// blobs and the proving cost. It must never change for a given version, as the output of the
+/// compression depends on it.
+const BROTLI_QUALITY: i32 = 9;
+
+/// Brotli window size (log2), enough to cover the state diff of a full batch.
+const BROTLI_LGWIN: i32 = 22;
+
+/// Most blobs a single transaction can carry in any fork (9 since Prague, 6 since Osaka).
+const MAX_BLOBS_PER_BATCH: usize = 9;
+
+/// Maximum size of the plain encoding of a batch's state diff. Batches are sealed by the size
+/// of their plain encoding, so a compressed state diff never decompresses to more than this.
+pub const MAX_STATE_DIFF_SIZE: usize = MAX_BLOBS_PER_BATCH * SAFE_BYTES_PER_BLOB;
+
 // State diff size for a simple transfer.
 // Two `AccountUpdates` with new_balance, one of which also has nonce_diff.
 pub const SIMPLE_TX_STATE_DIFF_SIZE: u64 = 108;
 
+// State diff size estimated for an ERC-4337 user operation in a bundle.
+// The sender's `AccountUpdate` with new_balance and one storage slot, plus the nonce and deposit
+// slots of the entry point's storage, whose header is shared by the whole bundle.
+pub const USER_OPERATION_STATE_DIFF_SIZE: u64 = 21 + 32 + 2 + 64 + 2 * 64;
+
 #[derive(Debug, thiserror::Error)]
 pub enum StateDiffError {
     #[error("StateDiff failed to deserialize: {0}")]
@@ -37,6 +69,10 @@ pub enum StateDiffError {
     InvalidAccountStateDiffType(u8),
     #[error("StateDiff unsupported version: {0}")]
     UnsupportedVersion(u8),
+    #[error("StateDiff failed to compress: {0}")]
+    FailedToCompress(String),
+    #[error("StateDiff failed to decompress: {0}")]
+    FailedToDecompress(String),
     #[error("Both bytecode and bytecode hash are set")]
     BytecodeAndBytecodeHashSet,
     #[error("Empty account diff")]
@@ -119,7 +155,7 @@ impl AccountStateDiffType {
 impl Default for StateDiff {
     fn default() -> Self {
         StateDiff {
-            version: 1,
+            version: COMPRESSED_STATE_DIFF_VERSION,
             last_header: BlockHeader::default(),
             modified_accounts: BTreeMap::new(),
             l1_messages: Vec::new(),
@@ -143,13 +179,40 @@ pub fn encode_block_header(block_header: &BlockHeader) -> Vec<u8> {
 }
 
 impl StateDiff {
+    /// Encodes the state diff with the encoding selected by its version.
     pub fn encode(&self) -> Result<Bytes, StateDiffError> {
-        if self.version != 1 {
-            return Err(StateDiffError::UnsupportedVersion(self.version));
+        let mut encoded: Vec<u8> = vec![self.version];
+        match self.version {
+            RAW_STATE_DIFF_VERSION => encoded.extend(self.encode_payload()?),
+            COMPRESSED_STATE_DIFF_VERSION => {
+                let compressed = compress(&self.encode_payload()?)?;
+                let compressed_len: u32 = compressed.len().try_into()?;
+                encoded.extend(compressed_len.to_be_bytes());
+                encoded.extend(compressed);
+            }
+            version => return Err(StateDiffError::UnsupportedVersion(version)),
         }
 
+        Ok(Bytes::from(encoded))
+    }
+
+    /// Upper bound of the size of `encode`, computed without compressing the state diff so it
+    /// can be checked cheaply every time a block is added to a batch. It is never smaller than
+    /// the plain encoding, so a state diff within a bound also decompresses within it.
+    pub fn encoded_size_bound(&self) -> Result<usize, StateDiffError> {
+        let payload_size = self.encode_payload()?.len();
+        match self.version {
+            RAW_STATE_DIFF_VERSION => Ok(1 + payload_size),
+            COMPRESSED_STATE_DIFF_VERSION => {
+                Ok(1 + 4 + brotli::enc::BrotliEncoderMaxCompressedSize(payload_size))
+            }
+            version => Err(StateDiffError::UnsupportedVersion(version)),
+        }
+    }
+
+    /// Plain encoding of the state diff, without the version byte.
+    fn encode_payload(&self) -> Result<Vec<u8>, StateDiffError> {
         let mut encoded: Vec<u8> = Vec::new();
-        encoded.push(self.version);
 
         let header_encoded = encode_block_header(&self.last_header);
         encoded.extend(header_encoded);
@@ -180,16 +243,37 @@ impl StateDiff {
             encoded.extend(privileged_tx_encoded);
         }
 
-        Ok(Bytes::from(encoded))
+        Ok(encoded)
     }
 
+    /// Decodes a state diff encoded with any of the supported versions. Trailing bytes, such
+    /// as the padding of the blobs, are ignored.
     pub fn decode(bytes: &[u8]) -> Result<Self, StateDiffError> {
         let mut decoder = Decoder::new(bytes);
 
         let version = decoder.get_u8()?;
-        if version != 0x01 {
-            return Err(StateDiffError::UnsupportedVersion(version));
+        match version {
+            RAW_STATE_DIFF_VERSION => {
+                let payload = bytes.get(decoder.consumed()..).ok_or(
+                    StateDiffError::FailedToDeserializeStateDiff("Not enough bytes".to_string()),
+                )?;
+                Self::decode_payload(version, payload)
+            }
+            COMPRESSED_STATE_DIFF_VERSION => {
+                let compressed_len = decoder.get_u32()?;
+                let compressed = decoder.get_bytes(compressed_len.try_into().map_err(|_| {
+                    StateDiffError::FailedToDeserializeStateDiff(
+                        "Compressed length too big".to_string(),
+                    )
+                })?)?;
+                Self::decode_payload(version, &decompress(&compressed)?)
+            }
+            version => Err(StateDiffError::UnsupportedVersion(version)),
         }
+    }
+
+    fn decode_payload(version: u8, bytes: &[u8]) -> Result<Self, StateDiffError> {
+        let mut decoder = Decoder::new(bytes);
 
         // Last header fields
         let last_header = BlockHeader {
@@ -506,6 +590,23 @@ impl Decoder {
         Ok(res)
     }
 
+    fn get_u32(&mut self) -> Result<u32, StateDiffError> {
+        let res = u32::from_be_bytes(
+            self.bytes
+                .get(self.offset..self.offset + 4)
+                .ok_or(StateDiffError::FailedToDeserializeStateDiff(
+                    "Not enough bytes".to_string(),
+                ))?
+                .try_into()
+                .map_err(|_| {
+                    StateDiffError::FailedToDeserializeStateDiff("Cannot parse u32".to_string())
+                })?,
+        );
+        self.offset += 4;
+
+        Ok(res)
+    }
+
     fn get_u64(&mut self) -> Result<u64, StateDiffError> {
         let res = u64::from_be_bytes(
             self.bytes
@@ -533,6 +634,35 @@ impl Decoder {
     }
 }
 
+fn compress(bytes: &[u8]) -> Result<Vec<u8>, StateDiffError> {
+    let params = brotli::enc::BrotliEncoderParams {
+        quality: BROTLI_QUALITY,
+        lgwin: BROTLI_LGWIN,
+        ..Default::default()
+    };
+    let mut compressed = Vec::new();
+    brotli::BrotliCompress(&mut Cursor::new(bytes), &mut compressed, &params)
+        .map_err(|e| StateDiffError::FailedToCompress(e.to_string()))?;
+    Ok(compressed)
+}
+
+/// Decompresses a state diff read from the blobs, failing if it decompresses to more than
+/// `MAX_STATE_DIFF_SIZE` so that crafted blob data can't exhaust the memory.
+fn decompress(bytes: &[u8]) -> Result<Vec<u8>, StateDiffError> {
+    let limit: u64 = (MAX_STATE_DIFF_SIZE + 1).try_into()?;
+    let mut decompressed = Vec::new();
+    brotli::Decompressor::new(Cursor::new(bytes), 4096)
+        .take(limit)
+        .read_to_end(&mut decompressed)
+        .map_err(|e| StateDiffError::FailedToDecompress(e.to_string()))?;
+    if decompressed.len() > MAX_STATE_DIFF_SIZE {
+        return Err(StateDiffError::FailedToDecompress(format!(
+            "state diff is bigger than {MAX_STATE_DIFF_SIZE} bytes"
+        )));
+    }
+    Ok(decompressed)
+}
+
 /// Calculates nonce_diff between current and previous block.
 pub fn get_nonce_diff(
     account_update: &AccountUpdate,
@@ -609,6 +739,20 @@ pub fn prepare_state_diff(
     Ok(state_diff)
 }
 
+/// Hash committed on L1 for the blobs holding a batch's state diff: the keccak hash of their
+/// concatenated versioned hashes, or zero if the batch has no blobs (validium).
+pub fn compute_blobs_hash(versioned_hashes: &[H256]) -> H256 {
+    if versioned_hashes.is_empty() {
+        return H256::zero();
+    }
+    keccak_hash::keccak(
+        versioned_hashes
+            .iter()
+            .flat_map(|hash| hash.to_fixed_bytes())
+            .collect::<Vec<u8>>(),
+    )
+}
+
 #[cfg(test)]
 #[allow(clippy::as_conversions)]
 mod tests {
@@ -658,4 +802,90 @@ mod tests {
             account_diff_1_size + account_diff_2_size
         );
     }
+
+    fn sample_state_diff(version: u8) -> StateDiff {
+        let modified_accounts = (0..64u64)
+            .map(|i| {
+                let diff = AccountStateDiff {
+                    new_balance: Some(U256::from(1_000_000_000 + i)),
+                    nonce_diff: 1,
+                    storage: BTreeMap::from([(H256::from_low_u64_be(i), U256::from(i))]),
+                    ..Default::default()
+                };
+                (Address::from_low_u64_be(i), diff)
+            })
+            .collect();
+        StateDiff {
+            version,
+            last_header: BlockHeader {
+                number: 10,
+                gas_limit: 30_000_000,
+                base_fee_per_gas: Some(7),
+                ..Default::default()
+            },
+            modified_accounts,
+            l1_messages: vec![L1Message::default()],
+            privileged_transactions: vec![PrivilegedTransactionLog::default()],
+        }
+    }
+
+    #[test]
+    fn test_state_diff_roundtrip() {
+        for version in [RAW_STATE_DIFF_VERSION, COMPRESSED_STATE_DIFF_VERSION] {
+            let state_diff = sample_state_diff(version);
+            let mut encoded = state_diff.encode().unwrap().to_vec();
+            assert_eq!(encoded.first(), Some(&version));
+            // Blobs are padded with zeros
+            encoded.extend([0; 64]);
+
+            let decoded = StateDiff::decode(&encoded).unwrap();
+            assert_eq!(decoded.version, version);
+            assert_eq!(decoded.encode().unwrap(), state_diff.encode().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_compressed_state_diff_is_smaller() {
+        let raw = sample_state_diff(RAW_STATE_DIFF_VERSION).encode().unwrap();
+        let compressed = sample_state_diff(COMPRESSED_STATE_DIFF_VERSION)
+            .encode()
+            .unwrap();
+        assert!(compressed.len() < raw.len());
+    }
+
+    #[test]
+    fn test_encoded_size_bound() {
+        for version in [RAW_STATE_DIFF_VERSION, COMPRESSED_STATE_DIFF_VERSION] {
+            let state_diff = sample_state_diff(version);
+            let bound = state_diff.encoded_size_bound().unwrap();
+            assert!(state_diff.encode().unwrap().len() <= bound);
+            assert!(state_diff.encode_payload().unwrap().len() < bound);
+        }
+    }
+
+    #[test]
+    fn test_decompression_is_capped() {
+        let oversized = compress(&vec![0; MAX_STATE_DIFF_SIZE + 1]).unwrap();
+        let compressed_len: u32 = oversized.len().try_into().unwrap();
+        let mut encoded = vec![COMPRESSED_STATE_DIFF_VERSION];
+        encoded.extend(compressed_len.to_be_bytes());
+        encoded.extend(oversized);
+
+        assert!(matches!(
+            StateDiff::decode(&encoded),
+            Err(StateDiffError::FailedToDecompress(_))
+        ));
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        assert!(matches!(
+            sample_state_diff(3).encode(),
+            Err(StateDiffError::UnsupportedVersion(3))
+        ));
+        assert!(matches!(
+            StateDiff::decode(&[3]),
+            Err(StateDiffError::UnsupportedVersion(3))
+        ));
+    }
 }
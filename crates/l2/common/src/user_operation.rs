@@ -0,0 +1,219 @@
+use std::sync::LazyLock;
+
+use bytes::Bytes;
+use ethereum_types::{Address, H256, U256};
+use ethrex_common::{serde_utils, types::Log};
+use keccak_hash::keccak;
+use serde::{Deserialize, Serialize};
+
+use crate::calldata::{AbiEncodeError, Value, encode_calldata, encode_tuple};
+
+/// Selector of the entry point's `handleOps(PackedUserOperation[],address)`.
+pub const HANDLE_OPS_SELECTOR: [u8; 4] = [0x76, 0x5e, 0x82, 0x7f];
+
+const HANDLE_OPS_SIGNATURE: &str =
+    "handleOps((address,uint256,bytes,bytes,bytes32,uint256,bytes32,bytes,bytes)[],address)";
+
+pub static USER_OPERATION_EVENT_SELECTOR: LazyLock<H256> = LazyLock::new(|| {
+    keccak("UserOperationEvent(bytes32,address,address,uint256,bool,uint256,uint256)".as_bytes())
+});
+
+/// ERC-4337 (https://eips.ethereum.org/EIPS/eip-4337) UserOperation for the v0.7 entry point, in
+/// the unpacked form used by the bundler RPC.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperation {
+    pub sender: Address,
+    pub nonce: U256,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub factory: Option<Address>,
+    #[serde(default, with = "serde_utils::bytes")]
+    pub factory_data: Bytes,
+    #[serde(with = "serde_utils::bytes")]
+    pub call_data: Bytes,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub call_gas_limit: u64,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub verification_gas_limit: u64,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub pre_verification_gas: u64,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub max_fee_per_gas: u64,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub max_priority_fee_per_gas: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paymaster: Option<Address>,
+    #[serde(default, with = "serde_utils::u64::hex_str")]
+    pub paymaster_verification_gas_limit: u64,
+    #[serde(default, with = "serde_utils::u64::hex_str")]
+    pub paymaster_post_op_gas_limit: u64,
+    #[serde(default, with = "serde_utils::bytes")]
+    pub paymaster_data: Bytes,
+    #[serde(with = "serde_utils::bytes")]
+    pub signature: Bytes,
+}
+
+impl UserOperation {
+    /// `factory || factoryData`, empty when the sender is already deployed.
+    pub fn init_code(&self) -> Bytes {
+        let Some(factory) = self.factory else {
+            return Bytes::new();
+        };
+        [factory.as_bytes(), self.factory_data.as_ref()]
+            .concat()
+            .into()
+    }
+
+    /// `paymaster || paymasterVerificationGasLimit (16 bytes) || paymasterPostOpGasLimit (16 bytes) || paymasterData`,
+    /// empty when the sender pays for itself.
+    pub fn paymaster_and_data(&self) -> Bytes {
+        let Some(paymaster) = self.paymaster else {
+            return Bytes::new();
+        };
+        let parts: [&[u8]; 4] = [
+            paymaster.as_bytes(),
+            &u128::from(self.paymaster_verification_gas_limit).to_be_bytes(),
+            &u128::from(self.paymaster_post_op_gas_limit).to_be_bytes(),
+            &self.paymaster_data,
+        ];
+        parts.concat().into()
+    }
+
+    /// `verificationGasLimit (16 bytes) || callGasLimit (16 bytes)`
+    pub fn account_gas_limits(&self) -> H256 {
+        pack_u128_pair(self.verification_gas_limit, self.call_gas_limit)
+    }
+
+    /// `maxPriorityFeePerGas (16 bytes) || maxFeePerGas (16 bytes)`
+    pub fn gas_fees(&self) -> H256 {
+        pack_u128_pair(self.max_priority_fee_per_gas, self.max_fee_per_gas)
+    }
+
+    /// Max gas the entry point may charge the operation for.
+    pub fn gas_limit(&self) -> u64 {
+        self.verification_gas_limit
+            .saturating_add(self.call_gas_limit)
+            .saturating_add(self.paymaster_verification_gas_limit)
+            .saturating_add(self.paymaster_post_op_gas_limit)
+            .saturating_add(self.pre_verification_gas)
+    }
+
+    /// Funds the entry point requires to be deposited before executing the operation.
+    pub fn required_prefund(&self) -> U256 {
+        U256::from(self.gas_limit()) * U256::from(self.max_fee_per_gas)
+    }
+
+    /// Hash identifying the operation, signed by the sender:
+    /// `keccak(abi.encode(keccak(abi.encode(<packed fields, hashing the dynamic ones>)), entryPoint, chainId))`
+    pub fn hash(&self, entry_point: Address, chain_id: u64) -> H256 {
+        let fields = [
+            H256::from(self.sender),
+            H256(self.nonce.to_big_endian()),
+            keccak(self.init_code()),
+            keccak(&self.call_data),
+            self.account_gas_limits(),
+            H256::from_low_u64_be(self.pre_verification_gas),
+            self.gas_fees(),
+            keccak(self.paymaster_and_data()),
+        ];
+        let packed_hash = keccak(fields.map(|field| field.0).concat());
+        keccak(
+            [
+                packed_hash,
+                H256::from(entry_point),
+                H256::from_low_u64_be(chain_id),
+            ]
+            .map(|field| field.0)
+            .concat(),
+        )
+    }
+
+    /// Fields of the operation as a `PackedUserOperation` tuple.
+    pub fn to_packed_values(&self) -> Vec<Value> {
+        vec![
+            Value::Address(self.sender),
+            Value::Uint(self.nonce),
+            Value::Bytes(self.init_code()),
+            Value::Bytes(self.call_data.clone()),
+            Value::FixedBytes(self.account_gas_limits().0.to_vec().into()),
+            Value::Uint(U256::from(self.pre_verification_gas)),
+            Value::FixedBytes(self.gas_fees().0.to_vec().into()),
+            Value::Bytes(self.paymaster_and_data()),
+            Value::Bytes(self.signature.clone()),
+        ]
+    }
+
+    /// ABI encoding of the operation as a `PackedUserOperation` tuple.
+    pub fn abi_encode(&self) -> Result<Vec<u8>, AbiEncodeError> {
+        encode_tuple(&self.to_packed_values())
+    }
+}
+
+fn pack_u128_pair(high: u64, low: u64) -> H256 {
+    H256(((U256::from(high) << 128) | U256::from(low)).to_big_endian())
+}
+
+/// Calldata of `handleOps(ops, beneficiary)`.
+pub fn encode_handle_ops(
+    user_operations: &[UserOperation],
+    beneficiary: Address,
+) -> Result<Bytes, AbiEncodeError> {
+    let user_operations = user_operations
+        .iter()
+        .map(|user_operation| Value::Tuple(user_operation.to_packed_values()))
+        .collect();
+    encode_calldata(
+        HANDLE_OPS_SIGNATURE,
+        &[Value::Array(user_operations), Value::Address(beneficiary)],
+    )
+    .map(Into::into)
+}
+
+/// `UserOperationEvent`, emitted by the entry point for every executed operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserOperationEvent {
+    pub user_operation_hash: H256,
+    pub sender: Address,
+    pub paymaster: Address,
+    pub nonce: U256,
+    pub success: bool,
+    pub actual_gas_cost: U256,
+    pub actual_gas_used: U256,
+}
+
+impl UserOperationEvent {
+    /// Decodes the event from a log, which must be checked to come from the entry point.
+    pub fn from_log(log: &Log) -> Option<Self> {
+        let [selector, user_operation_hash, sender, paymaster] = log.topics.as_slice() else {
+            return None;
+        };
+        if *selector != *USER_OPERATION_EVENT_SELECTOR {
+            return None;
+        }
+        let mut words = log.data.chunks_exact(32).map(U256::from_big_endian);
+        Some(Self {
+            user_operation_hash: *user_operation_hash,
+            sender: Address::from(*sender),
+            paymaster: Address::from(*paymaster),
+            nonce: words.next()?,
+            success: !words.next()?.is_zero(),
+            actual_gas_cost: words.next()?,
+            actual_gas_used: words.next()?,
+        })
+    }
+}
+
+/// Decodes the reason of a `FailedOp(uint256 opIndex, string reason)` revert of the entry point.
+pub fn decode_failed_op(output: &[u8]) -> Option<String> {
+    let data = output.get(4..)?;
+    let word = |index: usize| -> Option<usize> {
+        let start = index.checked_mul(32)?;
+        let word = U256::from_big_endian(data.get(start..start.checked_add(32)?)?);
+        usize::try_from(word).ok()
+    };
+    let offset = word(1)?;
+    let length_start = offset.checked_add(32)?;
+    let length = usize::try_from(U256::from_big_endian(data.get(offset..length_start)?)).ok()?;
+    let reason = data.get(length_start..length_start.checked_add(length)?)?;
+    String::from_utf8(reason.to_vec()).ok()
+}
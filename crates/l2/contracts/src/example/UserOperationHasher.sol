@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: MIT
+pragma solidity =0.8.29;
+
+import "account-abstraction/contracts/core/UserOperationLib.sol";
+
+/// @title UserOperation hasher
+/// @author LambdaClass
+/// @notice Hashes UserOperations like the v0.7 EntryPoint's getUserOpHash, for an arbitrary
+/// entry point address.
+contract UserOperationHasher {
+    using UserOperationLib for PackedUserOperation;
+
+    function getUserOpHash(
+        PackedUserOperation calldata userOp,
+        address entryPoint
+    ) external view returns (bytes32) {
+        return keccak256(abi.encode(userOp.hash(), entryPoint, block.chainid));
+    }
+}
@@ -246,15 +246,23 @@ contract CommonBridge is
     /// @inheritdoc ICommonBridge
     function getPendingTransactionsVersionedHash(
         uint16 number
+    ) public view returns (bytes32) {
+        return getPendingTransactionsVersionedHashAfter(0, number);
+    }
+
+    /// @inheritdoc ICommonBridge
+    function getPendingTransactionsVersionedHashAfter(
+        uint256 processed,
+        uint16 number
     ) public view returns (bytes32) {
         require(number > 0, "CommonBridge: number is zero (get)");
         require(
-            uint256(number) <= pendingTxHashes.length,
+            processed + uint256(number) <= pendingTxHashes.length,
             "CommonBridge: number is greater than the length of pendingTxHashes (get)"
         );
 
         bytes memory hashes;
-        for (uint i = 0; i < number; i++) {
+        for (uint i = processed; i < processed + number; i++) {
             hashes = bytes.concat(hashes, pendingTxHashes[i]);
         }
 
@@ -283,10 +291,20 @@ contract CommonBridge is
 
     /// @inheritdoc ICommonBridge
     function hasExpiredPrivilegedTransactions() public view returns (bool) {
-        if (pendingTxHashes.length == 0) {
+        return hasExpiredPrivilegedTransactionsAfter(0);
+    }
+
+    /// @inheritdoc ICommonBridge
+    function hasExpiredPrivilegedTransactionsAfter(
+        uint256 processed
+    ) public view returns (bool) {
+        // Deadlines are assigned in order, so if the first unprocessed transaction
+        // isn't expired neither are the ones after it.
+        if (processed >= pendingTxHashes.length) {
             return false;
         }
-        return block.timestamp > privilegedTxDeadline[pendingTxHashes[0]];
+        return
+            block.timestamp > privilegedTxDeadline[pendingTxHashes[processed]];
     }
 
     /// @inheritdoc ICommonBridge
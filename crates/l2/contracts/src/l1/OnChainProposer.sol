@@ -29,9 +29,11 @@ contract OnChainProposer is
     /// pendingTxHashes queue of the CommonBridge contract.
     /// @dev withdrawalsLogsMerkleRoot is the Merkle root of the Merkle tree containing
     /// all the withdrawals that were processed in the batch being committed
+    /// @dev blobsHash is the keccak256 hash of the concatenated versioned hashes
+    /// of the blobs holding the batch's state diff, or zero if it has no blobs.
     struct BatchCommitmentInfo {
         bytes32 newStateRoot;
-        bytes32 stateDiffKZGVersionedHash;
+        bytes32 blobsHash;
         bytes32 processedPrivilegedTransactionsRollingHash;
         bytes32 withdrawalsLogsMerkleRoot;
         bytes32 lastBlockHash;
@@ -91,6 +93,11 @@ contract OnChainProposer is
     /// @notice Chain ID of the network
     uint256 public CHAIN_ID;
 
+    /// @notice Number of privileged transactions processed by committed batches
+    /// that weren't verified yet.
+    /// @dev They're still pending on the bridge, which removes them on verification.
+    uint256 public unverifiedPrivilegedTransactions;
+
     modifier onlySequencer() {
         require(
             authorizedSequencerAddresses[msg.sender],
@@ -201,9 +208,12 @@ contract OnChainProposer is
             "OnChainProposer: lastBlockHash cannot be zero"
         );
 
+        // The privileged transactions of committed batches are only removed from the
+        // bridge's queue on verification, so this batch's ones come right after them.
         if (processedPrivilegedTransactionsRollingHash != bytes32(0)) {
             bytes32 claimedProcessedTransactions = ICommonBridge(BRIDGE)
-                .getPendingTransactionsVersionedHash(
+                .getPendingTransactionsVersionedHashAfter(
+                    unverifiedPrivilegedTransactions,
                     uint16(bytes2(processedPrivilegedTransactionsRollingHash))
                 );
             require(
@@ -212,6 +222,22 @@ contract OnChainProposer is
                 "OnChainProposer: invalid privileged transaction logs"
             );
         }
+        // Forced inclusion: while a privileged transaction is overdue, every batch
+        // has to process privileged transactions, which are taken in order starting
+        // with the overdue ones. Verification rejects batches with non-privileged
+        // transactions while any is still overdue, so the sequencer can catch up with
+        // a series of privileged-only batches.
+        uint16 privilegedTransactionCount = uint16(
+            bytes2(processedPrivilegedTransactionsRollingHash)
+        );
+        require(
+            privilegedTransactionCount > 0 ||
+                !ICommonBridge(BRIDGE).hasExpiredPrivilegedTransactionsAfter(
+                    unverifiedPrivilegedTransactions
+                ),
+            "OnChainProposer: exceeded privileged transaction inclusion deadline, overdue privileged transactions must be processed"
+        );
+        unverifiedPrivilegedTransactions += privilegedTransactionCount;
         if (withdrawalsLogsMerkleRoot != bytes32(0)) {
             ICommonBridge(BRIDGE).publishWithdrawals(
                 batchNumber,
@@ -219,23 +245,23 @@ contract OnChainProposer is
             );
         }
 
-        // Blob is published in the (EIP-4844) transaction that calls this function.
-        bytes32 blobVersionedHash = blobhash(0);
+        // Blobs are published in the (EIP-4844) transaction that calls this function.
+        bytes32 blobsHash = _blobsHash();
         if (VALIDIUM) {
             require(
-                blobVersionedHash == 0,
+                blobsHash == 0,
                 "L2 running as validium but blob was published"
             );
         } else {
             require(
-                blobVersionedHash != 0,
+                blobsHash != 0,
                 "L2 running as rollup but blob was not published"
             );
         }
 
         batchCommitments[batchNumber] = BatchCommitmentInfo(
             newStateRoot,
-            blobVersionedHash,
+            blobsHash,
             processedPrivilegedTransactionsRollingHash,
             withdrawalsLogsMerkleRoot,
             lastBlockHash
@@ -290,6 +316,7 @@ contract OnChainProposer is
             ICommonBridge(BRIDGE).removePendingTransactionHashes(
                 privileged_transaction_count
             );
+            unverifiedPrivilegedTransactions -= privileged_transaction_count;
         }
 
         if (R0VERIFIER != DEV_MODE) {
@@ -364,6 +391,8 @@ contract OnChainProposer is
                 ICommonBridge(BRIDGE).removePendingTransactionHashes(
                     privileged_transaction_count
                 );
+                unverifiedPrivilegedTransactions -=
+                    privileged_transaction_count;
             }
 
             // Verify public data for the batch
@@ -397,6 +426,21 @@ contract OnChainProposer is
         emit BatchVerified(lastVerifiedBatch);
     }
 
+    /// @notice Hashes the versioned hashes of the blobs published in the current
+    /// transaction, a batch's state diff can span several of them.
+    /// @return The keccak256 hash of the concatenated versioned hashes, or zero if
+    /// no blob was published.
+    function _blobsHash() internal view returns (bytes32) {
+        if (blobhash(0) == 0) {
+            return bytes32(0);
+        }
+        bytes memory versionedHashes;
+        for (uint256 i = 0; blobhash(i) != 0; i++) {
+            versionedHashes = bytes.concat(versionedHashes, blobhash(i));
+        }
+        return keccak256(versionedHashes);
+    }
+
     function _verifyPublicData(
         uint256 batchNumber,
         bytes calldata publicData
@@ -429,11 +473,10 @@ contract OnChainProposer is
                 privilegedTransactionsHash,
             "OnChainProposer: privileged transactions hash public input does not match with committed transactions"
         );
-        bytes32 blobVersionedHash = bytes32(publicData[128:160]);
+        bytes32 blobsHash = bytes32(publicData[128:160]);
         require(
-            batchCommitments[batchNumber].stateDiffKZGVersionedHash ==
-                blobVersionedHash,
-            "OnChainProposer: blob versioned hash public input does not match with committed hash"
+            batchCommitments[batchNumber].blobsHash == blobsHash,
+            "OnChainProposer: blobs hash public input does not match with committed hash"
         );
         bytes32 lastBlockHash = bytes32(publicData[160:192]);
         require(
@@ -470,6 +513,13 @@ contract OnChainProposer is
 
         // Remove old batches
         for (uint256 i = batchNumber; i < lastCommittedBatch; i++) {
+            // The first 2 bytes are the number of privileged transactions.
+            unverifiedPrivilegedTransactions -= uint16(
+                bytes2(
+                    batchCommitments[i + 1]
+                        .processedPrivilegedTransactionsRollingHash
+                )
+            );
             delete batchCommitments[i + 1];
         }
 
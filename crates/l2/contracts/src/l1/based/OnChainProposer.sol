@@ -28,9 +28,11 @@ contract OnChainProposer is
     /// pendingTxHashes queue of the CommonBridge contract.
     /// @dev withdrawalsLogsMerkleRoot is the Merkle root of the Merkle tree containing
     /// all the withdrawals that were processed in the batch being committed
+    /// @dev blobsHash is the keccak256 hash of the concatenated versioned hashes
+    /// of the blobs holding the batch's state diff, or zero if it has no blobs.
     struct BatchCommitmentInfo {
         bytes32 newStateRoot;
-        bytes32 blobVersionedHash;
+        bytes32 blobsHash;
         bytes32 processedPrivilegedTransactionsRollingHash;
         bytes32 withdrawalsLogsMerkleRoot;
         bytes32 lastBlockHash;
@@ -85,6 +87,11 @@ contract OnChainProposer is
     /// @notice Chain ID of the network
     uint256 public CHAIN_ID;
 
+    /// @notice Number of privileged transactions processed by committed batches
+    /// that weren't verified yet.
+    /// @dev They're still pending on the bridge, which removes them on verification.
+    uint256 public unverifiedPrivilegedTransactions;
+
     modifier onlyLeaderSequencer() {
         require(
             msg.sender ==
@@ -252,9 +259,12 @@ contract OnChainProposer is
 
         // Check if commitment is equivalent to blob's KZG commitment.
 
+        // The privileged transactions of committed batches are only removed from the
+        // bridge's queue on verification, so this batch's ones come right after them.
         if (processedPrivilegedTransactionsRollingHash != bytes32(0)) {
             bytes32 claimedProcessedTransactions = ICommonBridge(BRIDGE)
-                .getPendingTransactionsVersionedHash(
+                .getPendingTransactionsVersionedHashAfter(
+                    unverifiedPrivilegedTransactions,
                     uint16(bytes2(processedPrivilegedTransactionsRollingHash))
                 );
             require(
@@ -263,6 +273,22 @@ contract OnChainProposer is
                 "OnChainProposer: invalid privileged transactions log"
             );
         }
+        // Forced inclusion: while a privileged transaction is overdue, every batch
+        // has to process privileged transactions, which are taken in order starting
+        // with the overdue ones. Verification rejects batches with non-privileged
+        // transactions while any is still overdue, so the sequencer can catch up with
+        // a series of privileged-only batches.
+        uint16 privilegedTransactionCount = uint16(
+            bytes2(processedPrivilegedTransactionsRollingHash)
+        );
+        require(
+            privilegedTransactionCount > 0 ||
+                !ICommonBridge(BRIDGE).hasExpiredPrivilegedTransactionsAfter(
+                    unverifiedPrivilegedTransactions
+                ),
+            "OnChainProposer: exceeded privileged transaction inclusion deadline, overdue privileged transactions must be processed"
+        );
+        unverifiedPrivilegedTransactions += privilegedTransactionCount;
         if (withdrawalsLogsMerkleRoot != bytes32(0)) {
             ICommonBridge(BRIDGE).publishWithdrawals(
                 batchNumber,
@@ -270,23 +296,23 @@ contract OnChainProposer is
             );
         }
 
-        // Blob is published in the (EIP-4844) transaction that calls this function.
-        bytes32 blobVersionedHash = blobhash(0);
+        // Blobs are published in the (EIP-4844) transaction that calls this function.
+        bytes32 blobsHash = _blobsHash();
         if (VALIDIUM) {
             require(
-                blobVersionedHash == 0,
+                blobsHash == 0,
                 "L2 running as validium but blob was published"
             );
         } else {
             require(
-                blobVersionedHash != 0,
+                blobsHash != 0,
                 "L2 running as rollup but blob was not published"
             );
         }
 
         batchCommitments[batchNumber] = BatchCommitmentInfo(
             newStateRoot,
-            blobVersionedHash,
+            blobsHash,
             processedPrivilegedTransactionsRollingHash,
             withdrawalsLogsMerkleRoot,
             lastBlockHash
@@ -341,6 +367,7 @@ contract OnChainProposer is
             ICommonBridge(BRIDGE).removePendingTransactionHashes(
                 privileged_transaction_count
             );
+            unverifiedPrivilegedTransactions -= privileged_transaction_count;
         }
 
         if (R0VERIFIER != DEV_MODE) {
@@ -415,6 +442,8 @@ contract OnChainProposer is
                 ICommonBridge(BRIDGE).removePendingTransactionHashes(
                     privileged_transaction_count
                 );
+                unverifiedPrivilegedTransactions -=
+                    privileged_transaction_count;
             }
 
             // Verify public data for the batch
@@ -448,6 +477,21 @@ contract OnChainProposer is
         emit BatchVerified(lastVerifiedBatch);
     }
 
+    /// @notice Hashes the versioned hashes of the blobs published in the current
+    /// transaction, a batch's state diff can span several of them.
+    /// @return The keccak256 hash of the concatenated versioned hashes, or zero if
+    /// no blob was published.
+    function _blobsHash() internal view returns (bytes32) {
+        if (blobhash(0) == 0) {
+            return bytes32(0);
+        }
+        bytes memory versionedHashes;
+        for (uint256 i = 0; blobhash(i) != 0; i++) {
+            versionedHashes = bytes.concat(versionedHashes, blobhash(i));
+        }
+        return keccak256(versionedHashes);
+    }
+
     function _verifyPublicData(
         uint256 batchNumber,
         bytes calldata publicData
@@ -72,6 +72,16 @@ interface ICommonBridge {
         uint16 number
     ) external view returns (bytes32);
 
+    /// @notice Method to retrieve the versioned hash of the `number` pending
+    /// privileged transactions that follow the first `processed` ones.
+    /// @param processed Number of pending privileged transactions, in order,
+    /// already processed by committed batches.
+    /// @param number of pending privileged transaction to retrieve the versioned hash.
+    function getPendingTransactionsVersionedHashAfter(
+        uint256 processed,
+        uint16 number
+    ) external view returns (bytes32);
+
     /// @notice Remove pending transaction hashes from the queue.
     /// @dev This method is used by the L2 OnChainOperator to remove the pending
     /// privileged transactions from the queue after the transaction is included.
@@ -145,4 +155,12 @@ interface ICommonBridge {
 
     /// @notice Checks if the sequencer has exceeded it's processing deadlines
     function hasExpiredPrivilegedTransactions() external view returns (bool);
+
+    /// @notice Checks if any pending privileged transaction other than the first
+    /// `processed` ones has exceeded its inclusion deadline
+    /// @param processed Number of pending privileged transactions, in order,
+    /// already processed by committed batches.
+    function hasExpiredPrivilegedTransactionsAfter(
+        uint256 processed
+    ) external view returns (bool);
 }
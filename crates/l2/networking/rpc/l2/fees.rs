@@ -0,0 +1,83 @@
+use crate::{
+    rpc::{RpcApiContext, RpcHandler},
+    utils::RpcErr,
+};
+use ethrex_rpc::{GetTransactionReceiptRequest, RpcHandler as L1RpcHandler};
+use serde_json::Value;
+use tracing::warn;
+
+/// `eth_estimateGas` taking the L1 data fee into account.
+pub struct EstimateGasWithL1FeeRequest(ethrex_rpc::EstimateGasRequest);
+
+/// `eth_getTransactionReceipt` with the L1 data fee paid by the transaction in the `l1Fee` field.
+/// The field is omitted if the fee is unknown, e.g. for transactions included by another sequencer.
+pub struct GetTransactionReceiptWithL1FeeRequest(GetTransactionReceiptRequest);
+
+impl RpcHandler for EstimateGasWithL1FeeRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        Ok(Self(ethrex_rpc::EstimateGasRequest::parse(params)?))
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        estimate_gas(&self.0, context).await
+    }
+}
+
+/// The L1 data fee is charged as gas at the transaction's gas price, so a transaction estimated
+/// without a price wouldn't account for it. In that case the estimation is run at the block's base
+/// fee, which also caps the estimation by the sender's balance.
+pub async fn estimate_gas(
+    request: &ethrex_rpc::EstimateGasRequest,
+    context: RpcApiContext,
+) -> Result<Value, RpcErr> {
+    let mut transaction = request.transaction.clone();
+    if transaction.gas_price == 0 && transaction.max_fee_per_gas.unwrap_or_default() == 0 {
+        let block_header = request
+            .block
+            .clone()
+            .unwrap_or_default()
+            .resolve_block_header(&context.l1_ctx.storage)
+            .await?;
+        if let Some(base_fee) = block_header.and_then(|header| header.base_fee_per_gas) {
+            transaction.gas_price = base_fee;
+            transaction.max_fee_per_gas = None;
+            transaction.max_priority_fee_per_gas = None;
+        }
+    }
+
+    ethrex_rpc::EstimateGasRequest {
+        transaction,
+        block: request.block.clone(),
+    }
+    .handle(context.l1_ctx)
+    .await
+    .map_err(RpcErr::L1RpcErr)
+}
+
+impl RpcHandler for GetTransactionReceiptWithL1FeeRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        Ok(Self(GetTransactionReceiptRequest::parse(params)?))
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let mut receipt = self.0.handle(context.l1_ctx.clone()).await?;
+        let Some(fields) = receipt.as_object_mut() else {
+            // Transaction not found
+            return Ok(receipt);
+        };
+
+        // The L1 fee isn't part of the consensus receipt, so it's recorded when the block is produced
+        let l1_fee = context
+            .rollup_store
+            .get_l1_fee(self.0.transaction_hash)
+            .await
+            .inspect_err(|err| warn!("Failed to get the L1 fee of a transaction: {err}"))
+            .ok()
+            .flatten();
+        if let Some(l1_fee) = l1_fee {
+            fields.insert("l1Fee".to_string(), Value::String(format!("{l1_fee:#x}")));
+        }
+
+        Ok(receipt)
+    }
+}
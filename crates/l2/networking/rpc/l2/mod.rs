@@ -1,3 +1,7 @@
 pub mod batch;
+pub mod fees;
 pub mod l1_message;
+pub mod preconfirmation;
+pub mod sponsorship;
 pub mod transaction;
+pub mod user_operation;
@@ -0,0 +1,407 @@
+use std::{collections::HashMap, sync::Arc};
+
+use ethrex_common::{
+    Address, U256,
+    types::{
+        BlockHeader, L1FeeConfig, MempoolTransaction, Transaction, calculate_base_fee_per_gas,
+    },
+};
+use ethrex_l2_common::{
+    preconfirmation::{Preconfirmation, SignedPreconfirmation},
+    state_diff::SIMPLE_TX_STATE_DIFF_SIZE,
+};
+use ethrex_rpc::types::transaction::SendRawTransactionRequest;
+use ethrex_storage::Store;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::sync::{Mutex, Notify};
+use tracing::{debug, error};
+
+use crate::{
+    rpc::{RpcApiContext, RpcHandler},
+    utils::RpcErr,
+};
+
+/// Transactions the sequencer promised to include in the next block, in the promised order.
+/// Shared between the RPC, which hands out the preconfirmations, and the block producer, which
+/// executes them before any mempool transaction.
+#[derive(Debug, Clone, Default)]
+pub struct Preconfirmations {
+    block: Arc<Mutex<PreconfirmedBlock>>,
+    /// Wakes up the preconfirmations waiting for the parent of the preconfirmed block.
+    parent_stored: Arc<Notify>,
+}
+
+#[derive(Debug, Default)]
+struct PreconfirmedBlock {
+    block_number: u64,
+    /// Whether the block producer is building blocks that can honour new preconfirmations.
+    accepting: bool,
+    /// Base fee of the block, known once its parent is stored. Until then the parent is being
+    /// built, so the nonces it leaves its senders with are unknown and new preconfirmations wait.
+    base_fee_per_gas: Option<u64>,
+    transactions: Vec<MempoolTransaction>,
+    gas: u64,
+    /// Nonce the next preconfirmed transaction of each sender must have.
+    next_nonces: HashMap<Address, u64>,
+}
+
+impl Preconfirmations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes the transactions preconfirmed for `block_number`, to be included at the start of the
+    /// block in the returned order. If `accept_next` is set, preconfirmations for the block after
+    /// it are handed out once `block_number` is stored, see `parent_stored`.
+    pub async fn take(&self, block_number: u64, accept_next: bool) -> Vec<MempoolTransaction> {
+        let mut block = self.block.lock().await;
+        let previous = std::mem::take(&mut *block);
+        block.block_number = block_number.saturating_add(1);
+        block.accepting = accept_next;
+        // Preconfirmations waiting for the previous block are refused if the next isn't accepted
+        self.parent_stored.notify_waiters();
+
+        if previous.block_number != block_number && !previous.transactions.is_empty() {
+            error!(
+                "Breaking {} preconfirmations for block {}, producing block {block_number} instead",
+                previous.transactions.len(),
+                previous.block_number
+            );
+            return Vec::new();
+        }
+        previous.transactions
+    }
+
+    /// Starts handing out preconfirmations for the block after `parent`, once it's stored.
+    pub async fn parent_stored(&self, parent: &BlockHeader, elasticity_multiplier: u64) {
+        let mut block = self.block.lock().await;
+        if block.block_number != parent.number.saturating_add(1) {
+            return;
+        }
+        // The gas limit of L2 blocks doesn't change, a base fee that can't be computed means
+        // nothing can be preconfirmed
+        let base_fee_per_gas = calculate_base_fee_per_gas(
+            parent.gas_limit,
+            parent.gas_limit,
+            parent.gas_used,
+            parent.base_fee_per_gas.unwrap_or_default(),
+            elasticity_multiplier,
+        )
+        .unwrap_or(u64::MAX);
+        block.base_fee_per_gas = Some(base_fee_per_gas);
+        self.parent_stored.notify_waiters();
+    }
+
+    /// Waits until the parent of the preconfirmed block is stored, so that preconfirmations are
+    /// checked against the state the block is built on. Returns the number of the parent.
+    pub async fn wait_for_parent(&self) -> Result<u64, RpcErr> {
+        loop {
+            let parent_stored = self.parent_stored.notified();
+            {
+                let block = self.block.lock().await;
+                if !block.accepting {
+                    return Err(not_preconfirming());
+                }
+                if block.base_fee_per_gas.is_some() {
+                    return Ok(block.block_number.saturating_sub(1));
+                }
+            }
+            parent_stored.await;
+        }
+    }
+
+    /// Reserves the next slot of the block being preconfirmed for the transaction, returning the
+    /// preconfirmation to be signed.
+    pub async fn reserve(
+        &self,
+        transaction: &Transaction,
+        sender: Address,
+        parent: &ParentState,
+        chain_id: u64,
+        conditions: &PreconfirmationConditions,
+    ) -> Result<Preconfirmation, RpcErr> {
+        let mut block = self.block.lock().await;
+        if !block.accepting {
+            return Err(not_preconfirming());
+        }
+        let base_fee_per_gas = block
+            .base_fee_per_gas
+            .filter(|_| block.block_number == parent.number.saturating_add(1))
+            .ok_or(RpcErr::InvalidEthrexL2Message(format!(
+                "Block {} is not being preconfirmed anymore, try again",
+                parent.number.saturating_add(1)
+            )))?;
+        if conditions
+            .block_number_max
+            .is_some_and(|block_number_max| block_number_max < block.block_number)
+        {
+            return Err(RpcErr::InvalidEthrexL2Message(format!(
+                "Transaction can't be preconfirmed before block {}",
+                block.block_number
+            )));
+        }
+        let gas = block
+            .gas
+            .checked_add(transaction.gas_limit())
+            .filter(|gas| *gas <= parent.gas_limit)
+            .ok_or(RpcErr::InvalidEthrexL2Message(
+                "Not enough gas left to preconfirm the transaction in the next block".to_string(),
+            ))?;
+        let min_fee_per_gas = parent.min_fee_per_gas(base_fee_per_gas, transaction.gas_limit());
+        if U256::from(transaction.gas_price()) < min_fee_per_gas {
+            return Err(RpcErr::InvalidEthrexL2Message(format!(
+                "Max fee per gas too low to be included in the next block, at least {min_fee_per_gas} is needed"
+            )));
+        }
+        // Preconfirmed transactions are executed back to back, so they must not leave nonce gaps
+        let expected_nonce = block
+            .next_nonces
+            .get(&sender)
+            .copied()
+            .unwrap_or(parent.sender_nonce);
+        if transaction.nonce() != expected_nonce {
+            return Err(RpcErr::InvalidEthrexL2Message(format!(
+                "Invalid nonce, expected {expected_nonce}"
+            )));
+        }
+
+        let preconfirmation =
+            Preconfirmation {
+                chain_id,
+                tx_hash: transaction.hash(),
+                block_number: block.block_number,
+                index: block.transactions.len().try_into().map_err(|_| {
+                    RpcErr::Internal("Preconfirmation index overflows u64".to_string())
+                })?,
+            };
+        block.gas = gas;
+        block
+            .next_nonces
+            .insert(sender, expected_nonce.saturating_add(1));
+        block
+            .transactions
+            .push(MempoolTransaction::new(transaction.clone(), sender));
+        Ok(preconfirmation)
+    }
+
+    /// Gives back the slot of a preconfirmation that couldn't be signed. Only the last slot can
+    /// be given back, as the following ones are already promised, so otherwise the transaction
+    /// keeps its slot and is included without a preconfirmation. Returns whether it was given back.
+    pub async fn release(&self, preconfirmation: &Preconfirmation) -> bool {
+        let mut block = self.block.lock().await;
+        let is_last_slot = block.block_number == preconfirmation.block_number
+            && block.transactions.len().checked_sub(1)
+                == usize::try_from(preconfirmation.index).ok()
+            && block
+                .transactions
+                .last()
+                .is_some_and(|tx| tx.hash() == preconfirmation.tx_hash);
+        if !is_last_slot {
+            return false;
+        }
+        if let Some(tx) = block.transactions.pop() {
+            block.gas = block.gas.saturating_sub(tx.gas_limit());
+            block.next_nonces.insert(tx.sender(), tx.nonce());
+        }
+        true
+    }
+
+    /// Stops handing out preconfirmations, e.g. when the node stops sequencing. The ones already
+    /// handed out can't be honoured anymore.
+    pub async fn stop(&self) {
+        let mut block = self.block.lock().await;
+        if !block.transactions.is_empty() {
+            error!(
+                "Breaking {} preconfirmations for block {}, the node stopped sequencing",
+                block.transactions.len(),
+                block.block_number
+            );
+        }
+        *block = PreconfirmedBlock::default();
+        self.parent_stored.notify_waiters();
+    }
+}
+
+fn not_preconfirming() -> RpcErr {
+    RpcErr::InvalidEthrexL2Message("The sequencer is not preconfirming transactions".to_string())
+}
+
+/// State of the parent of the preconfirmed block that preconfirmations are checked against.
+#[derive(Debug, Clone, Default)]
+pub struct ParentState {
+    pub number: u64,
+    /// Nonce of the sender once the parent block is executed.
+    pub sender_nonce: u64,
+    pub gas_limit: u64,
+    pub l1_fee_config: Option<L1FeeConfig>,
+}
+
+impl ParentState {
+    pub async fn read(store: &Store, number: u64, sender: Address) -> Result<Self, RpcErr> {
+        let header = store
+            .get_block_header(number)?
+            .ok_or(RpcErr::Internal(format!("Block header {number} not found")))?;
+        let sender_nonce = store
+            .get_nonce_by_account_address(number, sender)
+            .await?
+            .unwrap_or_default();
+        Ok(Self {
+            number,
+            sender_nonce,
+            gas_limit: header.gas_limit,
+            l1_fee_config: L1FeeConfig::from_extra_data(&header.extra_data),
+        })
+    }
+
+    /// Lowest max fee per gas a transaction needs to be included in the preconfirmed block: its
+    /// base fee, plus the L1 data fee of the smallest state diff spread over the gas limit, as
+    /// the L1 fee is charged as gas at the transaction's gas price.
+    /// The L1 fee is the one of the parent, the block producer uses the latest L1 blob base fee.
+    fn min_fee_per_gas(&self, base_fee_per_gas: u64, gas_limit: u64) -> U256 {
+        let l1_fee = self
+            .l1_fee_config
+            .map(|config| config.l1_fee(SIMPLE_TX_STATE_DIFF_SIZE))
+            .unwrap_or_default();
+        let l1_fee_per_gas = if l1_fee.is_zero() {
+            U256::zero()
+        } else if gas_limit == 0 {
+            U256::MAX
+        } else {
+            let (fee, remainder) = l1_fee.div_mod(U256::from(gas_limit));
+            if remainder.is_zero() {
+                fee
+            } else {
+                fee.saturating_add(U256::one())
+            }
+        };
+        U256::from(base_fee_per_gas).saturating_add(l1_fee_per_gas)
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PreconfirmationConditions {
+    /// Latest block the transaction may be preconfirmed for.
+    #[serde(default, with = "ethrex_common::serde_utils::u64::hex_str_opt")]
+    pub block_number_max: Option<u64>,
+}
+
+pub struct SendRawTransactionConditional {
+    pub transaction: Transaction,
+    pub conditions: PreconfirmationConditions,
+}
+
+impl RpcHandler for SendRawTransactionConditional {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = params.as_ref().ok_or(ethrex_rpc::RpcErr::BadParams(
+            "No params provided".to_owned(),
+        ))?;
+        if params.is_empty() || params.len() > 2 {
+            return Err(ethrex_rpc::RpcErr::BadParams(format!(
+                "Expected one or two params and {} were provided",
+                params.len()
+            ))
+            .into());
+        }
+
+        let data = params.first().cloned().unwrap_or_default();
+        let data = serde_json::from_value::<String>(data)?;
+        let data = data
+            .strip_prefix("0x")
+            .ok_or(ethrex_rpc::RpcErr::BadParams(
+                "Params are not 0x prefixed".to_owned(),
+            ))
+            .and_then(|data| {
+                hex::decode(data).map_err(|error| ethrex_rpc::RpcErr::BadParams(error.to_string()))
+            })?;
+        let transaction = match SendRawTransactionRequest::decode_canonical(&data)
+            .map_err(|error| ethrex_rpc::RpcErr::BadParams(error.to_string()))?
+        {
+            SendRawTransactionRequest::EIP4844(_) | SendRawTransactionRequest::PrivilegedL2(_) => {
+                return Err(RpcErr::InvalidEthrexL2Message(
+                    "Only plain L2 transactions can be preconfirmed".to_string(),
+                ));
+            }
+            transaction => transaction.to_transaction(),
+        };
+
+        let conditions = match params.get(1) {
+            Some(conditions) => serde_json::from_value(conditions.clone())?,
+            None => PreconfirmationConditions::default(),
+        };
+
+        Ok(Self {
+            transaction,
+            conditions,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let blockchain = &context.l1_ctx.blockchain;
+        let storage = &context.l1_ctx.storage;
+        let tx_hash = self.transaction.hash();
+        let sender = self.transaction.sender()?;
+
+        // While the parent of the preconfirmed block is being built, its transactions are leaving
+        // the mempool without being part of the latest state, so nonces are read once it's stored
+        let parent_number = context.preconfirmations.wait_for_parent().await?;
+
+        // The transaction can't be in the mempool, or it could be included before its slot
+        if blockchain
+            .mempool
+            .contains_tx(tx_hash)
+            .map_err(ethrex_rpc::RpcErr::from)?
+            || blockchain
+                .validate_transaction(&self.transaction, sender)
+                .await
+                .map_err(ethrex_rpc::RpcErr::from)?
+                .is_some()
+        {
+            return Err(RpcErr::InvalidEthrexL2Message(
+                "Transaction conflicts with a pending mempool transaction".to_string(),
+            ));
+        }
+
+        let parent = ParentState::read(storage, parent_number, sender).await?;
+        let chain_id = storage.get_chain_config()?.chain_id;
+
+        // The slot is reserved first and signed without holding the lock, so that a slow remote
+        // signer doesn't hold up the other preconfirmations
+        let preconfirmation = context
+            .preconfirmations
+            .reserve(
+                &self.transaction,
+                sender,
+                &parent,
+                chain_id,
+                &self.conditions,
+            )
+            .await?;
+        let signature = match context
+            .sequencer_signer
+            .sign(preconfirmation.encode())
+            .await
+        {
+            Ok(signature) => signature,
+            Err(err) => {
+                if context.preconfirmations.release(&preconfirmation).await {
+                    return Err(RpcErr::Internal(err.to_string()));
+                }
+                return Err(RpcErr::Internal(format!(
+                    "{err}, the transaction will be included without a preconfirmation"
+                )));
+            }
+        };
+        debug!(
+            "Preconfirmed transaction {tx_hash:#x} at index {} of block {}",
+            preconfirmation.index, preconfirmation.block_number
+        );
+
+        serde_json::to_value(SignedPreconfirmation {
+            preconfirmation,
+            signature,
+        })
+        .map_err(|error| RpcErr::Internal(error.to_string()))
+    }
+}
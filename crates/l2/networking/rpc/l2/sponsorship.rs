@@ -0,0 +1,270 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bytes::Bytes;
+use ethrex_common::{Address, H256, Signature, U256, types::recover_address_from_message};
+use ethrex_storage_rollup::{SponsorshipUsage, StoreRollup};
+use keccak_hash::keccak;
+use serde_json::{Value, json};
+use tokio::sync::Mutex;
+
+use crate::{
+    rpc::{RpcApiContext, RpcHandler},
+    utils::RpcErr,
+};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Prefix of the message signed by accounts asking for a sponsored call, so the signature can't
+/// be mistaken for any other signature of the account key.
+pub const SPONSORSHIP_DOMAIN: &[u8] = b"ethrex sponsorship";
+
+/// Call sponsored for `account`, whose budgets it's accounted to.
+#[derive(Debug, Clone)]
+pub struct SponsorshipRequest {
+    pub chain_id: u64,
+    pub account: Address,
+    pub data: Bytes,
+}
+
+/// How a request proves that it can spend the budgets of its account.
+#[derive(Debug, Clone, Copy)]
+pub enum AccountProof {
+    /// The sponsored transaction carries EIP-7702 authorizations signed by the account.
+    Authorization,
+    /// The account signed the request, see [`SponsorshipRequest::encode`].
+    Signature(Signature),
+}
+
+impl SponsorshipRequest {
+    /// Message signed by the account:
+    /// domain || chain_id (32 bytes) || account (32 bytes) || keccak(data) || day (32 bytes) ||
+    /// index (32 bytes)
+    /// where `index` is the number of transactions sponsored for the account on `day`, so that
+    /// every signature is only accounted once.
+    pub fn encode(&self, day: u64, index: u64) -> Bytes {
+        let mut encoded = SPONSORSHIP_DOMAIN.to_vec();
+        for value in [
+            H256::from_low_u64_be(self.chain_id),
+            H256::from(self.account),
+            keccak(&self.data),
+            H256::from_low_u64_be(day),
+            H256::from_low_u64_be(index),
+        ] {
+            encoded.extend_from_slice(value.as_bytes());
+        }
+        encoded.into()
+    }
+
+    fn is_signed_by_account(&self, mut signature: Signature, day: u64, index: u64) -> bool {
+        // Wallets may return the recovery id as 27/28
+        if let Some(v) = signature.0.last_mut() {
+            if *v >= 27 {
+                *v -= 27;
+            }
+        }
+        recover_address_from_message(signature, &self.encode(day, index))
+            .is_ok_and(|signer| signer == self.account)
+    }
+}
+
+/// Rules `ethrex_sendTransaction` follows to decide which transactions to sponsor. Budgets are
+/// per UTC day and are accounted in the rollup store.
+#[derive(Debug, Clone)]
+pub struct SponsorshipPolicy {
+    /// Delegation contracts sponsored accounts can be delegated to, with the selectors that can
+    /// be called on each one. An empty list allows any selector.
+    pub allowed_targets: HashMap<Address, Vec<[u8; 4]>>,
+    pub max_gas_per_tx: u64,
+    /// Max sponsored transactions per account and day
+    pub max_txs_per_account: u64,
+    /// Max gas sponsored per account and day
+    pub max_gas_per_account: u64,
+    /// Max fees, in wei, the sponsor pays per day
+    pub daily_spend_cap: U256,
+    /// Serializes the budget checks with their accounting, so concurrent requests can't
+    /// overspend
+    budget_lock: Arc<Mutex<()>>,
+}
+
+impl SponsorshipPolicy {
+    pub fn new(
+        allowed_targets: HashMap<Address, Vec<[u8; 4]>>,
+        max_gas_per_tx: u64,
+        max_txs_per_account: u64,
+        max_gas_per_account: u64,
+        daily_spend_cap: U256,
+    ) -> Self {
+        Self {
+            allowed_targets,
+            max_gas_per_tx,
+            max_txs_per_account,
+            max_gas_per_account,
+            daily_spend_cap,
+            budget_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Checks that calling `data` on an account delegated to `delegation` can be sponsored.
+    pub fn check_target(&self, delegation: Address, data: &[u8]) -> Result<(), RpcErr> {
+        let Some(selectors) = self.allowed_targets.get(&delegation) else {
+            return Err(RpcErr::InvalidEthrexL2Message(
+                "Invalid tx trying to call delegated address not in sponsored addresses"
+                    .to_string(),
+            ));
+        };
+        if selectors.is_empty() || selectors.iter().any(|selector| data.starts_with(selector)) {
+            Ok(())
+        } else {
+            Err(RpcErr::InvalidEthrexL2Message(
+                "Invalid tx trying to call a selector that isn't sponsored".to_string(),
+            ))
+        }
+    }
+
+    /// Sends a transaction sponsored for the request's account if the request proves control of
+    /// it and it fits in today's budgets, and accounts for it once sent. `spent` is the max fee
+    /// the sponsor may pay for it.
+    pub async fn sponsor<T>(
+        &self,
+        rollup_store: &StoreRollup,
+        request: &SponsorshipRequest,
+        proof: AccountProof,
+        gas: u64,
+        spent: U256,
+        send: impl Future<Output = Result<T, RpcErr>>,
+    ) -> Result<T, RpcErr> {
+        if gas == 0 || gas > self.max_gas_per_tx {
+            return Err(RpcErr::InvalidEthrexL2Message(
+                "tx too expensive".to_string(),
+            ));
+        }
+
+        let account = request.account;
+        let _guard = self.budget_lock.lock().await;
+        let day = today()?;
+        let usage = rollup_store.get_sponsorship_usage(day, account).await?;
+        // Checked while holding the lock, so a signature can't be accounted twice
+        if let AccountProof::Signature(signature) = proof {
+            if !request.is_signed_by_account(signature, day, usage.transactions) {
+                return Err(RpcErr::InvalidEthrexL2Message(format!(
+                    "Invalid sponsorship signature, the account must sign request {:#x} of day {day:#x}",
+                    usage.transactions
+                )));
+            }
+        }
+        let usage = usage.add(gas, spent);
+        if usage.transactions > self.max_txs_per_account {
+            return Err(RpcErr::InvalidEthrexL2Message(
+                "Account exceeded its daily sponsored transactions".to_string(),
+            ));
+        }
+        if usage.gas > self.max_gas_per_account {
+            return Err(RpcErr::InvalidEthrexL2Message(
+                "Account exceeded its daily sponsored gas".to_string(),
+            ));
+        }
+        let total = rollup_store
+            .get_total_sponsorship_usage(day)
+            .await?
+            .add(gas, spent);
+        if total.spent > self.daily_spend_cap {
+            return Err(RpcErr::InvalidEthrexL2Message(
+                "Sponsor exceeded its daily spend cap".to_string(),
+            ));
+        }
+
+        let result = send.await?;
+        rollup_store
+            .add_sponsorship_usage(day, account, gas, spent)
+            .await?;
+        Ok(result)
+    }
+}
+
+/// Days since the unix epoch
+pub fn today() -> Result<u64, RpcErr> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| RpcErr::Internal(err.to_string()))?;
+    Ok(now.as_secs() / SECONDS_PER_DAY)
+}
+
+fn usage_to_json(usage: SponsorshipUsage) -> Value {
+    json!({
+        "transactions": format!("{:#x}", usage.transactions),
+        "gas": format!("{:#x}", usage.gas),
+        "spent": format!("{:#x}", usage.spent),
+    })
+}
+
+/// `admin_sponsorship`: returns the sponsorship policy and today's usage, for every account and
+/// optionally for a given one.
+pub struct GetSponsorshipStatus {
+    pub account: Option<Address>,
+}
+
+impl RpcHandler for GetSponsorshipStatus {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let account = match params.as_deref() {
+            None | Some([]) => None,
+            Some([account]) => Some(serde_json::from_value(account.clone())?),
+            Some(params) => {
+                return Err(ethrex_rpc::RpcErr::BadParams(format!(
+                    "Expected at most one param and {} were provided",
+                    params.len()
+                ))
+                .into());
+            }
+        };
+        Ok(Self { account })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let policy = &context.sponsorship_policy;
+        let day = today()?;
+
+        let allowed_targets: HashMap<String, Vec<String>> = policy
+            .allowed_targets
+            .iter()
+            .map(|(target, selectors)| {
+                (
+                    format!("{target:#x}"),
+                    selectors
+                        .iter()
+                        .map(|selector| format!("0x{}", hex::encode(selector)))
+                        .collect(),
+                )
+            })
+            .collect();
+        let mut status = json!({
+            "day": format!("{day:#x}"),
+            "policy": {
+                "allowedTargets": allowed_targets,
+                "maxGasPerTx": format!("{:#x}", policy.max_gas_per_tx),
+                "maxTxsPerAccount": format!("{:#x}", policy.max_txs_per_account),
+                "maxGasPerAccount": format!("{:#x}", policy.max_gas_per_account),
+                "dailySpendCap": format!("{:#x}", policy.daily_spend_cap),
+            },
+            "total": usage_to_json(
+                context
+                    .rollup_store
+                    .get_total_sponsorship_usage(day)
+                    .await?
+            ),
+        });
+        if let Some(account) = self.account {
+            status["account"] = usage_to_json(
+                context
+                    .rollup_store
+                    .get_sponsorship_usage(day, account)
+                    .await?,
+            );
+        }
+        Ok(status)
+    }
+}
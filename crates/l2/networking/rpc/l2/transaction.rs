@@ -1,23 +1,26 @@
 use crate::{
+    l2::{
+        fees::estimate_gas,
+        sponsorship::{AccountProof, SponsorshipRequest},
+    },
     rpc::{RpcApiContext, RpcHandler},
     signer::{LocalSigner, Signable},
     utils::RpcErr,
 };
 use bytes::Bytes;
 use ethrex_common::{
-    Address, U256,
+    Address, Signature, U256,
     types::{
         AuthorizationList, EIP1559Transaction, EIP7702Transaction, GenericTransaction, TxKind,
     },
 };
+use ethrex_levm::utils::eip7702_recover_address;
 use ethrex_rpc::types::transaction::SendRawTransactionRequest;
 use serde::Deserialize;
 use serde_json::Value;
 
 const DELGATION_PREFIX: [u8; 3] = [0xef, 0x01, 0x00];
 const EIP7702_DELEGATED_CODE_LEN: usize = 23;
-// This could be an environment variable set in the config.toml is the max amount of gas we are willing to sponsor
-const GAS_LIMIT_HARD_LIMIT: u64 = 200000;
 
 #[derive(Deserialize, Debug)]
 pub struct SponsoredTx {
@@ -26,6 +29,10 @@ pub struct SponsoredTx {
     #[serde(deserialize_with = "ethrex_common::serde_utils::bytes::deserialize")]
     pub data: Bytes,
     pub to: Address,
+    /// Signature of the `to` account over the request, see [`SponsorshipRequest::encode`].
+    /// Required to call an already delegated account, as nothing else in the request proves
+    /// the caller controls it.
+    pub signature: Option<Signature>,
 }
 
 // This endpoint is inspired by the work of Ithaca in Odyssey
@@ -68,16 +75,34 @@ impl RpcHandler for SponsoredTx {
             ));
         }
         // If tx is not EIP-7702 check we are calling a delegated account
-        if let Some(auth_list) = &self.authorization_list {
+        let proof = if let Some(auth_list) = &self.authorization_list {
+            // Only the account being delegated can be called, so that budgets are accounted to
+            // the authority and the selector runs on the delegation being checked
+            if auth_list.is_empty() {
+                return Err(RpcErr::InvalidEthrexL2Message(
+                    "Invalid tx with an empty authorization list".to_string(),
+                ));
+            }
             for tuple in auth_list {
-                if !context.valid_delegation_addresses.contains(&tuple.address) {
+                let authority = eip7702_recover_address(tuple)
+                    .map_err(|err| RpcErr::Internal(err.to_string()))?;
+                if authority != Some(self.to) {
                     return Err(RpcErr::InvalidEthrexL2Message(
-                        "Invalid tx trying to delegate to an address that isn't sponsored"
+                        "Invalid tx calling an account other than the authorization signer"
                             .to_string(),
                     ));
                 }
+                context
+                    .sponsorship_policy
+                    .check_target(tuple.address, &self.data)?;
             }
+            AccountProof::Authorization
         } else {
+            // Anyone can call a delegated account, so its budgets are only spent with its
+            // signature
+            let signature = self.signature.ok_or(RpcErr::InvalidEthrexL2Message(
+                "Invalid tx calling a delegated account without its signature".to_string(),
+            ))?;
             let dest_account = context
                 .l1_ctx
                 .storage
@@ -112,13 +137,11 @@ impl RpcHandler for SponsoredTx {
                     "Invalid tx trying to call non delegated account".to_string(),
                 ));
             }
-            if !context.valid_delegation_addresses.contains(&address) {
-                return Err(RpcErr::InvalidEthrexL2Message(
-                    "Invalid tx trying to call delegated address not in sponsored addresses"
-                        .to_string(),
-                ));
-            }
-        }
+            context
+                .sponsorship_policy
+                .check_target(address, &self.data)?;
+            AccountProof::Signature(signature)
+        };
         let sponsor_address =
             ethrex_rpc::clients::eth::get_address_from_secret_key(&context.sponsor_pk).map_err(
                 |_| RpcErr::InvalidEthrexL2Message("Ethrex L2 Rpc method not enabled".to_string()),
@@ -203,12 +226,12 @@ impl RpcHandler for SponsoredTx {
         generic.nonce = Some(nonce);
         generic.from = sponsor_address;
 
-        let estimate_gas_request = ethrex_rpc::RpcHandler::handle(
+        let estimate_gas_request = estimate_gas(
             &ethrex_rpc::EstimateGasRequest {
                 transaction: generic,
                 block: None,
             },
-            context.l1_ctx.clone(),
+            context.clone(),
         )
         .await?;
 
@@ -225,11 +248,6 @@ impl RpcHandler for SponsoredTx {
                 "Estimate gas request has invalid size: {error}"
             ))
         })?;
-        if gas_limit == 0 || gas_limit > GAS_LIMIT_HARD_LIMIT {
-            return Err(RpcErr::InvalidEthrexL2Message(
-                "tx too expensive".to_string(),
-            ));
-        }
 
         let signer = LocalSigner::new(context.sponsor_pk).into();
 
@@ -259,8 +277,27 @@ impl RpcHandler for SponsoredTx {
             }
         }
 
-        ethrex_rpc::RpcHandler::handle(&tx, context.l1_ctx)
+        // The sponsor pays at most the whole gas limit at the max fee
+        let max_fee = U256::from(gas_limit) * U256::from(max_fee_per_gas);
+        let request = SponsorshipRequest {
+            chain_id,
+            account: self.to,
+            data: self.data.clone(),
+        };
+        context
+            .sponsorship_policy
+            .sponsor(
+                &context.rollup_store,
+                &request,
+                proof,
+                gas_limit,
+                max_fee,
+                async {
+                    ethrex_rpc::RpcHandler::handle(&tx, context.l1_ctx.clone())
+                        .await
+                        .map_err(RpcErr::L1RpcErr)
+                },
+            )
             .await
-            .map_err(RpcErr::L1RpcErr)
     }
 }
@@ -0,0 +1,845 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap, VecDeque},
+    rc::Rc,
+    sync::Arc,
+};
+
+use bytes::Bytes;
+use ethrex_blockchain::{Blockchain, vm::StoreVmDatabase};
+use ethrex_common::{
+    Address, H256, U256,
+    types::{
+        BlockHeader, EIP1559Transaction, GenericTransaction, L1FeeConfig, Transaction, TxKind,
+    },
+};
+use ethrex_l2_common::{
+    state_diff::USER_OPERATION_STATE_DIFF_SIZE,
+    user_operation::{
+        USER_OPERATION_EVENT_SELECTOR, UserOperation, UserOperationEvent, decode_failed_op,
+        encode_handle_ops,
+    },
+};
+use ethrex_rpc::{GetTransactionReceiptRequest, RpcHandler as L1RpcHandler};
+use ethrex_storage::Store;
+use ethrex_vm::{
+    ExecutionResult, create_contract_address,
+    tracing::{Erc7562Tracer, Tracer},
+};
+use serde_json::{Value, json};
+use tokio::sync::Mutex;
+use tracing::{debug, info};
+
+use crate::{
+    rpc::{RpcApiContext, RpcHandler},
+    signer::{Signable, Signer},
+    utils::RpcErr,
+};
+
+/// Gas of `handleOps` not attributed to any operation.
+const HANDLE_OPS_GAS: u64 = 50_000;
+/// Gas of the bundle transaction each operation pays for through its `preVerificationGas`, on
+/// top of its calldata. Operations are priced as if they were bundled alone.
+const PER_USER_OPERATION_GAS: u64 = 21_000 + 18_300;
+/// Max amount of bundled operations whose receipts can be looked up.
+const MAX_BUNDLED_USER_OPERATIONS: usize = 10_000;
+/// Verification gas limits operations are simulated with to estimate their gas.
+const ESTIMATION_GAS_LIMIT: u64 = 10_000_000;
+
+/// ERC-4337 operations waiting to be bundled. Shared between the RPC, which simulates and adds
+/// them, and the block producer, which bundles them into `handleOps` transactions signed by the
+/// bundler.
+#[derive(Debug, Clone)]
+pub struct UserOperationPool {
+    pub entry_point: Address,
+    pub bundler: Signer,
+    /// Max amount of pending operations.
+    pub max_pending: usize,
+    /// Blocks after which pending operations that can't be bundled are dropped.
+    pub max_pending_blocks: u64,
+    inner: Arc<Mutex<PoolState>>,
+}
+
+#[derive(Debug, Default)]
+struct PoolState {
+    /// Operations not bundled yet, at most one per sender.
+    pending: BTreeMap<Address, PendingUserOperation>,
+    /// Transaction each bundled operation was sent in.
+    bundled: HashMap<H256, H256>,
+    /// Bundled operations, oldest first, to forget them once there are too many.
+    bundled_order: VecDeque<H256>,
+}
+
+#[derive(Debug, Clone)]
+struct PendingUserOperation {
+    hash: H256,
+    user_operation: UserOperation,
+    /// Number of the latest block when the operation was added.
+    added_at: u64,
+}
+
+/// Gas used by each phase of a simulated operation.
+struct Simulation {
+    factory_gas_used: u64,
+    validation_gas_used: u64,
+    paymaster_validation_gas_used: u64,
+}
+
+/// Simulates `handleOps` transactions sent by the bundler on top of the latest block.
+struct Simulator<'a> {
+    blockchain: &'a Blockchain,
+    storage: &'a Store,
+    header: BlockHeader,
+    entry_point: Address,
+    bundler: Address,
+    bundler_nonce: u64,
+}
+
+impl UserOperationPool {
+    pub fn new(
+        entry_point: Address,
+        bundler: Signer,
+        max_pending: usize,
+        max_pending_blocks: u64,
+    ) -> Self {
+        Self {
+            entry_point,
+            bundler,
+            max_pending,
+            max_pending_blocks,
+            inner: Arc::new(Mutex::new(PoolState::default())),
+        }
+    }
+
+    async fn add(
+        &self,
+        hash: H256,
+        user_operation: UserOperation,
+        added_at: u64,
+    ) -> Result<(), RpcErr> {
+        let mut state = self.inner.lock().await;
+        if state.pending.contains_key(&user_operation.sender) {
+            return Err(RpcErr::InvalidEthrexL2Message(
+                "Sender already has a pending user operation".to_string(),
+            ));
+        }
+        if state.pending.len() >= self.max_pending {
+            return Err(RpcErr::InvalidEthrexL2Message(
+                "The user operation pool is full".to_string(),
+            ));
+        }
+        state.pending.insert(
+            user_operation.sender,
+            PendingUserOperation {
+                hash,
+                user_operation,
+                added_at,
+            },
+        );
+        Ok(())
+    }
+
+    async fn bundle_transaction(&self, hash: H256) -> Option<H256> {
+        self.inner.lock().await.bundled.get(&hash).copied()
+    }
+
+    /// Re-simulates the pending operations and sends the valid ones to the mempool in a
+    /// `handleOps` transaction. Nothing is sent while the previous bundle is still in the mempool,
+    /// so that operations are always simulated on top of it.
+    /// Operations that can't be bundled yet wait for at most `max_pending_blocks` blocks.
+    pub async fn bundle(
+        &self,
+        storage: &Store,
+        blockchain: &Blockchain,
+    ) -> Result<Option<H256>, RpcErr> {
+        let simulator = Simulator::latest(blockchain, storage, self).await?;
+        let (bundler, nonce, header) = (
+            simulator.bundler,
+            simulator.bundler_nonce,
+            &simulator.header,
+        );
+        if blockchain
+            .mempool
+            .contains_sender_nonce(bundler, nonce, H256::zero())
+            .map_err(ethrex_rpc::RpcErr::from)?
+            .is_some()
+        {
+            return Ok(None);
+        }
+
+        let pending: Vec<PendingUserOperation> =
+            self.inner.lock().await.pending.values().cloned().collect();
+        let base_fee = header.base_fee_per_gas.unwrap_or_default();
+        let mut user_operations = Vec::new();
+        let mut bundled = Vec::new();
+        let mut dropped = Vec::new();
+        let mut gas_limit = HANDLE_OPS_GAS;
+        for PendingUserOperation {
+            hash,
+            user_operation,
+            added_at,
+        } in pending
+        {
+            if HANDLE_OPS_GAS.saturating_add(user_operation.gas_limit()) > header.gas_limit {
+                debug!("Dropping user operation {hash:#x}: it doesn't fit in a block");
+                dropped.push((user_operation.sender, hash));
+                continue;
+            }
+            let is_stale = header.number.saturating_sub(added_at) >= self.max_pending_blocks;
+            // Operations that can't pay the current base fee or L1 fee wait for them to go down,
+            // and the ones that don't fit wait for the next bundle, until they're stale
+            let bundle_gas_limit = gas_limit
+                .checked_add(user_operation.gas_limit())
+                .filter(|gas_limit| *gas_limit <= header.gas_limit);
+            let skip_reason = if user_operation.max_fee_per_gas < base_fee {
+                Some("it pays less than the base fee")
+            } else if user_operation.pre_verification_gas
+                < required_pre_verification_gas(&user_operation, header)?
+            {
+                Some("its preVerificationGas doesn't cover the L1 fee")
+            } else if bundle_gas_limit.is_none() {
+                Some("it doesn't fit in the bundle")
+            } else {
+                None
+            };
+            if let Some(reason) = skip_reason {
+                if is_stale {
+                    debug!("Dropping stale user operation {hash:#x}: {reason}");
+                    dropped.push((user_operation.sender, hash));
+                }
+                continue;
+            }
+            // Each operation is simulated after the ones already bundled, as handleOps will run it
+            if let Err(err) = simulator.simulate(&user_operations, &user_operation, false) {
+                debug!("Dropping user operation {hash:#x}: {err}");
+                dropped.push((user_operation.sender, hash));
+                continue;
+            }
+            gas_limit = bundle_gas_limit.unwrap_or(gas_limit);
+            bundled.push((user_operation.sender, hash));
+            user_operations.push(user_operation);
+        }
+
+        // The operations were simulated at no gas price, the bundle is simulated at its gas price
+        // so that its limit covers the L1 data fee. If it fails anyway, the last operation added
+        // broke it and is dropped.
+        let fork = storage.get_chain_config()?.get_fork(header.timestamp);
+        let (result, max_fee_per_gas, max_priority_fee_per_gas, data) = loop {
+            if user_operations.is_empty() {
+                self.inner.lock().await.remove(&dropped);
+                return Ok(None);
+            }
+            let max_fee_per_gas = user_operations
+                .iter()
+                .map(|user_operation| user_operation.max_fee_per_gas)
+                .min()
+                .unwrap_or_default();
+            let max_priority_fee_per_gas = user_operations
+                .iter()
+                .map(|user_operation| user_operation.max_priority_fee_per_gas)
+                .min()
+                .unwrap_or_default()
+                .min(max_fee_per_gas);
+            let data = encode_handle_ops(&user_operations, bundler)
+                .map_err(|err| RpcErr::Internal(err.to_string()))?;
+
+            let bundle = GenericTransaction {
+                to: TxKind::Call(self.entry_point),
+                from: bundler,
+                nonce: Some(nonce),
+                gas: Some(header.gas_limit),
+                max_fee_per_gas: Some(max_fee_per_gas),
+                max_priority_fee_per_gas: Some(max_priority_fee_per_gas),
+                input: data.clone(),
+                ..Default::default()
+            };
+            let vm_db = StoreVmDatabase::new(storage.clone(), header.hash());
+            let result = blockchain
+                .new_evm(vm_db)
+                .and_then(|mut vm| vm.simulate_tx_from_generic(&bundle, header, fork))
+                .map_err(ethrex_rpc::RpcErr::from)?;
+            if result.is_success() {
+                break (result, max_fee_per_gas, max_priority_fee_per_gas, data);
+            }
+            if let (Some(user_operation), Some((sender, hash))) =
+                (user_operations.pop(), bundled.pop())
+            {
+                debug!("Dropping user operation {hash:#x}, the bundle failed with it: {result:?}");
+                gas_limit = gas_limit.saturating_sub(user_operation.gas_limit());
+                dropped.push((sender, hash));
+            }
+        };
+        self.inner.lock().await.remove(&dropped);
+        let gas_used = result.gas_used();
+        let gas_limit = gas_limit
+            .max(gas_used.saturating_add(gas_used / 10))
+            .min(header.gas_limit);
+
+        let mut transaction = EIP1559Transaction {
+            chain_id: storage.get_chain_config()?.chain_id,
+            nonce,
+            max_priority_fee_per_gas,
+            max_fee_per_gas,
+            gas_limit,
+            to: TxKind::Call(self.entry_point),
+            value: U256::zero(),
+            data,
+            access_list: Vec::new(),
+            ..Default::default()
+        };
+        transaction
+            .sign_inplace(&self.bundler)
+            .await
+            .map_err(|err| RpcErr::Internal(err.to_string()))?;
+        let tx_hash = blockchain
+            .add_transaction_to_pool(Transaction::EIP1559Transaction(transaction))
+            .await
+            .map_err(ethrex_rpc::RpcErr::from)?;
+        info!(
+            "Bundled {} user operations in transaction {tx_hash:#x}",
+            bundled.len()
+        );
+
+        // The operations leave the pool only once their bundle is in the mempool, so that they
+        // are retried if anything before fails
+        let mut state = self.inner.lock().await;
+        state.remove(&bundled);
+        for (_, hash) in bundled {
+            state.bundled.insert(hash, tx_hash);
+            state.bundled_order.push_back(hash);
+        }
+        while state.bundled_order.len() > MAX_BUNDLED_USER_OPERATIONS {
+            if let Some(hash) = state.bundled_order.pop_front() {
+                state.bundled.remove(&hash);
+            }
+        }
+        Ok(Some(tx_hash))
+    }
+}
+
+impl PoolState {
+    /// Removes the given operations from the pending ones, unless their senders replaced them in
+    /// the meantime.
+    fn remove(&mut self, removed: &[(Address, H256)]) {
+        for (sender, hash) in removed {
+            if self
+                .pending
+                .get(sender)
+                .is_some_and(|pending| pending.hash == *hash)
+            {
+                self.pending.remove(sender);
+            }
+        }
+    }
+}
+
+impl<'a> Simulator<'a> {
+    async fn latest(
+        blockchain: &'a Blockchain,
+        storage: &'a Store,
+        pool: &UserOperationPool,
+    ) -> Result<Self, RpcErr> {
+        let latest_block_number = storage.get_latest_block_number().await?;
+        let header = storage
+            .get_block_header(latest_block_number)?
+            .ok_or(RpcErr::Internal(
+                "Latest block header not found".to_string(),
+            ))?;
+        let bundler = pool.bundler.address();
+        let bundler_nonce = storage
+            .get_nonce_by_account_address(latest_block_number, bundler)
+            .await?
+            .unwrap_or_default();
+        Ok(Self {
+            blockchain,
+            storage,
+            header,
+            entry_point: pool.entry_point,
+            bundler,
+            bundler_nonce,
+        })
+    }
+
+    /// Simulates `handleOps` with the operation after the already bundled ones, at no gas price,
+    /// checking that the operation's validation follows the ERC-7562 rules. With
+    /// `allow_invalid_signature`, signature failures are accepted, as estimations are signed with
+    /// dummy signatures.
+    fn simulate(
+        &self,
+        bundled: &[UserOperation],
+        user_operation: &UserOperation,
+        allow_invalid_signature: bool,
+    ) -> Result<Simulation, RpcErr> {
+        let user_operations: Vec<UserOperation> = bundled
+            .iter()
+            .chain(std::iter::once(user_operation))
+            .cloned()
+            .collect();
+        let gas_limit = user_operations
+            .iter()
+            .map(UserOperation::gas_limit)
+            .fold(HANDLE_OPS_GAS, u64::saturating_add)
+            .min(self.header.gas_limit);
+        let transaction = GenericTransaction {
+            to: TxKind::Call(self.entry_point),
+            from: self.bundler,
+            nonce: Some(self.bundler_nonce),
+            gas: Some(gas_limit),
+            input: encode_handle_ops(&user_operations, self.bundler)
+                .map_err(|err| RpcErr::Internal(err.to_string()))?,
+            ..Default::default()
+        };
+        let init_code = user_operation.init_code();
+        let tracer = Rc::new(RefCell::new(Erc7562Tracer::new(
+            self.entry_point,
+            user_operation.sender,
+            user_operation
+                .factory
+                .is_some()
+                .then_some(init_code.as_ref()),
+            user_operation.paymaster,
+        )));
+        let dyn_tracer: Rc<RefCell<dyn Tracer>> = tracer.clone();
+
+        let vm_db = StoreVmDatabase::new(self.storage.clone(), self.header.hash());
+        let result = self
+            .blockchain
+            .new_evm(vm_db)
+            .and_then(|mut vm| vm.simulate_tx_with_tracer(&transaction, &self.header, dyn_tracer))
+            .map_err(ethrex_rpc::RpcErr::from)?;
+
+        let tracer = tracer.borrow();
+        if let Some(violation) = tracer.violations.first() {
+            return Err(RpcErr::InvalidEthrexL2Message(format!(
+                "User operation violates {violation}"
+            )));
+        }
+        match result {
+            ExecutionResult::Success { .. } => {}
+            ExecutionResult::Revert { output, .. } => {
+                let reason =
+                    decode_failed_op(&output).unwrap_or_else(|| "handleOps reverted".to_string());
+                // The entry point checks the signatures once the account and the paymaster
+                // returned, so their validation was fully simulated
+                let is_signature_error = reason.starts_with("AA24") || reason.starts_with("AA34");
+                if !(allow_invalid_signature && is_signature_error) {
+                    return Err(RpcErr::InvalidEthrexL2Message(reason));
+                }
+            }
+            ExecutionResult::Halt { reason, .. } => {
+                return Err(RpcErr::InvalidEthrexL2Message(format!(
+                    "handleOps halted: {reason}"
+                )));
+            }
+        }
+        let validation_gas_used =
+            tracer
+                .validation_gas_used
+                .ok_or(RpcErr::InvalidEthrexL2Message(
+                    "The sender's validateUserOp was not called".to_string(),
+                ))?;
+        Ok(Simulation {
+            factory_gas_used: tracer.factory_gas_used.unwrap_or_default(),
+            validation_gas_used,
+            paymaster_validation_gas_used: tracer.paymaster_validation_gas_used.unwrap_or_default(),
+        })
+    }
+
+    /// Gas used by the operation's execution, measured by calling the sender from the entry point
+    /// after the factory deploys it. The entry point and the sender creator send these calls like
+    /// they do in `handleOps`, so their code is cleared for them to pass as EOAs, and calls back
+    /// to the entry point aren't accounted for.
+    async fn execution_gas_used(&self, user_operation: &UserOperation) -> Result<u64, RpcErr> {
+        if user_operation.call_data.is_empty() {
+            return Ok(0);
+        }
+        // The first contract deployed by the entry point
+        let sender_creator = create_contract_address(self.entry_point, 1);
+        let entry_point_nonce = self
+            .storage
+            .get_nonce_by_account_address(self.header.number, self.entry_point)
+            .await?
+            .unwrap_or_default();
+        let sender_creator_nonce = self
+            .storage
+            .get_nonce_by_account_address(self.header.number, sender_creator)
+            .await?
+            .unwrap_or_default();
+        let fork = self
+            .storage
+            .get_chain_config()?
+            .get_fork(self.header.timestamp);
+
+        let vm_db = StoreVmDatabase::new(self.storage.clone(), self.header.hash());
+        let mut vm = self
+            .blockchain
+            .new_evm(vm_db)
+            .map_err(ethrex_rpc::RpcErr::from)?;
+        let mut call = |from: Address,
+                        nonce: u64,
+                        to: Address,
+                        input: &Bytes|
+         -> Result<ExecutionResult, RpcErr> {
+            vm.clear_code(from).map_err(ethrex_rpc::RpcErr::from)?;
+            let transaction = GenericTransaction {
+                to: TxKind::Call(to),
+                from,
+                nonce: Some(nonce),
+                gas: Some(self.header.gas_limit),
+                input: input.clone(),
+                ..Default::default()
+            };
+            Ok(vm
+                .simulate_tx_from_generic(&transaction, &self.header, fork)
+                .map_err(ethrex_rpc::RpcErr::from)?)
+        };
+
+        if let Some(factory) = user_operation.factory {
+            let result = call(
+                sender_creator,
+                sender_creator_nonce,
+                factory,
+                &user_operation.factory_data,
+            )?;
+            if !result.is_success() {
+                return Err(RpcErr::InvalidEthrexL2Message(format!(
+                    "The factory failed to deploy the sender: {result:?}"
+                )));
+            }
+        }
+        let result = call(
+            self.entry_point,
+            entry_point_nonce,
+            user_operation.sender,
+            &user_operation.call_data,
+        )?;
+        if !result.is_success() {
+            return Err(RpcErr::InvalidEthrexL2Message(format!(
+                "The operation's execution failed: {result:?}"
+            )));
+        }
+        // Leaves out the intrinsic gas of the simulated transaction
+        Ok(result.gas_used().saturating_sub(21_000))
+    }
+}
+
+/// Gas the operation must pay through its `preVerificationGas` to be bundled on top of `header`:
+/// its share of the bundle transaction, and of the L1 data fee of the bundle, which is charged
+/// as gas at the bundle's gas price. The L1 fee is converted at the base fee, the lowest gas
+/// price the bundle can pay.
+fn required_pre_verification_gas(
+    user_operation: &UserOperation,
+    header: &BlockHeader,
+) -> Result<u64, RpcErr> {
+    let l1_fee = L1FeeConfig::from_extra_data(&header.extra_data)
+        .map(|config| config.l1_fee(USER_OPERATION_STATE_DIFF_SIZE))
+        .unwrap_or_default();
+    let base_fee = U256::from(header.base_fee_per_gas.unwrap_or_default());
+    // Nothing is charged for the L1 fee at no gas price
+    let l1_gas = if base_fee.is_zero() {
+        U256::zero()
+    } else {
+        let (l1_gas, remainder) = l1_fee.div_mod(base_fee);
+        if remainder.is_zero() {
+            l1_gas
+        } else {
+            l1_gas.saturating_add(U256::one())
+        }
+    };
+    Ok(user_operation
+        .abi_encode()
+        .map_err(|err| RpcErr::Internal(err.to_string()))?
+        .iter()
+        .map(|byte| if *byte == 0 { 4 } else { 16 })
+        .fold(PER_USER_OPERATION_GAS, u64::saturating_add)
+        .saturating_add(l1_gas.try_into().unwrap_or(u64::MAX)))
+}
+
+fn user_operation_pool(context: &RpcApiContext) -> Result<&UserOperationPool, RpcErr> {
+    context
+        .user_operations
+        .as_ref()
+        .ok_or(RpcErr::InvalidEthrexL2Message(
+            "The bundler is not enabled".to_string(),
+        ))
+}
+
+/// Checks the operation is for the pool's entry point and that its sender is deployed, unless
+/// the operation deploys it through a factory, returning a simulator on top of the latest block.
+async fn simulator<'a>(
+    context: &'a RpcApiContext,
+    user_operation: &UserOperation,
+    entry_point: Address,
+) -> Result<Simulator<'a>, RpcErr> {
+    let pool = user_operation_pool(context)?;
+    if entry_point != pool.entry_point {
+        return Err(RpcErr::InvalidEthrexL2Message(format!(
+            "Unsupported entry point {entry_point:#x}"
+        )));
+    }
+
+    let storage = &context.l1_ctx.storage;
+    let simulator = Simulator::latest(&context.l1_ctx.blockchain, storage, pool).await?;
+    let deployed = storage
+        .get_code_by_account_address(simulator.header.number, user_operation.sender)
+        .await?
+        .is_some_and(|code| !code.is_empty());
+    match (user_operation.factory, deployed) {
+        (Some(_), true) => Err(RpcErr::InvalidEthrexL2Message(
+            "Sender is already deployed".to_string(),
+        )),
+        (None, false) => Err(RpcErr::InvalidEthrexL2Message(
+            "Sender is not deployed".to_string(),
+        )),
+        _ => Ok(simulator),
+    }
+}
+
+/// Adds the margins for the gas the entry point spends around its calls to a verification gas.
+fn with_verification_margin(gas_used: u64) -> u64 {
+    gas_used
+        .saturating_add(gas_used / 10)
+        .saturating_add(10_000)
+}
+
+fn parse_user_operation(params: &Option<Vec<Value>>) -> Result<(UserOperation, Address), RpcErr> {
+    let params = params.as_ref().ok_or(ethrex_rpc::RpcErr::BadParams(
+        "No params provided".to_owned(),
+    ))?;
+    let [user_operation, entry_point] = params.as_slice() else {
+        return Err(ethrex_rpc::RpcErr::BadParams(format!(
+            "Expected two params and {} were provided",
+            params.len()
+        ))
+        .into());
+    };
+    Ok((
+        serde_json::from_value(user_operation.clone())?,
+        serde_json::from_value(entry_point.clone())?,
+    ))
+}
+
+/// `eth_sendUserOperation`: simulates an operation and adds it to the pool to be bundled.
+pub struct SendUserOperation {
+    pub user_operation: UserOperation,
+    pub entry_point: Address,
+}
+
+impl RpcHandler for SendUserOperation {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let (user_operation, entry_point) = parse_user_operation(params)?;
+        Ok(Self {
+            user_operation,
+            entry_point,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let user_operation = &self.user_operation;
+        if user_operation.max_priority_fee_per_gas > user_operation.max_fee_per_gas {
+            return Err(RpcErr::InvalidEthrexL2Message(
+                "maxPriorityFeePerGas is higher than maxFeePerGas".to_string(),
+            ));
+        }
+        let storage = &context.l1_ctx.storage;
+        let simulator = simulator(&context, user_operation, self.entry_point).await?;
+        let base_fee = simulator.header.base_fee_per_gas.unwrap_or_default();
+        if user_operation.max_fee_per_gas < base_fee {
+            return Err(RpcErr::InvalidEthrexL2Message(format!(
+                "maxFeePerGas is lower than the base fee {base_fee}"
+            )));
+        }
+        let required_pre_verification_gas =
+            required_pre_verification_gas(user_operation, &simulator.header)?;
+        if user_operation.pre_verification_gas < required_pre_verification_gas {
+            return Err(RpcErr::InvalidEthrexL2Message(format!(
+                "preVerificationGas is lower than {required_pre_verification_gas}"
+            )));
+        }
+
+        simulator.simulate(&[], user_operation, false)?;
+        let chain_id = storage.get_chain_config()?.chain_id;
+        let hash = user_operation.hash(self.entry_point, chain_id);
+        user_operation_pool(&context)?
+            .add(hash, user_operation.clone(), simulator.header.number)
+            .await?;
+        debug!("Added user operation {hash:#x} to the pool");
+        Ok(Value::String(format!("{hash:#x}")))
+    }
+}
+
+/// `eth_estimateUserOperationGas`: simulates an operation with generous gas limits to estimate
+/// them. The signature can be a dummy one, as long as the account and the paymaster fail on it
+/// without reverting.
+pub struct EstimateUserOperationGas {
+    pub user_operation: UserOperation,
+    pub entry_point: Address,
+}
+
+impl RpcHandler for EstimateUserOperationGas {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let (user_operation, entry_point) = parse_user_operation(params)?;
+        Ok(Self {
+            user_operation,
+            entry_point,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let simulator = simulator(&context, &self.user_operation, self.entry_point).await?;
+        let pre_verification_gas =
+            required_pre_verification_gas(&self.user_operation, &simulator.header)?;
+        // The validation is simulated without execution and at no gas price, so that nothing
+        // needs to be prefunded
+        let gas_limit = ESTIMATION_GAS_LIMIT.min(simulator.header.gas_limit / 4);
+        let user_operation = UserOperation {
+            verification_gas_limit: gas_limit,
+            paymaster_verification_gas_limit: if self.user_operation.paymaster.is_some() {
+                gas_limit
+            } else {
+                0
+            },
+            call_gas_limit: 0,
+            pre_verification_gas,
+            max_fee_per_gas: 0,
+            max_priority_fee_per_gas: 0,
+            ..self.user_operation.clone()
+        };
+        let simulation = simulator.simulate(&[], &user_operation, true)?;
+        let execution_gas_used = simulator.execution_gas_used(&user_operation).await?;
+
+        let verification_gas_limit = with_verification_margin(
+            simulation
+                .factory_gas_used
+                .saturating_add(simulation.validation_gas_used),
+        );
+        let call_gas_limit = execution_gas_used.saturating_add(execution_gas_used / 10);
+        let mut estimation = json!({
+            "preVerificationGas": format!("{pre_verification_gas:#x}"),
+            "verificationGasLimit": format!("{verification_gas_limit:#x}"),
+            "callGasLimit": format!("{call_gas_limit:#x}"),
+        });
+        if let (Some(_), Some(fields)) = (user_operation.paymaster, estimation.as_object_mut()) {
+            let paymaster_verification_gas_limit =
+                with_verification_margin(simulation.paymaster_validation_gas_used);
+            fields.insert(
+                "paymasterVerificationGasLimit".to_string(),
+                Value::String(format!("{paymaster_verification_gas_limit:#x}")),
+            );
+        }
+        Ok(estimation)
+    }
+}
+
+/// `eth_getUserOperationReceipt`: returns the outcome of a bundled operation, or null if it's not
+/// included yet.
+pub struct GetUserOperationReceipt {
+    pub user_operation_hash: H256,
+}
+
+impl RpcHandler for GetUserOperationReceipt {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = params.as_ref().ok_or(ethrex_rpc::RpcErr::BadParams(
+            "No params provided".to_owned(),
+        ))?;
+        let [user_operation_hash] = params.as_slice() else {
+            return Err(ethrex_rpc::RpcErr::BadParams(format!(
+                "Expected one param and {} were provided",
+                params.len()
+            ))
+            .into());
+        };
+        Ok(Self {
+            user_operation_hash: serde_json::from_value(user_operation_hash.clone())?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let pool = user_operation_pool(&context)?;
+        let storage = &context.l1_ctx.storage;
+        let Some(tx_hash) = pool.bundle_transaction(self.user_operation_hash).await else {
+            return Ok(Value::Null);
+        };
+        let Some((block_number, _, index)) = storage.get_transaction_location(tx_hash).await?
+        else {
+            return Ok(Value::Null);
+        };
+        let Some(receipt) = storage.get_receipt(block_number, index).await? else {
+            return Ok(Value::Null);
+        };
+
+        let is_event = |log: &ethrex_common::types::Log| {
+            log.address == pool.entry_point
+                && log.topics.first() == Some(&*USER_OPERATION_EVENT_SELECTOR)
+        };
+        let Some((event_index, event)) = receipt
+            .logs
+            .iter()
+            .enumerate()
+            .filter(|(_, log)| is_event(log))
+            .filter_map(|(index, log)| Some((index, UserOperationEvent::from_log(log)?)))
+            .find(|(_, event)| event.user_operation_hash == self.user_operation_hash)
+        else {
+            return Ok(Value::Null);
+        };
+        // The operation's logs are the ones emitted after the previous operation's event, leaving
+        // out the entry point's
+        let first_index = receipt
+            .logs
+            .iter()
+            .take(event_index)
+            .rposition(is_event)
+            .map_or(0, |index| index.saturating_add(1));
+
+        let rpc_receipt = GetTransactionReceiptRequest {
+            transaction_hash: tx_hash,
+        }
+        .handle(context.l1_ctx.clone())
+        .await?;
+        let rpc_logs = rpc_receipt
+            .get("logs")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        let logs: Vec<Value> = receipt
+            .logs
+            .iter()
+            .zip(rpc_logs)
+            .take(event_index)
+            .skip(first_index)
+            .filter(|(log, _)| log.address != pool.entry_point)
+            .map(|(_, rpc_log)| rpc_log)
+            .collect();
+
+        Ok(json!({
+            "userOpHash": format!("{:#x}", self.user_operation_hash),
+            "entryPoint": format!("{:#x}", pool.entry_point),
+            "sender": format!("{:#x}", event.sender),
+            "nonce": format!("{:#x}", event.nonce),
+            "paymaster": format!("{:#x}", event.paymaster),
+            "actualGasCost": format!("{:#x}", event.actual_gas_cost),
+            "actualGasUsed": format!("{:#x}", event.actual_gas_used),
+            "success": event.success,
+            "logs": logs,
+            "receipt": rpc_receipt,
+        }))
+    }
+}
+
+/// `eth_supportedEntryPoints`
+pub struct SupportedEntryPoints;
+
+impl RpcHandler for SupportedEntryPoints {
+    fn parse(_params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        Ok(Self)
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let entry_points: Vec<String> = context
+            .user_operations
+            .iter()
+            .map(|pool| format!("{:#x}", pool.entry_point))
+            .collect();
+        Ok(json!(entry_points))
+    }
+}
@@ -1,5 +1,13 @@
 use crate::l2::batch::GetBatchByBatchNumberRequest;
+use crate::l2::fees::{EstimateGasWithL1FeeRequest, GetTransactionReceiptWithL1FeeRequest};
 use crate::l2::l1_message::GetL1MessageProof;
+use crate::l2::preconfirmation::{Preconfirmations, SendRawTransactionConditional};
+use crate::l2::sponsorship::{GetSponsorshipStatus, SponsorshipPolicy};
+use crate::l2::user_operation::{
+    EstimateUserOperationGas, GetUserOperationReceipt, SendUserOperation, SupportedEntryPoints,
+    UserOperationPool,
+};
+use crate::signer::Signer;
 use crate::utils::{RpcErr, RpcNamespace, resolve_namespace};
 use axum::extract::State;
 use axum::{Json, Router, http::StatusCode, routing::post};
@@ -30,18 +38,21 @@ use tower_http::cors::CorsLayer;
 use tracing::{debug, info};
 
 use crate::l2::transaction::SponsoredTx;
-use ethrex_common::Address;
 use ethrex_storage_rollup::StoreRollup;
 use secp256k1::SecretKey;
 
 #[derive(Debug, Clone)]
 pub struct RpcApiContext {
     pub l1_ctx: ethrex_rpc::RpcApiContext,
-    pub valid_delegation_addresses: Vec<Address>,
+    pub sponsorship_policy: SponsorshipPolicy,
     pub sponsor_pk: SecretKey,
     pub rollup_store: StoreRollup,
+    pub preconfirmations: Preconfirmations,
+    pub sequencer_signer: Signer,
+    pub user_operations: Option<UserOperationPool>,
 }
 
+#[allow(async_fn_in_trait)]
 pub trait RpcHandler: Sized {
     fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr>;
 
@@ -69,13 +80,16 @@ pub async fn start_api(
     blockchain: Arc<Blockchain>,
     jwt_secret: Bytes,
     local_p2p_node: Node,
-    local_node_record: NodeRecord,
+    local_node_record: Arc<TokioMutex<NodeRecord>>,
     syncer: SyncManager,
     peer_handler: PeerHandler,
     client_version: String,
-    valid_delegation_addresses: Vec<Address>,
+    sponsorship_policy: SponsorshipPolicy,
     sponsor_pk: SecretKey,
     rollup_store: StoreRollup,
+    preconfirmations: Preconfirmations,
+    sequencer_signer: Signer,
+    user_operations: Option<UserOperationPool>,
 ) -> Result<(), RpcErr> {
     // TODO: Refactor how filters are handled,
     // filters are used by the filters endpoints (eth_newFilter, eth_getFilterChanges, ...etc)
@@ -95,9 +109,12 @@ pub async fn start_api(
             },
             gas_tip_estimator: Arc::new(TokioMutex::new(GasTipEstimator::new())),
         },
-        valid_delegation_addresses,
+        sponsorship_policy,
         sponsor_pk,
         rollup_store,
+        preconfirmations,
+        sequencer_signer,
+        user_operations,
     };
 
     // Periodically clean up the active filters for the filters endpoints.
@@ -175,6 +192,9 @@ pub async fn map_http_requests(req: &RpcRequest, context: RpcApiContext) -> Resu
             map_eth_requests(req, context).await
         }
         Ok(RpcNamespace::EthrexL2) => map_l2_requests(req, context).await,
+        Ok(RpcNamespace::L1RpcNamespace(ethrex_rpc::RpcNamespace::Admin)) => {
+            map_admin_requests(req, context).await
+        }
         _ => ethrex_rpc::map_http_requests(req, context.l1_ctx)
             .await
             .map_err(RpcErr::L1RpcErr),
@@ -198,17 +218,37 @@ pub async fn map_eth_requests(req: &RpcRequest, context: RpcApiContext) -> Resul
                 .await
                 .map_err(RpcErr::L1RpcErr)
         }
+        "eth_estimateGas" => EstimateGasWithL1FeeRequest::call(req, context).await,
+        "eth_getTransactionReceipt" => {
+            GetTransactionReceiptWithL1FeeRequest::call(req, context).await
+        }
+        "eth_sendUserOperation" => SendUserOperation::call(req, context).await,
+        "eth_estimateUserOperationGas" => EstimateUserOperationGas::call(req, context).await,
+        "eth_getUserOperationReceipt" => GetUserOperationReceipt::call(req, context).await,
+        "eth_supportedEntryPoints" => SupportedEntryPoints::call(req, context).await,
         _other_eth_method => ethrex_rpc::map_eth_requests(req, context.l1_ctx)
             .await
             .map_err(RpcErr::L1RpcErr),
     }
 }
 
+pub async fn map_admin_requests(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
+    match req.method.as_str() {
+        "admin_sponsorship" => GetSponsorshipStatus::call(req, context).await,
+        _other_admin_method => ethrex_rpc::map_http_requests(req, context.l1_ctx)
+            .await
+            .map_err(RpcErr::L1RpcErr),
+    }
+}
+
 pub async fn map_l2_requests(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
     match req.method.as_str() {
         "ethrex_sendTransaction" => SponsoredTx::call(req, context).await,
         "ethrex_getMessageProof" => GetL1MessageProof::call(req, context).await,
         "ethrex_getBatchByNumber" => GetBatchByBatchNumberRequest::call(req, context).await,
+        "ethrex_sendRawTransactionConditional" => {
+            SendRawTransactionConditional::call(req, context).await
+        }
         unknown_ethrex_l2_method => {
             Err(ethrex_rpc::RpcErr::MethodNotFound(unknown_ethrex_l2_method.to_owned()).into())
         }
@@ -1,6 +1,7 @@
 use std::str::FromStr;
 
 use clap::ValueEnum;
+use ethrex_l2_common::prover::ProverType;
 use serde::{Deserialize, Serialize};
 use zkvm_interface::io::ProgramOutput;
 
@@ -38,6 +39,23 @@ impl FromStr for Backend {
     }
 }
 
+impl Backend {
+    /// Type of the proofs generated with this backend, `Aligned` if they are meant to be sent
+    /// to Aligned.
+    pub fn prover_type(&self, aligned_mode: bool) -> ProverType {
+        if aligned_mode {
+            return ProverType::Aligned;
+        }
+        match self {
+            Backend::Exec => ProverType::Exec,
+            #[cfg(feature = "sp1")]
+            Backend::SP1 => ProverType::SP1,
+            #[cfg(feature = "risc0")]
+            Backend::RISC0 => ProverType::RISC0,
+        }
+    }
+}
+
 pub enum ProveOutput {
     Exec(ProgramOutput),
     #[cfg(feature = "sp1")]
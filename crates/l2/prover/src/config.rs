@@ -1,3 +1,4 @@
+use secp256k1::SecretKey;
 use serde::Deserialize;
 use url::Url;
 
@@ -9,4 +10,7 @@ pub struct ProverConfig {
     pub proof_coordinator: Url,
     pub proving_time_ms: u64,
     pub aligned_mode: bool,
+    /// Key the requests to the proof coordinator are signed with, if it has an allowlist.
+    #[serde(skip)]
+    pub auth_private_key: Option<SecretKey>,
 }
@@ -1,6 +1,7 @@
 use crate::{backends::Backend, config::ProverConfig, prove, to_batch_proof};
-use ethrex_l2::sequencer::proof_coordinator::{ProofData, get_commit_hash};
-use ethrex_l2_common::prover::BatchProof;
+use ethrex_l2::sequencer::proof_coordinator::{ProofData, generate_prover_id, get_commit_hash};
+use ethrex_l2_common::prover::{BatchProof, ProverType};
+use secp256k1::SecretKey;
 use std::time::Duration;
 use tokio::{
     io::{AsyncReadExt, AsyncWriteExt},
@@ -27,6 +28,9 @@ struct Prover {
     proving_time_ms: u64,
     aligned_mode: bool,
     commit_hash: String,
+    prover_id: u64,
+    prover_type: ProverType,
+    auth_private_key: Option<SecretKey>,
 }
 
 impl Prover {
@@ -37,11 +41,17 @@ impl Prover {
             proving_time_ms: cfg.proving_time_ms,
             aligned_mode: cfg.aligned_mode,
             commit_hash: get_commit_hash(),
+            prover_id: generate_prover_id(),
+            prover_type: cfg.backend.prover_type(cfg.aligned_mode),
+            auth_private_key: cfg.auth_private_key,
         }
     }
 
     pub async fn start(&self) {
-        info!("Prover started on {}", self.proof_coordinator_endpoint);
+        info!(
+            "{} prover {} started on {}",
+            self.prover_type, self.prover_id, self.proof_coordinator_endpoint
+        );
         // Build the prover depending on the prover_type passed as argument.
         loop {
             sleep(Duration::from_millis(self.proving_time_ms)).await;
@@ -77,8 +87,10 @@ impl Prover {
 
     async fn request_new_input(&self) -> Result<Option<ProverData>, String> {
         // Request the input with the correct batch_number
-        let request = ProofData::batch_request(self.commit_hash.clone());
-        let response = connect_to_prover_server_wr(&self.proof_coordinator_endpoint, &request)
+        let request =
+            ProofData::batch_request(self.commit_hash.clone(), self.prover_id, self.prover_type);
+        let response = self
+            .send_request(&request)
             .await
             .map_err(|e| format!("Failed to get Response: {e}"))?;
 
@@ -93,6 +105,11 @@ impl Prover {
                     commit_hash, self.commit_hash
                 ));
             }
+            ProofData::Unauthorized { reason } => {
+                return Err(format!(
+                    "Request rejected by the proof coordinator: {reason}"
+                ));
+            }
             _ => return Err("Expecting ProofData::Response".to_owned()),
         };
 
@@ -111,9 +128,9 @@ impl Prover {
                 db: input.db,
                 elasticity_multiplier: input.elasticity_multiplier,
                 #[cfg(feature = "l2")]
-                blob_commitment: input.blob_commitment,
+                blob_commitments: input.blob_commitments,
                 #[cfg(feature = "l2")]
-                blob_proof: input.blob_proof,
+                blob_proofs: input.blob_proofs,
             },
         }))
     }
@@ -121,17 +138,35 @@ impl Prover {
     async fn submit_proof(&self, batch_number: u64, batch_proof: BatchProof) -> Result<(), String> {
         let submit = ProofData::proof_submit(batch_number, batch_proof);
 
-        let ProofData::ProofSubmitACK { batch_number } =
-            connect_to_prover_server_wr(&self.proof_coordinator_endpoint, &submit)
-                .await
-                .map_err(|e| format!("Failed to get SubmitAck: {e}"))?
-        else {
-            return Err("Expecting ProofData::SubmitAck".to_owned());
+        let batch_number = match self
+            .send_request(&submit)
+            .await
+            .map_err(|e| format!("Failed to get SubmitAck: {e}"))?
+        {
+            ProofData::ProofSubmitACK { batch_number } => batch_number,
+            ProofData::Unauthorized { reason } => {
+                return Err(format!("Proof rejected by the proof coordinator: {reason}"));
+            }
+            _ => return Err("Expecting ProofData::SubmitAck".to_owned()),
         };
 
         info!("Received submit ack for batch_number: {batch_number}");
         Ok(())
     }
+
+    /// Sends the request, signed with the prover's key if it has one.
+    async fn send_request(
+        &self,
+        request: &ProofData,
+    ) -> Result<ProofData, Box<dyn std::error::Error>> {
+        match &self.auth_private_key {
+            Some(key) => {
+                let request = request.authenticated(key)?;
+                connect_to_prover_server_wr(&self.proof_coordinator_endpoint, &request).await
+            }
+            None => connect_to_prover_server_wr(&self.proof_coordinator_endpoint, request).await,
+        }
+    }
 }
 
 async fn connect_to_prover_server_wr(
@@ -21,7 +21,7 @@ use std::collections::HashMap;
 use ethrex_common::{
     kzg::KzgError,
     types::{
-        BlobsBundleError, Commitment, PrivilegedL2Transaction, Proof, Receipt, blob_from_bytes,
+        BlobsBundleError, Commitment, PrivilegedL2Transaction, Proof, Receipt, blobs_from_bytes,
         kzg_commitment_to_versioned_hash,
     },
 };
@@ -31,7 +31,7 @@ use ethrex_l2_common::{
         PrivilegedTransactionError, compute_privileged_transactions_hash,
         get_block_privileged_transactions,
     },
-    state_diff::{StateDiff, StateDiffError, prepare_state_diff},
+    state_diff::{StateDiff, StateDiffError, compute_blobs_hash, prepare_state_diff},
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -98,9 +98,9 @@ pub fn execution_program(input: ProgramInput) -> Result<ProgramOutput, Stateless
         db,
         elasticity_multiplier,
         #[cfg(feature = "l2")]
-        blob_commitment,
+        blob_commitments,
         #[cfg(feature = "l2")]
-        blob_proof,
+        blob_proofs,
     } = input;
 
     let chain_id = db.chain_config.chain_id;
@@ -111,8 +111,8 @@ pub fn execution_program(input: ProgramInput) -> Result<ProgramOutput, Stateless
             &blocks,
             db,
             elasticity_multiplier,
-            blob_commitment,
-            blob_proof,
+            &blob_commitments,
+            &blob_proofs,
             chain_id,
         );
     }
@@ -141,7 +141,7 @@ pub fn stateless_validation_l1(
         #[cfg(feature = "l2")]
         privileged_transactions_hash: H256::zero(),
         #[cfg(feature = "l2")]
-        blob_versioned_hash: H256::zero(),
+        blobs_hash: H256::zero(),
         last_block_hash,
         chain_id: chain_id.into(),
         non_privileged_count,
@@ -153,8 +153,8 @@ pub fn stateless_validation_l2(
     blocks: &[Block],
     db: ExecutionWitnessResult,
     elasticity_multiplier: u64,
-    blob_commitment: Commitment,
-    blob_proof: Proof,
+    blob_commitments: &[Commitment],
+    blob_proofs: &[Proof],
     chain_id: u64,
 ) -> Result<ProgramOutput, StatelessExecutionError> {
     let mut initial_db = ExecutionWitnessResult {
@@ -187,10 +187,10 @@ pub fn stateless_validation_l2(
         )?;
 
     // TODO: this could be replaced with something like a ProverConfig in the future.
-    let validium = (blob_commitment, blob_proof) == ([0; 48], [0; 48]);
+    let validium = blob_commitments.is_empty() && blob_proofs.is_empty();
 
     // Check state diffs are valid
-    let blob_versioned_hash = if !validium {
+    let blobs_hash = if !validium {
         initial_db
             .rebuild_state_trie()
             .map_err(|_| StatelessExecutionError::InvalidInitialStateTrie)?;
@@ -202,7 +202,7 @@ pub fn stateless_validation_l2(
             &privileged_transactions,
             account_updates.values().cloned().collect(),
         )?;
-        verify_blob(state_diff, blob_commitment, blob_proof)?
+        verify_blobs(state_diff, blob_commitments, blob_proofs)?
     } else {
         H256::zero()
     };
@@ -212,7 +212,7 @@ pub fn stateless_validation_l2(
         final_state_hash,
         l1messages_merkle_root,
         privileged_transactions_hash,
-        blob_versioned_hash,
+        blobs_hash,
         last_block_hash,
         chain_id: chain_id.into(),
         non_privileged_count,
@@ -396,20 +396,31 @@ fn compute_l1messages_and_privileged_transactions_digests(
     Ok((l1message_merkle_root, privileged_transactions_hash))
 }
 
+/// Verifies the commitments are to the blobs holding the encoded state diff, which may span
+/// several of them, and returns the hash committed on L1 for those blobs.
 #[cfg(feature = "l2")]
-fn verify_blob(
+fn verify_blobs(
     state_diff: StateDiff,
-    commitment: Commitment,
-    proof: Proof,
+    commitments: &[Commitment],
+    proofs: &[Proof],
 ) -> Result<H256, StatelessExecutionError> {
     use ethrex_common::kzg::verify_blob_kzg_proof;
 
     let encoded_state_diff = state_diff.encode()?;
-    let blob_data = blob_from_bytes(encoded_state_diff)?;
-
-    if !verify_blob_kzg_proof(blob_data, commitment, proof)? {
+    let blobs = blobs_from_bytes(encoded_state_diff)?;
+    if blobs.len() != commitments.len() || blobs.len() != proofs.len() {
         return Err(StatelessExecutionError::InvalidBlobProof);
     }
 
-    Ok(kzg_commitment_to_versioned_hash(&commitment))
+    for ((blob, commitment), proof) in blobs.into_iter().zip(commitments).zip(proofs) {
+        if !verify_blob_kzg_proof(blob, *commitment, *proof)? {
+            return Err(StatelessExecutionError::InvalidBlobProof);
+        }
+    }
+
+    let versioned_hashes: Vec<H256> = commitments
+        .iter()
+        .map(kzg_commitment_to_versioned_hash)
+        .collect();
+    Ok(compute_blobs_hash(&versioned_hashes))
 }
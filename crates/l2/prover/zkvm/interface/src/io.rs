@@ -20,13 +20,13 @@ pub struct ProgramInput {
     /// value used to calculate base fee
     pub elasticity_multiplier: u64,
     #[cfg(feature = "l2")]
-    /// KZG commitment to the blob data
-    #[serde_as(as = "[_; 48]")]
-    pub blob_commitment: blobs_bundle::Commitment,
+    /// KZG commitments to the blobs holding the state diff, empty in validium mode
+    #[serde_as(as = "Vec<[_; 48]>")]
+    pub blob_commitments: Vec<blobs_bundle::Commitment>,
     #[cfg(feature = "l2")]
-    /// KZG opening for a challenge over the blob commitment
-    #[serde_as(as = "[_; 48]")]
-    pub blob_proof: blobs_bundle::Proof,
+    /// KZG openings for a challenge over each blob commitment
+    #[serde_as(as = "Vec<[_; 48]>")]
+    pub blob_proofs: Vec<blobs_bundle::Proof>,
 }
 
 /// JSON serializable program input. This struct is forced to serialize into JSON format.
@@ -43,9 +43,9 @@ impl Default for ProgramInput {
             db: Default::default(),
             elasticity_multiplier: Default::default(),
             #[cfg(feature = "l2")]
-            blob_commitment: [0; 48],
+            blob_commitments: Vec::new(),
             #[cfg(feature = "l2")]
-            blob_proof: [0; 48],
+            blob_proofs: Vec::new(),
         }
     }
 }
@@ -87,8 +87,8 @@ pub struct ProgramOutput {
     /// hash of all the privileged transactions made in a batch
     pub privileged_transactions_hash: H256,
     #[cfg(feature = "l2")]
-    /// blob commitment versioned hash
-    pub blob_versioned_hash: H256,
+    /// keccak hash of the concatenated versioned hashes of the state diff blobs
+    pub blobs_hash: H256,
     /// hash of the last block in a batch
     pub last_block_hash: H256,
     /// chain_id of the network
@@ -107,7 +107,7 @@ impl ProgramOutput {
             #[cfg(feature = "l2")]
             self.privileged_transactions_hash.to_fixed_bytes(),
             #[cfg(feature = "l2")]
-            self.blob_versioned_hash.to_fixed_bytes(),
+            self.blobs_hash.to_fixed_bytes(),
             self.last_block_hash.to_fixed_bytes(),
             self.chain_id.to_big_endian(),
             self.non_privileged_count.to_big_endian(),
@@ -11,24 +11,28 @@ use ethrex_blockchain::{
     payload::{BuildPayloadArgs, create_payload},
     validate_block,
 };
-use ethrex_common::Address;
+use ethrex_common::{Address, types::L1FeeConfig};
+use ethrex_l2_rpc::l2::{preconfirmation::Preconfirmations, user_operation::UserOperationPool};
 use ethrex_storage::Store;
 use ethrex_storage_rollup::StoreRollup;
 use ethrex_vm::BlockExecutionResult;
 use keccak_hash::H256;
-use payload_builder::build_payload;
+pub use payload_builder::build_payload;
 use spawned_concurrency::{
     messages::Unused,
     tasks::{CastResponse, GenServer, GenServerHandle, send_after},
 };
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use crate::{
     BlockProducerConfig, SequencerConfig,
     based::sequencer_state::{SequencerState, SequencerStatus},
 };
 
-use super::errors::BlockProducerError;
+use super::{
+    errors::BlockProducerError,
+    l1_watcher::{L1BlobBaseFee, OverduePrivilegedTransactions},
+};
 
 use ethrex_metrics::metrics;
 #[cfg(feature = "metrics")]
@@ -52,6 +56,11 @@ pub struct BlockProducer {
     coinbase_address: Address,
     elasticity_multiplier: u64,
     rollup_store: StoreRollup,
+    l1_fee_vault_address: Option<Address>,
+    l1_blob_base_fee: L1BlobBaseFee,
+    overdue_privileged_transactions: OverduePrivilegedTransactions,
+    preconfirmations: Preconfirmations,
+    user_operations: Option<UserOperationPool>,
 }
 
 impl BlockProducer {
@@ -61,11 +70,16 @@ impl BlockProducer {
         rollup_store: StoreRollup,
         blockchain: Arc<Blockchain>,
         sequencer_state: SequencerState,
+        l1_blob_base_fee: L1BlobBaseFee,
+        overdue_privileged_transactions: OverduePrivilegedTransactions,
+        preconfirmations: Preconfirmations,
+        user_operations: Option<UserOperationPool>,
     ) -> Self {
         let BlockProducerConfig {
             block_time_ms,
             coinbase_address,
             elasticity_multiplier,
+            l1_fee_vault_address,
         } = config;
         Self {
             store,
@@ -75,6 +89,11 @@ impl BlockProducer {
             coinbase_address: *coinbase_address,
             elasticity_multiplier: *elasticity_multiplier,
             rollup_store,
+            l1_fee_vault_address: *l1_fee_vault_address,
+            l1_blob_base_fee,
+            overdue_privileged_transactions,
+            preconfirmations,
+            user_operations,
         }
     }
 
@@ -84,6 +103,10 @@ impl BlockProducer {
         blockchain: Arc<Blockchain>,
         cfg: SequencerConfig,
         sequencer_state: SequencerState,
+        l1_blob_base_fee: L1BlobBaseFee,
+        overdue_privileged_transactions: OverduePrivilegedTransactions,
+        preconfirmations: Preconfirmations,
+        user_operations: Option<UserOperationPool>,
     ) -> Result<(), BlockProducerError> {
         let mut block_producer = Self::new(
             &cfg.block_producer,
@@ -91,6 +114,10 @@ impl BlockProducer {
             rollup_store,
             blockchain,
             sequencer_state,
+            l1_blob_base_fee,
+            overdue_privileged_transactions,
+            preconfirmations,
+            user_operations,
         )
         .start();
         block_producer
@@ -111,12 +138,36 @@ impl BlockProducer {
         let head_hash = head_header.hash();
         let head_beacon_block_root = H256::zero();
 
+        // Blocks built before knowing the L1 blob base fee wouldn't charge the L1 data fee
+        let l1_fee_config = match self.l1_fee_vault_address {
+            Some(l1_fee_vault) => {
+                let Some(l1_fee_per_blob_gas) = self.l1_blob_base_fee.get() else {
+                    return Err(BlockProducerError::Custom(
+                        "L1 blob base fee not fetched yet, waiting for the L1 watcher".to_string(),
+                    ));
+                };
+                Some(L1FeeConfig {
+                    l1_fee_vault,
+                    l1_fee_per_blob_gas,
+                })
+            }
+            None => None,
+        };
+
         // The proposer leverages the execution payload framework used for the engine API,
         // but avoids calling the API methods and unnecesary re-execution.
 
         info!("Producing block");
         debug!("Head block hash: {head_hash:#x}");
 
+        // Pending user operations are bundled into a transaction that goes through the mempool
+        if let Some(user_operations) = &self.user_operations {
+            let _ = user_operations
+                .bundle(&self.store, &self.blockchain)
+                .await
+                .inspect_err(|err| warn!("Failed to bundle user operations: {err}"));
+        }
+
         // Proposer creates a new payload
         let args = BuildPayloadArgs {
             parent: head_hash,
@@ -128,14 +179,28 @@ impl BlockProducer {
             version,
             elasticity_multiplier: self.elasticity_multiplier,
         };
-        let payload = create_payload(&args, &self.store)?;
+        let mut payload = create_payload(&args, &self.store)?;
+        // The L1 fee parameters are part of the block so that every node charges the same fee
+        if let Some(l1_fee_config) = l1_fee_config {
+            payload.header.extra_data = l1_fee_config.encode_extra_data().into();
+        }
+
+        // New transactions are not preconfirmed while overdue privileged transactions are pending,
+        // so that they can take up the following blocks
+        let only_privileged = self.overdue_privileged_transactions.is_set();
+        let preconfirmed = self
+            .preconfirmations
+            .take(payload.header.number, !only_privileged)
+            .await;
 
-        // Blockchain builds the payload from mempool txs and executes them
-        let payload_build_result = build_payload(
+        // Blockchain builds the payload from preconfirmed and mempool txs and executes them
+        let (payload_build_result, l1_fees) = build_payload(
             self.blockchain.clone(),
             payload,
             &self.store,
             &self.rollup_store,
+            only_privileged,
+            preconfirmed,
         )
         .await?;
         info!(
@@ -176,9 +241,17 @@ impl BlockProducer {
             .store_account_updates_by_block_number(block.header.number, account_updates)
             .await?;
 
+        // The L1 fee is not part of the receipt, so it's kept for the RPC
+        self.rollup_store.store_l1_fees(l1_fees).await?;
+
         // Make the new head be part of the canonical chain
         apply_fork_choice(&self.store, block.hash(), block.hash(), block.hash()).await?;
 
+        // New preconfirmations are checked against the state the next block is built on
+        self.preconfirmations
+            .parent_stored(&block.header, self.elasticity_multiplier)
+            .await;
+
         metrics!(
             let _ = METRICS_BLOCKS
             .set_block_number(block.header.number)
@@ -211,6 +284,8 @@ impl GenServer for BlockProducer {
                 .produce_block()
                 .await
                 .inspect_err(|e| error!("Block Producer Error: {e}"));
+        } else {
+            self.preconfirmations.stop().await;
         }
         send_after(
             Duration::from_millis(self.block_time_ms),
@@ -2,14 +2,12 @@ use crate::sequencer::errors::BlockProducerError;
 use ethrex_blockchain::{
     Blockchain,
     constants::TX_GAS_COST,
-    payload::{
-        HeadTransaction, PayloadBuildContext, PayloadBuildResult, TransactionQueue,
-        apply_plain_transaction,
-    },
+    error::ChainError,
+    payload::{HeadTransaction, PayloadBuildContext, PayloadBuildResult, TransactionQueue},
 };
 use ethrex_common::{
-    Address,
-    types::{Block, Receipt, SAFE_BYTES_PER_BLOB, Transaction, TxType},
+    Address, H256, U256,
+    types::{Block, MempoolTransaction, Receipt, SAFE_BYTES_PER_BLOB, Transaction, TxType},
 };
 use ethrex_l2_common::l1_messages::get_block_l1_messages;
 use ethrex_l2_common::state_diff::{
@@ -37,12 +35,15 @@ const PRIVILEGED_TX_BUDGET: usize = 300;
 /// L2 payload builder
 /// Completes the payload building process, return the block value
 /// Same as `blockchain::build_payload` without applying system operations and using a different `fill_transactions`
+/// Also returns the L1 fee paid by each included transaction
 pub async fn build_payload(
     blockchain: Arc<Blockchain>,
     payload: Block,
     store: &Store,
     rollup_store: &StoreRollup,
-) -> Result<PayloadBuildResult, BlockProducerError> {
+    only_privileged: bool,
+    preconfirmed: Vec<MempoolTransaction>,
+) -> Result<(PayloadBuildResult, Vec<(H256, U256)>), BlockProducerError> {
     let since = Instant::now();
     let gas_limit = payload.header.gas_limit;
 
@@ -54,7 +55,15 @@ pub async fn build_payload(
         blockchain.r#type.clone(),
     )?;
 
-    fill_transactions(blockchain.clone(), &mut context, store, rollup_store).await?;
+    let l1_fees = fill_transactions(
+        blockchain.clone(),
+        &mut context,
+        store,
+        rollup_store,
+        only_privileged,
+        preconfirmed,
+    )
+    .await?;
     blockchain.finalize_payload(&mut context).await?;
 
     let interval = Instant::now().duration_since(since).as_millis();
@@ -90,25 +99,64 @@ pub async fn build_payload(
             .inspect_err(|e| tracing::error!("Failed to set metrics for: blob tx mempool size {}", e.to_string()));
     );
 
-    Ok(context.into())
+    Ok((context.into(), l1_fees))
 }
 
 /// Same as `blockchain::fill_transactions` but enforces that the `StateDiff` size
 /// stays within the blob size limit after processing each transaction.
+/// If `only_privileged` is set, non-privileged transactions are left in the mempool so that
+/// overdue privileged transactions are included before their deadline.
+/// `preconfirmed` transactions are included first, in the order they were promised.
+/// Returns the L1 fee paid by each included transaction.
 pub async fn fill_transactions(
     blockchain: Arc<Blockchain>,
     context: &mut PayloadBuildContext,
     store: &Store,
     rollup_store: &StoreRollup,
-) -> Result<(), BlockProducerError> {
-    // version (u8) + header fields (struct) + messages_len (u16) + privileged_tx_len (u16) + accounts_diffs_len (u16)
-    let mut acc_size_without_accounts = 1 + BLOCK_HEADER_LEN + 2 + 2 + 2;
-    let mut size_accounts_diffs = 0;
-    let mut account_diffs = HashMap::new();
+    only_privileged: bool,
+    preconfirmed: Vec<MempoolTransaction>,
+) -> Result<Vec<(H256, U256)>, BlockProducerError> {
+    let mut state_diff_size = BlockStateDiffSize::default();
+    let mut l1_fees = Vec::new();
     let safe_bytes_per_blob: u64 = SAFE_BYTES_PER_BLOB.try_into()?;
 
     let chain_config = store.get_chain_config()?;
 
+    for (index, tx) in preconfirmed.into_iter().enumerate() {
+        let tx_hash = tx.hash();
+        // Skipping a preconfirmed transaction also breaks the preconfirmations that follow it,
+        // since they end up at a lower index than promised
+        let Some(tip) = tx.effective_gas_tip(context.payload.header.base_fee_per_gas) else {
+            error!("Breaking preconfirmation {index} of transaction {tx_hash:#x}: fee too low");
+            continue;
+        };
+        let head_tx = HeadTransaction { tx, tip };
+        if context.remaining_gas < head_tx.tx.gas_limit() {
+            error!("Breaking preconfirmation {index} of transaction {tx_hash:#x}: no gas left");
+            continue;
+        }
+        let (receipt, l1_fee) = match apply_l2_transaction(&head_tx, context) {
+            Ok(result) => result,
+            Err(e) => {
+                error!("Breaking preconfirmation {index} of transaction {tx_hash:#x}: {e}");
+                metrics!(METRICS_TX.inc_tx_errors(e.to_metric()));
+                continue;
+            }
+        };
+        if !state_diff_size.fit_last_tx(context, &head_tx, &receipt, safe_bytes_per_blob)? {
+            error!(
+                "Breaking preconfirmation {index} of transaction {tx_hash:#x}: no StateDiff space left"
+            );
+            continue;
+        }
+        // In case it was also sent to the mempool
+        blockchain.remove_transaction_from_pool(&tx_hash)?;
+        debug!("Adding preconfirmed transaction: {} to payload", tx_hash);
+        context.payload.body.transactions.push(head_tx.into());
+        context.receipts.push(receipt);
+        l1_fees.push((tx_hash, l1_fee));
+    }
+
     debug!("Fetching transactions from mempool");
     // Fetch mempool transactions
     let latest_block_number = store.get_latest_block_number().await?;
@@ -124,9 +172,7 @@ pub async fn fill_transactions(
         };
 
         // Check if we have enough space for the StateDiff to run more transactions
-        if acc_size_without_accounts + size_accounts_diffs + SIMPLE_TX_STATE_DIFF_SIZE
-            > safe_bytes_per_blob
-        {
+        if state_diff_size.total() + SIMPLE_TX_STATE_DIFF_SIZE > safe_bytes_per_blob {
             debug!("No more StateDiff space to run transactions");
             break;
         };
@@ -136,6 +182,12 @@ pub async fn fill_transactions(
             break;
         };
 
+        // Privileged transactions are sorted first, so there are none left
+        if only_privileged && !head_tx.is_privileged() {
+            debug!("Only including privileged transactions, overdue ones are pending");
+            break;
+        }
+
         // Check we don't have an excessive number of privileged transactions
         if head_tx.tx_type() == TxType::Privileged {
             let id = head_tx.nonce();
@@ -191,8 +243,8 @@ pub async fn fill_transactions(
         }
 
         // Execute tx
-        let receipt = match apply_plain_transaction(&head_tx, context) {
-            Ok(receipt) => receipt,
+        let (receipt, l1_fee) = match apply_l2_transaction(&head_tx, context) {
+            Ok(result) => result,
             Err(e) => {
                 debug!("Failed to execute transaction: {}, {e}", tx_hash);
                 metrics!(METRICS_TX.inc_tx_errors(e.to_metric()));
@@ -202,24 +254,12 @@ pub async fn fill_transactions(
             }
         };
 
-        let account_diffs_in_tx = get_account_diffs_in_tx(context)?;
-        let merged_diffs = merge_diffs(&account_diffs, account_diffs_in_tx);
-
-        let (tx_size_without_accounts, new_accounts_diff_size) =
-            calculate_tx_diff_size(&merged_diffs, &head_tx, &receipt)?;
-
-        if acc_size_without_accounts + tx_size_without_accounts + new_accounts_diff_size
-            > safe_bytes_per_blob
-        {
+        if !state_diff_size.fit_last_tx(context, &head_tx, &receipt, safe_bytes_per_blob)? {
             debug!(
                 "No more StateDiff space to run this transactions. Skipping transaction: {:?}",
                 tx_hash
             );
             txs.pop();
-
-            // This transaction state change is too big, we need to undo it.
-            context.vm.undo_last_tx()?;
-
             continue;
         }
 
@@ -227,16 +267,12 @@ pub async fn fill_transactions(
         // Pull transaction from the mempool
         blockchain.remove_transaction_from_pool(&head_tx.tx.hash())?;
 
-        // We only add the messages and privileged transaction length because the accounts diffs may change
-        acc_size_without_accounts += tx_size_without_accounts;
-        size_accounts_diffs = new_accounts_diff_size;
-        // Include the new accounts diffs
-        account_diffs = merged_diffs;
         // Add transaction to block
         debug!("Adding transaction: {} to payload", tx_hash);
         context.payload.body.transactions.push(head_tx.into());
         // Save receipt for hash calculation
         context.receipts.push(receipt);
+        l1_fees.push((tx_hash, l1_fee));
     }
 
     rollup_store
@@ -252,7 +288,77 @@ pub async fn fill_transactions(
             .for_each(|tx| METRICS_TX.inc_tx_with_type(MetricsTxType(tx.tx_type())))
     );
 
-    Ok(())
+    Ok(l1_fees)
+}
+
+/// Same as `blockchain::payload::apply_plain_transaction` but also returns the L1 fee paid by the transaction
+fn apply_l2_transaction(
+    head: &HeadTransaction,
+    context: &mut PayloadBuildContext,
+) -> Result<(Receipt, U256), ChainError> {
+    let (receipt, gas_used, l1_fee) = context.vm.execute_tx_with_l1_fee(
+        &head.tx,
+        &context.payload.header,
+        &mut context.remaining_gas,
+        head.tx.sender(),
+    )?;
+    context.block_value += U256::from(gas_used) * head.tip;
+    Ok((receipt, l1_fee))
+}
+
+/// Size of the `StateDiff` of the transactions added to the payload so far.
+struct BlockStateDiffSize {
+    size_without_accounts: u64,
+    size_accounts_diffs: u64,
+    account_diffs: HashMap<Address, AccountStateDiff>,
+}
+
+impl Default for BlockStateDiffSize {
+    fn default() -> Self {
+        Self {
+            // version (u8) + header fields (struct) + messages_len (u16) + privileged_tx_len (u16) + accounts_diffs_len (u16)
+            size_without_accounts: 1 + BLOCK_HEADER_LEN + 2 + 2 + 2,
+            size_accounts_diffs: 0,
+            account_diffs: HashMap::new(),
+        }
+    }
+}
+
+impl BlockStateDiffSize {
+    fn total(&self) -> u64 {
+        self.size_without_accounts + self.size_accounts_diffs
+    }
+
+    /// Adds the state diff of the last executed transaction if the result fits in a blob.
+    /// Otherwise the transaction is undone and `false` is returned.
+    fn fit_last_tx(
+        &mut self,
+        context: &mut PayloadBuildContext,
+        head_tx: &HeadTransaction,
+        receipt: &Receipt,
+        safe_bytes_per_blob: u64,
+    ) -> Result<bool, BlockProducerError> {
+        let account_diffs_in_tx = get_account_diffs_in_tx(context)?;
+        let merged_diffs = merge_diffs(&self.account_diffs, account_diffs_in_tx);
+
+        let (tx_size_without_accounts, new_accounts_diff_size) =
+            calculate_tx_diff_size(&merged_diffs, head_tx, receipt)?;
+
+        if self.size_without_accounts + tx_size_without_accounts + new_accounts_diff_size
+            > safe_bytes_per_blob
+        {
+            // This transaction state change is too big, we need to undo it.
+            context.vm.undo_last_tx()?;
+            return Ok(false);
+        }
+
+        // We only add the messages and privileged transaction length because the accounts diffs may change
+        self.size_without_accounts += tx_size_without_accounts;
+        self.size_accounts_diffs = new_accounts_diff_size;
+        // Include the new accounts diffs
+        self.account_diffs = merged_diffs;
+        Ok(true)
+    }
 }
 
 // TODO: Once #2857 is implemented, we can completely ignore the blobs pool.
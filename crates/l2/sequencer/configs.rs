@@ -1,5 +1,5 @@
 use aligned_sdk::common::types::Network;
-use ethrex_common::{Address, U256};
+use ethrex_common::{Address, U256, types::Fork};
 use ethrex_l2_rpc::signer::Signer;
 use reqwest::Url;
 use secp256k1::SecretKey;
@@ -23,6 +23,7 @@ pub struct BlockProducerConfig {
     pub block_time_ms: u64,
     pub coinbase_address: Address,
     pub elasticity_multiplier: u64,
+    pub l1_fee_vault_address: Option<Address>,
 }
 
 #[derive(Clone, Debug)]
@@ -32,6 +33,8 @@ pub struct CommitterConfig {
     pub arbitrary_base_blob_gas_price: u64,
     pub validium: bool,
     pub signer: Signer,
+    /// Fork of the L1, which sets the blob limit and the blob proofs of the commit transactions.
+    pub l1_fork: Fork,
 }
 
 #[derive(Clone, Debug)]
@@ -58,6 +61,9 @@ pub struct ProofCoordinatorConfig {
     pub listen_ip: IpAddr,
     pub listen_port: u16,
     pub proof_send_interval_ms: u64,
+    pub lease_timeout_ms: u64,
+    pub prover_allowlist: Vec<Address>,
+    pub allow_any_prover: bool,
     pub signer: Signer,
     pub validium: bool,
     pub tdx_private_key: Option<SecretKey>,
@@ -117,6 +117,41 @@ pub enum ProofCoordinatorError {
     MissingBlob(u64),
     #[error("Missing TDX private key")]
     MissingTDXPrivateKey,
+    #[error(
+        "No prover allowlist set, pass --proof-coordinator.prover-allowlist or explicitly accept any prover with --proof-coordinator.allow-any-prover"
+    )]
+    MissingProverAllowlist,
+}
+
+/// Reasons for the proof coordinator to reject a prover's request.
+#[derive(Debug, thiserror::Error)]
+pub enum ProverAuthError {
+    #[error("The request is not authenticated")]
+    Unauthenticated,
+    #[error("Invalid request signature: {0}")]
+    InvalidSignature(String),
+    #[error("Prover {0:#x} is not in the allowlist")]
+    UnknownProver(Address),
+    #[error("The request timestamp {0} is too far from the current time")]
+    StaleRequest(u64),
+    #[error("Failed to parse the authenticated request: {0}")]
+    InvalidPayload(#[from] serde_json::Error),
+    #[error("Request from prover {0:#x} was already received")]
+    ReplayedRequest(Address),
+}
+
+impl ProverAuthError {
+    /// Label used to meter the rejected requests.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProverAuthError::Unauthenticated => "unauthenticated",
+            ProverAuthError::InvalidSignature(_) => "invalid_signature",
+            ProverAuthError::UnknownProver(_) => "unknown_prover",
+            ProverAuthError::StaleRequest(_) => "stale_request",
+            ProverAuthError::InvalidPayload(_) => "invalid_payload",
+            ProverAuthError::ReplayedRequest(_) => "replayed_request",
+        }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+
+use ethrex_common::H256;
+use ethrex_rpc::{
+    clients::{EthClientError, eth::EthClient},
+    types::block_identifier::BlockIdentifier,
+};
+
+/// Max depth of an L1 reorg the sequencer recovers from. Blocks this deep are considered final
+/// and stop being tracked.
+pub const MAX_L1_REORG_DEPTH: u64 = 64;
+
+/// Remembers the hashes of the L1 blocks a sequencer component acted upon (the blocks it fetched
+/// logs from or where its transactions were mined), along with what it did in each of them, so
+/// that it can tell when they are reorged out of the canonical chain.
+#[derive(Debug)]
+pub struct L1BlockTracker<T> {
+    blocks: BTreeMap<u64, (H256, Vec<T>)>,
+}
+
+/// Tracked blocks that are no longer part of the L1 canonical chain.
+#[derive(Debug, PartialEq)]
+pub struct L1Reorg<T> {
+    /// Latest tracked block that is still canonical, the component should resume from it.
+    pub last_canonical_block: Option<u64>,
+    /// First tracked block that was reorged out.
+    pub first_reorged_block: u64,
+    /// Items of the reorged blocks, in block order.
+    pub reorged: Vec<T>,
+}
+
+impl<T> Default for L1BlockTracker<T> {
+    fn default() -> Self {
+        Self {
+            blocks: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T> L1BlockTracker<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts tracking a block, adding `items` to the ones already tracked for it. If the block
+    /// was tracked with a different hash, the previous items are replaced.
+    pub fn track(&mut self, number: u64, hash: H256, items: impl IntoIterator<Item = T>) {
+        let entry = self.blocks.entry(number).or_insert((hash, Vec::new()));
+        if entry.0 != hash {
+            *entry = (hash, Vec::new());
+        }
+        entry.1.extend(items);
+    }
+
+    pub fn latest_block(&self) -> Option<u64> {
+        self.blocks.last_key_value().map(|(number, _)| *number)
+    }
+
+    /// Compares the tracked blocks against the L1 canonical chain. Reorged blocks stop being
+    /// tracked and are returned, and blocks deeper than [`MAX_L1_REORG_DEPTH`] are forgotten.
+    pub async fn check_reorg(
+        &mut self,
+        eth_client: &EthClient,
+    ) -> Result<Option<L1Reorg<T>>, EthClientError> {
+        let latest_l1_block: u64 = eth_client
+            .get_block_number()
+            .await?
+            .try_into()
+            .map_err(|_| EthClientError::Custom("L1 block number overflows u64".to_owned()))?;
+
+        // If a block is canonical so are its ancestors, so we walk back from the latest one
+        let mut first_reorged_block = None;
+        for (&number, (hash, _)) in self.blocks.iter().rev() {
+            if number <= latest_l1_block
+                && eth_client
+                    .get_block_by_number(BlockIdentifier::Number(number))
+                    .await?
+                    .hash
+                    == *hash
+            {
+                break;
+            }
+            first_reorged_block = Some(number);
+        }
+
+        let reorg = first_reorged_block.map(|first_reorged_block| {
+            let reorged = self.blocks.split_off(&first_reorged_block);
+            L1Reorg {
+                last_canonical_block: self.latest_block(),
+                first_reorged_block,
+                reorged: reorged.into_values().flat_map(|(_, items)| items).collect(),
+            }
+        });
+
+        self.blocks = self
+            .blocks
+            .split_off(&latest_l1_block.saturating_sub(MAX_L1_REORG_DEPTH));
+
+        Ok(reorg)
+    }
+}
@@ -9,8 +9,9 @@ use ethrex_blockchain::{Blockchain, vm::StoreVmDatabase};
 use ethrex_common::{
     Address, H256, U256,
     types::{
-        AccountUpdate, BLOB_BASE_FEE_UPDATE_FRACTION, BlobsBundle, Block, BlockNumber,
-        MIN_BASE_FEE_PER_BLOB_GAS, TxType, batch::Batch, blobs_bundle, fake_exponential_checked,
+        AccountUpdate, BLOB_BASE_FEE_UPDATE_FRACTION, BlobsBundle, BlobsBundleError, Block,
+        BlockNumber, Fork, MIN_BASE_FEE_PER_BLOB_GAS, SAFE_BYTES_PER_BLOB, TxType, batch::Batch,
+        blobs_bundle, fake_exponential_checked,
     },
 };
 use ethrex_l2_common::{
@@ -38,7 +39,11 @@ use ethrex_storage_rollup::StoreRollup;
 use std::{collections::HashMap, sync::Arc};
 use tracing::{debug, error, info, warn};
 
-use super::{errors::BlobEstimationError, utils::random_duration};
+use super::{
+    errors::BlobEstimationError,
+    l1_block_tracker::L1BlockTracker,
+    utils::{forget_reorged_batches, random_duration},
+};
 use spawned_concurrency::{
     messages::Unused,
     tasks::{CastResponse, GenServer, GenServerHandle, send_after},
@@ -70,8 +75,12 @@ pub struct L1Committer {
     arbitrary_base_blob_gas_price: u64,
     validium: bool,
     signer: Signer,
+    /// Fork of the L1, which sets the blob limit and the blob proofs of the commit transactions.
+    l1_fork: Fork,
     based: bool,
     sequencer_state: SequencerState,
+    /// L1 blocks where commit transactions were mined, with the batches they committed.
+    commit_l1_blocks: L1BlockTracker<u64>,
 }
 
 impl L1Committer {
@@ -103,8 +112,10 @@ impl L1Committer {
             arbitrary_base_blob_gas_price: committer_config.arbitrary_base_blob_gas_price,
             validium: committer_config.validium,
             signer: committer_config.signer.clone(),
+            l1_fork: committer_config.l1_fork,
             based,
             sequencer_state,
+            commit_l1_blocks: L1BlockTracker::new(),
         })
     }
 
@@ -133,6 +144,16 @@ impl L1Committer {
 
     async fn commit_next_batch_to_l1(&mut self) -> Result<(), CommitterError> {
         info!("Running committer main loop");
+        // The next batch to commit is taken from the L1 state, so reorged commitments are sent
+        // again as soon as they are no longer part of the canonical chain.
+        if let Some(reorg) = self.commit_l1_blocks.check_reorg(&self.eth_client).await? {
+            warn!(
+                "L1 reorg detected at block {}, commitments for batches {:?} will be sent again",
+                reorg.first_reorged_block, reorg.reorged
+            );
+            forget_reorged_batches(&self.rollup_store, &reorg.reorged, true).await?;
+        }
+
         // Get the batch to commit
         let last_committed_batch_number = self
             .eth_client
@@ -225,6 +246,18 @@ impl L1Committer {
                     .store_commit_tx_by_batch(batch.number, commit_tx_hash)
                     .await?;
 
+                if let Some(receipt) = self
+                    .eth_client
+                    .get_transaction_receipt(commit_tx_hash)
+                    .await?
+                {
+                    self.commit_l1_blocks.track(
+                        receipt.block_info.block_number,
+                        receipt.block_info.block_hash,
+                        [batch.number],
+                    );
+                }
+
                 info!(
                     "Commitment sent for batch {}, with tx hash {commit_tx_hash:#x}.",
                     batch.number
@@ -243,7 +276,9 @@ impl L1Committer {
         mut last_added_block_number: BlockNumber,
     ) -> Result<(BlobsBundle, H256, Vec<H256>, H256, BlockNumber), CommitterError> {
         let first_block_of_batch = last_added_block_number + 1;
-        let mut blobs_bundle = BlobsBundle::default();
+        let max_blobs = max_blobs_per_batch(self.l1_fork);
+        // State diff of the blocks added so far, the blobs bundle is generated from it once the batch is sealed.
+        let mut batch_state_diff = None;
 
         let mut acc_messages = vec![];
         let mut acc_privileged_txs = vec![];
@@ -254,7 +289,6 @@ impl L1Committer {
 
         #[cfg(feature = "metrics")]
         let mut tx_count = 0_u64;
-        let mut _blob_size = 0_usize;
 
         info!("Preparing state diff from block {first_block_of_batch}");
 
@@ -346,7 +380,7 @@ impl L1Committer {
                 .parent_hash;
             let parent_db = StoreVmDatabase::new(self.store.clone(), parent_block_hash);
 
-            let result = if !self.validium {
+            if !self.validium {
                 // Prepare current state diff.
                 let state_diff = prepare_state_diff(
                     block_to_commit_header,
@@ -355,22 +389,22 @@ impl L1Committer {
                     &acc_privileged_txs,
                     acc_account_updates.clone().into_values().collect(),
                 )?;
-                generate_blobs_bundle(&state_diff)
-            } else {
-                Ok((BlobsBundle::default(), 0_usize))
-            };
-
-            let Ok((bundle, latest_blob_size)) = result else {
-                warn!(
-                    "Batch size limit reached. Any remaining blocks will be processed in the next batch."
-                );
-                // Break loop. Use the previous generated blobs_bundle.
-                break;
-            };
+                // The state diff is only compressed once the batch is sealed, here a bound of
+                // its encoded size is enough to know whether it still fits in the blobs.
+                let fits_in_blobs = state_diff
+                    .encoded_size_bound()
+                    .is_ok_and(|size| size.div_ceil(SAFE_BYTES_PER_BLOB) <= max_blobs);
+                if !fits_in_blobs {
+                    warn!(
+                        "Batch size limit reached. Any remaining blocks will be processed in the next batch."
+                    );
+                    // Break loop. Use the previous state diff.
+                    break;
+                }
 
-            // Save current blobs_bundle and continue to add more blocks.
-            blobs_bundle = bundle;
-            _blob_size = latest_blob_size;
+                // Save current state diff and continue to add more blocks.
+                batch_state_diff = Some(state_diff);
+            }
 
             privileged_transactions_hashes.extend(
                 privileged_transactions
@@ -390,7 +424,8 @@ impl L1Committer {
             last_added_block_number += 1;
         }
 
-        metrics!(if let (Ok(privileged_transaction_count), Ok(messages_count)) = (
+        metrics!(
+            if let (Ok(privileged_transaction_count), Ok(messages_count)) = (
                 privileged_transactions_hashes.len().try_into(),
                 message_hashes.len().try_into()
             ) {
@@ -402,8 +437,17 @@ impl L1Committer {
                         tracing::error!("Failed to update operations metric: {}", e.to_string())
                     });
             }
+        );
+
+        let (blobs_bundle, _blob_size) = match batch_state_diff {
+            Some(state_diff) => generate_blobs_bundle(&state_diff, self.l1_fork)?,
+            None => (BlobsBundle::default(), 0),
+        };
+
+        metrics!(
             #[allow(clippy::as_conversions)]
-            let blob_usage_percentage = _blob_size as f64 * 100_f64 / ethrex_common::types::BYTES_PER_BLOB_F64;
+            let blob_usage_percentage = _blob_size as f64 * 100_f64
+                / (blobs_bundle.blobs.len().max(1) as f64 * ethrex_common::types::BYTES_PER_BLOB_F64);
             METRICS.set_blob_usage_percentage(blob_usage_percentage);
         );
 
@@ -559,20 +603,33 @@ impl GenServer for L1Committer {
     }
 }
 
-/// Generate the blob bundle necessary for the EIP-4844 transaction.
+/// Maximum amount of blobs a batch's state diff can span, as they are all sent in its commit transaction.
+pub fn max_blobs_per_batch(l1_fork: Fork) -> usize {
+    usize::try_from(blobs_bundle::max_blobs_per_tx(l1_fork)).unwrap_or(1)
+}
+
+/// Generate the blobs bundle necessary for the EIP-4844 transaction.
+/// The encoded state diff is split across as many blobs as `l1_fork` allows in a transaction,
+/// which also decides whether the bundle carries blob proofs or, from Osaka, cell proofs.
 pub fn generate_blobs_bundle(
     state_diff: &StateDiff,
+    l1_fork: Fork,
 ) -> Result<(BlobsBundle, usize), CommitterError> {
     let blob_data = state_diff.encode().map_err(CommitterError::from)?;
 
     let blob_size = blob_data.len();
 
-    let blob = blobs_bundle::blob_from_bytes(blob_data).map_err(CommitterError::from)?;
+    let blobs = blobs_bundle::blobs_from_bytes(blob_data).map_err(CommitterError::from)?;
+    if blobs.len() > max_blobs_per_batch(l1_fork) {
+        return Err(BlobsBundleError::MaxBlobsExceeded.into());
+    }
 
-    Ok((
-        BlobsBundle::create_from_blobs(&vec![blob]).map_err(CommitterError::from)?,
-        blob_size,
-    ))
+    let bundle = if l1_fork >= Fork::Osaka {
+        BlobsBundle::create_from_blobs_with_cell_proofs(&blobs)
+    } else {
+        BlobsBundle::create_from_blobs(&blobs)
+    };
+    Ok((bundle.map_err(CommitterError::from)?, blob_size))
 }
 
 fn get_last_block_hash(
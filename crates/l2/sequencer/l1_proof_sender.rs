@@ -13,11 +13,12 @@ use spawned_concurrency::{
     messages::Unused,
     tasks::{CastResponse, GenServer, GenServerHandle, send_after},
 };
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use super::{
     configs::AlignedConfig,
-    utils::{get_latest_sent_batch, random_duration, send_verify_tx},
+    l1_block_tracker::L1BlockTracker,
+    utils::{forget_reorged_batches, get_latest_sent_batch, random_duration, send_verify_tx},
 };
 
 use crate::{
@@ -56,6 +57,8 @@ pub struct L1ProofSender {
     network: Network,
     fee_estimate: FeeEstimationType,
     aligned_sp1_elf_path: String,
+    /// L1 blocks where verify transactions were mined, with the batches they verified.
+    verify_l1_blocks: L1BlockTracker<u64>,
 }
 
 impl L1ProofSender {
@@ -87,6 +90,7 @@ impl L1ProofSender {
             network: aligned_cfg.network.clone(),
             fee_estimate,
             aligned_sp1_elf_path,
+            verify_l1_blocks: L1BlockTracker::new(),
         })
     }
 
@@ -114,6 +118,17 @@ impl L1ProofSender {
     }
 
     async fn verify_and_send_proof(&mut self) -> Result<(), ProofSenderError> {
+        // Proofs are kept in the rollup store, so reorged ones are sent again: the next batch to
+        // verify is taken from the L1 state, or from the latest sent proof in Aligned mode, which
+        // is rewound here and by the committer when the batch commitment is reorged.
+        if let Some(reorg) = self.verify_l1_blocks.check_reorg(&self.eth_client).await? {
+            warn!(
+                "L1 reorg detected at block {}, proofs for batches {:?} will be sent again",
+                reorg.first_reorged_block, reorg.reorged
+            );
+            forget_reorged_batches(&self.rollup_store, &reorg.reorged, false).await?;
+        }
+
         let batch_to_send = 1 + get_latest_sent_batch(
             self.needed_proof_types.clone(),
             &self.rollup_store,
@@ -290,6 +305,18 @@ impl L1ProofSender {
             .store_verify_tx_by_batch(batch_number, verify_tx_hash)
             .await?;
 
+        if let Some(receipt) = self
+            .eth_client
+            .get_transaction_receipt(verify_tx_hash)
+            .await?
+        {
+            self.verify_l1_blocks.track(
+                receipt.block_info.block_number,
+                receipt.block_info.block_hash,
+                [batch_number],
+            );
+        }
+
         info!(
             ?batch_number,
             ?verify_tx_hash,
@@ -1,3 +1,4 @@
+use super::l1_block_tracker::{L1BlockTracker, MAX_L1_REORG_DEPTH};
 use super::utils::random_duration;
 use crate::based::sequencer_state::{SequencerState, SequencerStatus};
 use crate::{EthConfig, L1WatcherConfig, SequencerConfig};
@@ -8,6 +9,7 @@ use ethrex_blockchain::Blockchain;
 use ethrex_common::types::{PrivilegedL2Transaction, TxType};
 use ethrex_common::{H160, types::Transaction};
 use ethrex_rpc::clients::EthClientError;
+use ethrex_rpc::types::block_identifier::{BlockIdentifier, BlockTag};
 use ethrex_rpc::types::receipt::RpcLog;
 use ethrex_rpc::{
     clients::eth::{EthClient, Overrides},
@@ -19,7 +21,13 @@ use spawned_concurrency::messages::Unused;
 use spawned_concurrency::tasks::{
     CastResponse, GenServer, GenServerHandle, InitResult, Success, send_after,
 };
-use std::{cmp::min, sync::Arc};
+use std::{
+    cmp::min,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+};
 use tracing::{debug, error, info, warn};
 
 #[derive(Clone)]
@@ -34,6 +42,44 @@ pub enum OutMessage {
     Error,
 }
 
+/// Latest L1 blob base fee observed by the [`L1Watcher`], used to price the L1 data fee of the
+/// blocks built by the sequencer.
+/// The L1 blob base fee is at least 1 wei, so 0 means it wasn't polled yet.
+#[derive(Debug, Clone, Default)]
+pub struct L1BlobBaseFee(Arc<AtomicU64>);
+
+impl L1BlobBaseFee {
+    /// Returns `None` until the [`L1Watcher`] polls the L1 for the first time.
+    pub fn get(&self) -> Option<u64> {
+        Some(self.0.load(Ordering::Relaxed)).filter(|blob_base_fee| *blob_base_fee != 0)
+    }
+
+    pub fn set(&self, blob_base_fee: u64) {
+        self.0.store(blob_base_fee, Ordering::Relaxed);
+    }
+}
+
+/// Time before a privileged transaction's inclusion deadline at which the sequencer starts
+/// prioritizing it, leaving room for its batch to be sealed and committed.
+const FORCED_INCLUSION_MARGIN_SECS: u64 = 600;
+
+/// Set by the [`L1Watcher`] while a pending privileged transaction that wasn't included in an
+/// L2 block yet is about to exceed its inclusion deadline. The `OnChainProposer` rejects batch
+/// commitments that skip overdue privileged transactions, so while set the block producer only
+/// includes privileged transactions.
+#[derive(Debug, Clone, Default)]
+pub struct OverduePrivilegedTransactions(Arc<AtomicBool>);
+
+impl OverduePrivilegedTransactions {
+    pub fn is_set(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn set(&self, overdue: bool) {
+        self.0.store(overdue, Ordering::Relaxed);
+    }
+}
+
 pub struct L1Watcher {
     pub store: Store,
     pub blockchain: Arc<Blockchain>,
@@ -45,6 +91,10 @@ pub struct L1Watcher {
     pub check_interval: u64,
     pub l1_block_delay: u64,
     pub sequencer_state: SequencerState,
+    pub l1_blob_base_fee: L1BlobBaseFee,
+    pub overdue_privileged_transactions: OverduePrivilegedTransactions,
+    /// L1 blocks whose logs were fetched, with the privileged transactions added from each.
+    pub fetched_l1_blocks: L1BlockTracker<H256>,
 }
 
 impl L1Watcher {
@@ -54,6 +104,8 @@ impl L1Watcher {
         eth_config: &EthConfig,
         watcher_config: &L1WatcherConfig,
         sequencer_state: SequencerState,
+        l1_blob_base_fee: L1BlobBaseFee,
+        overdue_privileged_transactions: OverduePrivilegedTransactions,
     ) -> Result<Self, L1WatcherError> {
         let eth_client = EthClient::new_with_multiple_urls(eth_config.rpc_url.clone())?;
         let l2_client = EthClient::new("http://localhost:1729")?;
@@ -69,6 +121,9 @@ impl L1Watcher {
             check_interval: watcher_config.check_interval_ms,
             l1_block_delay: watcher_config.watcher_block_delay,
             sequencer_state,
+            l1_blob_base_fee,
+            overdue_privileged_transactions,
+            fetched_l1_blocks: L1BlockTracker::new(),
         })
     }
 
@@ -77,6 +132,8 @@ impl L1Watcher {
         blockchain: Arc<Blockchain>,
         cfg: SequencerConfig,
         sequencer_state: SequencerState,
+        l1_blob_base_fee: L1BlobBaseFee,
+        overdue_privileged_transactions: OverduePrivilegedTransactions,
     ) -> Result<(), L1WatcherError> {
         let state = Self::new(
             store,
@@ -84,12 +141,24 @@ impl L1Watcher {
             &cfg.eth,
             &cfg.l1_watcher,
             sequencer_state,
+            l1_blob_base_fee,
+            overdue_privileged_transactions,
         )?;
         state.start();
         Ok(())
     }
 
     async fn watch(&mut self) {
+        let _ = self
+            .handle_l1_reorg()
+            .await
+            .inspect_err(|err| error!("L1 Watcher Error: {err}"));
+
+        let _ = self
+            .update_l1_blob_base_fee()
+            .await
+            .inspect_err(|err| error!("L1 Watcher Error: {err}"));
+
         let Ok(logs) = self
             .get_privileged_transactions()
             .await
@@ -105,6 +174,115 @@ impl L1Watcher {
                 .await
                 .inspect_err(|err| error!("L1 Watcher Error: {}", err));
         };
+
+        let _ = self
+            .update_overdue_privileged_transactions()
+            .await
+            .inspect_err(|err| error!("L1 Watcher Error: {err}"));
+    }
+
+    async fn update_l1_blob_base_fee(&mut self) -> Result<(), L1WatcherError> {
+        let blob_base_fee = self
+            .eth_client
+            .get_blob_base_fee()
+            .await?
+            .try_into()
+            .map_err(|_| L1WatcherError::Custom("L1 blob base fee overflows u64".to_string()))?;
+        debug!("L1 blob base fee: {blob_base_fee}");
+        self.l1_blob_base_fee.set(blob_base_fee);
+        Ok(())
+    }
+
+    /// Checks the inclusion deadline of the oldest pending privileged transaction that wasn't
+    /// included in an L2 block yet. Deadlines are assigned in order, so it's the first to expire.
+    async fn update_overdue_privileged_transactions(&mut self) -> Result<(), L1WatcherError> {
+        let pending_privileged_transactions = self
+            .eth_client
+            .get_pending_privileged_transactions(self.address)
+            .await?;
+
+        let mut first_not_included = None;
+        for tx_hash in pending_privileged_transactions {
+            if self
+                .store
+                .get_transaction_by_hash(tx_hash)
+                .await
+                .map_err(L1WatcherError::FailedAccessingStore)?
+                .is_none()
+            {
+                first_not_included = Some(tx_hash);
+                break;
+            }
+        }
+
+        let Some(tx_hash) = first_not_included else {
+            self.overdue_privileged_transactions.set(false);
+            return Ok(());
+        };
+
+        let deadline = self
+            .eth_client
+            .get_privileged_transaction_deadline(self.address, tx_hash)
+            .await?;
+        let l1_timestamp = self
+            .eth_client
+            .get_block_by_number(BlockIdentifier::Tag(BlockTag::Latest))
+            .await?
+            .header
+            .timestamp;
+
+        let overdue = l1_timestamp.saturating_add(FORCED_INCLUSION_MARGIN_SECS) >= deadline;
+        if overdue {
+            warn!(
+                "Privileged transaction {tx_hash:#x} is close to its inclusion deadline ({deadline}), prioritizing privileged transactions"
+            );
+        }
+        self.overdue_privileged_transactions.set(overdue);
+        Ok(())
+    }
+
+    /// Rewinds to the last canonical L1 block if the blocks the logs were fetched from were
+    /// reorged, so their logs are fetched again. Privileged transactions from reorged blocks are
+    /// removed from the mempool and added back if they are still part of the new chain.
+    ///
+    /// A privileged transaction that was already included in an L2 block can't be taken back.
+    /// The `OnChainProposer` only accepts batches whose privileged transactions are pending in
+    /// the bridge, so the batch with that block can't be committed until the same transaction is
+    /// sent again on L1. If it never is, the L2 chain has to be reverted to before that block.
+    pub async fn handle_l1_reorg(&mut self) -> Result<(), L1WatcherError> {
+        let Some(reorg) = self.fetched_l1_blocks.check_reorg(&self.eth_client).await? else {
+            return Ok(());
+        };
+
+        let resume_from = reorg
+            .last_canonical_block
+            .unwrap_or(reorg.first_reorged_block.saturating_sub(MAX_L1_REORG_DEPTH));
+        warn!(
+            "L1 reorg detected at block {}, fetching logs again from block {resume_from}",
+            reorg.first_reorged_block
+        );
+
+        for tx_hash in reorg.reorged {
+            if self
+                .store
+                .get_transaction_by_hash(tx_hash)
+                .await
+                .map_err(L1WatcherError::FailedAccessingStore)?
+                .is_some()
+            {
+                // Its batch can only be committed once the same transaction is sent again on L1
+                error!(
+                    "Privileged transaction {tx_hash:#x} was already included in an L2 block but its L1 transaction was reorged, its batch can't be committed until it's sent again"
+                );
+                continue;
+            }
+            self.blockchain
+                .remove_transaction_from_pool(&tx_hash)
+                .map_err(L1WatcherError::FailedAccessingStore)?;
+        }
+
+        self.last_block_fetched = self.last_block_fetched.min(resume_from.into());
+        Ok(())
     }
 
     pub async fn get_privileged_transactions(&mut self) -> Result<Vec<RpcLog>, L1WatcherError> {
@@ -171,6 +349,18 @@ impl L1Watcher {
 
         debug!("Logs: {:#?}", logs);
 
+        // Remember the last block of the range, a reorg of any block in it changes its hash
+        let new_last_block_number = new_last_block
+            .try_into()
+            .map_err(|_| L1WatcherError::Custom("L1 block number overflows u64".to_owned()))?;
+        let new_last_block_hash = self
+            .eth_client
+            .get_block_by_number(BlockIdentifier::Number(new_last_block_number))
+            .await?
+            .hash;
+        self.fetched_l1_blocks
+            .track(new_last_block_number, new_last_block_hash, []);
+
         // If we have an error adding the tx to the mempool we may assign it to the next
         // block to fetch, but we may lose a privileged tx.
         self.last_block_fetched = new_last_block;
@@ -185,6 +375,7 @@ impl L1Watcher {
         let mut privileged_txs = Vec::new();
 
         for log in logs {
+            let (log_block_number, log_block_hash) = (log.block_number, log.block_hash);
             let privileged_transaction_data = PrivilegedTransactionData::from_log(log.log)?;
 
             let gas_price = self.l2_client.get_gas_price().await?;
@@ -236,6 +427,8 @@ impl L1Watcher {
             };
 
             info!("Mint transaction added to mempool {hash:#x}",);
+            self.fetched_l1_blocks
+                .track(log_block_number, log_block_hash, [hash]);
             privileged_txs.push(hash);
         }
 
@@ -7,11 +7,12 @@ use crate::{BlockFetcher, SequencerConfig, StateUpdater};
 use block_producer::BlockProducer;
 use ethrex_blockchain::Blockchain;
 use ethrex_l2_common::prover::ProverType;
+use ethrex_l2_rpc::l2::{preconfirmation::Preconfirmations, user_operation::UserOperationPool};
 use ethrex_storage::Store;
 use ethrex_storage_rollup::StoreRollup;
 use l1_committer::L1Committer;
 use l1_proof_sender::L1ProofSender;
-use l1_watcher::L1Watcher;
+use l1_watcher::{L1BlobBaseFee, L1Watcher, OverduePrivilegedTransactions};
 #[cfg(feature = "metrics")]
 use metrics::MetricsGatherer;
 use proof_coordinator::ProofCoordinator;
@@ -20,6 +21,7 @@ use tracing::{error, info};
 use utils::get_needed_proof_types;
 
 pub mod block_producer;
+pub mod l1_block_tracker;
 pub mod l1_committer;
 pub mod l1_proof_sender;
 pub mod l1_proof_verifier;
@@ -39,6 +41,8 @@ pub async fn start_l2(
     blockchain: Arc<Blockchain>,
     cfg: SequencerConfig,
     cancellation_token: CancellationToken,
+    preconfirmations: Preconfirmations,
+    user_operations: Option<UserOperationPool>,
     #[cfg(feature = "metrics")] l2_url: String,
 ) -> Result<(), errors::SequencerError> {
     let initial_status = if cfg.based.enabled {
@@ -50,6 +54,8 @@ pub async fn start_l2(
     info!("Starting Sequencer in {initial_status} mode");
 
     let shared_state = SequencerState::from(initial_status);
+    let l1_blob_base_fee = L1BlobBaseFee::default();
+    let overdue_privileged_transactions = OverduePrivilegedTransactions::default();
 
     let Ok(needed_proof_types) = get_needed_proof_types(
         cfg.eth.rpc_url.clone(),
@@ -80,6 +86,8 @@ pub async fn start_l2(
         blockchain.clone(),
         cfg.clone(),
         shared_state.clone(),
+        l1_blob_base_fee.clone(),
+        overdue_privileged_transactions.clone(),
     )
     .await
     .inspect_err(|err| {
@@ -124,6 +132,10 @@ pub async fn start_l2(
         blockchain.clone(),
         cfg.clone(),
         shared_state.clone(),
+        l1_blob_base_fee,
+        overdue_privileged_transactions,
+        preconfirmations,
+        user_operations,
     )
     .await
     .inspect_err(|err| {
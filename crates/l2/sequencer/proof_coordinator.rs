@@ -1,4 +1,4 @@
-use crate::sequencer::errors::{ConnectionHandlerError, ProofCoordinatorError};
+use crate::sequencer::errors::{ConnectionHandlerError, ProofCoordinatorError, ProverAuthError};
 use crate::sequencer::setup::{prepare_quote_prerequisites, register_tdx_key};
 use crate::sequencer::utils::get_latest_sent_batch;
 use crate::{
@@ -9,23 +9,31 @@ use ethrex_blockchain::Blockchain;
 use ethrex_common::types::BlobsBundle;
 use ethrex_common::types::block_execution_witness::ExecutionWitnessResult;
 use ethrex_common::{
-    Address,
-    types::{Block, blobs_bundle},
+    Address, H256, Signature,
+    types::{Block, blobs_bundle, recover_address},
 };
 use ethrex_l2_common::prover::{BatchProof, ProverType};
+use ethrex_l2_rpc::signer::LocalSigner;
+#[cfg(feature = "metrics")]
+use ethrex_metrics::l2::metrics::METRICS;
+use ethrex_metrics::metrics;
 use ethrex_rpc::clients::eth::EthClient;
 use ethrex_storage::Store;
 use ethrex_storage_rollup::StoreRollup;
+use keccak_hash::keccak;
 use secp256k1::SecretKey;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use spawned_concurrency::messages::Unused;
 use spawned_concurrency::tasks::{CastResponse, GenServer, GenServerHandle};
+use std::collections::{HashMap, HashSet};
 use std::net::{IpAddr, SocketAddr};
-use std::sync::Arc;
+use std::sync::{Arc, PoisonError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::{
     io::{AsyncReadExt, AsyncWriteExt},
     net::{TcpListener, TcpStream},
+    sync::Mutex,
 };
 use tracing::{debug, error, info, warn};
 
@@ -36,11 +44,11 @@ pub struct ProverInputData {
     pub db: ExecutionWitnessResult,
     pub elasticity_multiplier: u64,
     #[cfg(feature = "l2")]
-    #[serde_as(as = "[_; 48]")]
-    pub blob_commitment: blobs_bundle::Commitment,
+    #[serde_as(as = "Vec<[_; 48]>")]
+    pub blob_commitments: Vec<blobs_bundle::Commitment>,
     #[cfg(feature = "l2")]
-    #[serde_as(as = "[_; 48]")]
-    pub blob_proof: blobs_bundle::Proof,
+    #[serde_as(as = "Vec<[_; 48]>")]
+    pub blob_proofs: Vec<blobs_bundle::Proof>,
 }
 
 /// Enum for the ProverServer <--> ProverClient Communication Protocol.
@@ -63,7 +71,12 @@ pub enum ProofData {
     /// The Client initiates the connection with a BatchRequest.
     /// Asking for the ProverInputData the prover_server considers/needs.
     /// The commit hash is used to ensure the client and server are compatible.
-    BatchRequest { commit_hash: String },
+    /// The prover id and type are used to hand distinct batches to each prover.
+    BatchRequest {
+        commit_hash: String,
+        prover_id: u64,
+        prover_type: ProverType,
+    },
 
     /// 4.
     /// The Server responds with an InvalidCodeVersion if the code version is not compatible.
@@ -89,6 +102,22 @@ pub enum ProofData {
     /// 7.
     /// The Server acknowledges the receipt of the proof and updates its state,
     ProofSubmitACK { batch_number: u64 },
+
+    /// 8.
+    /// The Client wraps any of its requests in an Authenticated message, signed with a key
+    /// from the Server's allowlist. The payload is the JSON encoded request, and the signature
+    /// covers the payload followed by the big endian timestamp (in seconds) and the big endian
+    /// nonce, a random number that makes each signed request valid only once.
+    Authenticated {
+        payload: Bytes,
+        timestamp: u64,
+        nonce: u64,
+        signature: Signature,
+    },
+
+    /// 9.
+    /// The Server rejects a request that isn't authenticated by an allowed prover.
+    Unauthorized { reason: String },
 }
 
 impl ProofData {
@@ -106,8 +135,12 @@ impl ProofData {
     }
 
     /// Builder function for creating a BatchRequest
-    pub fn batch_request(commit_hash: String) -> Self {
-        ProofData::BatchRequest { commit_hash }
+    pub fn batch_request(commit_hash: String, prover_id: u64, prover_type: ProverType) -> Self {
+        ProofData::BatchRequest {
+            commit_hash,
+            prover_id,
+            prover_type,
+        }
     }
 
     /// Builder function for creating a InvalidCodeVersion
@@ -142,12 +175,242 @@ impl ProofData {
     pub fn proof_submit_ack(batch_number: u64) -> Self {
         ProofData::ProofSubmitACK { batch_number }
     }
+
+    /// Builder function for creating an Authenticated request wrapping this one
+    pub fn authenticated(&self, key: &SecretKey) -> Result<Self, serde_json::Error> {
+        let payload = Bytes::from(serde_json::to_vec(self)?);
+        let timestamp = unix_timestamp();
+        let nonce = rand::random();
+        let signature =
+            LocalSigner::new(*key).sign(authenticated_message(&payload, timestamp, nonce));
+        Ok(ProofData::Authenticated {
+            payload,
+            timestamp,
+            nonce,
+            signature,
+        })
+    }
+
+    /// Builder function for creating an Unauthorized
+    pub fn unauthorized(reason: String) -> Self {
+        ProofData::Unauthorized { reason }
+    }
+}
+
+/// How far from the current time the timestamp of an authenticated request can be, so that
+/// captured requests can't be replayed later on.
+pub const MAX_REQUEST_TIME_DRIFT_SECS: u64 = 300;
+
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+fn authenticated_message(payload: &Bytes, timestamp: u64, nonce: u64) -> Bytes {
+    [
+        payload.as_ref(),
+        &timestamp.to_be_bytes(),
+        &nonce.to_be_bytes(),
+    ]
+    .concat()
+    .into()
+}
+
+/// Provers allowed to talk with the coordinator, identified by the address of the key they
+/// sign their requests with.
+#[derive(Debug, Clone)]
+pub struct ProverAllowlist {
+    /// `None` if requests from any prover are accepted, which has to be explicitly enabled.
+    addresses: Option<HashSet<Address>>,
+    /// Timestamps of the authenticated requests accepted within the allowed time drift, by
+    /// signer and nonce, so that each of them is only accepted once.
+    accepted_requests: Arc<std::sync::Mutex<HashMap<(Address, u64), u64>>>,
+}
+
+impl ProverAllowlist {
+    /// Builds the allowlist of the coordinator. An empty list of addresses is only accepted
+    /// if `allow_any_prover` is set, in which case requests from any prover are accepted.
+    pub fn new(
+        addresses: &[Address],
+        allow_any_prover: bool,
+    ) -> Result<Self, ProofCoordinatorError> {
+        let addresses = if addresses.is_empty() {
+            if !allow_any_prover {
+                return Err(ProofCoordinatorError::MissingProverAllowlist);
+            }
+            warn!(
+                "The proof coordinator accepts requests from any prover, set a prover allowlist to only accept known ones"
+            );
+            None
+        } else {
+            Some(addresses.iter().copied().collect())
+        };
+        Ok(Self {
+            addresses,
+            accepted_requests: Arc::new(std::sync::Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// Checks the request comes from an allowed prover at the given unix time (in seconds),
+    /// returning the request to handle.
+    pub fn authorize(&self, request: ProofData, now: u64) -> Result<ProofData, ProverAuthError> {
+        let ProofData::Authenticated {
+            payload,
+            timestamp,
+            nonce,
+            signature,
+        } = request
+        else {
+            if self.addresses.is_none() {
+                return Ok(request);
+            }
+            return Err(ProverAuthError::Unauthenticated);
+        };
+
+        if now.abs_diff(timestamp) > MAX_REQUEST_TIME_DRIFT_SECS {
+            return Err(ProverAuthError::StaleRequest(timestamp));
+        }
+        let message_hash: H256 = keccak(authenticated_message(&payload, timestamp, nonce));
+        let prover = recover_address(signature, message_hash)
+            .map_err(|err| ProverAuthError::InvalidSignature(err.to_string()))?;
+        if self
+            .addresses
+            .as_ref()
+            .is_some_and(|addresses| !addresses.contains(&prover))
+        {
+            return Err(ProverAuthError::UnknownProver(prover));
+        }
+
+        let request = serde_json::from_slice(&payload)?;
+        if matches!(request, ProofData::Authenticated { .. }) {
+            return Err(ProverAuthError::InvalidSignature(
+                "nested authenticated request".to_string(),
+            ));
+        }
+
+        let mut accepted_requests = self
+            .accepted_requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        // Requests older than the allowed drift are rejected as stale, no need to keep them
+        accepted_requests
+            .retain(|_, timestamp| now.abs_diff(*timestamp) <= MAX_REQUEST_TIME_DRIFT_SECS);
+        if accepted_requests
+            .insert((prover, nonce), timestamp)
+            .is_some()
+        {
+            return Err(ProverAuthError::ReplayedRequest(prover));
+        }
+        Ok(request)
+    }
 }
 
 pub fn get_commit_hash() -> String {
     env!("VERGEN_GIT_SHA").to_string()
 }
 
+/// Random id a prover uses to identify itself in its requests during its lifetime.
+pub fn generate_prover_id() -> u64 {
+    rand::random()
+}
+
+/// A batch handed to a prover, which is not handed to any other prover of the same type until
+/// the lease expires or the prover asks for another batch.
+#[derive(Debug, Clone, Copy)]
+struct Lease {
+    prover_id: u64,
+    expires_at: Instant,
+}
+
+/// In-flight assignments of batches to provers, shared by every connection.
+#[derive(Debug, Default)]
+pub struct BatchAssignments {
+    leases: HashMap<(u64, ProverType), Lease>,
+}
+
+impl BatchAssignments {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Leases the first committed batch after `latest_sent_batch` without a proof of the given
+    /// type that isn't already being proven by another prover of that type. Stale leases are
+    /// released first.
+    pub async fn assign(
+        &mut self,
+        rollup_store: &StoreRollup,
+        latest_sent_batch: u64,
+        prover_id: u64,
+        prover_type: ProverType,
+        now: Instant,
+        lease_timeout: Duration,
+    ) -> Result<Option<u64>, ProofCoordinatorError> {
+        self.release_stale(latest_sent_batch, prover_id, now);
+
+        let mut batch_number = latest_sent_batch + 1;
+        while rollup_store.contains_batch(&batch_number).await? {
+            let already_proven = rollup_store
+                .get_proof_by_batch_and_type(batch_number, prover_type)
+                .await?
+                .is_some();
+            if !already_proven && !self.is_leased(batch_number, prover_type) {
+                self.lease(batch_number, prover_type, prover_id, now + lease_timeout);
+                return Ok(Some(batch_number));
+            }
+            batch_number += 1;
+        }
+
+        Ok(None)
+    }
+
+    /// Drops the leases of already verified batches, expired ones, and the one held by the
+    /// given prover, since a prover asking for a batch is no longer working on its previous one.
+    pub fn release_stale(&mut self, latest_sent_batch: u64, prover_id: u64, now: Instant) {
+        self.leases.retain(|(batch_number, prover_type), lease| {
+            if *batch_number <= latest_sent_batch || lease.prover_id == prover_id {
+                return false;
+            }
+            if lease.expires_at <= now {
+                warn!(
+                    batch_number,
+                    ?prover_type,
+                    prover_id = lease.prover_id,
+                    "Lease expired, the batch will be handed to another prover"
+                );
+                return false;
+            }
+            true
+        });
+    }
+
+    pub fn is_leased(&self, batch_number: u64, prover_type: ProverType) -> bool {
+        self.leases.contains_key(&(batch_number, prover_type))
+    }
+
+    pub fn lease(
+        &mut self,
+        batch_number: u64,
+        prover_type: ProverType,
+        prover_id: u64,
+        expires_at: Instant,
+    ) {
+        self.leases.insert(
+            (batch_number, prover_type),
+            Lease {
+                prover_id,
+                expires_at,
+            },
+        );
+    }
+
+    /// Releases the lease of a batch, e.g. once its proof is received.
+    pub fn release(&mut self, batch_number: u64, prover_type: ProverType) {
+        self.leases.remove(&(batch_number, prover_type));
+    }
+}
+
 #[derive(Clone)]
 pub enum ProofCordInMessage {
     Listen { listener: Arc<TcpListener> },
@@ -173,6 +436,9 @@ pub struct ProofCoordinator {
     validium: bool,
     needed_proof_types: Vec<ProverType>,
     commit_hash: String,
+    lease_timeout: Duration,
+    assignments: Arc<Mutex<BatchAssignments>>,
+    allowlist: ProverAllowlist,
 }
 
 impl ProofCoordinator {
@@ -220,6 +486,9 @@ impl ProofCoordinator {
             validium: config.validium,
             needed_proof_types,
             commit_hash: get_commit_hash(),
+            lease_timeout: Duration::from_millis(config.lease_timeout_ms),
+            assignments: Arc::new(Mutex::new(BatchAssignments::new())),
+            allowlist: ProverAllowlist::new(&config.prover_allowlist, config.allow_any_prover)?,
         })
     }
 
@@ -280,8 +549,10 @@ impl ProofCoordinator {
         &mut self,
         stream: &mut TcpStream,
         commit_hash: String,
+        prover_id: u64,
+        prover_type: ProverType,
     ) -> Result<(), ProofCoordinatorError> {
-        info!("BatchRequest received");
+        info!("BatchRequest received from {prover_type} prover {prover_id}");
 
         if commit_hash != self.commit_hash {
             error!(
@@ -295,7 +566,44 @@ impl ProofCoordinator {
             return Ok(());
         }
 
-        let batch_to_verify = 1 + get_latest_sent_batch(
+        if !self.needed_proof_types.contains(&prover_type) {
+            warn!("Received a BatchRequest from a {prover_type} prover, which is not needed");
+            send_response(stream, &ProofData::empty_batch_response()).await?;
+            return Ok(());
+        }
+
+        let Some(batch_to_verify) = self.assign_batch(prover_id, prover_type).await? else {
+            debug!("Sending empty BatchResponse");
+            send_response(stream, &ProofData::empty_batch_response()).await?;
+            return Ok(());
+        };
+
+        let response = match self.create_prover_input(batch_to_verify).await {
+            Ok(input) => ProofData::batch_response(batch_to_verify, input),
+            Err(err) => {
+                // Let other provers take the batch instead of waiting for the lease to expire
+                self.assignments
+                    .lock()
+                    .await
+                    .release(batch_to_verify, prover_type);
+                return Err(err);
+            }
+        };
+
+        send_response(stream, &response).await?;
+        info!("BatchResponse sent for batch number: {batch_to_verify}");
+
+        Ok(())
+    }
+
+    /// Leases the first committed batch without a proof of the given type that isn't already
+    /// being proven by another prover of that type.
+    async fn assign_batch(
+        &mut self,
+        prover_id: u64,
+        prover_type: ProverType,
+    ) -> Result<Option<u64>, ProofCoordinatorError> {
+        let latest_sent_batch = get_latest_sent_batch(
             self.needed_proof_types.clone(),
             &self.rollup_store,
             &self.eth_client,
@@ -304,33 +612,19 @@ impl ProofCoordinator {
         .await
         .map_err(|err| ProofCoordinatorError::InternalError(err.to_string()))?;
 
-        let mut all_proofs_exist = true;
-        for proof_type in &self.needed_proof_types {
-            if self
-                .rollup_store
-                .get_proof_by_batch_and_type(batch_to_verify, *proof_type)
-                .await?
-                .is_none()
-            {
-                all_proofs_exist = false;
-                break;
-            }
-        }
-
-        let response =
-            if all_proofs_exist || !self.rollup_store.contains_batch(&batch_to_verify).await? {
-                debug!("Sending empty BatchResponse");
-                ProofData::empty_batch_response()
-            } else {
-                let input = self.create_prover_input(batch_to_verify).await?;
-                debug!("Sending BatchResponse for block_number: {batch_to_verify}");
-                ProofData::batch_response(batch_to_verify, input)
-            };
-
-        send_response(stream, &response).await?;
-        info!("BatchResponse sent for batch number: {batch_to_verify}");
-
-        Ok(())
+        // Held until the lease is taken, so that concurrent requests get distinct batches
+        self.assignments
+            .lock()
+            .await
+            .assign(
+                &self.rollup_store,
+                latest_sent_batch,
+                prover_id,
+                prover_type,
+                Instant::now(),
+                self.lease_timeout,
+            )
+            .await
     }
 
     async fn handle_submit(
@@ -360,6 +654,10 @@ impl ProofCoordinator {
                 .store_proof_by_batch_and_type(batch_number, prover_type, batch_proof)
                 .await?;
         }
+        self.assignments
+            .lock()
+            .await
+            .release(batch_number, prover_type);
         let response = ProofData::proof_submit_ack(batch_number);
         send_response(stream, &response).await?;
         info!("ProofSubmit ACK sent");
@@ -432,24 +730,24 @@ impl ProofCoordinator {
             .await
             .map_err(ProofCoordinatorError::from)?;
 
-        // Get blobs bundle cached by the L1 Committer (blob, commitment, proof)
-        let (blob_commitment, blob_proof) = if self.validium {
-            ([0; 48], [0; 48])
+        // Get blobs bundle cached by the L1 Committer (blobs, commitments, proofs)
+        let (blob_commitments, blob_proofs) = if self.validium {
+            (Vec::new(), Vec::new())
         } else {
-            let blob = self
+            let blobs = self
                 .rollup_store
                 .get_blobs_by_batch(batch_number)
                 .await?
                 .ok_or(ProofCoordinatorError::MissingBlob(batch_number))?;
             let BlobsBundle {
-                mut commitments,
-                mut proofs,
+                commitments,
+                proofs,
                 ..
-            } = BlobsBundle::create_from_blobs(&blob)?;
-            match (commitments.pop(), proofs.pop()) {
-                (Some(commitment), Some(proof)) => (commitment, proof),
-                _ => return Err(ProofCoordinatorError::MissingBlob(batch_number)),
+            } = BlobsBundle::create_from_blobs(&blobs)?;
+            if commitments.is_empty() {
+                return Err(ProofCoordinatorError::MissingBlob(batch_number));
             }
+            (commitments, proofs)
         };
 
         debug!("Created prover input for batch {batch_number}");
@@ -459,9 +757,9 @@ impl ProofCoordinator {
             blocks,
             elasticity_multiplier: self.elasticity_multiplier,
             #[cfg(feature = "l2")]
-            blob_commitment,
+            blob_commitments,
             #[cfg(feature = "l2")]
-            blob_proof,
+            blob_proofs,
         })
     }
 
@@ -542,11 +840,38 @@ impl ConnectionHandler {
             stream.read_to_end(&mut buffer).await?;
 
             let data: Result<ProofData, _> = serde_json::from_slice(&buffer);
+            let data = match data {
+                Ok(request) => match self
+                    .proof_coordinator
+                    .allowlist
+                    .authorize(request, unix_timestamp())
+                {
+                    Ok(request) => Ok(request),
+                    Err(err) => {
+                        warn!("Rejected prover request: {err}");
+                        metrics!(
+                            let _ = METRICS
+                                .inc_rejected_prover_requests(err.label())
+                                .inspect_err(|e| {
+                                    error!("Failed to update rejected requests metric: {e}")
+                                });
+                        );
+                        send_response(&mut stream, &ProofData::unauthorized(err.to_string()))
+                            .await?;
+                        return Ok(());
+                    }
+                },
+                Err(err) => Err(err),
+            };
             match data {
-                Ok(ProofData::BatchRequest { commit_hash }) => {
+                Ok(ProofData::BatchRequest {
+                    commit_hash,
+                    prover_id,
+                    prover_type,
+                }) => {
                     if let Err(e) = self
                         .proof_coordinator
-                        .handle_request(&mut stream, commit_hash)
+                        .handle_request(&mut stream, commit_hash, prover_id, prover_type)
                         .await
                     {
                         error!("Failed to handle BatchRequest: {e}");
@@ -124,6 +124,36 @@ pub async fn get_latest_sent_batch(
     }
 }
 
+/// Forgets the L1 transactions of batches whose commitment or verification was reorged out of the
+/// L1 chain, so they aren't reported as committed or verified. The latest sent proof, from which
+/// the next batch to verify is taken in Aligned mode, is rewound so their proofs are sent again.
+/// A reorged commitment also drops the verification of its batch, which was mined after it.
+pub async fn forget_reorged_batches(
+    rollup_store: &StoreRollup,
+    reorged_batches: &[u64],
+    commitments_reorged: bool,
+) -> Result<(), RollupStoreError> {
+    for batch_number in reorged_batches {
+        if commitments_reorged {
+            rollup_store
+                .remove_commit_tx_by_batch(*batch_number)
+                .await?;
+        }
+        rollup_store
+            .remove_verify_tx_by_batch(*batch_number)
+            .await?;
+    }
+
+    if let Some(first_reorged_batch) = reorged_batches.iter().min() {
+        if rollup_store.get_lastest_sent_batch_proof().await? >= *first_reorged_batch {
+            rollup_store
+                .set_lastest_sent_batch_proof(first_reorged_batch.saturating_sub(1))
+                .await?;
+        }
+    }
+    Ok(())
+}
+
 pub fn resolve_aligned_network(network: &str) -> Network {
     match network {
         "devnet" => Network::Devnet,
@@ -3,12 +3,12 @@
 use std::{fmt::Debug, ops::Range};
 
 use ethrex_common::{
-    H256,
+    Address, H256, U256,
     types::{AccountUpdate, Blob, BlockNumber, batch::Batch},
 };
 use ethrex_l2_common::prover::{BatchProof, ProverType};
 
-use crate::error::RollupStoreError;
+use crate::{error::RollupStoreError, store::SponsorshipUsage};
 
 // We need async_trait because the stabilized feature lacks support for object safety
 // (i.e. dyn StoreEngine)
@@ -58,6 +58,8 @@ pub trait StoreEngineRollup: Debug + Send + Sync {
         commit_tx: H256,
     ) -> Result<(), RollupStoreError>;
 
+    async fn remove_commit_tx_by_batch(&self, batch_number: u64) -> Result<(), RollupStoreError>;
+
     async fn seal_batch(&self, batch: Batch) -> Result<(), RollupStoreError>;
 
     async fn get_verify_tx_by_batch(
@@ -71,6 +73,8 @@ pub trait StoreEngineRollup: Debug + Send + Sync {
         verify_tx: H256,
     ) -> Result<(), RollupStoreError>;
 
+    async fn remove_verify_tx_by_batch(&self, batch_number: u64) -> Result<(), RollupStoreError>;
+
     async fn update_operations_count(
         &self,
         transaction_inc: u64,
@@ -146,4 +150,32 @@ pub trait StoreEngineRollup: Debug + Send + Sync {
         &self,
         range: Option<Range<u64>>,
     ) -> Result<(), RollupStoreError>;
+
+    /// Returns the transactions sponsored for the account on the given day.
+    async fn get_sponsorship_usage(
+        &self,
+        day: u64,
+        account: Address,
+    ) -> Result<SponsorshipUsage, RollupStoreError>;
+
+    /// Returns the transactions sponsored for every account on the given day.
+    async fn get_total_sponsorship_usage(
+        &self,
+        day: u64,
+    ) -> Result<SponsorshipUsage, RollupStoreError>;
+
+    /// Accounts a sponsored transaction for the account and for the day total.
+    async fn add_sponsorship_usage(
+        &self,
+        day: u64,
+        account: Address,
+        gas: u64,
+        spent: U256,
+    ) -> Result<(), RollupStoreError>;
+
+    /// Stores the L1 fee paid by each transaction of a produced block.
+    async fn store_l1_fees(&self, l1_fees: Vec<(H256, U256)>) -> Result<(), RollupStoreError>;
+
+    /// Returns the L1 fee paid by the transaction, if it was included by this node.
+    async fn get_l1_fee(&self, tx_hash: H256) -> Result<Option<U256>, RollupStoreError>;
 }
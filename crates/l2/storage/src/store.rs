@@ -6,7 +6,7 @@ use crate::store_db::in_memory::Store as InMemoryStore;
 #[cfg(feature = "sql")]
 use crate::store_db::sql::SQLStore;
 use ethrex_common::{
-    H256,
+    Address, H256, U256,
     types::{AccountUpdate, Blob, BlobsBundle, BlockNumber, batch::Batch},
 };
 use ethrex_l2_common::prover::{BatchProof, ProverType};
@@ -25,6 +25,25 @@ impl Default for Store {
     }
 }
 
+/// Transactions sponsored by `ethrex_sendTransaction` during a day
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SponsorshipUsage {
+    pub transactions: u64,
+    pub gas: u64,
+    /// Max fee of the sponsored transactions, in wei
+    pub spent: U256,
+}
+
+impl SponsorshipUsage {
+    pub fn add(&self, gas: u64, spent: U256) -> Self {
+        Self {
+            transactions: self.transactions.saturating_add(1),
+            gas: self.gas.saturating_add(gas),
+            spent: self.spent.saturating_add(spent),
+        }
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EngineType {
@@ -133,6 +152,13 @@ impl Store {
             .await
     }
 
+    pub async fn remove_commit_tx_by_batch(
+        &self,
+        batch_number: u64,
+    ) -> Result<(), RollupStoreError> {
+        self.engine.remove_commit_tx_by_batch(batch_number).await
+    }
+
     pub async fn get_verify_tx_by_batch(
         &self,
         batch_number: u64,
@@ -150,6 +176,13 @@ impl Store {
             .await
     }
 
+    pub async fn remove_verify_tx_by_batch(
+        &self,
+        batch_number: u64,
+    ) -> Result<(), RollupStoreError> {
+        self.engine.remove_verify_tx_by_batch(batch_number).await
+    }
+
     pub async fn get_batch(&self, batch_number: u64) -> Result<Option<Batch>, RollupStoreError> {
         let Some(blocks) = self.get_block_numbers_by_batch(batch_number).await? else {
             return Ok(None);
@@ -355,4 +388,45 @@ impl Store {
     ) -> Result<(), RollupStoreError> {
         self.engine.update_precommit_privileged(range).await
     }
+
+    /// Returns the transactions sponsored for the account on the given day
+    /// (days are counted since the unix epoch)
+    pub async fn get_sponsorship_usage(
+        &self,
+        day: u64,
+        account: Address,
+    ) -> Result<SponsorshipUsage, RollupStoreError> {
+        self.engine.get_sponsorship_usage(day, account).await
+    }
+
+    /// Returns the transactions sponsored for every account on the given day
+    pub async fn get_total_sponsorship_usage(
+        &self,
+        day: u64,
+    ) -> Result<SponsorshipUsage, RollupStoreError> {
+        self.engine.get_total_sponsorship_usage(day).await
+    }
+
+    /// Accounts a sponsored transaction for the account and for the day total
+    pub async fn add_sponsorship_usage(
+        &self,
+        day: u64,
+        account: Address,
+        gas: u64,
+        spent: U256,
+    ) -> Result<(), RollupStoreError> {
+        self.engine
+            .add_sponsorship_usage(day, account, gas, spent)
+            .await
+    }
+
+    /// Stores the L1 fee paid by each transaction of a produced block
+    pub async fn store_l1_fees(&self, l1_fees: Vec<(H256, U256)>) -> Result<(), RollupStoreError> {
+        self.engine.store_l1_fees(l1_fees).await
+    }
+
+    /// Returns the L1 fee paid by the transaction, if it was included by this node
+    pub async fn get_l1_fee(&self, tx_hash: H256) -> Result<Option<U256>, RollupStoreError> {
+        self.engine.get_l1_fee(tx_hash).await
+    }
 }
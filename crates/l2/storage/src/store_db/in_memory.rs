@@ -5,9 +5,9 @@ use std::{
     sync::{Arc, Mutex, MutexGuard},
 };
 
-use crate::error::RollupStoreError;
+use crate::{error::RollupStoreError, store::SponsorshipUsage};
 use ethrex_common::{
-    H256,
+    Address, H256, U256,
     types::{AccountUpdate, Blob, BlockNumber, batch::Batch},
 };
 use ethrex_l2_common::prover::{BatchProof, ProverType};
@@ -49,6 +49,12 @@ struct StoreInner {
     verify_txs: HashMap<u64, H256>,
     /// Privileged transactions included in the batch being built
     precommit_privileged: Option<Range<u64>>,
+    /// Map of (day, account) to sponsored transactions
+    sponsorships: HashMap<(u64, Address), SponsorshipUsage>,
+    /// Map of day to sponsored transactions for every account
+    sponsorship_totals: HashMap<u64, SponsorshipUsage>,
+    /// Map of transaction hash to the L1 fee it paid
+    l1_fees: HashMap<H256, U256>,
 }
 
 impl Store {
@@ -136,6 +142,11 @@ impl StoreEngineRollup for Store {
         Ok(())
     }
 
+    async fn remove_commit_tx_by_batch(&self, batch_number: u64) -> Result<(), RollupStoreError> {
+        self.inner()?.commit_txs.remove(&batch_number);
+        Ok(())
+    }
+
     async fn get_verify_tx_by_batch(
         &self,
         batch_number: u64,
@@ -152,6 +163,11 @@ impl StoreEngineRollup for Store {
         Ok(())
     }
 
+    async fn remove_verify_tx_by_batch(&self, batch_number: u64) -> Result<(), RollupStoreError> {
+        self.inner()?.verify_txs.remove(&batch_number);
+        Ok(())
+    }
+
     async fn contains_batch(&self, batch_number: &u64) -> Result<bool, RollupStoreError> {
         Ok(self
             .inner()?
@@ -337,6 +353,55 @@ impl StoreEngineRollup for Store {
         self.inner()?.precommit_privileged = range;
         Ok(())
     }
+
+    async fn get_sponsorship_usage(
+        &self,
+        day: u64,
+        account: Address,
+    ) -> Result<SponsorshipUsage, RollupStoreError> {
+        Ok(self
+            .inner()?
+            .sponsorships
+            .get(&(day, account))
+            .copied()
+            .unwrap_or_default())
+    }
+
+    async fn get_total_sponsorship_usage(
+        &self,
+        day: u64,
+    ) -> Result<SponsorshipUsage, RollupStoreError> {
+        Ok(self
+            .inner()?
+            .sponsorship_totals
+            .get(&day)
+            .copied()
+            .unwrap_or_default())
+    }
+
+    async fn add_sponsorship_usage(
+        &self,
+        day: u64,
+        account: Address,
+        gas: u64,
+        spent: U256,
+    ) -> Result<(), RollupStoreError> {
+        let mut inner = self.inner()?;
+        let usage = inner.sponsorships.entry((day, account)).or_default();
+        *usage = usage.add(gas, spent);
+        let total = inner.sponsorship_totals.entry(day).or_default();
+        *total = total.add(gas, spent);
+        Ok(())
+    }
+
+    async fn store_l1_fees(&self, l1_fees: Vec<(H256, U256)>) -> Result<(), RollupStoreError> {
+        self.inner()?.l1_fees.extend(l1_fees);
+        Ok(())
+    }
+
+    async fn get_l1_fee(&self, tx_hash: H256) -> Result<Option<U256>, RollupStoreError> {
+        Ok(self.inner()?.l1_fees.get(&tx_hash).copied())
+    }
 }
 
 impl Debug for Store {
@@ -1,9 +1,9 @@
 use std::{fmt::Debug, ops::Range, sync::Arc, time::Duration};
 use tokio::sync::Mutex;
 
-use crate::{RollupStoreError, api::StoreEngineRollup};
+use crate::{RollupStoreError, SponsorshipUsage, api::StoreEngineRollup};
 use ethrex_common::{
-    H256,
+    Address, H256, U256,
     types::{AccountUpdate, Blob, BlockNumber, batch::Batch},
 };
 use ethrex_l2_common::prover::{BatchProof, ProverType};
@@ -47,6 +47,13 @@ const DB_SCHEMA: [&str; 16] = [
     "CREATE TABLE precommit_privileged (_id INT PRIMARY KEY, start INT, end INT)",
 ];
 
+/// Tables added after `DB_SCHEMA`, which are also created in existing databases
+const DB_SCHEMA_ADDITIONS: [&str; 3] = [
+    "CREATE TABLE IF NOT EXISTS sponsorships (day INT, account BLOB, transactions INT, gas INT, spent BLOB, PRIMARY KEY (day, account))",
+    "CREATE TABLE IF NOT EXISTS sponsorship_totals (day INT PRIMARY KEY, transactions INT, gas INT, spent BLOB)",
+    "CREATE TABLE IF NOT EXISTS l1_fees (tx_hash BLOB PRIMARY KEY, l1_fee BLOB)",
+];
+
 impl SQLStore {
     pub fn new(path: &str) -> Result<Self, RollupStoreError> {
         futures::executor::block_on(async {
@@ -87,14 +94,19 @@ impl SQLStore {
                 (),
             )
             .await?;
+        let empty_param = ().into_params()?;
         if rows.next().await?.is_none() {
-            let empty_param = ().into_params()?;
             let queries = DB_SCHEMA
                 .iter()
                 .map(|v| (*v, empty_param.clone()))
                 .collect();
             self.execute_in_tx(queries, None).await?;
         }
+        let queries = DB_SCHEMA_ADDITIONS
+            .iter()
+            .map(|v| (*v, empty_param.clone()))
+            .collect();
+        self.execute_in_tx(queries, None).await?;
         Ok(())
     }
 
@@ -303,6 +315,18 @@ fn read_from_row_blob(row: &Row, index: i32) -> Result<Vec<u8>, RollupStoreError
     }
 }
 
+/// Reads a `SponsorshipUsage` from the first row of a `SELECT transactions, gas, spent` query
+async fn read_sponsorship_usage(mut rows: Rows) -> Result<SponsorshipUsage, RollupStoreError> {
+    let Some(row) = rows.next().await? else {
+        return Ok(SponsorshipUsage::default());
+    };
+    Ok(SponsorshipUsage {
+        transactions: read_from_row_int(&row, 0)?,
+        gas: read_from_row_int(&row, 1)?,
+        spent: U256::from_big_endian(&read_from_row_blob(&row, 2)?),
+    })
+}
+
 #[async_trait::async_trait]
 impl StoreEngineRollup for SQLStore {
     async fn get_batch_number_by_block(
@@ -433,6 +457,14 @@ impl StoreEngineRollup for SQLStore {
             .await
     }
 
+    async fn remove_commit_tx_by_batch(&self, batch_number: u64) -> Result<(), RollupStoreError> {
+        let queries = vec![(
+            "DELETE FROM commit_txs WHERE batch = ?1",
+            vec![batch_number].into_params()?,
+        )];
+        self.execute_in_tx(queries, None).await
+    }
+
     async fn get_commit_tx_by_batch(
         &self,
         batch_number: u64,
@@ -459,6 +491,14 @@ impl StoreEngineRollup for SQLStore {
             .await
     }
 
+    async fn remove_verify_tx_by_batch(&self, batch_number: u64) -> Result<(), RollupStoreError> {
+        let queries = vec![(
+            "DELETE FROM verify_txs WHERE batch = ?1",
+            vec![batch_number].into_params()?,
+        )];
+        self.execute_in_tx(queries, None).await
+    }
+
     async fn get_verify_tx_by_batch(
         &self,
         batch_number: u64,
@@ -782,6 +822,99 @@ impl StoreEngineRollup for SQLStore {
         }
         self.execute_in_tx(queries, None).await
     }
+
+    async fn get_sponsorship_usage(
+        &self,
+        day: u64,
+        account: Address,
+    ) -> Result<SponsorshipUsage, RollupStoreError> {
+        let rows = self
+            .query(
+                "SELECT transactions, gas, spent FROM sponsorships WHERE day = ?1 AND account = ?2",
+                (day, Vec::from(account.to_fixed_bytes())),
+            )
+            .await?;
+        read_sponsorship_usage(rows).await
+    }
+
+    async fn get_total_sponsorship_usage(
+        &self,
+        day: u64,
+    ) -> Result<SponsorshipUsage, RollupStoreError> {
+        let rows = self
+            .query(
+                "SELECT transactions, gas, spent FROM sponsorship_totals WHERE day = ?1",
+                vec![day],
+            )
+            .await?;
+        read_sponsorship_usage(rows).await
+    }
+
+    async fn add_sponsorship_usage(
+        &self,
+        day: u64,
+        account: Address,
+        gas: u64,
+        spent: U256,
+    ) -> Result<(), RollupStoreError> {
+        let usage = self
+            .get_sponsorship_usage(day, account)
+            .await?
+            .add(gas, spent);
+        let total = self.get_total_sponsorship_usage(day).await?.add(gas, spent);
+        let queries = vec![
+            (
+                "INSERT OR REPLACE INTO sponsorships VALUES (?1, ?2, ?3, ?4, ?5)",
+                (
+                    day,
+                    Vec::from(account.to_fixed_bytes()),
+                    usage.transactions,
+                    usage.gas,
+                    usage.spent.to_big_endian().to_vec(),
+                )
+                    .into_params()?,
+            ),
+            (
+                "INSERT OR REPLACE INTO sponsorship_totals VALUES (?1, ?2, ?3, ?4)",
+                (
+                    day,
+                    total.transactions,
+                    total.gas,
+                    total.spent.to_big_endian().to_vec(),
+                )
+                    .into_params()?,
+            ),
+        ];
+        self.execute_in_tx(queries, None).await
+    }
+
+    async fn store_l1_fees(&self, l1_fees: Vec<(H256, U256)>) -> Result<(), RollupStoreError> {
+        let mut queries = Vec::new();
+        for (tx_hash, l1_fee) in l1_fees {
+            queries.push((
+                "INSERT OR REPLACE INTO l1_fees VALUES (?1, ?2)",
+                (
+                    Vec::from(tx_hash.to_fixed_bytes()),
+                    l1_fee.to_big_endian().to_vec(),
+                )
+                    .into_params()?,
+            ));
+        }
+        self.execute_in_tx(queries, None).await
+    }
+
+    async fn get_l1_fee(&self, tx_hash: H256) -> Result<Option<U256>, RollupStoreError> {
+        let mut rows = self
+            .query(
+                "SELECT l1_fee FROM l1_fees WHERE tx_hash = ?1",
+                vec![Vec::from(tx_hash.to_fixed_bytes())],
+            )
+            .await?;
+        rows.next()
+            .await?
+            .map(|row| read_from_row_blob(&row, 0).map(|vec| U256::from_big_endian(&vec)))
+            .transpose()
+    }
 }
 
 #[cfg(test)]
@@ -804,6 +937,9 @@ mod tests {
             "block_signatures",
             "batch_signatures",
             "precommit_privileged",
+            "sponsorships",
+            "sponsorship_totals",
+            "l1_fees",
         ];
         let mut attributes = Vec::new();
         for table in tables {
@@ -851,6 +987,17 @@ mod tests {
                 ("precommit_privileged", "_id") => "INT",
                 ("precommit_privileged", "start") => "INT",
                 ("precommit_privileged", "end") => "INT",
+                ("sponsorships", "day") => "INT",
+                ("sponsorships", "account") => "BLOB",
+                ("sponsorships", "transactions") => "INT",
+                ("sponsorships", "gas") => "INT",
+                ("sponsorships", "spent") => "BLOB",
+                ("sponsorship_totals", "day") => "INT",
+                ("sponsorship_totals", "transactions") => "INT",
+                ("sponsorship_totals", "gas") => "INT",
+                ("sponsorship_totals", "spent") => "BLOB",
+                ("l1_fees", "tx_hash") => "BLOB",
+                ("l1_fees", "l1_fee") => "BLOB",
                 _ => {
                     return Err(anyhow::Error::msg(
                         "unexpected attribute {name} in table {table}",
@@ -861,4 +1008,91 @@ mod tests {
         }
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_schema_additions_on_existing_db() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!(
+            "ethrex_rollup_store_additions_{}.db",
+            std::process::id()
+        ));
+        let path = path.to_str().ok_or(anyhow::Error::msg("invalid path"))?;
+        let _ = std::fs::remove_file(path);
+        {
+            // A database created before the additions
+            let db = Builder::new_local(path).build().await?;
+            let conn = db.connect()?;
+            for query in DB_SCHEMA {
+                conn.execute(query, ()).await?;
+            }
+        }
+
+        let store = SQLStore::new(path)?;
+        store
+            .add_sponsorship_usage(1, Address::repeat_byte(1), 21_000, 1.into())
+            .await?;
+        assert_eq!(store.get_total_sponsorship_usage(1).await?.transactions, 1);
+        drop(store);
+        // Reopening an up to date database keeps its data
+        let store = SQLStore::new(path)?;
+        assert_eq!(store.get_total_sponsorship_usage(1).await?.transactions, 1);
+
+        drop(store);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{path}{suffix}"));
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sponsorship_usage() -> anyhow::Result<()> {
+        let store = SQLStore::new(":memory:")?;
+        let (alice, bob) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        store
+            .add_sponsorship_usage(7, alice, 100, 1000.into())
+            .await?;
+        store
+            .add_sponsorship_usage(7, alice, 50, 500.into())
+            .await?;
+        store.add_sponsorship_usage(7, bob, 10, 100.into()).await?;
+        store.add_sponsorship_usage(8, bob, 1, 1.into()).await?;
+
+        assert_eq!(
+            store.get_sponsorship_usage(7, alice).await?,
+            SponsorshipUsage {
+                transactions: 2,
+                gas: 150,
+                spent: 1500.into(),
+            }
+        );
+        assert_eq!(
+            store.get_total_sponsorship_usage(7).await?,
+            SponsorshipUsage {
+                transactions: 3,
+                gas: 160,
+                spent: 1600.into(),
+            }
+        );
+        assert_eq!(
+            store.get_sponsorship_usage(9, alice).await?,
+            SponsorshipUsage::default()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_l1_fees() -> anyhow::Result<()> {
+        let store = SQLStore::new(":memory:")?;
+        let (tx, other_tx) = (H256::repeat_byte(1), H256::repeat_byte(2));
+        store
+            .store_l1_fees(vec![(tx, 1000.into()), (other_tx, U256::zero())])
+            .await?;
+        assert_eq!(store.get_l1_fee(tx).await?, Some(1000.into()));
+        assert_eq!(store.get_l1_fee(other_tx).await?, Some(U256::zero()));
+        assert_eq!(store.get_l1_fee(H256::repeat_byte(3)).await?, None);
+
+        // A transaction included again after a reorg keeps its latest fee
+        store.store_l1_fees(vec![(tx, 2000.into())]).await?;
+        assert_eq!(store.get_l1_fee(tx).await?, Some(2000.into()));
+        Ok(())
+    }
 }
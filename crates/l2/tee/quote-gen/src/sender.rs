@@ -8,14 +8,17 @@ use ethrex_l2::sequencer::proof_coordinator::ProofData;
 use ethrex_l2_common::prover::{BatchProof, ProverType};
 
 use ethrex_common::Bytes;
+use secp256k1::SecretKey;
 
 const SERVER_URL: &str = "172.17.0.1:3900";
 const SERVER_URL_DEV: &str = "localhost:3900";
 
-pub async fn get_batch(commit_hash: String) -> Result<(u64, ProgramInput), String> {
-    let batch = connect_to_prover_server_wr(&ProofData::BatchRequest {
-        commit_hash: commit_hash.clone(),
-    })
+pub async fn get_batch(commit_hash: String, prover_id: u64) -> Result<(u64, ProgramInput), String> {
+    let batch = connect_to_prover_server_wr(&ProofData::batch_request(
+        commit_hash.clone(),
+        prover_id,
+        ProverType::TDX,
+    ))
     .await
     .map_err(|e| format!("Failed to get Response: {e}"))?;
     match batch {
@@ -30,9 +33,9 @@ pub async fn get_batch(commit_hash: String) -> Result<(u64, ProgramInput), Strin
                     db: input.db,
                     elasticity_multiplier: input.elasticity_multiplier,
                     #[cfg(feature = "l2")]
-                    blob_commitment: input.blob_commitment,
+                    blob_commitments: input.blob_commitments,
                     #[cfg(feature = "l2")]
-                    blob_proof: input.blob_proof,
+                    blob_proofs: input.blob_proofs,
                 },
             )),
             _ => Err("No blocks to prove.".to_owned()),
@@ -83,7 +86,16 @@ async fn connect_to_prover_server_wr(
     };
     let mut stream = TcpStream::connect(addr).await?;
 
-    stream.write_all(&serde_json::to_vec(&write)?).await?;
+    // Requests are signed when the proof coordinator only accepts allowlisted provers
+    let authenticated = match std::env::var("ETHREX_TDX_AUTH_PRIVATE_KEY") {
+        Ok(key) => {
+            let key = SecretKey::from_slice(&hex::decode(key.trim_start_matches("0x"))?)?;
+            Some(write.authenticated(&key)?)
+        }
+        Err(_) => None,
+    };
+    let write = authenticated.as_ref().unwrap_or(write);
+    stream.write_all(&serde_json::to_vec(write)?).await?;
     stream.shutdown().await?;
 
     let mut buffer = Vec::new();
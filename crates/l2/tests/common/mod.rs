@@ -0,0 +1,158 @@
+//! Fixtures shared by the tests that produce L2 blocks.
+// Each test binary only uses some of them
+#![allow(dead_code)]
+use std::{collections::HashMap, sync::Arc};
+
+use bytes::Bytes;
+use ethrex_blockchain::{
+    Blockchain, BlockchainType,
+    fork_choice::apply_fork_choice,
+    payload::{BuildPayloadArgs, PayloadBuildResult, create_payload},
+};
+use ethrex_common::{
+    Address, H256, U256,
+    types::{Block, Genesis, GenesisAccount, L1FeeConfig, MempoolTransaction},
+};
+use ethrex_l2::sequencer::block_producer::build_payload;
+use ethrex_l2_rpc::signer::{LocalSigner, Signer};
+use ethrex_storage::{EngineType, Store};
+use ethrex_storage_rollup::{EngineTypeRollup, StoreRollup};
+use ethrex_vm::{BlockExecutionResult, EvmEngine};
+use secp256k1::SecretKey;
+
+pub const CHAIN_ID: u64 = 65536999;
+
+pub fn signer(key: u8) -> Signer {
+    LocalSigner::new(SecretKey::from_slice(&[key; 32]).unwrap()).into()
+}
+
+/// The L2 genesis with the given accounts funded.
+pub fn genesis(rich_accounts: &[Address]) -> Genesis {
+    let mut genesis: Genesis =
+        serde_json::from_str(&std::fs::read_to_string("../../fixtures/genesis/l2.json").unwrap())
+            .unwrap();
+    for address in rich_accounts {
+        genesis.alloc.insert(
+            *address,
+            GenesisAccount {
+                code: Bytes::new(),
+                storage: HashMap::new(),
+                balance: U256::from(u64::MAX),
+                nonce: 0,
+            },
+        );
+    }
+    genesis
+}
+
+/// In-memory stores initialized with the genesis, and an L2 blockchain on top of them.
+pub async fn setup(genesis: Genesis) -> (Arc<Blockchain>, Store, StoreRollup) {
+    let store = Store::new("", EngineType::InMemory).unwrap();
+    store.add_initial_state(genesis).await.unwrap();
+    let rollup_store = StoreRollup::new("", EngineTypeRollup::InMemory).unwrap();
+    rollup_store.init().await.unwrap();
+    let blockchain = Arc::new(Blockchain::new(
+        EvmEngine::LEVM,
+        store.clone(),
+        BlockchainType::L2,
+    ));
+    (blockchain, store, rollup_store)
+}
+
+/// Builds a block on top of the latest one, like the block producer does.
+pub async fn build_block(
+    blockchain: &Arc<Blockchain>,
+    store: &Store,
+    rollup_store: &StoreRollup,
+    only_privileged: bool,
+    preconfirmed: Vec<MempoolTransaction>,
+) -> PayloadBuildResult {
+    build_block_with_l1_fee(
+        blockchain,
+        store,
+        rollup_store,
+        only_privileged,
+        preconfirmed,
+        None,
+    )
+    .await
+}
+
+/// Same as [`build_block`], charging the L1 data fee with the given config.
+pub async fn build_block_with_l1_fee(
+    blockchain: &Arc<Blockchain>,
+    store: &Store,
+    rollup_store: &StoreRollup,
+    only_privileged: bool,
+    preconfirmed: Vec<MempoolTransaction>,
+    l1_fee_config: Option<L1FeeConfig>,
+) -> PayloadBuildResult {
+    let head = store
+        .get_block_header(store.get_latest_block_number().await.unwrap())
+        .unwrap()
+        .unwrap();
+    let args = BuildPayloadArgs {
+        parent: head.hash(),
+        timestamp: head.timestamp + 1,
+        fee_recipient: Address::zero(),
+        random: H256::zero(),
+        withdrawals: Default::default(),
+        beacon_root: Some(H256::zero()),
+        version: 3,
+        elasticity_multiplier: 2,
+    };
+    let mut payload = create_payload(&args, store).unwrap();
+    if let Some(l1_fee_config) = l1_fee_config {
+        payload.header.extra_data = l1_fee_config.encode_extra_data().into();
+    }
+    let (result, _) = build_payload(
+        blockchain.clone(),
+        payload,
+        store,
+        rollup_store,
+        only_privileged,
+        preconfirmed,
+    )
+    .await
+    .unwrap();
+    result
+}
+
+/// Stores a built block and makes it the head of the chain.
+pub async fn store_block(
+    blockchain: &Arc<Blockchain>,
+    store: &Store,
+    result: PayloadBuildResult,
+) -> Block {
+    let block = result.payload;
+    let account_updates_list = store
+        .apply_account_updates_batch(block.header.parent_hash, &result.account_updates)
+        .await
+        .unwrap()
+        .unwrap();
+    blockchain
+        .store_block(
+            &block,
+            account_updates_list,
+            BlockExecutionResult {
+                receipts: result.receipts,
+                requests: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+    apply_fork_choice(store, block.hash(), block.hash(), block.hash())
+        .await
+        .unwrap();
+    block
+}
+
+/// Builds and stores a block with the mempool transactions.
+pub async fn produce_block(
+    blockchain: &Arc<Blockchain>,
+    store: &Store,
+    rollup_store: &StoreRollup,
+) -> Block {
+    let result = build_block(blockchain, store, rollup_store, false, Vec::new()).await;
+    store_block(blockchain, store, result).await
+}
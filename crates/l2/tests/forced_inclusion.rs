@@ -0,0 +1,90 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::expect_used)]
+use ethrex_blockchain::Blockchain;
+use ethrex_common::{
+    Address, H256, U256,
+    types::{Block, EIP1559Transaction, PrivilegedL2Transaction, Transaction, TxKind},
+};
+use ethrex_l2_rpc::signer::{Signable, Signer};
+
+mod common;
+
+use common::{CHAIN_ID, build_block, setup, signer};
+
+fn transaction_hashes(block: &Block) -> Vec<H256> {
+    block
+        .body
+        .transactions
+        .iter()
+        .map(Transaction::hash)
+        .collect()
+}
+
+/// Adds a transfer and a privileged transaction to the mempool.
+async fn fill_mempool(blockchain: &Blockchain, signer: &Signer) -> (Transaction, Transaction) {
+    let mut transfer = Transaction::EIP1559Transaction(EIP1559Transaction {
+        chain_id: CHAIN_ID,
+        nonce: 0,
+        max_priority_fee_per_gas: 1_000_000_000,
+        max_fee_per_gas: 10_000_000_000,
+        gas_limit: 21_000,
+        to: TxKind::Call(Address::repeat_byte(0x22)),
+        value: U256::one(),
+        ..Default::default()
+    });
+    transfer.sign_inplace(signer).await.unwrap();
+    let privileged = Transaction::PrivilegedL2Transaction(PrivilegedL2Transaction {
+        chain_id: CHAIN_ID,
+        nonce: 0,
+        max_priority_fee_per_gas: 1_000_000_000,
+        max_fee_per_gas: 10_000_000_000,
+        gas_limit: 21_000 * 5,
+        to: TxKind::Call(Address::repeat_byte(0x33)),
+        from: Address::repeat_byte(0x44),
+        ..Default::default()
+    });
+    blockchain
+        .add_transaction_to_pool(transfer.clone())
+        .await
+        .unwrap();
+    blockchain
+        .add_transaction_to_pool(privileged.clone())
+        .await
+        .unwrap();
+    (transfer, privileged)
+}
+
+#[tokio::test]
+async fn overdue_privileged_transactions_get_privileged_only_blocks() {
+    let signer = signer(0x11);
+    let (blockchain, store, rollup_store) = setup(common::genesis(&[signer.address()])).await;
+    let (transfer, privileged) = fill_mempool(&blockchain, &signer).await;
+
+    // While overdue privileged transactions are pending, the rest are left in the mempool
+    let block = build_block(&blockchain, &store, &rollup_store, true, Vec::new())
+        .await
+        .payload;
+    assert_eq!(transaction_hashes(&block), vec![privileged.hash()]);
+    assert!(blockchain.mempool.contains_tx(transfer.hash()).unwrap());
+    assert!(!blockchain.mempool.contains_tx(privileged.hash()).unwrap());
+
+    let block = build_block(&blockchain, &store, &rollup_store, false, Vec::new())
+        .await
+        .payload;
+    assert_eq!(transaction_hashes(&block), vec![transfer.hash()]);
+}
+
+#[tokio::test]
+async fn privileged_transactions_share_blocks_when_none_is_overdue() {
+    let signer = signer(0x11);
+    let (blockchain, store, rollup_store) = setup(common::genesis(&[signer.address()])).await;
+    let (transfer, privileged) = fill_mempool(&blockchain, &signer).await;
+
+    let block = build_block(&blockchain, &store, &rollup_store, false, Vec::new())
+        .await
+        .payload;
+    assert_eq!(
+        transaction_hashes(&block),
+        vec![privileged.hash(), transfer.hash()]
+    );
+}
@@ -0,0 +1,344 @@
+#![allow(clippy::panic)]
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::expect_used)]
+#![allow(clippy::as_conversions)]
+#![allow(clippy::indexing_slicing)]
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    types::{BlockBody, BlockHeader, PrivilegedL2Transaction, Transaction, TxKind},
+};
+use ethrex_l2::{
+    based::sequencer_state::{SequencerState, SequencerStatus},
+    sequencer::{
+        l1_block_tracker::{L1BlockTracker, L1Reorg, MAX_L1_REORG_DEPTH},
+        l1_watcher::{L1BlobBaseFee, L1Watcher, OverduePrivilegedTransactions},
+        utils::forget_reorged_batches,
+    },
+};
+use ethrex_rpc::{clients::eth::EthClient, types::block::RpcBlock};
+use ethrex_storage_rollup::{EngineTypeRollup, StoreRollup};
+use serde_json::{Value, json};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+mod common;
+
+/// Local stand-in for an L1 node that serves a chain of empty blocks over JSON-RPC and can be
+/// reorged at will.
+#[derive(Clone)]
+struct L1StandIn {
+    url: String,
+    chain: Arc<Mutex<Vec<BlockHeader>>>,
+}
+
+impl L1StandIn {
+    async fn start(length: u64) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let l1 = Self {
+            url: format!("http://{}", listener.local_addr().unwrap()),
+            chain: Arc::new(Mutex::new(Vec::new())),
+        };
+        l1.extend(length, 0);
+
+        let server = l1.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(server.clone().serve(stream));
+            }
+        });
+        l1
+    }
+
+    /// Appends blocks until the chain has `length` blocks. `fork` sets apart the blocks of
+    /// different branches.
+    fn extend(&self, length: u64, fork: u8) {
+        let mut chain = self.chain.lock().unwrap();
+        while (chain.len() as u64) < length {
+            let parent_hash = chain.last().map(BlockHeader::hash).unwrap_or_default();
+            chain.push(BlockHeader {
+                number: chain.len() as u64,
+                parent_hash,
+                extra_data: Bytes::from(vec![fork]),
+                ..Default::default()
+            });
+        }
+    }
+
+    /// Replaces every block from `first_reorged_block` on with a new branch of `new_length`
+    /// blocks in total.
+    fn reorg(&self, first_reorged_block: u64, new_length: u64, fork: u8) {
+        self.chain
+            .lock()
+            .unwrap()
+            .truncate(first_reorged_block as usize);
+        self.extend(new_length, fork);
+    }
+
+    fn block_hash(&self, number: u64) -> H256 {
+        self.chain.lock().unwrap()[number as usize].hash()
+    }
+
+    fn handle(&self, request: &Value) -> Value {
+        let chain = self.chain.lock().unwrap();
+        match request["method"].as_str().unwrap() {
+            "eth_blockNumber" => json!(format!("{:#x}", chain.len() - 1)),
+            "eth_getBlockByNumber" => {
+                let number = u64::from_str_radix(
+                    request["params"][0]
+                        .as_str()
+                        .unwrap()
+                        .trim_start_matches("0x"),
+                    16,
+                )
+                .unwrap();
+                chain.get(number as usize).map_or(Value::Null, |header| {
+                    let block =
+                        RpcBlock::build(header.clone(), BlockBody::default(), header.hash(), false)
+                            .unwrap();
+                    serde_json::to_value(block).unwrap()
+                })
+            }
+            method => panic!("L1 stand-in doesn't support {method}"),
+        }
+    }
+
+    async fn serve(self, stream: TcpStream) {
+        let mut stream = BufReader::new(stream);
+        loop {
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                if stream.read_line(&mut line).await.unwrap_or(0) == 0 {
+                    return;
+                }
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+
+            let mut body = vec![0; content_length];
+            stream.read_exact(&mut body).await.unwrap();
+            let request: Value = serde_json::from_slice(&body).unwrap();
+            let response = json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": self.handle(&request),
+            })
+            .to_string();
+
+            stream
+                .get_mut()
+                .write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{response}",
+                        response.len()
+                    )
+                    .as_bytes(),
+                )
+                .await
+                .unwrap();
+        }
+    }
+}
+
+#[tokio::test]
+async fn l1_block_tracker_detects_reorgs() {
+    let l1 = L1StandIn::start(30).await;
+    let eth_client = EthClient::new(&l1.url).unwrap();
+
+    let mut tracker = L1BlockTracker::new();
+    tracker.track(10, l1.block_hash(10), [1]);
+    tracker.track(20, l1.block_hash(20), [2]);
+    tracker.track(25, l1.block_hash(25), [3]);
+    tracker.track(25, l1.block_hash(25), [4]);
+    assert_eq!(tracker.check_reorg(&eth_client).await.unwrap(), None);
+
+    // Blocks from 15 on are replaced by a longer branch
+    l1.reorg(15, 35, 1);
+    assert_eq!(
+        tracker.check_reorg(&eth_client).await.unwrap(),
+        Some(L1Reorg {
+            last_canonical_block: Some(10),
+            first_reorged_block: 20,
+            reorged: vec![2, 3, 4],
+        })
+    );
+    assert_eq!(tracker.latest_block(), Some(10));
+    assert_eq!(tracker.check_reorg(&eth_client).await.unwrap(), None);
+
+    // Blocks from 20 on are replaced by a shorter branch, the tracked block is now past the head
+    tracker.track(14, l1.block_hash(14), [5]);
+    tracker.track(30, l1.block_hash(30), [6]);
+    l1.reorg(20, 25, 2);
+    assert_eq!(
+        tracker.check_reorg(&eth_client).await.unwrap(),
+        Some(L1Reorg {
+            last_canonical_block: Some(14),
+            first_reorged_block: 30,
+            reorged: vec![6],
+        })
+    );
+
+    // Every tracked block is reorged
+    l1.reorg(5, 25, 3);
+    assert_eq!(
+        tracker.check_reorg(&eth_client).await.unwrap(),
+        Some(L1Reorg {
+            last_canonical_block: None,
+            first_reorged_block: 10,
+            reorged: vec![1, 5],
+        })
+    );
+    assert_eq!(tracker.latest_block(), None);
+}
+
+#[tokio::test]
+async fn l1_block_tracker_forgets_final_blocks() {
+    let l1 = L1StandIn::start(30).await;
+    let eth_client = EthClient::new(&l1.url).unwrap();
+
+    let mut tracker = L1BlockTracker::new();
+    tracker.track(10, l1.block_hash(10), [1]);
+    tracker.track(20, l1.block_hash(20), [2]);
+
+    // Block 20 is now deeper than the max reorg depth
+    l1.extend(20 + MAX_L1_REORG_DEPTH + 2, 0);
+    assert_eq!(tracker.check_reorg(&eth_client).await.unwrap(), None);
+    assert_eq!(tracker.latest_block(), None);
+}
+
+#[tokio::test]
+async fn l1_watcher_fetches_reorged_logs_again() {
+    let l1 = L1StandIn::start(30).await;
+    let (blockchain, store, _) = common::setup(common::genesis(&[])).await;
+    let mut watcher = L1Watcher {
+        store,
+        blockchain: blockchain.clone(),
+        eth_client: EthClient::new(&l1.url).unwrap(),
+        l2_client: EthClient::new("http://localhost:1729").unwrap(),
+        address: Address::zero(),
+        max_block_step: U256::from(100),
+        last_block_fetched: U256::from(25),
+        check_interval: 1000,
+        l1_block_delay: 0,
+        sequencer_state: SequencerState::from(SequencerStatus::Sequencing),
+        l1_blob_base_fee: L1BlobBaseFee::default(),
+        overdue_privileged_transactions: OverduePrivilegedTransactions::default(),
+        fetched_l1_blocks: L1BlockTracker::new(),
+    };
+
+    // A privileged transaction was added to the mempool from the logs of block 20
+    let privileged = Transaction::PrivilegedL2Transaction(PrivilegedL2Transaction {
+        chain_id: common::CHAIN_ID,
+        gas_limit: 21_000 * 5,
+        to: TxKind::Call(Address::repeat_byte(0x33)),
+        from: Address::repeat_byte(0x44),
+        ..Default::default()
+    });
+    let privileged_hash = blockchain
+        .add_transaction_to_pool(privileged)
+        .await
+        .unwrap();
+    watcher.fetched_l1_blocks.track(10, l1.block_hash(10), []);
+    watcher
+        .fetched_l1_blocks
+        .track(20, l1.block_hash(20), [privileged_hash]);
+    watcher.fetched_l1_blocks.track(25, l1.block_hash(25), []);
+
+    watcher.handle_l1_reorg().await.unwrap();
+    assert_eq!(watcher.last_block_fetched, U256::from(25));
+    assert!(blockchain.mempool.contains_tx(privileged_hash).unwrap());
+
+    // Its block is reorged, so the logs are fetched again from the last canonical block
+    l1.reorg(15, 35, 1);
+    watcher.handle_l1_reorg().await.unwrap();
+    assert_eq!(watcher.last_block_fetched, U256::from(10));
+    assert!(!blockchain.mempool.contains_tx(privileged_hash).unwrap());
+    assert_eq!(watcher.fetched_l1_blocks.latest_block(), Some(10));
+}
+
+#[tokio::test]
+async fn reorged_batches_are_committed_and_verified_again() {
+    let rollup_store = StoreRollup::new("", EngineTypeRollup::InMemory).unwrap();
+    rollup_store.init().await.unwrap();
+    for batch in 1..=3 {
+        rollup_store
+            .store_commit_tx_by_batch(batch, H256::repeat_byte(batch as u8))
+            .await
+            .unwrap();
+        rollup_store
+            .store_verify_tx_by_batch(batch, H256::repeat_byte(0x10 + batch as u8))
+            .await
+            .unwrap();
+    }
+    rollup_store.set_lastest_sent_batch_proof(3).await.unwrap();
+
+    // The committer drops the commitments of batches 2 and 3, and their verifications with them
+    forget_reorged_batches(&rollup_store, &[2, 3], true)
+        .await
+        .unwrap();
+    for batch in 2..=3 {
+        assert_eq!(
+            rollup_store.get_commit_tx_by_batch(batch).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            rollup_store.get_verify_tx_by_batch(batch).await.unwrap(),
+            None
+        );
+    }
+    assert!(
+        rollup_store
+            .get_commit_tx_by_batch(1)
+            .await
+            .unwrap()
+            .is_some()
+    );
+    assert!(
+        rollup_store
+            .get_verify_tx_by_batch(1)
+            .await
+            .unwrap()
+            .is_some()
+    );
+    assert_eq!(
+        rollup_store.get_lastest_sent_batch_proof().await.unwrap(),
+        1
+    );
+
+    // The proof sender only drops the verification of batch 1
+    forget_reorged_batches(&rollup_store, &[1], false)
+        .await
+        .unwrap();
+    assert!(
+        rollup_store
+            .get_commit_tx_by_batch(1)
+            .await
+            .unwrap()
+            .is_some()
+    );
+    assert_eq!(rollup_store.get_verify_tx_by_batch(1).await.unwrap(), None);
+    assert_eq!(
+        rollup_store.get_lastest_sent_batch_proof().await.unwrap(),
+        0
+    );
+
+    // Proofs that weren't sent yet are left as they are
+    forget_reorged_batches(&rollup_store, &[3], false)
+        .await
+        .unwrap();
+    assert_eq!(
+        rollup_store.get_lastest_sent_batch_proof().await.unwrap(),
+        0
+    );
+}
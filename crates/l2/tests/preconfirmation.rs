@@ -0,0 +1,495 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::expect_used)]
+use std::time::Duration;
+
+use ethrex_common::{
+    Address, H256, U256,
+    types::{
+        Block, BlockBody, BlockHeader, EIP1559Transaction, L1FeeConfig, MempoolTransaction,
+        Transaction, TxKind, compute_transactions_root,
+    },
+};
+use ethrex_l2_common::{
+    preconfirmation::{
+        Preconfirmation, PreconfirmationError, PreconfirmationViolation, SignedPreconfirmation,
+    },
+    state_diff::SIMPLE_TX_STATE_DIFF_SIZE,
+};
+use ethrex_l2_rpc::{
+    l2::preconfirmation::{ParentState, PreconfirmationConditions, Preconfirmations},
+    signer::{Signable, Signer},
+};
+
+mod common;
+
+use common::{CHAIN_ID, signer};
+
+const GAS_LIMIT: u64 = 30_000_000;
+const BASE_FEE: u64 = 1_000_000_000;
+const ELASTICITY_MULTIPLIER: u64 = 2;
+
+async fn sign(preconfirmation: Preconfirmation, signer: &Signer) -> SignedPreconfirmation {
+    SignedPreconfirmation {
+        preconfirmation,
+        signature: signer.sign(preconfirmation.encode()).await.unwrap(),
+    }
+}
+
+fn block(number: u64, parent_hash: H256, transactions: Vec<Transaction>) -> Block {
+    let header = BlockHeader {
+        number,
+        parent_hash,
+        transactions_root: compute_transactions_root(&transactions),
+        ..Default::default()
+    };
+    Block::new(
+        header,
+        BlockBody {
+            transactions,
+            ..Default::default()
+        },
+    )
+}
+
+fn tx(nonce: u64) -> Transaction {
+    tx_with_fee(nonce, BASE_FEE)
+}
+
+fn tx_with_fee(nonce: u64, max_fee_per_gas: u64) -> Transaction {
+    Transaction::EIP1559Transaction(EIP1559Transaction {
+        nonce,
+        max_fee_per_gas,
+        gas_limit: 21_000,
+        ..Default::default()
+    })
+}
+
+/// Header of a half full block, which leaves the base fee of the next one at `BASE_FEE`.
+fn parent_header(number: u64) -> BlockHeader {
+    BlockHeader {
+        number,
+        gas_limit: GAS_LIMIT,
+        gas_used: GAS_LIMIT / 2,
+        base_fee_per_gas: Some(BASE_FEE),
+        ..Default::default()
+    }
+}
+
+/// Takes the transactions preconfirmed for `block_number` and stores it, like the block
+/// producer does.
+async fn produce(
+    preconfirmations: &Preconfirmations,
+    block_number: u64,
+    accept_next: bool,
+) -> Vec<MempoolTransaction> {
+    let taken = preconfirmations.take(block_number, accept_next).await;
+    preconfirmations
+        .parent_stored(&parent_header(block_number), ELASTICITY_MULTIPLIER)
+        .await;
+    taken
+}
+
+fn parent(number: u64) -> ParentState {
+    ParentState {
+        number,
+        gas_limit: GAS_LIMIT,
+        ..Default::default()
+    }
+}
+
+async fn transfer(signer: &Signer, nonce: u64, max_priority_fee_per_gas: u64) -> Transaction {
+    let mut transfer = Transaction::EIP1559Transaction(EIP1559Transaction {
+        chain_id: CHAIN_ID,
+        nonce,
+        max_priority_fee_per_gas,
+        max_fee_per_gas: 100_000_000_000,
+        gas_limit: 21_000,
+        to: TxKind::Call(Address::repeat_byte(0x22)),
+        value: U256::one(),
+        ..Default::default()
+    });
+    transfer.sign_inplace(signer).await.unwrap();
+    transfer
+}
+
+async fn reserve(
+    preconfirmations: &Preconfirmations,
+    transaction: &Transaction,
+    sender: Address,
+    conditions: PreconfirmationConditions,
+) -> Option<Preconfirmation> {
+    let parent_number = preconfirmations.wait_for_parent().await.ok()?;
+    preconfirmations
+        .reserve(
+            transaction,
+            sender,
+            &parent(parent_number),
+            CHAIN_ID,
+            &conditions,
+        )
+        .await
+        .ok()
+}
+
+fn no_conditions() -> PreconfirmationConditions {
+    PreconfirmationConditions::default()
+}
+
+#[tokio::test]
+async fn preconfirmation_violation_is_provable() {
+    let signer = signer(0x11);
+    let preconfirmed = tx(0);
+    let preconfirmation = sign(
+        Preconfirmation {
+            chain_id: CHAIN_ID,
+            tx_hash: preconfirmed.hash(),
+            block_number: 10,
+            index: 0,
+        },
+        &signer,
+    )
+    .await;
+    assert_eq!(preconfirmation.signer(), Ok(signer.address()));
+
+    // The preconfirmed transaction was included, but at another index
+    let broken = block(10, H256::zero(), vec![tx(1), preconfirmed.clone()]);
+    let child = block(11, broken.hash(), vec![]).header;
+    let violation = PreconfirmationViolation {
+        preconfirmation,
+        block: broken,
+        descendants: vec![child.clone()],
+    };
+    assert_eq!(violation.verify(signer.address(), child.hash()), Ok(()));
+    assert_eq!(
+        violation.verify(Address::zero(), child.hash()),
+        Err(PreconfirmationError::WrongSigner)
+    );
+    assert_eq!(
+        violation.verify(signer.address(), H256::zero()),
+        Err(PreconfirmationError::NotCommitted)
+    );
+
+    let honoured = block(10, H256::zero(), vec![preconfirmed]);
+    let honoured_hash = honoured.hash();
+    let not_a_violation = PreconfirmationViolation {
+        block: honoured,
+        descendants: vec![],
+        ..violation
+    };
+    assert_eq!(
+        not_a_violation.verify(signer.address(), honoured_hash),
+        Err(PreconfirmationError::Honoured)
+    );
+}
+
+#[tokio::test]
+async fn preconfirmation_violation_rejects_tampered_blocks() {
+    let signer = signer(0x22);
+    let preconfirmed = tx(0);
+    let preconfirmation = sign(
+        Preconfirmation {
+            chain_id: 1,
+            tx_hash: preconfirmed.hash(),
+            block_number: 3,
+            index: 0,
+        },
+        &signer,
+    )
+    .await;
+
+    // The transactions don't match the header the batch committed to
+    let mut block = block(3, H256::zero(), vec![preconfirmed]);
+    block.body.transactions = vec![tx(1)];
+    let block_hash = block.hash();
+    let violation = PreconfirmationViolation {
+        preconfirmation,
+        block,
+        descendants: vec![],
+    };
+    assert_eq!(
+        violation.verify(signer.address(), block_hash),
+        Err(PreconfirmationError::InvalidBlockBody)
+    );
+
+    // A signature of a different preconfirmation doesn't prove anything
+    let mut forged = violation.preconfirmation;
+    forged.preconfirmation.index = 1;
+    assert_ne!(forged.signer(), Ok(signer.address()));
+}
+
+#[tokio::test]
+async fn preconfirmations_take_consecutive_slots_and_nonces() {
+    let preconfirmations = Preconfirmations::new();
+    let (alice, bob) = (Address::repeat_byte(0xa), Address::repeat_byte(0xb));
+
+    // Nothing is preconfirmed until the block producer starts accepting preconfirmations
+    assert_eq!(
+        reserve(&preconfirmations, &tx(0), alice, no_conditions()).await,
+        None
+    );
+    assert!(produce(&preconfirmations, 9, true).await.is_empty());
+
+    let first = reserve(&preconfirmations, &tx(0), alice, no_conditions())
+        .await
+        .unwrap();
+    assert_eq!(
+        first,
+        Preconfirmation {
+            chain_id: CHAIN_ID,
+            tx_hash: tx(0).hash(),
+            block_number: 10,
+            index: 0,
+        }
+    );
+    // A sender's transactions can't leave nonce gaps or reuse nonces
+    assert_eq!(
+        reserve(&preconfirmations, &tx(2), alice, no_conditions()).await,
+        None
+    );
+    assert_eq!(
+        reserve(&preconfirmations, &tx(0), alice, no_conditions()).await,
+        None
+    );
+    let second = reserve(&preconfirmations, &tx(1), alice, no_conditions())
+        .await
+        .unwrap();
+    assert_eq!((second.block_number, second.index), (10, 1));
+    let third = reserve(&preconfirmations, &tx(0), bob, no_conditions())
+        .await
+        .unwrap();
+    assert_eq!((third.block_number, third.index), (10, 2));
+
+    // The block must be early enough and have gas left
+    let too_late = PreconfirmationConditions {
+        block_number_max: Some(9),
+    };
+    assert_eq!(
+        reserve(&preconfirmations, &tx(1), bob, too_late).await,
+        None
+    );
+    let too_big = Transaction::EIP1559Transaction(EIP1559Transaction {
+        nonce: 1,
+        max_fee_per_gas: BASE_FEE,
+        gas_limit: GAS_LIMIT + 1,
+        ..Default::default()
+    });
+    assert_eq!(
+        reserve(&preconfirmations, &too_big, bob, no_conditions()).await,
+        None
+    );
+
+    let taken: Vec<H256> = produce(&preconfirmations, 10, true)
+        .await
+        .iter()
+        .map(|tx| tx.hash())
+        .collect();
+    assert_eq!(taken, vec![first.tx_hash, second.tx_hash, third.tx_hash]);
+
+    // Nonces start over from the state, and preconfirmations for a block that isn't the one
+    // produced are dropped
+    let next = reserve(&preconfirmations, &tx(0), alice, no_conditions())
+        .await
+        .unwrap();
+    assert_eq!((next.block_number, next.index), (11, 0));
+    assert!(produce(&preconfirmations, 12, false).await.is_empty());
+    assert_eq!(
+        reserve(&preconfirmations, &tx(0), alice, no_conditions()).await,
+        None
+    );
+}
+
+#[tokio::test]
+async fn only_the_last_preconfirmation_slot_is_released() {
+    let preconfirmations = Preconfirmations::new();
+    let (alice, bob) = (Address::repeat_byte(0xa), Address::repeat_byte(0xb));
+    produce(&preconfirmations, 0, true).await;
+
+    let first = reserve(&preconfirmations, &tx(0), alice, no_conditions())
+        .await
+        .unwrap();
+    let second = reserve(&preconfirmations, &tx(1), alice, no_conditions())
+        .await
+        .unwrap();
+    let third = reserve(&preconfirmations, &tx(0), bob, no_conditions())
+        .await
+        .unwrap();
+
+    // The slots after the first one were already promised
+    assert!(!preconfirmations.release(&first).await);
+    assert!(preconfirmations.release(&third).await);
+    assert!(preconfirmations.release(&second).await);
+
+    // The released slot and nonce are handed out again
+    let retried = reserve(&preconfirmations, &tx(1), alice, no_conditions())
+        .await
+        .unwrap();
+    assert_eq!(retried, second);
+    assert_eq!(preconfirmations.take(1, false).await.len(), 2);
+}
+
+#[tokio::test]
+async fn preconfirmations_need_a_fee_cap_above_the_next_base_fee_and_l1_fee() {
+    let preconfirmations = Preconfirmations::new();
+    let alice = Address::repeat_byte(0xa);
+    preconfirmations.take(9, true).await;
+    // A full parent raises the base fee by an eighth
+    let full_parent = BlockHeader {
+        gas_used: GAS_LIMIT,
+        ..parent_header(9)
+    };
+    preconfirmations
+        .parent_stored(&full_parent, ELASTICITY_MULTIPLIER)
+        .await;
+    let base_fee = BASE_FEE + BASE_FEE / 8;
+
+    // Enough for the parent, but not for the preconfirmed block
+    assert_eq!(
+        reserve(&preconfirmations, &tx(0), alice, no_conditions()).await,
+        None
+    );
+    let exact = tx_with_fee(0, base_fee);
+    assert!(
+        reserve(&preconfirmations, &exact, alice, no_conditions())
+            .await
+            .is_some()
+    );
+
+    // The L1 data fee is charged as gas too, so the fee cap must leave room for it
+    let l1_fee_config = L1FeeConfig {
+        l1_fee_vault: Address::repeat_byte(0xf),
+        l1_fee_per_blob_gas: 1_000_000_000_000,
+    };
+    let l1_fee = l1_fee_config.l1_fee(SIMPLE_TX_STATE_DIFF_SIZE);
+    let l1_fee_per_gas = ((l1_fee + U256::from(20_999)) / U256::from(21_000)).as_u64();
+    let with_l1_fee = ParentState {
+        l1_fee_config: Some(l1_fee_config),
+        ..parent(9)
+    };
+    let bob = Address::repeat_byte(0xb);
+    let below_l1_fee = tx_with_fee(0, base_fee + l1_fee_per_gas - 1);
+    assert!(
+        preconfirmations
+            .reserve(&below_l1_fee, bob, &with_l1_fee, CHAIN_ID, &no_conditions())
+            .await
+            .is_err()
+    );
+    let covering_l1_fee = tx_with_fee(0, base_fee + l1_fee_per_gas);
+    let preconfirmation = preconfirmations
+        .reserve(
+            &covering_l1_fee,
+            bob,
+            &with_l1_fee,
+            CHAIN_ID,
+            &no_conditions(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        (preconfirmation.block_number, preconfirmation.index),
+        (10, 1)
+    );
+}
+
+#[tokio::test]
+async fn preconfirmations_use_the_nonces_of_the_block_being_built() {
+    let alice = signer(0x11);
+    let (blockchain, store, rollup_store) =
+        common::setup(common::genesis(&[alice.address()])).await;
+    let sent = transfer(&alice, 0, 1_000_000_000).await;
+    blockchain.add_transaction_to_pool(sent).await.unwrap();
+
+    // The block producer started block 1, which takes the transaction out of the mempool before
+    // it's part of the latest state
+    let preconfirmations = Preconfirmations::new();
+    preconfirmations.take(1, true).await;
+    let waiting = tokio::spawn({
+        let preconfirmations = preconfirmations.clone();
+        async move { preconfirmations.wait_for_parent().await }
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!waiting.is_finished());
+
+    let block = common::produce_block(&blockchain, &store, &rollup_store).await;
+    assert_eq!(block.body.transactions.len(), 1);
+    preconfirmations
+        .parent_stored(&block.header, ELASTICITY_MULTIPLIER)
+        .await;
+    let parent_number = waiting.await.unwrap().unwrap();
+    assert_eq!(parent_number, 1);
+
+    let parent = ParentState::read(&store, parent_number, alice.address())
+        .await
+        .unwrap();
+    assert_eq!(parent.sender_nonce, 1);
+    let reused_nonce = transfer(&alice, 0, 2_000_000_000).await;
+    assert!(
+        preconfirmations
+            .reserve(
+                &reused_nonce,
+                alice.address(),
+                &parent,
+                CHAIN_ID,
+                &no_conditions()
+            )
+            .await
+            .is_err()
+    );
+    let next = transfer(&alice, 1, 1_000_000_000).await;
+    let preconfirmation = preconfirmations
+        .reserve(&next, alice.address(), &parent, CHAIN_ID, &no_conditions())
+        .await
+        .unwrap();
+    assert_eq!(
+        (preconfirmation.block_number, preconfirmation.index),
+        (2, 0)
+    );
+}
+
+#[tokio::test]
+async fn block_producer_honours_preconfirmed_order() {
+    let (alice, bob, carol) = (signer(0x11), signer(0x22), signer(0x33));
+    let (blockchain, store, rollup_store) = common::setup(common::genesis(&[
+        alice.address(),
+        bob.address(),
+        carol.address(),
+    ]))
+    .await;
+
+    // Carol pays the highest tip, but the preconfirmed transactions go first in the promised order
+    let carols = transfer(&carol, 0, 10_000_000_000).await;
+    blockchain
+        .add_transaction_to_pool(carols.clone())
+        .await
+        .unwrap();
+    let preconfirmations = Preconfirmations::new();
+    preconfirmations.take(0, true).await;
+    let genesis = store.get_block_header(0).unwrap().unwrap();
+    preconfirmations
+        .parent_stored(&genesis, ELASTICITY_MULTIPLIER)
+        .await;
+    let bobs = transfer(&bob, 0, 1_000_000_000).await;
+    let alices = transfer(&alice, 0, 2_000_000_000).await;
+    for (tx, signer) in [(&bobs, &bob), (&alices, &alice)] {
+        reserve(&preconfirmations, tx, signer.address(), no_conditions())
+            .await
+            .unwrap();
+    }
+    // Also sent to the mempool, which must not include it twice
+    blockchain
+        .add_transaction_to_pool(alices.clone())
+        .await
+        .unwrap();
+
+    let preconfirmed = preconfirmations.take(1, true).await;
+    let result = common::build_block(&blockchain, &store, &rollup_store, false, preconfirmed).await;
+
+    let included: Vec<H256> = result
+        .payload
+        .body
+        .transactions
+        .iter()
+        .map(Transaction::hash)
+        .collect();
+    assert_eq!(included, vec![bobs.hash(), alices.hash(), carols.hash()]);
+    assert!(!blockchain.mempool.contains_tx(alices.hash()).unwrap());
+}
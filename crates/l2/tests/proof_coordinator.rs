@@ -0,0 +1,272 @@
+#![allow(clippy::panic)]
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::expect_used)]
+use std::time::{Duration, Instant};
+
+use ethrex_common::types::Batch;
+use ethrex_l2::sequencer::{
+    errors::{ProofCoordinatorError, ProverAuthError},
+    proof_coordinator::{
+        BatchAssignments, MAX_REQUEST_TIME_DRIFT_SECS, ProofData, ProverAllowlist, unix_timestamp,
+    },
+};
+use ethrex_l2_common::prover::{BatchProof, ProofCalldata, ProverType};
+use ethrex_l2_rpc::signer::LocalSigner;
+use ethrex_storage_rollup::{EngineTypeRollup, StoreRollup};
+use secp256k1::SecretKey;
+
+const PROVER_ID: u64 = 7;
+
+fn key(byte: u8) -> SecretKey {
+    SecretKey::from_slice(&[byte; 32]).unwrap()
+}
+
+fn batch_request() -> ProofData {
+    ProofData::batch_request("commit".to_string(), PROVER_ID, ProverType::Exec)
+}
+
+fn copy(request: &ProofData) -> ProofData {
+    serde_json::from_slice(&serde_json::to_vec(request).unwrap()).unwrap()
+}
+
+fn allowlist() -> ProverAllowlist {
+    ProverAllowlist::new(&[LocalSigner::new(key(1)).address], false).unwrap()
+}
+
+#[test]
+fn allowed_provers_are_accepted() {
+    let request = batch_request().authenticated(&key(1)).unwrap();
+
+    let request = allowlist().authorize(request, unix_timestamp()).unwrap();
+
+    assert!(matches!(
+        request,
+        ProofData::BatchRequest {
+            prover_id: PROVER_ID,
+            prover_type: ProverType::Exec,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn unknown_and_unauthenticated_provers_are_rejected() {
+    let allowlist = allowlist();
+    let unknown = LocalSigner::new(key(2)).address;
+
+    let request = batch_request().authenticated(&key(2)).unwrap();
+    assert!(matches!(
+        allowlist.authorize(request, unix_timestamp()),
+        Err(ProverAuthError::UnknownProver(prover)) if prover == unknown
+    ));
+    assert!(matches!(
+        allowlist.authorize(batch_request(), unix_timestamp()),
+        Err(ProverAuthError::Unauthenticated)
+    ));
+}
+
+#[test]
+fn tampered_requests_are_rejected() {
+    let ProofData::Authenticated {
+        timestamp,
+        nonce,
+        signature,
+        ..
+    } = batch_request().authenticated(&key(1)).unwrap()
+    else {
+        panic!("expected an authenticated request");
+    };
+    // The signature of a batch request can't be used to submit a proof
+    let payload = serde_json::to_vec(&ProofData::proof_submit_ack(1))
+        .unwrap()
+        .into();
+    let tampered = ProofData::Authenticated {
+        payload,
+        timestamp,
+        nonce,
+        signature,
+    };
+
+    assert!(matches!(
+        allowlist().authorize(tampered, unix_timestamp()),
+        Err(ProverAuthError::UnknownProver(_))
+    ));
+}
+
+#[test]
+fn expired_requests_are_rejected() {
+    let request = batch_request().authenticated(&key(1)).unwrap();
+
+    let later = unix_timestamp() + MAX_REQUEST_TIME_DRIFT_SECS + 1;
+    assert!(matches!(
+        allowlist().authorize(request, later),
+        Err(ProverAuthError::StaleRequest(_))
+    ));
+}
+
+#[test]
+fn replayed_requests_are_rejected() {
+    let allowlist = allowlist();
+    let request = batch_request().authenticated(&key(1)).unwrap();
+    let replayed = copy(&request);
+
+    assert!(allowlist.authorize(request, unix_timestamp()).is_ok());
+    assert!(matches!(
+        allowlist.authorize(replayed, unix_timestamp()),
+        Err(ProverAuthError::ReplayedRequest(_))
+    ));
+    // The same request signed again gets a new nonce
+    let resigned = batch_request().authenticated(&key(1)).unwrap();
+    assert!(allowlist.authorize(resigned, unix_timestamp()).is_ok());
+}
+
+#[test]
+fn accepting_any_prover_must_be_explicit() {
+    assert!(matches!(
+        ProverAllowlist::new(&[], false),
+        Err(ProofCoordinatorError::MissingProverAllowlist)
+    ));
+
+    let any_prover = ProverAllowlist::new(&[], true).unwrap();
+    assert!(
+        any_prover
+            .authorize(batch_request(), unix_timestamp())
+            .is_ok()
+    );
+    let request = batch_request().authenticated(&key(2)).unwrap();
+    assert!(any_prover.authorize(request, unix_timestamp()).is_ok());
+}
+
+const LEASE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Rollup store with the given amount of committed batches, one block each.
+async fn rollup_store(batches: u64) -> StoreRollup {
+    let rollup_store = StoreRollup::new("", EngineTypeRollup::InMemory).unwrap();
+    rollup_store.init().await.unwrap();
+    for number in 1..=batches {
+        rollup_store
+            .seal_batch(Batch {
+                number,
+                first_block: number,
+                last_block: number,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+    rollup_store
+}
+
+async fn assign(
+    assignments: &mut BatchAssignments,
+    rollup_store: &StoreRollup,
+    prover_id: u64,
+    now: Instant,
+) -> Option<u64> {
+    assignments
+        .assign(
+            rollup_store,
+            0,
+            prover_id,
+            ProverType::Exec,
+            now,
+            LEASE_TIMEOUT,
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn provers_are_assigned_distinct_batches() {
+    let rollup_store = rollup_store(2).await;
+    let mut assignments = BatchAssignments::new();
+    let now = Instant::now();
+
+    assert_eq!(
+        assign(&mut assignments, &rollup_store, 1, now).await,
+        Some(1)
+    );
+    assert_eq!(
+        assign(&mut assignments, &rollup_store, 2, now).await,
+        Some(2)
+    );
+    assert_eq!(assign(&mut assignments, &rollup_store, 3, now).await, None);
+
+    // Provers of another type prove the same batches
+    let other_type = assignments
+        .assign(&rollup_store, 0, 4, ProverType::SP1, now, LEASE_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(other_type, Some(1));
+
+    // A prover asking again is done with its previous batch, which it can get again
+    assert_eq!(
+        assign(&mut assignments, &rollup_store, 1, now).await,
+        Some(1)
+    );
+}
+
+#[tokio::test]
+async fn expired_leases_are_reassigned() {
+    let rollup_store = rollup_store(1).await;
+    let mut assignments = BatchAssignments::new();
+    let now = Instant::now();
+
+    assert_eq!(
+        assign(&mut assignments, &rollup_store, 1, now).await,
+        Some(1)
+    );
+    let before_expiry = now + LEASE_TIMEOUT - Duration::from_secs(1);
+    assert_eq!(
+        assign(&mut assignments, &rollup_store, 2, before_expiry).await,
+        None
+    );
+    assert!(assignments.is_leased(1, ProverType::Exec));
+
+    let after_expiry = now + LEASE_TIMEOUT;
+    assert_eq!(
+        assign(&mut assignments, &rollup_store, 2, after_expiry).await,
+        Some(1)
+    );
+}
+
+#[tokio::test]
+async fn proven_batches_are_released_and_not_assigned_again() {
+    let rollup_store = rollup_store(3).await;
+    let mut assignments = BatchAssignments::new();
+    let now = Instant::now();
+
+    assert_eq!(
+        assign(&mut assignments, &rollup_store, 1, now).await,
+        Some(1)
+    );
+    assert_eq!(
+        assign(&mut assignments, &rollup_store, 2, now).await,
+        Some(2)
+    );
+
+    // The proof of batch 1 is received
+    let proof = BatchProof::ProofCalldata(ProofCalldata {
+        prover_type: ProverType::Exec,
+        calldata: Vec::new(),
+    });
+    rollup_store
+        .store_proof_by_batch_and_type(1, ProverType::Exec, proof)
+        .await
+        .unwrap();
+    assignments.release(1, ProverType::Exec);
+    assert!(!assignments.is_leased(1, ProverType::Exec));
+
+    assert_eq!(
+        assign(&mut assignments, &rollup_store, 3, now).await,
+        Some(3)
+    );
+    assert_eq!(assign(&mut assignments, &rollup_store, 4, now).await, None);
+
+    // Batches that were already verified release their leases
+    assignments
+        .assign(&rollup_store, 2, 5, ProverType::Exec, now, LEASE_TIMEOUT)
+        .await
+        .unwrap();
+    assert!(!assignments.is_leased(2, ProverType::Exec));
+}
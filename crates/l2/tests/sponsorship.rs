@@ -0,0 +1,179 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::expect_used)]
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use ethrex_common::{Address, U256};
+use ethrex_l2_rpc::{
+    l2::sponsorship::{AccountProof, SponsorshipPolicy, SponsorshipRequest, today},
+    utils::RpcErr,
+};
+use ethrex_storage_rollup::StoreRollup;
+
+mod common;
+
+use common::{CHAIN_ID, signer};
+
+const EXECUTE_SELECTOR: [u8; 4] = [0xb6, 0x1d, 0x27, 0xf6];
+
+fn policy() -> SponsorshipPolicy {
+    SponsorshipPolicy::new(
+        HashMap::from([
+            (Address::repeat_byte(1), vec![EXECUTE_SELECTOR]),
+            (Address::repeat_byte(2), vec![]),
+        ]),
+        100_000,
+        3,
+        250_000,
+        U256::from(1_000_000),
+    )
+}
+
+fn request(account: Address) -> SponsorshipRequest {
+    SponsorshipRequest {
+        chain_id: CHAIN_ID,
+        account,
+        data: Bytes::from(EXECUTE_SELECTOR.to_vec()),
+    }
+}
+
+async fn sponsor(
+    policy: &SponsorshipPolicy,
+    store: &StoreRollup,
+    account: Address,
+    gas: u64,
+    spent: u64,
+) -> Result<(), RpcErr> {
+    policy
+        .sponsor(
+            store,
+            &request(account),
+            AccountProof::Authorization,
+            gas,
+            spent.into(),
+            async { Ok(()) },
+        )
+        .await
+}
+
+#[test]
+fn sponsorship_policy_allows_listed_targets_and_selectors() {
+    let policy = policy();
+    let mut execute_call = EXECUTE_SELECTOR.to_vec();
+    execute_call.extend_from_slice(&[0; 32]);
+
+    assert!(
+        policy
+            .check_target(Address::repeat_byte(1), &execute_call)
+            .is_ok()
+    );
+    assert!(
+        policy
+            .check_target(Address::repeat_byte(1), &[0xde, 0xad, 0xbe, 0xef])
+            .is_err()
+    );
+    assert!(policy.check_target(Address::repeat_byte(1), &[]).is_err());
+    // No selectors listed means any call is sponsored
+    assert!(
+        policy
+            .check_target(Address::repeat_byte(2), &[0xde, 0xad, 0xbe, 0xef])
+            .is_ok()
+    );
+    assert!(
+        policy
+            .check_target(Address::repeat_byte(3), &execute_call)
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn sponsorship_policy_enforces_budgets() {
+    let policy = policy();
+    let store = StoreRollup::default();
+    let (alice, bob, carol, dave) = (
+        Address::repeat_byte(0xa),
+        Address::repeat_byte(0xb),
+        Address::repeat_byte(0xc),
+        Address::repeat_byte(0xd),
+    );
+
+    // Per transaction gas limit
+    assert!(sponsor(&policy, &store, alice, 100_001, 1).await.is_err());
+    assert!(sponsor(&policy, &store, alice, 0, 1).await.is_err());
+
+    // Per account gas budget
+    sponsor(&policy, &store, alice, 100_000, 1).await.unwrap();
+    sponsor(&policy, &store, alice, 100_000, 1).await.unwrap();
+    assert!(sponsor(&policy, &store, alice, 60_000, 1).await.is_err());
+
+    // Per account transaction budget
+    for _ in 0..3 {
+        sponsor(&policy, &store, bob, 21_000, 1).await.unwrap();
+    }
+    assert!(sponsor(&policy, &store, bob, 21_000, 1).await.is_err());
+
+    // Daily spend cap, shared by every account
+    assert!(
+        sponsor(&policy, &store, carol, 21_000, 999_996)
+            .await
+            .is_err()
+    );
+    sponsor(&policy, &store, carol, 21_000, 999_995)
+        .await
+        .unwrap();
+    assert!(sponsor(&policy, &store, alice, 21_000, 1).await.is_err());
+
+    // Failed sends are not accounted
+    let failed = policy
+        .sponsor(
+            &store,
+            &request(dave),
+            AccountProof::Authorization,
+            21_000,
+            U256::zero(),
+            async { Err::<(), _>(RpcErr::Internal("mempool rejected the tx".to_string())) },
+        )
+        .await;
+    assert!(failed.is_err());
+    for _ in 0..3 {
+        sponsor(&policy, &store, dave, 21_000, 0).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn sponsorship_of_delegated_accounts_needs_their_signature() {
+    let policy = policy();
+    let store = StoreRollup::default();
+    let (alice, mallory) = (signer(0x11), signer(0x22));
+    let request = request(alice.address());
+    let day = today().unwrap();
+    let sponsor_signed = async |signature| {
+        policy
+            .sponsor(
+                &store,
+                &request,
+                AccountProof::Signature(signature),
+                21_000,
+                U256::one(),
+                async { Ok(()) },
+            )
+            .await
+    };
+
+    // Someone else can't spend the account's budgets
+    let forged = mallory.sign(request.encode(day, 0)).await.unwrap();
+    assert!(sponsor_signed(forged).await.is_err());
+    let other_call = SponsorshipRequest {
+        data: Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]),
+        ..request.clone()
+    };
+    let other_call_signature = alice.sign(other_call.encode(day, 0)).await.unwrap();
+    assert!(sponsor_signed(other_call_signature).await.is_err());
+
+    // Each signature is accounted once, the next request signs the next index
+    let first = alice.sign(request.encode(day, 0)).await.unwrap();
+    sponsor_signed(first).await.unwrap();
+    assert!(sponsor_signed(first).await.is_err());
+    let second = alice.sign(request.encode(day, 1)).await.unwrap();
+    sponsor_signed(second).await.unwrap();
+}
@@ -7,6 +7,8 @@ use ethrex_l2::monitor::widget::l2_to_l1_messages::{L2ToL1MessageKind, L2ToL1Mes
 use ethrex_l2::monitor::widget::{L2ToL1MessagesTable, l2_to_l1_messages::L2ToL1MessageRow};
 use ethrex_l2::sequencer::l1_watcher::PrivilegedTransactionData;
 use ethrex_l2_common::calldata::Value;
+use ethrex_l2_common::privileged_transactions::compute_privileged_transactions_hash;
+use ethrex_l2_common::user_operation::UserOperation;
 use ethrex_l2_rpc::clients::send_generic_transaction;
 use ethrex_l2_rpc::{
     clients::deploy,
@@ -124,6 +126,10 @@ async fn l2_integration_test() -> Result<(), Box<dyn std::error::Error>> {
     )
     .await?;
 
+    test_privileged_tx_inclusion_deadline(&l1_client, &l2_client, &rich_wallet_private_key).await?;
+
+    test_overdue_privileged_tx_commit(&l1_client, &rich_wallet_private_key).await?;
+
     test_gas_burning(&l1_client, &rich_wallet_private_key).await?;
 
     test_privileged_tx_with_contract_call(&l1_client, &l2_client, &rich_wallet_private_key).await?;
@@ -145,6 +151,8 @@ async fn l2_integration_test() -> Result<(), Box<dyn std::error::Error>> {
 
     test_erc20_failed_deposit(&l1_client, &l2_client, &rich_wallet_private_key).await?;
 
+    test_user_operation_hash(&l2_client, &rich_wallet_private_key).await?;
+
     test_forced_withdrawal(&l1_client, &l2_client, &rich_wallet_private_key).await?;
 
     let withdrawals_count = std::env::var("INTEGRATION_TEST_WITHDRAW_COUNT")
@@ -797,6 +805,79 @@ async fn test_forced_withdrawal(
     Ok(())
 }
 
+/// Checks the hash of a UserOperation with a factory and a paymaster against the v0.7 EntryPoint's
+/// `getUserOpHash`, reproduced with its `UserOperationLib`.
+async fn test_user_operation_hash(
+    l2_client: &EthClient,
+    private_key: &SecretKey,
+) -> Result<(), Box<dyn std::error::Error>> {
+    println!("test_user_operation_hash: Downloading account-abstraction contracts");
+    let contracts_path = Path::new("contracts");
+    std::fs::create_dir_all(contracts_path.join("lib"))?;
+    git_clone(
+        "https://github.com/eth-infinitism/account-abstraction.git",
+        contracts_path
+            .join("lib/account-abstraction")
+            .to_str()
+            .expect("Failed to convert path to str"),
+        Some("v0.7.0"),
+        false,
+    )?;
+    let remappings = [(
+        "account-abstraction",
+        contracts_path.join("lib/account-abstraction"),
+    )];
+    compile_contract(
+        contracts_path,
+        &contracts_path.join("src/example/UserOperationHasher.sol"),
+        false,
+        Some(&remappings),
+        &[contracts_path],
+    )?;
+    let init_code = hex::decode(String::from_utf8(std::fs::read(
+        "contracts/solc_out/UserOperationHasher.bin",
+    )?)?)?;
+    let hasher = test_deploy(l2_client, &init_code, private_key).await?;
+
+    // v0.7 canonical entry point
+    let entry_point = Address::from_str("0x0000000071727De22E5E9d8BAf0edAc6f37da032")?;
+    let user_operation = UserOperation {
+        sender: Address::repeat_byte(0xaa),
+        nonce: U256::from(7) << 64,
+        factory: Some(Address::repeat_byte(0xfa)),
+        factory_data: Bytes::from(vec![0x5f, 0xbf, 0xb9, 0xcf, 0x01]),
+        call_data: Bytes::from(vec![0xb6, 0x1d, 0x27, 0xf6, 0x02, 0x03]),
+        call_gas_limit: 100_000,
+        verification_gas_limit: 200_000,
+        pre_verification_gas: 50_000,
+        max_fee_per_gas: 2_000_000_000,
+        max_priority_fee_per_gas: 1_000_000_000,
+        paymaster: Some(Address::repeat_byte(0xbb)),
+        paymaster_verification_gas_limit: 30_000,
+        paymaster_post_op_gas_limit: 10_000,
+        paymaster_data: Bytes::from(vec![0xcc; 40]),
+        signature: Bytes::from(vec![0x11; 65]),
+    };
+    let calldata = encode_calldata(
+        "getUserOpHash((address,uint256,bytes,bytes,bytes32,uint256,bytes32,bytes,bytes),address)",
+        &[
+            Value::Tuple(user_operation.to_packed_values()),
+            Value::Address(entry_point),
+        ],
+    )?;
+    let hash = l2_client
+        .call(hasher, calldata.into(), Overrides::default())
+        .await?;
+    let chain_id = l2_client.get_chain_id().await?.as_u64();
+
+    assert_eq!(
+        H256::from_slice(&parse_hex(&hash)?),
+        user_operation.hash(entry_point, chain_id),
+        "UserOperation hash doesn't match the entry point's"
+    );
+    Ok(())
+}
+
 async fn test_balance_of(client: &EthClient, token: Address, user: Address) -> U256 {
     let res = client
         .call(
@@ -1057,6 +1138,298 @@ async fn test_transfer_with_privileged_tx(
     Ok(())
 }
 
+/// Checks that privileged transactions get an inclusion deadline on L1 and that the sequencer
+/// commits them before it expires.
+async fn test_privileged_tx_inclusion_deadline(
+    l1_client: &EthClient,
+    l2_client: &EthClient,
+    private_key: &SecretKey,
+) -> Result<(), Box<dyn std::error::Error>> {
+    println!("inclusion_deadline: Sending privileged transaction");
+    let address = get_address_from_secret_key(private_key)?;
+    let bridge = bridge_address()?;
+
+    let mut batch_number = l1_client
+        .get_last_verified_batch(on_chain_proposer_address())
+        .await?;
+
+    let l1_to_l2_tx_hash = ethrex_l2_sdk::send_l1_to_l2_tx(
+        address,
+        Some(0),
+        None,
+        L1ToL2TransactionData::new(address, 21000 * 5, U256::zero(), Bytes::new()),
+        private_key,
+        bridge,
+        l1_client,
+    )
+    .await?;
+    let l1_to_l2_tx_receipt = wait_for_transaction_receipt(l1_to_l2_tx_hash, l1_client, 5).await?;
+    assert!(
+        l1_to_l2_tx_receipt.receipt.status,
+        "L1 to L2 transaction failed"
+    );
+
+    let l2_receipt =
+        wait_for_l2_deposit_receipt(&l1_to_l2_tx_receipt, l1_client, l2_client).await?;
+    let l2_tx_hash = l2_receipt.tx_info.transaction_hash;
+
+    println!("inclusion_deadline: Checking the deadline set by the bridge");
+
+    let max_wait = from_hex_string_to_u256(
+        &l1_client
+            .call(
+                bridge,
+                encode_calldata("PRIVILEGED_TX_MAX_WAIT_BEFORE_INCLUSION()", &[])?.into(),
+                Overrides::default(),
+            )
+            .await?,
+    )?;
+    let sent_at = l1_client
+        .get_block_by_number(BlockIdentifier::Number(
+            l1_to_l2_tx_receipt.block_info.block_number,
+        ))
+        .await?
+        .header
+        .timestamp;
+    let deadline = l1_client
+        .get_privileged_transaction_deadline(bridge, l2_tx_hash)
+        .await?;
+    assert_eq!(
+        U256::from(deadline),
+        U256::from(sent_at) + max_wait,
+        "Privileged transaction deadline doesn't match the bridge's max wait"
+    );
+
+    println!("inclusion_deadline: Waiting for the batch including the transaction to be committed");
+
+    let commit_tx = loop {
+        match l2_client.get_batch_by_number(batch_number).await {
+            Ok(batch) if batch.batch.last_block < l2_receipt.block_info.block_number => {
+                batch_number += 1;
+            }
+            Ok(batch) if batch.batch.commit_tx.is_some() => {
+                break batch.batch.commit_tx.unwrap();
+            }
+            _ => tokio::time::sleep(Duration::from_secs(2)).await,
+        }
+    };
+    let commit_receipt = wait_for_transaction_receipt(commit_tx, l1_client, 5).await?;
+    let committed_at = l1_client
+        .get_block_by_number(BlockIdentifier::Number(
+            commit_receipt.block_info.block_number,
+        ))
+        .await?
+        .header
+        .timestamp;
+    assert!(
+        committed_at <= deadline,
+        "Privileged transaction was committed after its inclusion deadline"
+    );
+
+    let has_expired = l1_client
+        .call(
+            bridge,
+            encode_calldata("hasExpiredPrivilegedTransactions()", &[])?.into(),
+            Overrides::default(),
+        )
+        .await?;
+    assert!(
+        from_hex_string_to_u256(&has_expired)?.is_zero(),
+        "Bridge has expired privileged transactions"
+    );
+
+    Ok(())
+}
+
+/// Deploys a standalone OnChainProposer and CommonBridge, with the test account as sequencer,
+/// and checks that batches can't be committed without the overdue privileged transactions,
+/// which can be processed by several batches committed before any is verified.
+async fn test_overdue_privileged_tx_commit(
+    l1_client: &EthClient,
+    private_key: &SecretKey,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let address = get_address_from_secret_key(private_key)?;
+
+    println!("overdue_privileged_tx_commit: Compiling L1 contracts");
+    let contracts_path = Path::new("contracts");
+    get_contract_dependencies(contracts_path);
+    let remappings = [
+        (
+            "@openzeppelin/contracts",
+            contracts_path.join(
+                "lib/openzeppelin-contracts-upgradeable/lib/openzeppelin-contracts/contracts",
+            ),
+        ),
+        (
+            "@openzeppelin/contracts-upgradeable",
+            contracts_path.join("lib/openzeppelin-contracts-upgradeable/contracts"),
+        ),
+    ];
+    for contract in ["OnChainProposer.sol", "CommonBridge.sol"] {
+        compile_contract(
+            contracts_path,
+            &contracts_path.join("src/l1").join(contract),
+            false,
+            Some(&remappings),
+            &[contracts_path],
+        )?;
+    }
+
+    println!("overdue_privileged_tx_commit: Deploying L1 contracts");
+    let proposer_code = hex::decode(std::fs::read("contracts/solc_out/OnChainProposer.bin")?)?;
+    let proposer = test_deploy_l1(l1_client, &proposer_code, private_key).await?;
+    let bridge_code = hex::decode(std::fs::read("contracts/solc_out/CommonBridge.bin")?)?;
+    let bridge = test_deploy_l1(l1_client, &bridge_code, private_key).await?;
+
+    // The verifiers are never called, they only need to be set
+    let verifier = Address::repeat_byte(0xaa);
+    test_send(
+        l1_client,
+        private_key,
+        proposer,
+        "initialize(bool,address,address,address,address,address,bytes32,bytes32,bytes32,address[],uint256)",
+        &[
+            Value::Bool(true),
+            Value::Address(address),
+            Value::Address(verifier),
+            Value::Address(verifier),
+            Value::Address(verifier),
+            Value::Address(verifier),
+            Value::FixedBytes(H256::zero().0.to_vec().into()),
+            Value::FixedBytes(H256::zero().0.to_vec().into()),
+            Value::FixedBytes(H256::zero().0.to_vec().into()),
+            Value::Array(vec![Value::Address(address)]),
+            Value::Uint(U256::from(1729)),
+        ],
+    )
+    .await;
+    test_send(
+        l1_client,
+        private_key,
+        proposer,
+        "initializeBridgeAddress(address)",
+        &[Value::Address(bridge)],
+    )
+    .await;
+    // Privileged transactions become overdue one second after being sent
+    test_send(
+        l1_client,
+        private_key,
+        bridge,
+        "initialize(address,address,uint256)",
+        &[
+            Value::Address(address),
+            Value::Address(proposer),
+            Value::Uint(U256::one()),
+        ],
+    )
+    .await;
+
+    println!("overdue_privileged_tx_commit: Sending privileged transactions");
+    for _ in 0..2 {
+        let l1_to_l2_tx_hash = ethrex_l2_sdk::send_l1_to_l2_tx(
+            address,
+            Some(0),
+            None,
+            L1ToL2TransactionData::new(address, 21000 * 5, U256::zero(), Bytes::new()),
+            private_key,
+            bridge,
+            l1_client,
+        )
+        .await?;
+        let l1_to_l2_tx_receipt =
+            wait_for_transaction_receipt(l1_to_l2_tx_hash, l1_client, 5).await?;
+        assert!(
+            l1_to_l2_tx_receipt.receipt.status,
+            "L1 to L2 transaction failed"
+        );
+    }
+
+    loop {
+        let has_expired = l1_client
+            .call(
+                bridge,
+                encode_calldata("hasExpiredPrivilegedTransactions()", &[])?.into(),
+                Overrides::default(),
+            )
+            .await?;
+        if !from_hex_string_to_u256(&has_expired)?.is_zero() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+
+    println!("overdue_privileged_tx_commit: Committing batches");
+    let commit_batch_signature = "commitBatch(uint256,bytes32,bytes32,bytes32,bytes32)";
+    let commit_batch_args = |batch_number: u64, rolling_hash: H256| {
+        vec![
+            Value::Uint(U256::from(batch_number)),
+            Value::FixedBytes(H256::repeat_byte(0x01).0.to_vec().into()),
+            Value::FixedBytes(H256::zero().0.to_vec().into()),
+            Value::FixedBytes(rolling_hash.0.to_vec().into()),
+            Value::FixedBytes(H256::repeat_byte(0x02).0.to_vec().into()),
+        ]
+    };
+    let as_sequencer = || Overrides {
+        from: Some(address),
+        ..Default::default()
+    };
+
+    let skipping_commit = l1_client
+        .call(
+            proposer,
+            encode_calldata(commit_batch_signature, &commit_batch_args(1, H256::zero()))?.into(),
+            as_sequencer(),
+        )
+        .await;
+    assert!(
+        skipping_commit.is_err(),
+        "Batch skipping an overdue privileged transaction was committed"
+    );
+
+    // Each batch processes one privileged transaction, the second one is committed before
+    // the first one is verified
+    for (index, batch_number) in [(0, 1), (1, 2)] {
+        let pending_tx_hash = H256::from_slice(&parse_hex(
+            &l1_client
+                .call(
+                    bridge,
+                    encode_calldata(
+                        "pendingTxHashes(uint256)",
+                        &[Value::Uint(U256::from(index))],
+                    )?
+                    .into(),
+                    Overrides::default(),
+                )
+                .await?,
+        )?);
+        let rolling_hash = compute_privileged_transactions_hash(vec![pending_tx_hash])?;
+        let commit_receipt = test_send(
+            l1_client,
+            private_key,
+            proposer,
+            commit_batch_signature,
+            &commit_batch_args(batch_number, rolling_hash),
+        )
+        .await;
+        assert!(
+            commit_receipt.receipt.status,
+            "Batch {batch_number} processing an overdue privileged transaction wasn't committed"
+        );
+    }
+
+    // The overdue transactions are already committed, so the next batch doesn't need any
+    l1_client
+        .call(
+            proposer,
+            encode_calldata(commit_batch_signature, &commit_batch_args(3, H256::zero()))?.into(),
+            as_sequencer(),
+        )
+        .await?;
+
+    Ok(())
+}
+
 async fn test_gas_burning(
     l1_client: &EthClient,
     rich_wallet_private_key: &SecretKey,
@@ -0,0 +1,595 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::expect_used)]
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use bytes::Bytes;
+use ethrex_blockchain::Blockchain;
+use ethrex_common::{
+    Address, H160, H256, H512, U256,
+    types::{Genesis, GenesisAccount, L1FeeConfig, Log},
+};
+use ethrex_l2_common::{
+    state_diff::USER_OPERATION_STATE_DIFF_SIZE,
+    user_operation::{
+        HANDLE_OPS_SELECTOR, USER_OPERATION_EVENT_SELECTOR, UserOperation, UserOperationEvent,
+        decode_failed_op, encode_handle_ops,
+    },
+};
+use ethrex_l2_rpc::{
+    RpcApiContext, RpcHandler,
+    l2::{
+        preconfirmation::Preconfirmations,
+        sponsorship::SponsorshipPolicy,
+        user_operation::{
+            EstimateUserOperationGas, GetUserOperationReceipt, SendUserOperation,
+            SupportedEntryPoints, UserOperationPool,
+        },
+    },
+    signer::LocalSigner,
+    utils::RpcErr,
+};
+use ethrex_p2p::{
+    peer_handler::PeerHandler,
+    sync_manager::SyncManager,
+    types::{Node, NodeRecord},
+};
+use ethrex_rpc::{GasTipEstimator, NodeData};
+use ethrex_storage::Store;
+use ethrex_storage_rollup::StoreRollup;
+use secp256k1::SecretKey;
+use serde_json::{Value, json};
+use tokio::sync::Mutex as TokioMutex;
+
+mod common;
+
+use common::CHAIN_ID;
+
+fn word(data: &[u8], index: usize) -> U256 {
+    U256::from_big_endian(&data[index * 32..(index + 1) * 32])
+}
+
+fn user_operation() -> UserOperation {
+    UserOperation {
+        sender: Address::repeat_byte(0xaa),
+        nonce: U256::from(7),
+        call_data: Bytes::from(vec![0xb6, 0x1d, 0x27, 0xf6]),
+        call_gas_limit: 100_000,
+        verification_gas_limit: 200_000,
+        pre_verification_gas: 50_000,
+        max_fee_per_gas: 2_000_000_000,
+        max_priority_fee_per_gas: 1_000_000_000,
+        signature: Bytes::from(vec![0x11; 65]),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn user_operation_packs_its_gas_fields() {
+    let user_operation = user_operation();
+    let account_gas_limits = U256::from_big_endian(user_operation.account_gas_limits().as_bytes());
+    assert_eq!(account_gas_limits >> 128, U256::from(200_000));
+    assert_eq!(account_gas_limits.low_u128(), 100_000);
+    let gas_fees = U256::from_big_endian(user_operation.gas_fees().as_bytes());
+    assert_eq!(gas_fees >> 128, U256::from(1_000_000_000));
+    assert_eq!(gas_fees.low_u128(), 2_000_000_000);
+    assert_eq!(user_operation.gas_limit(), 350_000);
+    assert!(user_operation.init_code().is_empty());
+    assert!(user_operation.paymaster_and_data().is_empty());
+
+    let with_paymaster = UserOperation {
+        paymaster: Some(Address::repeat_byte(0xbb)),
+        paymaster_verification_gas_limit: 1,
+        paymaster_post_op_gas_limit: 2,
+        paymaster_data: Bytes::from(vec![0xcc]),
+        ..user_operation
+    };
+    let paymaster_and_data = with_paymaster.paymaster_and_data();
+    assert_eq!(paymaster_and_data.len(), 20 + 16 + 16 + 1);
+    assert_eq!(paymaster_and_data[35], 1);
+    assert_eq!(paymaster_and_data[51], 2);
+    assert_eq!(with_paymaster.gas_limit(), 350_003);
+}
+
+#[test]
+fn handle_ops_calldata_follows_the_abi_layout() {
+    let user_operation = user_operation();
+    let beneficiary = Address::repeat_byte(0xbe);
+    let calldata = encode_handle_ops(std::slice::from_ref(&user_operation), beneficiary).unwrap();
+
+    assert_eq!(calldata[..4], HANDLE_OPS_SELECTOR);
+    let data = &calldata[4..];
+    assert_eq!(data.len() % 32, 0);
+    // Head: offset of the array and the beneficiary
+    assert_eq!(word(data, 0), U256::from(0x40));
+    assert_eq!(&data[44..64], beneficiary.as_bytes());
+    // Array: length and the offset of its only element
+    assert_eq!(word(data, 2), U256::one());
+    assert_eq!(word(data, 3), U256::from(0x20));
+
+    // The operation, as encoded on its own
+    let encoded = &data[4 * 32..];
+    assert_eq!(encoded, user_operation.abi_encode().unwrap().as_slice());
+    assert_eq!(
+        word(encoded, 0),
+        U256::from_big_endian(H256::from(user_operation.sender).as_bytes())
+    );
+    assert_eq!(word(encoded, 1), U256::from(7));
+    // initCode is the first dynamic field, right after the 9 fields of the head
+    assert_eq!(word(encoded, 2), U256::from(9 * 32));
+    assert_eq!(word(encoded, 9), U256::zero());
+    // callData follows the empty initCode
+    assert_eq!(word(encoded, 3), U256::from(10 * 32));
+    assert_eq!(word(encoded, 10), U256::from(4));
+    assert_eq!(encoded[11 * 32..11 * 32 + 4], [0xb6, 0x1d, 0x27, 0xf6]);
+    assert_eq!(word(encoded, 5), U256::from(50_000));
+}
+
+#[test]
+fn user_operation_hash_commits_to_the_entry_point_and_chain() {
+    let user_operation = user_operation();
+    let entry_point = Address::repeat_byte(0xee);
+    let hash = user_operation.hash(entry_point, 1729);
+
+    assert_ne!(hash, user_operation.hash(Address::repeat_byte(0xef), 1729));
+    assert_ne!(hash, user_operation.hash(entry_point, 1730));
+    // The signature is not part of the hash
+    let resigned = UserOperation {
+        signature: Bytes::from(vec![0x22; 65]),
+        ..user_operation.clone()
+    };
+    assert_eq!(hash, resigned.hash(entry_point, 1729));
+    let other_nonce = UserOperation {
+        nonce: U256::from(8),
+        ..user_operation
+    };
+    assert_ne!(hash, other_nonce.hash(entry_point, 1729));
+}
+
+#[test]
+fn user_operation_event_is_decoded_from_its_log() {
+    let hash = H256::repeat_byte(0x01);
+    let sender = Address::repeat_byte(0xaa);
+    let data: Vec<u8> = [
+        U256::from(7),
+        U256::one(),
+        U256::from(123_456),
+        U256::from(61_728),
+    ]
+    .iter()
+    .flat_map(|word| word.to_big_endian())
+    .collect();
+    let log = Log {
+        address: Address::repeat_byte(0xee),
+        topics: vec![
+            *USER_OPERATION_EVENT_SELECTOR,
+            hash,
+            H256::from(sender),
+            H256::zero(),
+        ],
+        data: data.into(),
+    };
+
+    let event = UserOperationEvent::from_log(&log).unwrap();
+    assert_eq!(event.user_operation_hash, hash);
+    assert_eq!(event.sender, sender);
+    assert_eq!(event.paymaster, Address::zero());
+    assert_eq!(event.nonce, U256::from(7));
+    assert!(event.success);
+    assert_eq!(event.actual_gas_cost, U256::from(123_456));
+    assert_eq!(event.actual_gas_used, U256::from(61_728));
+
+    let other_event = Log {
+        topics: vec![
+            H256::repeat_byte(0x02),
+            hash,
+            H256::from(sender),
+            H256::zero(),
+        ],
+        ..log.clone()
+    };
+    assert!(UserOperationEvent::from_log(&other_event).is_none());
+    let truncated = Log {
+        data: log.data.slice(..96),
+        ..log
+    };
+    assert!(UserOperationEvent::from_log(&truncated).is_none());
+}
+
+#[test]
+fn failed_op_reason_is_decoded() {
+    // FailedOp(0, "AA21 didn't pay prefund")
+    let reason = "AA21 didn't pay prefund";
+    let mut output = vec![0x22, 0x02, 0x66, 0xb6];
+    output.extend(U256::zero().to_big_endian());
+    output.extend(U256::from(0x40).to_big_endian());
+    output.extend(U256::from(reason.len()).to_big_endian());
+    let mut padded = reason.as_bytes().to_vec();
+    padded.resize(32, 0);
+    output.extend(padded);
+
+    assert_eq!(decode_failed_op(&output).as_deref(), Some(reason));
+    assert_eq!(decode_failed_op(&output[..100]), None);
+    assert_eq!(decode_failed_op(&[]), None);
+}
+
+const ENTRY_POINT: Address = H160([0xe0; 20]);
+const ACCOUNT: Address = H160([0xac; 20]);
+const OTHER_ACCOUNT: Address = H160([0xab; 20]);
+
+/// Entry point that calls `validateUserOp` on the sender of the first operation passed to
+/// `handleOps` and emits a successful `UserOperationEvent` for it with the given hash.
+fn entry_point_code(user_operation_hash: H256) -> Bytes {
+    let mut code = vec![
+        // PUSH4 <validateUserOp> PUSH1 0xe0 SHL PUSH1 0x00 MSTORE
+        0x63, 0x19, 0x82, 0x2f, 0x7c, 0x60, 0xe0, 0x1b, 0x60, 0x00, 0x52,
+        // CALL(GAS, CALLDATALOAD(0x84), 0, 0, 4, 0, 0x20) POP, 0x84 being the offset of the
+        // sender in the calldata
+        0x60, 0x20, 0x60, 0x00, 0x60, 0x04, 0x60, 0x00, 0x60, 0x00, 0x60, 0x84, 0x35, 0x5a, 0xf1,
+        0x50,
+        // Event data (nonce, success, actualGasCost, actualGasUsed) = (0, 1, 0, 0):
+        // PUSH1 0x00 PUSH1 0x00 MSTORE PUSH1 0x01 PUSH1 0x20 MSTORE
+        // PUSH1 0x00 PUSH1 0x40 MSTORE PUSH1 0x00 PUSH1 0x60 MSTORE
+        0x60, 0x00, 0x60, 0x00, 0x52, 0x60, 0x01, 0x60, 0x20, 0x52, 0x60, 0x00, 0x60, 0x40, 0x52,
+        0x60, 0x00, 0x60, 0x60, 0x52,
+        // Topics, last first: no paymaster, the sender with PUSH1 0x00 PUSH1 0x84 CALLDATALOAD,
+        // then PUSH32 <hash>
+        0x60, 0x00, 0x60, 0x84, 0x35, 0x7f,
+    ];
+    code.extend_from_slice(user_operation_hash.as_bytes());
+    // PUSH32 <UserOperationEvent>
+    code.push(0x7f);
+    code.extend_from_slice(USER_OPERATION_EVENT_SELECTOR.as_bytes());
+    // LOG4(0, 0x80) STOP
+    code.extend([0x60, 0x80, 0x60, 0x00, 0xa4, 0x00]);
+    code.into()
+}
+
+fn contract(code: Bytes) -> GenesisAccount {
+    GenesisAccount {
+        code,
+        storage: HashMap::new(),
+        balance: U256::zero(),
+        nonce: 1,
+    }
+}
+
+fn pool_user_operation() -> UserOperation {
+    UserOperation {
+        sender: ACCOUNT,
+        max_fee_per_gas: 10_000_000_000,
+        max_priority_fee_per_gas: 1_000_000_000,
+        pre_verification_gas: 100_000,
+        ..user_operation()
+    }
+}
+
+/// L2 genesis with a funded bundler, the entry point and two accounts that accept every operation.
+fn genesis(bundler: Address, user_operation_hash: H256) -> Genesis {
+    let mut genesis = common::genesis(&[bundler]);
+    genesis
+        .alloc
+        .insert(ENTRY_POINT, contract(entry_point_code(user_operation_hash)));
+    // Returns a zero validationData: PUSH1 0x20 PUSH1 0x00 RETURN
+    for account in [ACCOUNT, OTHER_ACCOUNT] {
+        genesis.alloc.insert(
+            account,
+            contract(Bytes::from(vec![0x60, 0x20, 0x60, 0x00, 0xf3])),
+        );
+    }
+    genesis
+}
+
+async fn setup(
+    bundler: Address,
+    user_operation_hash: H256,
+) -> (Arc<Blockchain>, Store, StoreRollup) {
+    common::setup(genesis(bundler, user_operation_hash)).await
+}
+
+fn rpc_context(
+    blockchain: Arc<Blockchain>,
+    store: Store,
+    rollup_store: StoreRollup,
+    pool: UserOperationPool,
+) -> RpcApiContext {
+    let secret_key = SecretKey::from_slice(&[0x22; 32]).unwrap();
+    RpcApiContext {
+        l1_ctx: ethrex_rpc::RpcApiContext {
+            storage: store,
+            blockchain,
+            active_filters: Arc::new(Mutex::new(HashMap::new())),
+            syncer: Arc::new(SyncManager::dummy()),
+            peer_handler: PeerHandler::dummy(),
+            node_data: NodeData {
+                jwt_secret: Bytes::new(),
+                local_p2p_node: Node::new("127.0.0.1".parse().unwrap(), 30303, 30303, H512::zero()),
+                local_node_record: Arc::new(TokioMutex::new(NodeRecord::default())),
+                client_version: "test".to_string(),
+            },
+            gas_tip_estimator: Arc::new(TokioMutex::new(GasTipEstimator::new())),
+        },
+        sponsorship_policy: SponsorshipPolicy::new(HashMap::new(), 0, 0, 0, U256::zero()),
+        sponsor_pk: secret_key,
+        rollup_store,
+        preconfirmations: Preconfirmations::new(),
+        sequencer_signer: LocalSigner::new(secret_key).into(),
+        user_operations: Some(pool),
+    }
+}
+
+async fn send(
+    context: &RpcApiContext,
+    user_operation: UserOperation,
+    entry_point: Address,
+) -> Result<Value, RpcErr> {
+    SendUserOperation {
+        user_operation,
+        entry_point,
+    }
+    .handle(context.clone())
+    .await
+}
+
+async fn receipt(context: &RpcApiContext, user_operation_hash: H256) -> Value {
+    GetUserOperationReceipt {
+        user_operation_hash,
+    }
+    .handle(context.clone())
+    .await
+    .unwrap()
+}
+
+fn error_message(result: Result<Value, RpcErr>) -> Option<String> {
+    match result {
+        Err(RpcErr::InvalidEthrexL2Message(message)) => Some(message),
+        _ => None,
+    }
+}
+
+fn field(value: &Value, name: &str) -> Value {
+    value.get(name).cloned().unwrap_or_default()
+}
+
+fn gas(value: &Value, name: &str) -> u64 {
+    let value = field(value, name);
+    u64::from_str_radix(value.as_str().unwrap().trim_start_matches("0x"), 16).unwrap()
+}
+
+async fn estimate(context: &RpcApiContext, user_operation: &UserOperation) -> Value {
+    EstimateUserOperationGas {
+        user_operation: user_operation.clone(),
+        entry_point: ENTRY_POINT,
+    }
+    .handle(context.clone())
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn user_operations_are_pooled_bundled_and_looked_up() {
+    let bundler = common::signer(0x11);
+    let user_operation = pool_user_operation();
+    let hash = user_operation.hash(ENTRY_POINT, CHAIN_ID);
+    let (blockchain, store, rollup_store) = setup(bundler.address(), hash).await;
+    let pool = UserOperationPool::new(ENTRY_POINT, bundler, 4096, 64);
+    let context = rpc_context(
+        blockchain.clone(),
+        store.clone(),
+        rollup_store.clone(),
+        pool.clone(),
+    );
+
+    let entry_points = SupportedEntryPoints.handle(context.clone()).await.unwrap();
+    assert_eq!(entry_points, json!([format!("{ENTRY_POINT:#x}")]));
+
+    // The estimation ignores the operation's gas limits
+    let estimation = estimate(
+        &context,
+        &UserOperation {
+            verification_gas_limit: 0,
+            call_gas_limit: 0,
+            ..user_operation.clone()
+        },
+    )
+    .await;
+    assert!(gas(&estimation, "preVerificationGas") <= user_operation.pre_verification_gas);
+    assert!(gas(&estimation, "verificationGasLimit") > 10_000);
+    assert!(gas(&estimation, "callGasLimit") > 0);
+    assert_eq!(
+        field(&estimation, "paymasterVerificationGasLimit"),
+        Value::Null
+    );
+
+    let sent = send(&context, user_operation.clone(), ENTRY_POINT)
+        .await
+        .unwrap();
+    assert_eq!(sent, json!(format!("{hash:#x}")));
+    let other_entry_point = Address::repeat_byte(0xe1);
+    assert_eq!(
+        error_message(send(&context, user_operation.clone(), other_entry_point).await),
+        Some(format!("Unsupported entry point {other_entry_point:#x}"))
+    );
+    assert_eq!(
+        error_message(send(&context, user_operation.clone(), ENTRY_POINT).await).as_deref(),
+        Some("Sender already has a pending user operation")
+    );
+    let undeployed = UserOperation {
+        sender: Address::repeat_byte(0xad),
+        ..user_operation.clone()
+    };
+    assert_eq!(
+        error_message(send(&context, undeployed, ENTRY_POINT).await).as_deref(),
+        Some("Sender is not deployed")
+    );
+    assert_eq!(receipt(&context, hash).await, Value::Null);
+
+    let tx_hash = pool.bundle(&store, &blockchain).await.unwrap().unwrap();
+    assert!(blockchain.mempool.contains_tx(tx_hash).unwrap());
+    // The bundled operation left the pool
+    assert_eq!(pool.bundle(&store, &blockchain).await.unwrap(), None);
+    // Not included yet
+    assert_eq!(receipt(&context, hash).await, Value::Null);
+
+    common::produce_block(&blockchain, &store, &rollup_store).await;
+    let receipt = receipt(&context, hash).await;
+    assert_eq!(field(&receipt, "userOpHash"), json!(format!("{hash:#x}")));
+    assert_eq!(field(&receipt, "sender"), json!(format!("{ACCOUNT:#x}")));
+    assert_eq!(field(&receipt, "success"), json!(true));
+    assert_eq!(
+        field(&field(&receipt, "receipt"), "transactionHash"),
+        json!(format!("{tx_hash:#x}"))
+    );
+}
+
+#[tokio::test]
+async fn pre_verification_gas_covers_the_l1_fee() {
+    let bundler = common::signer(0x11);
+    let user_operation = pool_user_operation();
+    let hash = user_operation.hash(ENTRY_POINT, CHAIN_ID);
+    let (blockchain, store, rollup_store) = setup(bundler.address(), hash).await;
+    let pool = UserOperationPool::new(ENTRY_POINT, bundler.clone(), 4096, 64);
+    let without_l1_fee = gas(
+        &estimate(
+            &rpc_context(blockchain, store, rollup_store, pool.clone()),
+            &user_operation,
+        )
+        .await,
+        "preVerificationGas",
+    );
+
+    // The latest block charges an L1 fee, which the operation pays at the base fee
+    let l1_fee_config = L1FeeConfig {
+        l1_fee_vault: Address::repeat_byte(0xf),
+        l1_fee_per_blob_gas: 1_000_000_000_000,
+    };
+    let mut genesis = genesis(bundler.address(), hash);
+    genesis.extra_data = l1_fee_config.encode_extra_data().into();
+    let (blockchain, store, rollup_store) = common::setup(genesis).await;
+    let base_fee = store
+        .get_block_header(0)
+        .unwrap()
+        .unwrap()
+        .base_fee_per_gas
+        .unwrap();
+    let l1_fee = l1_fee_config.l1_fee(USER_OPERATION_STATE_DIFF_SIZE);
+    let l1_gas = ((l1_fee + U256::from(base_fee - 1)) / U256::from(base_fee)).as_u64();
+    assert!(l1_gas > 0);
+    let context = rpc_context(blockchain, store, rollup_store, pool);
+    let with_l1_fee = gas(
+        &estimate(&context, &user_operation).await,
+        "preVerificationGas",
+    );
+    assert_eq!(with_l1_fee, without_l1_fee + l1_gas);
+
+    let underpaying = UserOperation {
+        pre_verification_gas: without_l1_fee,
+        ..user_operation.clone()
+    };
+    assert!(
+        error_message(send(&context, underpaying, ENTRY_POINT).await)
+            .unwrap()
+            .starts_with("preVerificationGas is lower than")
+    );
+    let paying = UserOperation {
+        pre_verification_gas: with_l1_fee + 1_000,
+        ..user_operation
+    };
+    send(&context, paying, ENTRY_POINT).await.unwrap();
+}
+
+#[tokio::test]
+async fn user_operation_pool_is_capped_and_drops_stale_operations() {
+    let bundler = common::signer(0x11);
+    let user_operation = pool_user_operation();
+    let hash = user_operation.hash(ENTRY_POINT, CHAIN_ID);
+    let (blockchain, store, rollup_store) = setup(bundler.address(), hash).await;
+    let pool = UserOperationPool::new(ENTRY_POINT, bundler, 1, 2);
+    let context = rpc_context(
+        blockchain.clone(),
+        store.clone(),
+        rollup_store.clone(),
+        pool.clone(),
+    );
+
+    send(&context, user_operation.clone(), ENTRY_POINT)
+        .await
+        .unwrap();
+    let other = UserOperation {
+        sender: OTHER_ACCOUNT,
+        ..user_operation.clone()
+    };
+    assert_eq!(
+        error_message(send(&context, other.clone(), ENTRY_POINT).await).as_deref(),
+        Some("The user operation pool is full")
+    );
+
+    // The L1 fee rises above what the operation's preVerificationGas covers, so it waits
+    let l1_fee_config = L1FeeConfig {
+        l1_fee_vault: Address::repeat_byte(0xf),
+        l1_fee_per_blob_gas: 1_000_000_000_000,
+    };
+    let produce_block = async || {
+        let result = common::build_block_with_l1_fee(
+            &blockchain,
+            &store,
+            &rollup_store,
+            false,
+            Vec::new(),
+            Some(l1_fee_config),
+        )
+        .await;
+        common::store_block(&blockchain, &store, result).await;
+    };
+    produce_block().await;
+    assert_eq!(pool.bundle(&store, &blockchain).await.unwrap(), None);
+    let replacement = UserOperation {
+        pre_verification_gas: 1_000_000,
+        ..user_operation
+    };
+    assert_eq!(
+        error_message(send(&context, replacement, ENTRY_POINT).await).as_deref(),
+        Some("Sender already has a pending user operation")
+    );
+
+    // Until it's been pending for too many blocks
+    produce_block().await;
+    assert_eq!(pool.bundle(&store, &blockchain).await.unwrap(), None);
+    let other = UserOperation {
+        pre_verification_gas: 1_000_000,
+        ..other
+    };
+    send(&context, other, ENTRY_POINT).await.unwrap();
+}
+
+#[tokio::test]
+async fn user_operation_breaking_the_bundle_is_dropped() {
+    let bundler = common::signer(0x11);
+    let user_operation = pool_user_operation();
+    let hash = user_operation.hash(ENTRY_POINT, CHAIN_ID);
+    // The entry point halts when it's paid for gas, so the operation passes its simulation but
+    // the bundle fails: GASPRICE ISZERO PUSH1 0x06 JUMPI INVALID JUMPDEST
+    let mut entry_point = vec![0x3a, 0x15, 0x60, 0x06, 0x57, 0xfe, 0x5b];
+    entry_point.extend_from_slice(&entry_point_code(hash));
+    let mut genesis = genesis(bundler.address(), hash);
+    genesis
+        .alloc
+        .insert(ENTRY_POINT, contract(entry_point.into()));
+    let (blockchain, store, rollup_store) = common::setup(genesis).await;
+    let pool = UserOperationPool::new(ENTRY_POINT, bundler, 4096, 64);
+    let context = rpc_context(
+        blockchain.clone(),
+        store.clone(),
+        rollup_store,
+        pool.clone(),
+    );
+
+    send(&context, user_operation.clone(), ENTRY_POINT)
+        .await
+        .unwrap();
+    assert_eq!(pool.bundle(&store, &blockchain).await.unwrap(), None);
+    // The operation left the pool, so it can be sent again
+    send(&context, user_operation, ENTRY_POINT).await.unwrap();
+}
@@ -77,9 +77,8 @@ impl Discv4LookupHandler {
             self.ctx.tracker.spawn({
                 let self_clone = self.clone();
                 async move {
-                    self_clone
-                        .recursive_lookup(self_clone.ctx.local_node.public_key)
-                        .await
+                    let public_key = self_clone.ctx.local_node().await.public_key;
+                    self_clone.recursive_lookup(public_key).await
                 }
             });
 
@@ -114,7 +113,7 @@ impl Discv4LookupHandler {
         let mut seen_peers: HashSet<H512> = HashSet::default();
         let mut asked_peers = HashSet::default();
 
-        seen_peers.insert(self.ctx.local_node.public_key);
+        seen_peers.insert(self.ctx.local_node().await.public_key);
         for node in &peers_to_ask {
             seen_peers.insert(node.public_key);
         }
@@ -302,8 +301,8 @@ mod tests {
         // because the table is filled, before making the connection, remove a node from the `b` bucket
         // otherwise it won't be added.
         let b_bucket = bucket_number(
-            server_a.ctx.local_node.node_id(),
-            server_b.ctx.local_node.node_id(),
+            server_a.ctx.local_node().await.node_id(),
+            server_b.ctx.local_node().await.node_id(),
         );
         let node_id_to_remove = server_a.ctx.table.lock().await.buckets()[b_bucket].peers[0]
             .node
@@ -323,18 +322,18 @@ mod tests {
             .table
             .lock()
             .await
-            .get_closest_nodes(server_b.ctx.local_node.node_id());
+            .get_closest_nodes(server_b.ctx.local_node().await.node_id());
         let nodes_to_ask = server_b
             .ctx
             .table
             .lock()
             .await
-            .get_closest_nodes(server_b.ctx.local_node.node_id());
+            .get_closest_nodes(server_b.ctx.local_node().await.node_id());
 
         let lookup_handler = lookup_handler_from_server(server_b.clone());
         lookup_handler
             .lookup(
-                server_b.ctx.local_node.public_key,
+                server_b.ctx.local_node().await.public_key,
                 &mut HashSet::default(),
                 &nodes_to_ask,
             )
@@ -349,7 +348,7 @@ mod tests {
             let node = table.get_by_node_id(peer.node_id());
             // sometimes nodes can send ourselves as a neighbor
             // make sure we don't add it
-            if peer.node_id() == server_b.ctx.local_node.node_id() {
+            if peer.node_id() == server_b.ctx.local_node().await.node_id() {
                 assert!(node.is_none());
             } else {
                 assert!(node.is_some());
@@ -389,7 +388,7 @@ mod tests {
                 .table
                 .lock()
                 .await
-                .get_closest_nodes(server_a.ctx.local_node.node_id()),
+                .get_closest_nodes(server_a.ctx.local_node().await.node_id()),
         );
         expected_peers.extend(
             server_c
@@ -397,7 +396,7 @@ mod tests {
                 .table
                 .lock()
                 .await
-                .get_closest_nodes(server_a.ctx.local_node.node_id()),
+                .get_closest_nodes(server_a.ctx.local_node().await.node_id()),
         );
         expected_peers.extend(
             server_d
@@ -405,14 +404,14 @@ mod tests {
                 .table
                 .lock()
                 .await
-                .get_closest_nodes(server_a.ctx.local_node.node_id()),
+                .get_closest_nodes(server_a.ctx.local_node().await.node_id()),
         );
 
         let lookup_handler = lookup_handler_from_server(server_a.clone());
 
         // we'll run a recursive lookup closest to the server itself
         lookup_handler
-            .recursive_lookup(server_a.ctx.local_node.public_key)
+            .recursive_lookup(server_a.ctx.local_node().await.public_key)
             .await;
 
         // sometimes nodes can send ourselves as a neighbor
@@ -421,7 +420,7 @@ mod tests {
             let table = server_a.ctx.table.lock().await;
             let node = table.get_by_node_id(peer.node_id());
 
-            if peer.node_id() == server_a.ctx.local_node.node_id() {
+            if peer.node_id() == server_a.ctx.local_node().await.node_id() {
                 assert!(node.is_none());
             } else {
                 assert!(node.is_some());
@@ -10,6 +10,7 @@ use super::{
 };
 use crate::{
     kademlia::{KademliaTable, MAX_NODES_PER_BUCKET},
+    nat::ExternalIpVotes,
     network::P2PContext,
     rlpx::{connection::server::RLPxConnection, utils::node_id},
     types::{Endpoint, Node},
@@ -22,7 +23,10 @@ use std::{
     sync::Arc,
     time::Duration,
 };
-use tokio::{net::UdpSocket, sync::MutexGuard};
+use tokio::{
+    net::UdpSocket,
+    sync::{Mutex, MutexGuard},
+};
 use tracing::{debug, error};
 
 const MAX_DISC_PACKET_SIZE: usize = 1280;
@@ -51,13 +55,14 @@ pub struct Discv4Server {
     pub(super) udp_socket: Arc<UdpSocket>,
     pub(super) revalidation_interval_seconds: u64,
     pub(super) lookup_interval_minutes: u64,
+    pub(super) external_ip_votes: Arc<Mutex<ExternalIpVotes>>,
 }
 
 impl Discv4Server {
     /// Initializes a Discv4 UDP socket and creates a new `Discv4Server` instance.
     /// Returns an error if the socket binding fails.
-    pub async fn try_new(ctx: P2PContext) -> Result<Self, DiscoveryError> {
-        let udp_socket = UdpSocket::bind(ctx.local_node.udp_addr())
+    pub async fn try_new(ctx: P2PContext, udp_addr: SocketAddr) -> Result<Self, DiscoveryError> {
+        let udp_socket = UdpSocket::bind(udp_addr)
             .await
             .map_err(DiscoveryError::BindSocket)?;
 
@@ -66,6 +71,7 @@ impl Discv4Server {
             udp_socket: Arc::new(udp_socket),
             revalidation_interval_seconds: REVALIDATION_INTERVAL_IN_SECONDS,
             lookup_interval_minutes: PEERS_RANDOM_LOOKUP_TIME_IN_MIN,
+            external_ip_votes: Default::default(),
         })
     }
 
@@ -200,6 +206,19 @@ impl Discv4Server {
                     .await
                     .pong_answered(peer.node.node_id(), current_unix_time());
 
+                // the `to` endpoint tells us how the peer sees us, which is our
+                // external address if we are behind a NAT
+                if self.ctx.nat.allows_ip_discovery() {
+                    let external_ip = self
+                        .external_ip_votes
+                        .lock()
+                        .await
+                        .add_vote(peer.node.node_id(), msg.to.ip);
+                    if let Some(external_ip) = external_ip {
+                        self.ctx.set_external_ip(external_ip).await;
+                    }
+                }
+
                 // if the ENR_seq field is not up to date, don't establish a rlpx connection yet
                 if let Some(enr_seq) = msg.enr_seq {
                     if enr_seq > peer.record.seq {
@@ -615,7 +634,7 @@ impl Discv4Server {
     async fn try_add_peer_and_ping(&self, node: Node) -> Result<(), DiscoveryError> {
         // sanity check to make sure we are not storing ourselves
         // a case that may happen in a neighbor message for example
-        if node.node_id() == self.ctx.local_node.node_id() {
+        if node.node_id() == self.ctx.local_node().await.node_id() {
             return Ok(());
         }
 
@@ -634,10 +653,11 @@ impl Discv4Server {
     async fn ping(&self, node: &Node) -> Result<(), DiscoveryError> {
         let mut buf = Vec::new();
         let expiration: u64 = get_msg_expiration_from_seconds(20);
+        let local_node = self.ctx.local_node().await;
         let from = Endpoint {
-            ip: self.ctx.local_node.ip,
-            udp_port: self.ctx.local_node.udp_port,
-            tcp_port: self.ctx.local_node.tcp_port,
+            ip: local_node.ip,
+            udp_port: local_node.udp_port,
+            tcp_port: local_node.tcp_port,
         };
         let to = Endpoint {
             ip: node.ip,
@@ -788,8 +808,9 @@ pub(super) mod tests {
             NodeRecord::from_node(&local_node, 1, &signer)
                 .expect("Node record could not be created from local node"),
         ));
+        let (udp_addr, tcp_addr) = (local_node.udp_addr(), local_node.tcp_addr());
         let ctx = P2PContext {
-            local_node,
+            local_node: Arc::new(Mutex::new(local_node)),
             local_node_record,
             tracker: tracker.clone(),
             signer,
@@ -799,9 +820,11 @@ pub(super) mod tests {
             broadcast,
             client_version: "ethrex/test".to_string(),
             based_context: None,
+            nat: Default::default(),
+            block_gossip: false,
         };
 
-        let discv4 = Discv4Server::try_new(ctx.clone()).await?;
+        let discv4 = Discv4Server::try_new(ctx.clone(), udp_addr).await?;
 
         if should_start_server {
             tracker.spawn({
@@ -812,7 +835,7 @@ pub(super) mod tests {
             });
             // we need to spawn the p2p service, as the nodes will try to connect each other via tcp once bonded
             // if that connection fails, then they are remove themselves from the table, we want them to be bonded for these tests
-            ctx.tracker.spawn(serve_p2p_requests(ctx.clone()));
+            ctx.tracker.spawn(serve_p2p_requests(ctx.clone(), tcp_addr));
         }
 
         Ok(discv4)
@@ -824,7 +847,7 @@ pub(super) mod tests {
         server_b: &mut Discv4Server,
     ) -> Result<(), DiscoveryError> {
         server_a
-            .try_add_peer_and_ping(server_b.ctx.local_node.clone())
+            .try_add_peer_and_ping(server_b.ctx.local_node().await)
             .await?;
 
         // allow some time for the server to respond
@@ -899,7 +922,7 @@ pub(super) mod tests {
             sleep(Duration::from_millis(2500)).await;
             // by now, b should've send a revalidation to a
             let table = server_b.ctx.table.lock().await;
-            let node = table.get_by_node_id(server_a.ctx.local_node.node_id());
+            let node = table.get_by_node_id(server_a.ctx.local_node().await.node_id());
             assert!(node.is_some_and(|n| n.revalidation.is_some()));
         }
 
@@ -907,7 +930,7 @@ pub(super) mod tests {
         // we can do that by checking the liveness
         {
             let table = server_b.ctx.table.lock().await;
-            let node = table.get_by_node_id(server_a.ctx.local_node.node_id());
+            let node = table.get_by_node_id(server_a.ctx.local_node().await.node_id());
             assert_eq!(node.map_or(0, |n| n.liveness), 6);
         }
 
@@ -915,7 +938,7 @@ pub(super) mod tests {
         // so we'll instead change its port, so that no one responds
         {
             let mut table = server_b.ctx.table.lock().await;
-            let node = table.get_by_node_id_mut(server_a.ctx.local_node.node_id());
+            let node = table.get_by_node_id_mut(server_a.ctx.local_node().await.node_id());
             if let Some(node) = node {
                 node.node.udp_port = 0
             };
@@ -926,7 +949,7 @@ pub(super) mod tests {
         for _ in 0..2 {
             sleep(Duration::from_millis(2500)).await;
             let table = server_b.ctx.table.lock().await;
-            let node = table.get_by_node_id(server_a.ctx.local_node.node_id());
+            let node = table.get_by_node_id(server_a.ctx.local_node().await.node_id());
             assert!(node.is_some_and(|n| n.revalidation.is_some()));
         }
         sleep(Duration::from_millis(2500)).await;
@@ -935,7 +958,7 @@ pub(super) mod tests {
         let table = server_b.ctx.table.lock().await;
         assert!(
             table
-                .get_by_node_id(server_a.ctx.local_node.node_id())
+                .get_by_node_id(server_a.ctx.local_node().await.node_id())
                 .is_none()
         );
         Ok(())
@@ -969,7 +992,7 @@ pub(super) mod tests {
             .table
             .lock()
             .await
-            .get_by_node_id(server_b.ctx.local_node.node_id())
+            .get_by_node_id(server_b.ctx.local_node().await.node_id())
             .cloned()
             .unwrap();
 
@@ -984,7 +1007,7 @@ pub(super) mod tests {
             .table
             .lock()
             .await
-            .get_by_node_id_mut(server_b.ctx.local_node.node_id())
+            .get_by_node_id_mut(server_b.ctx.local_node().await.node_id())
             .unwrap()
             .node
             .tcp_port = 10;
@@ -1002,7 +1025,7 @@ pub(super) mod tests {
         // Send a ping from server_b to server_a.
         // server_a should notice the enr_seq is outdated
         // and trigger a enr-request to server_b to update the record.
-        server_b.ping(&server_a.ctx.local_node).await?;
+        server_b.ping(&server_a.ctx.local_node().await).await?;
 
         // Wait for the update to propagate.
         sleep(Duration::from_millis(2500)).await;
@@ -1010,10 +1033,10 @@ pub(super) mod tests {
         // Verify that server_a has updated its record of server_b with the correct TCP port.
         let table_lock = server_a.ctx.table.lock().await;
         let server_a_node_b_record = table_lock
-            .get_by_node_id(server_b.ctx.local_node.node_id())
+            .get_by_node_id(server_b.ctx.local_node().await.node_id())
             .unwrap();
 
-        assert!(server_a_node_b_record.node.tcp_port == server_b.ctx.local_node.tcp_port);
+        assert!(server_a_node_b_record.node.tcp_port == server_b.ctx.local_node().await.tcp_port);
 
         Ok(())
     }
@@ -1092,7 +1115,7 @@ pub(super) mod tests {
                 .table
                 .lock()
                 .await
-                .get_by_node_id(server_b.ctx.local_node.node_id())
+                .get_by_node_id(server_b.ctx.local_node().await.node_id())
                 .is_some()
         );
 
@@ -1102,7 +1125,7 @@ pub(super) mod tests {
                 .table
                 .lock()
                 .await
-                .get_by_node_id(server_c.ctx.local_node.node_id())
+                .get_by_node_id(server_c.ctx.local_node().await.node_id())
                 .is_none()
         );
 
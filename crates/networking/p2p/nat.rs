@@ -0,0 +1,325 @@
+mod pmp;
+mod upnp;
+
+use crate::network::P2PContext;
+use ethrex_common::H256;
+use pmp::PmpGateway;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Display,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    time::Duration,
+};
+use tracing::{debug, warn};
+use upnp::UpnpGateway;
+
+/// Lifetime requested for the port mappings created in the gateway.
+/// Mappings are renewed when half of it has elapsed.
+const PORT_MAPPING_LIFETIME: Duration = Duration::from_secs(20 * 60);
+/// Description attached to the port mappings, shown by most router UIs.
+const PORT_MAPPING_DESCRIPTION: &str = "ethrex p2p";
+/// Minimum amount of distinct peers that must agree on our external ip before advertising it.
+const MIN_EXTERNAL_IP_VOTES: usize = 3;
+/// Amount of most recent PONG reports taken into account when voting for the external ip.
+const MAX_EXTERNAL_IP_VOTES: usize = 20;
+
+#[derive(Debug, thiserror::Error)]
+pub enum NatError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("No NAT gateway found")]
+    NoGateway,
+    #[error("Timed out waiting for the NAT gateway")]
+    Timeout,
+    #[error("Invalid gateway response: {0}")]
+    InvalidResponse(String),
+    #[error("Gateway rejected the request with code {0}")]
+    Rejected(u16),
+}
+
+/// Determines how the node finds out the address it's reachable at from the outside.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum NatConfig {
+    /// No port mapping is done. The external ip is learned from the `to` endpoint
+    /// our peers report back in discv4 PONG messages.
+    #[default]
+    None,
+    /// The given ip is always advertised as the external one.
+    ExtIp(IpAddr),
+    /// Map the p2p ports through an UPnP Internet Gateway Device found via SSDP.
+    Upnp,
+    /// Map the p2p ports through a NAT-PMP gateway, the system's default gateway is used if none is given.
+    Pmp(Option<IpAddr>),
+}
+
+impl NatConfig {
+    /// Whether the external ip can be updated from what our peers report.
+    /// An explicit ip or the one informed by the gateway take precedence.
+    pub fn allows_ip_discovery(&self) -> bool {
+        matches!(self, NatConfig::None)
+    }
+}
+
+impl FromStr for NatConfig {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mechanism, arg) = match s.split_once(':') {
+            Some((mechanism, arg)) => (mechanism, Some(arg)),
+            None => (s, None),
+        };
+        let parse_ip = |arg: &str| {
+            IpAddr::from_str(arg).map_err(|_| format!("Invalid ip address {arg:?} in nat option"))
+        };
+        match (mechanism.to_lowercase().as_str(), arg) {
+            ("none", None) => Ok(NatConfig::None),
+            ("extip", Some(ip)) => Ok(NatConfig::ExtIp(parse_ip(ip)?)),
+            ("upnp", None) => Ok(NatConfig::Upnp),
+            ("pmp" | "natpmp", None) => Ok(NatConfig::Pmp(None)),
+            ("pmp" | "natpmp", Some(gateway)) => Ok(NatConfig::Pmp(Some(parse_ip(gateway)?))),
+            _ => Err(format!(
+                "Invalid nat option {s:?}, expected one of: none, extip:<IP>, upnp, pmp[:<GATEWAY_IP>]"
+            )),
+        }
+    }
+}
+
+impl Display for NatConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NatConfig::None => write!(f, "none"),
+            NatConfig::ExtIp(ip) => write!(f, "extip:{ip}"),
+            NatConfig::Upnp => write!(f, "upnp"),
+            NatConfig::Pmp(None) => write!(f, "pmp"),
+            NatConfig::Pmp(Some(gateway)) => write!(f, "pmp:{gateway}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl Display for Protocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Protocol::Tcp => write!(f, "TCP"),
+            Protocol::Udp => write!(f, "UDP"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Gateway {
+    Upnp(UpnpGateway),
+    Pmp(PmpGateway),
+}
+
+impl Gateway {
+    async fn discover(config: &NatConfig) -> Result<Self, NatError> {
+        match config {
+            NatConfig::Upnp => Ok(Gateway::Upnp(UpnpGateway::discover().await?)),
+            NatConfig::Pmp(Some(gateway_ip)) => Ok(Gateway::Pmp(PmpGateway::new(*gateway_ip))),
+            NatConfig::Pmp(None) => {
+                let gateway_ip = pmp::default_gateway().ok_or(NatError::NoGateway)?;
+                Ok(Gateway::Pmp(PmpGateway::new(gateway_ip)))
+            }
+            NatConfig::None | NatConfig::ExtIp(_) => Err(NatError::NoGateway),
+        }
+    }
+
+    async fn external_ip(&self) -> Result<IpAddr, NatError> {
+        match self {
+            Gateway::Upnp(gateway) => gateway.external_ip().await,
+            Gateway::Pmp(gateway) => gateway.external_ip().await,
+        }
+    }
+
+    /// Maps `port` in the gateway to the same port on this host, returns the external port assigned.
+    async fn add_port_mapping(&self, protocol: Protocol, port: u16) -> Result<u16, NatError> {
+        match self {
+            Gateway::Upnp(gateway) => {
+                gateway
+                    .add_port_mapping(protocol, port, port, PORT_MAPPING_LIFETIME)
+                    .await
+            }
+            Gateway::Pmp(gateway) => {
+                gateway
+                    .add_port_mapping(protocol, port, port, PORT_MAPPING_LIFETIME)
+                    .await
+            }
+        }
+    }
+
+    /// Maps the discovery and listener ports and advertises the gateway's external ip.
+    async fn map_ports(&self, ctx: &P2PContext) -> Result<(), NatError> {
+        let local_node = ctx.local_node().await;
+        for (protocol, port) in [
+            (Protocol::Udp, local_node.udp_port),
+            (Protocol::Tcp, local_node.tcp_port),
+        ] {
+            let external_port = self.add_port_mapping(protocol, port).await?;
+            if external_port != port {
+                warn!(
+                    "NAT gateway mapped {protocol} port {port} to external port {external_port}, peers may not be able to reach us"
+                );
+            }
+        }
+        let external_ip = self.external_ip().await?;
+        ctx.set_external_ip(external_ip).await;
+        Ok(())
+    }
+}
+
+/// Sets up the external address of the node according to the nat configuration in the context.
+///
+/// - `extip`: the given ip is advertised right away.
+/// - `upnp`/`pmp`: the p2p ports are mapped in the gateway, the gateway's external ip is advertised
+///   and a task is spawned to renew the mappings before they expire.
+/// - `none`: nothing is done here, the discovery server updates the ip from our peers' PONG messages.
+pub async fn start_nat(ctx: &P2PContext) {
+    match &ctx.nat {
+        NatConfig::None => {}
+        NatConfig::ExtIp(ip) => ctx.set_external_ip(*ip).await,
+        config @ (NatConfig::Upnp | NatConfig::Pmp(_)) => {
+            let gateway = match Gateway::discover(config).await {
+                Ok(gateway) => gateway,
+                Err(e) => {
+                    warn!("Could not set up {config} port mapping: {e}");
+                    return;
+                }
+            };
+            if let Err(e) = gateway.map_ports(ctx).await {
+                warn!("Could not map p2p ports using {config}: {e}");
+            }
+            ctx.tracker.spawn(renew_port_mappings(gateway, ctx.clone()));
+        }
+    }
+}
+
+async fn renew_port_mappings(gateway: Gateway, ctx: P2PContext) {
+    let mut interval = tokio::time::interval(PORT_MAPPING_LIFETIME / 2);
+    // first tick starts immediately, the ports were just mapped
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        debug!("Renewing NAT port mappings");
+        if let Err(e) = gateway.map_ports(&ctx).await {
+            warn!("Could not renew NAT port mappings: {e}");
+        }
+    }
+}
+
+/// Keeps track of the ip our peers see us at, as reported in the `to` field of their PONG messages.
+#[derive(Debug, Default)]
+pub struct ExternalIpVotes {
+    votes: VecDeque<(H256, IpAddr)>,
+}
+
+impl ExternalIpVotes {
+    /// Registers the ip reported by the peer with `node_id`, replacing its previous report.
+    /// Returns the ip that most of the recent reports agree on, if there's one.
+    pub fn add_vote(&mut self, node_id: H256, ip: IpAddr) -> Option<IpAddr> {
+        // Private and loopback addresses are what we see inside our own network,
+        // they say nothing about how we're reached from the outside.
+        if !is_public_ip(&ip) {
+            return None;
+        }
+        self.votes.retain(|(voter, _)| *voter != node_id);
+        self.votes.push_back((node_id, ip));
+        if self.votes.len() > MAX_EXTERNAL_IP_VOTES {
+            self.votes.pop_front();
+        }
+
+        let mut counts: HashMap<IpAddr, usize> = HashMap::new();
+        for (_, ip) in &self.votes {
+            *counts.entry(*ip).or_default() += 1;
+        }
+        counts
+            .into_iter()
+            .max_by_key(|(_, count)| *count)
+            .filter(|(_, count)| *count >= MIN_EXTERNAL_IP_VOTES && *count * 2 > self.votes.len())
+            .map(|(ip, _)| ip)
+    }
+}
+
+fn is_public_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation())
+        }
+        // The node record only supports ipv4 addresses for now
+        IpAddr::V6(_) => false,
+    }
+}
+
+/// Returns the local ip used to reach `remote`, used to tell the gateway where to forward the ports to.
+async fn local_ip_towards(remote: SocketAddr) -> Result<IpAddr, NatError> {
+    let socket = tokio::net::UdpSocket::bind("0.0.0.0:0").await?;
+    socket.connect(remote).await?;
+    Ok(socket.local_addr()?.ip())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn parse_nat_config() {
+        assert_eq!(NatConfig::from_str("none"), Ok(NatConfig::None));
+        assert_eq!(NatConfig::from_str("upnp"), Ok(NatConfig::Upnp));
+        assert_eq!(NatConfig::from_str("pmp"), Ok(NatConfig::Pmp(None)));
+        assert_eq!(
+            NatConfig::from_str("pmp:192.168.1.1"),
+            Ok(NatConfig::Pmp(Some(IpAddr::V4(Ipv4Addr::new(
+                192, 168, 1, 1
+            )))))
+        );
+        assert_eq!(
+            NatConfig::from_str("extip:203.0.113.7"),
+            Ok(NatConfig::ExtIp(IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7))))
+        );
+        assert!(NatConfig::from_str("extip").is_err());
+        assert!(NatConfig::from_str("extip:not-an-ip").is_err());
+        assert!(NatConfig::from_str("stun").is_err());
+
+        for config in ["none", "upnp", "pmp", "pmp:10.0.0.1", "extip:1.2.3.4"] {
+            assert_eq!(NatConfig::from_str(config).unwrap().to_string(), config);
+        }
+    }
+
+    #[test]
+    fn external_ip_needs_a_quorum_of_distinct_peers() {
+        let mut votes = ExternalIpVotes::default();
+        let external_ip = IpAddr::V4(Ipv4Addr::new(8, 8, 4, 4));
+        let private_ip = IpAddr::V4(Ipv4Addr::new(192, 168, 0, 10));
+        let other_ip = IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1));
+        let peer = |n: u64| H256::from_low_u64_be(n);
+
+        // the same peer voting many times only counts once
+        for _ in 0..MIN_EXTERNAL_IP_VOTES {
+            assert_eq!(votes.add_vote(peer(1), external_ip), None);
+        }
+        // private addresses are ignored
+        for n in 10..20 {
+            assert_eq!(votes.add_vote(peer(n), private_ip), None);
+        }
+
+        assert_eq!(votes.add_vote(peer(2), external_ip), None);
+        assert_eq!(votes.add_vote(peer(3), external_ip), Some(external_ip));
+
+        // a single dissenting peer doesn't change the outcome
+        assert_eq!(votes.add_vote(peer(4), other_ip), Some(external_ip));
+    }
+}
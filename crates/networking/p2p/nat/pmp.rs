@@ -0,0 +1,220 @@
+//! Minimal NAT-PMP client, see https://datatracker.ietf.org/doc/html/rfc6886
+
+use super::{NatError, Protocol};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+use tokio::net::UdpSocket;
+
+const NAT_PMP_PORT: u16 = 5351;
+const NAT_PMP_VERSION: u8 = 0;
+const OP_EXTERNAL_ADDRESS: u8 = 0;
+const OP_MAP_UDP: u8 = 1;
+const OP_MAP_TCP: u8 = 2;
+/// Responses have the same opcode as the request plus 128
+const OP_RESPONSE_OFFSET: u8 = 128;
+/// The rfc starts with a 250ms timeout and doubles it on each retry
+const INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+const MAX_ATTEMPTS: usize = 4;
+
+#[derive(Debug, Clone)]
+pub(super) struct PmpGateway {
+    addr: SocketAddr,
+}
+
+impl PmpGateway {
+    pub fn new(gateway_ip: IpAddr) -> Self {
+        Self {
+            addr: SocketAddr::new(gateway_ip, NAT_PMP_PORT),
+        }
+    }
+
+    pub async fn external_ip(&self) -> Result<IpAddr, NatError> {
+        let response = self
+            .request(&[NAT_PMP_VERSION, OP_EXTERNAL_ADDRESS], OP_EXTERNAL_ADDRESS)
+            .await?;
+        // version (1) | opcode (1) | result code (2) | epoch (4) | external ip (4)
+        let ip: [u8; 4] = response
+            .get(8..12)
+            .and_then(|ip| ip.try_into().ok())
+            .ok_or(NatError::InvalidResponse(
+                "external address response too short".into(),
+            ))?;
+        Ok(IpAddr::V4(Ipv4Addr::from(ip)))
+    }
+
+    pub async fn add_port_mapping(
+        &self,
+        protocol: Protocol,
+        internal_port: u16,
+        external_port: u16,
+        lifetime: Duration,
+    ) -> Result<u16, NatError> {
+        let opcode = match protocol {
+            Protocol::Udp => OP_MAP_UDP,
+            Protocol::Tcp => OP_MAP_TCP,
+        };
+        let lifetime = u32::try_from(lifetime.as_secs()).unwrap_or(u32::MAX);
+        // version (1) | opcode (1) | reserved (2) | internal port (2) | external port (2) | lifetime (4)
+        let mut request = vec![NAT_PMP_VERSION, opcode, 0, 0];
+        request.extend_from_slice(&internal_port.to_be_bytes());
+        request.extend_from_slice(&external_port.to_be_bytes());
+        request.extend_from_slice(&lifetime.to_be_bytes());
+
+        let response = self.request(&request, opcode).await?;
+        // version (1) | opcode (1) | result code (2) | epoch (4) | internal port (2) | mapped port (2) | lifetime (4)
+        let mapped_port: [u8; 2] = response
+            .get(10..12)
+            .and_then(|port| port.try_into().ok())
+            .ok_or(NatError::InvalidResponse(
+                "port mapping response too short".into(),
+            ))?;
+        Ok(u16::from_be_bytes(mapped_port))
+    }
+
+    /// Sends `request` to the gateway, retrying with an increasing timeout, and validates
+    /// the header of the response.
+    async fn request(&self, request: &[u8], opcode: u8) -> Result<Vec<u8>, NatError> {
+        let socket = UdpSocket::bind(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0)).await?;
+        socket.connect(self.addr).await?;
+
+        let mut buf = [0u8; 16];
+        let mut timeout = INITIAL_TIMEOUT;
+        for _ in 0..MAX_ATTEMPTS {
+            socket.send(request).await?;
+            match tokio::time::timeout(timeout, socket.recv(&mut buf)).await {
+                Ok(read) => {
+                    let response = &buf[..read?];
+                    check_response_header(response, opcode)?;
+                    return Ok(response.to_vec());
+                }
+                Err(_) => timeout *= 2,
+            }
+        }
+        Err(NatError::Timeout)
+    }
+}
+
+fn check_response_header(response: &[u8], opcode: u8) -> Result<(), NatError> {
+    let [version, response_opcode, result_hi, result_lo, ..] = response else {
+        return Err(NatError::InvalidResponse("response too short".into()));
+    };
+    if *version != NAT_PMP_VERSION || *response_opcode != opcode + OP_RESPONSE_OFFSET {
+        return Err(NatError::InvalidResponse(format!(
+            "unexpected version {version} or opcode {response_opcode}"
+        )));
+    }
+    let result_code = u16::from_be_bytes([*result_hi, *result_lo]);
+    if result_code != 0 {
+        return Err(NatError::Rejected(result_code));
+    }
+    Ok(())
+}
+
+/// Reads the default gateway from the kernel routing table.
+/// Only supported on linux, on other platforms the gateway has to be given explicitly.
+pub(super) fn default_gateway() -> Option<IpAddr> {
+    let routes = std::fs::read_to_string("/proc/net/route").ok()?;
+    parse_default_gateway(&routes)
+}
+
+fn parse_default_gateway(routes: &str) -> Option<IpAddr> {
+    // Iface  Destination  Gateway  Flags ...
+    // the addresses are written as little endian hex numbers
+    routes.lines().skip(1).find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let destination = fields.nth(1)?;
+        let gateway = fields.next()?;
+        if destination != "00000000" {
+            return None;
+        }
+        let gateway = u32::from_str_radix(gateway, 16).ok()?;
+        Some(IpAddr::V4(Ipv4Addr::from(gateway.to_le_bytes())))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXTERNAL_IP: Ipv4Addr = Ipv4Addr::new(203, 0, 113, 7);
+
+    /// Answers NAT-PMP requests the way a home router would, mapping every port to itself + 1000.
+    async fn start_mock_gateway() -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 16];
+            loop {
+                let (read, from) = socket.recv_from(&mut buf).await.unwrap();
+                let opcode = buf[1];
+                let mut response = vec![NAT_PMP_VERSION, opcode + OP_RESPONSE_OFFSET, 0, 0];
+                // seconds since the mappings table was initialized
+                response.extend_from_slice(&42u32.to_be_bytes());
+                match opcode {
+                    OP_EXTERNAL_ADDRESS => response.extend_from_slice(&EXTERNAL_IP.octets()),
+                    OP_MAP_UDP | OP_MAP_TCP if read == 12 => {
+                        let internal_port = u16::from_be_bytes([buf[4], buf[5]]);
+                        response.extend_from_slice(&internal_port.to_be_bytes());
+                        response.extend_from_slice(&(internal_port + 1000).to_be_bytes());
+                        response.extend_from_slice(&buf[8..12]);
+                    }
+                    _ => {
+                        // unsupported opcode
+                        response[3] = 5;
+                    }
+                }
+                socket.send_to(&response, from).await.unwrap();
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn map_ports_with_mock_gateway() {
+        let gateway = PmpGateway {
+            addr: start_mock_gateway().await,
+        };
+
+        assert_eq!(
+            gateway.external_ip().await.unwrap(),
+            IpAddr::V4(EXTERNAL_IP)
+        );
+        let mapped_port = gateway
+            .add_port_mapping(Protocol::Tcp, 30303, 30303, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(mapped_port, 31303);
+        let mapped_port = gateway
+            .add_port_mapping(Protocol::Udp, 30304, 30304, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(mapped_port, 31304);
+    }
+
+    #[tokio::test]
+    async fn times_out_without_gateway() {
+        // bind a socket that never answers
+        let silent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let gateway = PmpGateway {
+            addr: silent.local_addr().unwrap(),
+        };
+        assert!(matches!(
+            gateway.external_ip().await,
+            Err(NatError::Timeout)
+        ));
+    }
+
+    #[test]
+    fn parse_gateway_from_route_table() {
+        let routes = "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n\
+                      eth0\t0000A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0\n\
+                      eth0\t00000000\t0100A8C0\t0003\t0\t0\t0\t00000000\t0\t0\t0\n";
+        assert_eq!(
+            parse_default_gateway(routes),
+            Some(IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1)))
+        );
+        assert_eq!(parse_default_gateway("Iface\tDestination\tGateway\n"), None);
+    }
+}
@@ -0,0 +1,374 @@
+//! Minimal UPnP Internet Gateway Device client, only the actions needed to expose the p2p ports.
+//! See https://openconnectivity.org/developer/specifications/upnp-resources/upnp/internet-gateway-device-igd-v-2-0/
+
+use super::{NatError, PORT_MAPPING_DESCRIPTION, Protocol, local_ip_towards};
+use reqwest::Url;
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+use tokio::net::UdpSocket;
+use tracing::debug;
+
+const SSDP_MULTICAST_ADDR: SocketAddr =
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::new(239, 255, 255, 250)), 1900);
+const SSDP_TIMEOUT: Duration = Duration::from_secs(3);
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+const IGD_DEVICE_TYPE: &str = "urn:schemas-upnp-org:device:InternetGatewayDevice:1";
+/// Services that can forward ports, in order of preference
+const WAN_SERVICE_TYPES: [&str; 3] = [
+    "urn:schemas-upnp-org:service:WANIPConnection:2",
+    "urn:schemas-upnp-org:service:WANIPConnection:1",
+    "urn:schemas-upnp-org:service:WANPPPConnection:1",
+];
+/// Error returned by gateways that don't support leases other than 0 (infinite)
+const ONLY_PERMANENT_LEASES_SUPPORTED: u16 = 725;
+
+#[derive(Debug, Clone)]
+pub(super) struct UpnpGateway {
+    client: reqwest::Client,
+    service_type: String,
+    control_url: Url,
+    /// The ip of this host in the gateway's network, where the ports are forwarded to
+    local_ip: IpAddr,
+}
+
+impl UpnpGateway {
+    /// Searches for an Internet Gateway Device in the local network via SSDP
+    pub async fn discover() -> Result<Self, NatError> {
+        Self::discover_at(SSDP_MULTICAST_ADDR).await
+    }
+
+    pub(super) async fn discover_at(ssdp_addr: SocketAddr) -> Result<Self, NatError> {
+        let socket = UdpSocket::bind(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0)).await?;
+        let search = format!(
+            "M-SEARCH * HTTP/1.1\r\n\
+             HOST: {SSDP_MULTICAST_ADDR}\r\n\
+             ST: {IGD_DEVICE_TYPE}\r\n\
+             MAN: \"ssdp:discover\"\r\n\
+             MX: 2\r\n\r\n"
+        );
+        socket.send_to(search.as_bytes(), ssdp_addr).await?;
+
+        let mut buf = [0u8; 2048];
+        let (read, from) = tokio::time::timeout(SSDP_TIMEOUT, socket.recv_from(&mut buf))
+            .await
+            .map_err(|_| NatError::NoGateway)??;
+        let response = String::from_utf8_lossy(&buf[..read]);
+        let location = response
+            .lines()
+            .find_map(|line| {
+                let (header, value) = line.split_once(':')?;
+                header
+                    .trim()
+                    .eq_ignore_ascii_case("location")
+                    .then(|| value.trim().to_string())
+            })
+            .ok_or(NatError::InvalidResponse(
+                "SSDP response without location".into(),
+            ))?;
+        debug!("Found UPnP gateway at {from}, description at {location}");
+
+        let local_ip = local_ip_towards(from).await?;
+        Self::from_location(&location, local_ip).await
+    }
+
+    /// Reads the device description at `location` and picks its WAN connection service
+    pub(super) async fn from_location(location: &str, local_ip: IpAddr) -> Result<Self, NatError> {
+        let client = reqwest::Client::builder().timeout(HTTP_TIMEOUT).build()?;
+        let location = Url::parse(location)
+            .map_err(|e| NatError::InvalidResponse(format!("invalid location: {e}")))?;
+        let description = client
+            .get(location.clone())
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        let (service_type, control_path) =
+            find_wan_service(&description).ok_or(NatError::NoGateway)?;
+        let base_url = match xml_tag(&description, "URLBase") {
+            Some(base) if !base.is_empty() => Url::parse(base)
+                .map_err(|e| NatError::InvalidResponse(format!("invalid URLBase: {e}")))?,
+            _ => location,
+        };
+        let control_url = base_url
+            .join(control_path)
+            .map_err(|e| NatError::InvalidResponse(format!("invalid controlURL: {e}")))?;
+
+        Ok(Self {
+            client,
+            service_type: service_type.to_string(),
+            control_url,
+            local_ip,
+        })
+    }
+
+    pub async fn external_ip(&self) -> Result<IpAddr, NatError> {
+        let response = self.soap_request("GetExternalIPAddress", &[]).await?;
+        let ip = xml_tag(&response, "NewExternalIPAddress").ok_or(NatError::InvalidResponse(
+            "missing NewExternalIPAddress".into(),
+        ))?;
+        ip.parse()
+            .map_err(|_| NatError::InvalidResponse(format!("invalid external ip {ip:?}")))
+    }
+
+    pub async fn add_port_mapping(
+        &self,
+        protocol: Protocol,
+        internal_port: u16,
+        external_port: u16,
+        lifetime: Duration,
+    ) -> Result<u16, NatError> {
+        match self
+            .request_port_mapping(protocol, internal_port, external_port, lifetime.as_secs())
+            .await
+        {
+            Err(NatError::Rejected(ONLY_PERMANENT_LEASES_SUPPORTED)) => {
+                self.request_port_mapping(protocol, internal_port, external_port, 0)
+                    .await
+            }
+            result => result,
+        }
+    }
+
+    async fn request_port_mapping(
+        &self,
+        protocol: Protocol,
+        internal_port: u16,
+        external_port: u16,
+        lease_duration: u64,
+    ) -> Result<u16, NatError> {
+        self.soap_request(
+            "AddPortMapping",
+            &[
+                ("NewRemoteHost", String::new()),
+                ("NewExternalPort", external_port.to_string()),
+                ("NewProtocol", protocol.to_string()),
+                ("NewInternalPort", internal_port.to_string()),
+                ("NewInternalClient", self.local_ip.to_string()),
+                ("NewEnabled", "1".to_string()),
+                (
+                    "NewPortMappingDescription",
+                    PORT_MAPPING_DESCRIPTION.to_string(),
+                ),
+                ("NewLeaseDuration", lease_duration.to_string()),
+            ],
+        )
+        .await?;
+        // The gateway either maps the requested port or fails
+        Ok(external_port)
+    }
+
+    async fn soap_request(
+        &self,
+        action: &str,
+        args: &[(&str, String)],
+    ) -> Result<String, NatError> {
+        let args: String = args
+            .iter()
+            .map(|(name, value)| format!("<{name}>{value}</{name}>"))
+            .collect();
+        let body = format!(
+            "<?xml version=\"1.0\"?>\
+             <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+             <s:Body><u:{action} xmlns:u=\"{service_type}\">{args}</u:{action}></s:Body>\
+             </s:Envelope>",
+            service_type = self.service_type
+        );
+        let response = self
+            .client
+            .post(self.control_url.clone())
+            .header("Content-Type", "text/xml; charset=\"utf-8\"")
+            .header("SOAPAction", format!("\"{}#{action}\"", self.service_type))
+            .body(body)
+            .send()
+            .await?;
+        let status = response.status();
+        let text = response.text().await?;
+        if !status.is_success() {
+            let code = xml_tag(&text, "errorCode")
+                .and_then(|code| code.parse().ok())
+                .unwrap_or(status.as_u16());
+            return Err(NatError::Rejected(code));
+        }
+        Ok(text)
+    }
+}
+
+/// Returns the type and control url of the first WAN connection service found in the device description
+fn find_wan_service(description: &str) -> Option<(&str, &str)> {
+    let services: Vec<(&str, &str)> = description
+        .split("<service>")
+        .skip(1)
+        .filter_map(|service| {
+            Some((
+                xml_tag(service, "serviceType")?,
+                xml_tag(service, "controlURL")?,
+            ))
+        })
+        .collect();
+    WAN_SERVICE_TYPES.iter().find_map(|wanted| {
+        services
+            .iter()
+            .find(|(service_type, _)| service_type == wanted)
+            .copied()
+    })
+}
+
+/// Returns the trimmed contents of the first `<tag>` element, good enough for the flat documents gateways return
+fn xml_tag<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{tag}>"))? + tag.len() + 2;
+    let end = start + xml[start..].find(&format!("</{tag}>"))?;
+    Some(xml[start..end].trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    const DESCRIPTION: &str = r#"<?xml version="1.0"?>
+<root xmlns="urn:schemas-upnp-org:device-1-0">
+  <device>
+    <deviceType>urn:schemas-upnp-org:device:InternetGatewayDevice:1</deviceType>
+    <serviceList>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:Layer3Forwarding:1</serviceType>
+        <controlURL>/ctl/L3F</controlURL>
+      </service>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>
+        <controlURL>/ctl/IPConn</controlURL>
+      </service>
+    </serviceList>
+  </device>
+</root>"#;
+
+    /// Reads a full http request and returns its head and body
+    async fn read_request(stream: &mut tokio::net::TcpStream) -> (String, String) {
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 1024];
+        loop {
+            let read = stream.read(&mut chunk).await.unwrap();
+            buf.extend_from_slice(&chunk[..read]);
+            let request = String::from_utf8_lossy(&buf).to_string();
+            let Some(head_end) = request.find("\r\n\r\n") else {
+                continue;
+            };
+            let (head, body) = request.split_at(head_end + 4);
+            let content_length = head
+                .lines()
+                .find_map(|line| {
+                    let (header, value) = line.split_once(':')?;
+                    header
+                        .eq_ignore_ascii_case("content-length")
+                        .then(|| value.trim().parse::<usize>().ok())?
+                })
+                .unwrap_or(0);
+            if body.len() >= content_length || read == 0 {
+                return (head.to_string(), body.to_string());
+            }
+        }
+    }
+
+    /// Serves the device description and answers SOAP requests like a router supporting only permanent leases
+    async fn start_mock_gateway() -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let (head, body) = read_request(&mut stream).await;
+                let (status, response) = if head.starts_with("GET /rootDesc.xml") {
+                    ("200 OK", DESCRIPTION.to_string())
+                } else if !head.starts_with("POST /ctl/IPConn") {
+                    ("404 Not Found", String::new())
+                } else if head.contains("#GetExternalIPAddress") {
+                    (
+                        "200 OK",
+                        "<s:Envelope><s:Body><u:GetExternalIPAddressResponse><NewExternalIPAddress>203.0.113.7</NewExternalIPAddress></u:GetExternalIPAddressResponse></s:Body></s:Envelope>".to_string(),
+                    )
+                } else if head.contains("#AddPortMapping")
+                    && body.contains("<NewLeaseDuration>0</NewLeaseDuration>")
+                    && body.contains("<NewInternalClient>127.0.0.1</NewInternalClient>")
+                {
+                    (
+                        "200 OK",
+                        "<s:Envelope><s:Body><u:AddPortMappingResponse/></s:Body></s:Envelope>"
+                            .to_string(),
+                    )
+                } else {
+                    (
+                        "500 Internal Server Error",
+                        "<s:Envelope><s:Body><s:Fault><detail><UPnPError><errorCode>725</errorCode></UPnPError></detail></s:Fault></s:Body></s:Envelope>".to_string(),
+                    )
+                };
+                let response = format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: text/xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                    response.len()
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        addr
+    }
+
+    async fn start_mock_ssdp(http_addr: SocketAddr) -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 1024];
+            let (read, from) = socket.recv_from(&mut buf).await.unwrap();
+            assert!(String::from_utf8_lossy(&buf[..read]).starts_with("M-SEARCH"));
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nST: {IGD_DEVICE_TYPE}\r\nLocation: http://{http_addr}/rootDesc.xml\r\n\r\n"
+            );
+            socket.send_to(response.as_bytes(), from).await.unwrap();
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn map_ports_with_mock_gateway() {
+        let http_addr = start_mock_gateway().await;
+        let ssdp_addr = start_mock_ssdp(http_addr).await;
+
+        let gateway = UpnpGateway::discover_at(ssdp_addr).await.unwrap();
+        assert_eq!(
+            gateway.service_type,
+            "urn:schemas-upnp-org:service:WANIPConnection:1"
+        );
+        assert_eq!(
+            gateway.control_url.as_str(),
+            format!("http://{http_addr}/ctl/IPConn")
+        );
+
+        assert_eq!(
+            gateway.external_ip().await.unwrap(),
+            IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7))
+        );
+        // the mock rejects finite leases, so this also covers the permanent lease fallback
+        let mapped_port = gateway
+            .add_port_mapping(Protocol::Tcp, 30303, 30303, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(mapped_port, 30303);
+    }
+
+    #[test]
+    fn find_preferred_wan_service() {
+        assert_eq!(
+            find_wan_service(DESCRIPTION),
+            Some((
+                "urn:schemas-upnp-org:service:WANIPConnection:1",
+                "/ctl/IPConn"
+            ))
+        );
+        assert_eq!(find_wan_service("<root></root>"), None);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::discv4::server::{DiscoveryError, Discv4Server};
 use crate::kademlia::{self, KademliaTable};
+use crate::nat::{self, NatConfig};
 use crate::rlpx::connection::server::{RLPxConnBroadcastSender, RLPxConnection};
 use crate::rlpx::l2::l2_connection::P2PBasedContext;
 use crate::rlpx::message::Message as RLPxMessage;
@@ -10,7 +11,11 @@ use ethrex_common::{H256, H512};
 use ethrex_storage::Store;
 use secp256k1::{PublicKey, SecretKey};
 
-use std::{io, net::SocketAddr, sync::Arc};
+use std::{
+    io,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
 use tokio::{
     net::{TcpListener, TcpSocket},
     sync::Mutex,
@@ -41,10 +46,14 @@ pub struct P2PContext {
     pub storage: Store,
     pub blockchain: Arc<Blockchain>,
     pub(crate) broadcast: RLPxConnBroadcastSender,
-    pub local_node: Node,
+    /// Our node as advertised to peers, its ip changes when our external address is found.
+    pub local_node: Arc<Mutex<Node>>,
     pub local_node_record: Arc<Mutex<NodeRecord>>,
     pub client_version: String,
     pub based_context: Option<P2PBasedContext>,
+    pub nat: NatConfig,
+    /// Whether new blocks are gossiped over eth, for networks without a consensus client
+    pub block_gossip: bool,
 }
 
 impl P2PContext {
@@ -59,6 +68,8 @@ impl P2PContext {
         blockchain: Arc<Blockchain>,
         client_version: String,
         based_context: Option<P2PBasedContext>,
+        nat: NatConfig,
+        block_gossip: bool,
     ) -> Self {
         let (channel_broadcast_send_end, _) = tokio::sync::broadcast::channel::<(
             tokio::task::Id,
@@ -66,7 +77,7 @@ impl P2PContext {
         )>(MAX_MESSAGES_TO_BROADCAST);
 
         P2PContext {
-            local_node,
+            local_node: Arc::new(Mutex::new(local_node)),
             local_node_record,
             tracker,
             signer,
@@ -76,6 +87,8 @@ impl P2PContext {
             broadcast: channel_broadcast_send_end,
             client_version,
             based_context,
+            nat,
+            block_gossip,
         }
     }
 
@@ -88,33 +101,53 @@ impl P2PContext {
         }
         Ok(())
     }
+
+    pub async fn local_node(&self) -> Node {
+        self.local_node.lock().await.clone()
+    }
+
+    /// Advertises `ip` as our external address in the local node and its record.
+    pub async fn set_external_ip(&self, ip: IpAddr) {
+        let updated = self.local_node_record.lock().await.set_ip(ip, &self.signer);
+        match updated {
+            Ok(true) => {
+                let mut node = self.local_node.lock().await;
+                node.ip = ip;
+                info!(
+                    "External address updated to {ip}, node: {}",
+                    node.enode_url()
+                );
+            }
+            Ok(false) => {}
+            Err(e) => error!("Could not update the node record with external ip {ip}: {e}"),
+        }
+    }
 }
 
 pub async fn start_network(context: P2PContext, bootnodes: Vec<Node>) -> Result<(), NetworkError> {
-    let discovery = Discv4Server::try_new(context.clone())
+    // The sockets are bound to our local address, not to the external one set up by the NAT
+    let local_node = context.local_node().await;
+    nat::start_nat(&context).await;
+
+    let discovery = Discv4Server::try_new(context.clone(), local_node.udp_addr())
         .await
         .map_err(NetworkError::DiscoveryStart)?;
 
-    info!(
-        "Starting discovery service at {}",
-        context.local_node.udp_addr()
-    );
+    info!("Starting discovery service at {}", local_node.udp_addr());
     discovery
         .start(bootnodes)
         .await
         .map_err(NetworkError::DiscoveryStart)?;
 
-    info!(
-        "Listening for requests at {}",
-        context.local_node.tcp_addr()
-    );
-    context.tracker.spawn(serve_p2p_requests(context.clone()));
+    info!("Listening for requests at {}", local_node.tcp_addr());
+    context
+        .tracker
+        .spawn(serve_p2p_requests(context.clone(), local_node.tcp_addr()));
 
     Ok(())
 }
 
-pub(crate) async fn serve_p2p_requests(context: P2PContext) {
-    let tcp_addr = context.local_node.tcp_addr();
+pub(crate) async fn serve_p2p_requests(context: P2PContext, tcp_addr: SocketAddr) {
     let listener = match listener(tcp_addr) {
         Ok(result) => result,
         Err(e) => {
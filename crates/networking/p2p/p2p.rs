@@ -1,5 +1,6 @@
 pub(crate) mod discv4;
 pub mod kademlia;
+pub mod nat;
 pub mod network;
 pub mod peer_handler;
 pub mod rlpx;
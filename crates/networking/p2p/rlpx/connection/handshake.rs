@@ -1,8 +1,4 @@
-use std::{
-    collections::{HashMap, HashSet},
-    net::SocketAddr,
-    sync::Arc,
-};
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
 
 use super::{
     codec::RLPxCodec,
@@ -12,6 +8,7 @@ use crate::{
     rlpx::{
         connection::server::{Established, InnerState},
         error::RLPxError,
+        eth::{block_gossip::BlockGossipState, tx_propagation::KnownTransactions},
         l2::l2_connection::L2ConnState,
         utils::{
             compress_pubkey, decompress_pubkey, ecdh_xchng, kdf, log_peer_debug, sha256,
@@ -129,7 +126,8 @@ pub(crate) async fn perform(
             negotiated_eth_capability: None,
             negotiated_snap_capability: None,
             last_block_range_update_block: 0,
-            broadcasted_txs: HashSet::new(),
+            known_txs: KnownTransactions::default(),
+            block_gossip: context.block_gossip.then(BlockGossipState::default),
             requested_pooled_txs: HashMap::new(),
             client_version: context.client_version.clone(),
             connection_broadcast_send: context.broadcast.clone(),
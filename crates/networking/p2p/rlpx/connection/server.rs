@@ -1,15 +1,7 @@
-use std::{
-    collections::{HashMap, HashSet},
-    net::SocketAddr,
-    sync::Arc,
-    time::Duration,
-};
+use std::{collections::HashMap, net::SocketAddr, sync::Arc, time::Duration};
 
 use ethrex_blockchain::Blockchain;
-use ethrex_common::{
-    H256,
-    types::{MempoolTransaction, Transaction},
-};
+use ethrex_common::types::{MempoolTransaction, Transaction};
 use ethrex_storage::Store;
 use futures::{SinkExt as _, Stream, stream::SplitSink};
 use rand::random;
@@ -41,10 +33,12 @@ use crate::{
         error::RLPxError,
         eth::{
             backend,
+            block_gossip::{self, BlockGossipState},
             blocks::{BlockBodies, BlockHeaders},
             receipts::{GetReceipts, Receipts},
             status::StatusMessage,
             transactions::{GetPooledTransactions, NewPooledTransactionHashes, Transactions},
+            tx_propagation::{KnownTransactions, can_be_sent_in_full, is_direct_broadcast_peer},
             update::BlockRangeUpdate,
         },
         l2::{
@@ -71,6 +65,7 @@ use crate::{
 const PING_INTERVAL: Duration = Duration::from_secs(10);
 const TX_BROADCAST_INTERVAL: Duration = Duration::from_millis(500);
 const BLOCK_RANGE_UPDATE_INTERVAL: Duration = Duration::from_secs(60);
+const BLOCK_GOSSIP_INTERVAL: Duration = Duration::from_millis(500);
 // Soft limit for the number of transaction hashes sent in a single NewPooledTransactionHashes message as per [the spec](https://github.com/ethereum/devp2p/blob/master/caps/eth.md#newpooledtransactionhashes-0x080)
 const NEW_POOLED_TRANSACTION_HASHES_SOFT_LIMIT: usize = 4096;
 
@@ -108,7 +103,10 @@ pub struct Established {
     pub(crate) negotiated_eth_capability: Option<Capability>,
     pub(crate) negotiated_snap_capability: Option<Capability>,
     pub(crate) last_block_range_update_block: u64,
-    pub(crate) broadcasted_txs: HashSet<H256>,
+    /// Transactions the peer already has, so we don't send or announce them again
+    pub(crate) known_txs: KnownTransactions,
+    /// Present only when blocks are gossiped over eth, see `block_gossip`
+    pub(crate) block_gossip: Option<BlockGossipState>,
     pub(crate) requested_pooled_txs: HashMap<u64, NewPooledTransactionHashes>,
     pub(crate) client_version: String,
     //// Send end of the channel used to broadcast messages
@@ -174,6 +172,7 @@ pub enum CastMessage {
     BackendMessage(Message),
     SendPing,
     SendNewPooledTxHashes,
+    SendNewBlocks,
     BlockRangeUpdate,
     BroadcastMessage(task::Id, Arc<Message>),
     L2(L2Cast),
@@ -289,6 +288,9 @@ impl GenServer for RLPxConnection {
                 Self::CastMsg::SendNewPooledTxHashes => {
                     send_new_pooled_tx_hashes(established_state).await
                 }
+                Self::CastMsg::SendNewBlocks => {
+                    block_gossip::send_new_blocks(established_state).await
+                }
                 Self::CastMsg::BroadcastMessage(id, msg) => {
                     log_peer_debug(
                         &established_state.node,
@@ -429,6 +431,16 @@ where
         CastMessage::BlockRangeUpdate,
     );
 
+    // Periodic new blocks gossip, only for networks without a consensus client.
+    if state.block_gossip.is_some() && state.negotiated_eth_capability.is_some() {
+        block_gossip::init(state).await?;
+        send_interval(
+            BLOCK_GOSSIP_INTERVAL,
+            handle.clone(),
+            CastMessage::SendNewBlocks,
+        );
+    }
+
     // Periodic L2 messages events.
     if state.l2_state.connection_state().is_ok() {
         send_interval(
@@ -464,7 +476,7 @@ async fn send_new_pooled_tx_hashes(state: &mut Established) -> Result<(), RLPxEr
         .iter()
         .any(|cap| state.capabilities.contains(cap))
     {
-        let filter = |tx: &Transaction| -> bool { !state.broadcasted_txs.contains(&tx.hash()) };
+        let filter = |tx: &Transaction| -> bool { !state.known_txs.contains(&tx.hash()) };
         let txs: Vec<MempoolTransaction> = state
             .blockchain
             .mempool
@@ -478,7 +490,7 @@ async fn send_new_pooled_tx_hashes(state: &mut Established) -> Result<(), RLPxEr
                 let mut txs_to_send = Vec::with_capacity(tx_count);
                 for tx in tx_chunk {
                     txs_to_send.push((**tx).clone());
-                    state.broadcasted_txs.insert(tx.hash());
+                    state.known_txs.insert(tx.hash());
                 }
 
                 send(
@@ -771,28 +783,25 @@ async fn handle_peer_message(state: &mut Established, message: Message) -> Resul
             if state.blockchain.is_synced() {
                 let mut valid_txs = vec![];
                 for tx in txs.transactions {
-                    // Mark as broadcasted for the sender so we don't include it in the next
+                    // Mark as known for the sender so we don't include it in the next
                     // `SendNewPooledTxHashes` message to this peer. Doing so violates spec.
                     // For broadcast itself, `handle_broadcast` filters by task id already.
-                    state.broadcasted_txs.insert(tx.hash());
+                    state.known_txs.insert(tx.hash());
                     if let Err(e) = state.blockchain.add_transaction_to_pool(tx.clone()).await {
                         log_peer_warn(&state.node, &format!("Error adding transaction: {e}"));
                         continue;
                     }
                     valid_txs.push(tx);
                 }
-                // FIXME(#1131): we're supposed to send `Transaction` message only to a random
-                // subset and send only the hashes to everyone else.
-                // Consider sending to the sqrt of the number of peers like geth does.
-                if !valid_txs.is_empty() {
-                    log_peer_debug(
-                        &state.node,
-                        &format!("Broadcasted {} transactions to peers", valid_txs.len()),
-                    );
-                    broadcast_message(state, Message::Transactions(Transactions::new(valid_txs)))?;
-                }
+                broadcast_transactions(state, valid_txs)?;
             }
         }
+        Message::NewBlock(msg) if peer_supports_eth => {
+            block_gossip::handle_new_block(state, msg).await?;
+        }
+        Message::NewBlockHashes(msg) if peer_supports_eth => {
+            block_gossip::handle_new_block_hashes(state, msg).await?;
+        }
         Message::GetBlockHeaders(msg_data) if peer_supports_eth => {
             let response = BlockHeaders {
                 id: msg_data.id,
@@ -831,6 +840,13 @@ async fn handle_peer_message(state: &mut Established, message: Message) -> Resul
             );
         }
         Message::NewPooledTransactionHashes(new_pooled_transaction_hashes) if peer_supports_eth => {
+            // The peer has these, there's no need to announce them back
+            state.known_txs.extend(
+                new_pooled_transaction_hashes
+                    .transaction_hashes
+                    .iter()
+                    .copied(),
+            );
             let hashes =
                 new_pooled_transaction_hashes.get_transactions_to_request(&state.blockchain)?;
 
@@ -859,7 +875,11 @@ async fn handle_peer_message(state: &mut Established, message: Message) -> Resul
                         state.requested_pooled_txs.remove(&msg.id);
                     }
                 }
-                msg.handle(&state.node, &state.blockchain).await?;
+                state
+                    .known_txs
+                    .extend(msg.pooled_transactions.iter().map(|tx| tx.compute_hash()));
+                let accepted_txs = msg.handle(&state.node, &state.blockchain).await?;
+                broadcast_transactions(state, accepted_txs)?;
             }
         }
         Message::GetStorageRanges(req) => {
@@ -877,6 +897,23 @@ async fn handle_peer_message(state: &mut Established, message: Message) -> Resul
         Message::L2(req) if peer_supports_l2 => {
             handle_based_capability_message(state, req).await?;
         }
+        // Responses to the requests made to fetch announced blocks
+        Message::BlockHeaders(msg)
+            if state
+                .block_gossip
+                .as_ref()
+                .is_some_and(|gossip| gossip.is_requested_headers(msg.id)) =>
+        {
+            block_gossip::handle_block_headers(state, msg).await?;
+        }
+        Message::BlockBodies(msg)
+            if state
+                .block_gossip
+                .as_ref()
+                .is_some_and(|gossip| gossip.is_requested_bodies(msg.id)) =>
+        {
+            block_gossip::handle_block_bodies(state, msg).await?;
+        }
         // Send response messages to the backend
         message @ Message::AccountRange(_)
         | message @ Message::StorageRanges(_)
@@ -915,14 +952,21 @@ async fn handle_broadcast(
     if id != tokio::task::id() {
         match broadcasted_msg.as_ref() {
             Message::Transactions(txs) => {
+                let peer_count = state.table.lock().await.count_connected_peers();
+                let node_id = state.node.node_id();
                 let mut filtered = Vec::with_capacity(txs.transactions.len());
                 for tx in &txs.transactions {
                     let tx_hash = tx.hash();
-                    if state.broadcasted_txs.contains(&tx_hash) {
+                    // Only a subset of the peers get the full transaction, the rest will get
+                    // its hash in the next `NewPooledTransactionHashes` announcement
+                    if state.known_txs.contains(&tx_hash)
+                        || !can_be_sent_in_full(tx)
+                        || !is_direct_broadcast_peer(tx_hash, node_id, peer_count)
+                    {
                         continue;
                     }
                     filtered.push(tx.clone());
-                    state.broadcasted_txs.insert(tx_hash);
+                    state.known_txs.insert(tx_hash);
                 }
                 if !filtered.is_empty() {
                     log_peer_debug(
@@ -959,6 +1003,20 @@ async fn handle_block_range_update(state: &mut Established) -> Result<(), RLPxEr
     }
 }
 
+/// Forwards the transactions we accepted into the mempool to the rest of the connections,
+/// each of them decides if the peer gets the full transactions or just their hashes.
+fn broadcast_transactions(state: &Established, txs: Vec<Transaction>) -> Result<(), RLPxError> {
+    let txs: Vec<Transaction> = txs.into_iter().filter(can_be_sent_in_full).collect();
+    if txs.is_empty() {
+        return Ok(());
+    }
+    log_peer_debug(
+        &state.node,
+        &format!("Broadcasted {} transactions to peers", txs.len()),
+    );
+    broadcast_message(state, Message::Transactions(Transactions::new(txs)))
+}
+
 pub(crate) fn broadcast_message(state: &Established, msg: Message) -> Result<(), RLPxError> {
     match msg {
         txs_msg @ Message::Transactions(_) => {
@@ -0,0 +1,438 @@
+//! Propagation of new blocks over the eth protocol through `NewBlock` and `NewBlockHashes`.
+//!
+//! After the merge blocks are gossiped by the consensus layer, and peers sending these
+//! messages are misbehaving. This mode is only meant for private and dev networks without a
+//! consensus client, where several nodes need to follow the blocks produced by one of them.
+//! It's opt-in and must be enabled on every node of the network.
+
+use std::{
+    collections::{HashMap, HashSet},
+    ops::RangeInclusive,
+};
+
+use ethrex_blockchain::{error::ChainError, fork_choice::apply_fork_choice};
+use ethrex_common::{
+    H256, U256,
+    types::{Block, BlockHeader},
+};
+use rand::random;
+use tracing::info;
+
+use crate::rlpx::{
+    connection::server::{Established, send},
+    error::RLPxError,
+    eth::{
+        blocks::{
+            BlockBodies, BlockHeaders, GetBlockBodies, GetBlockHeaders, HashOrNumber,
+            NewBlockHashes, NewBlockMessage,
+        },
+        tx_propagation::is_direct_broadcast_peer,
+    },
+    message::Message,
+    utils::{log_peer_debug, log_peer_warn},
+};
+
+/// Maximum amount of blocks sent to a peer in each round, if it falls further behind
+/// it only gets the latest ones
+const MAX_BLOCKS_PER_ROUND: u64 = 32;
+/// Maximum amount of headers requested at once to fill the gap to an announced block
+const MAX_BLOCKS_TO_FETCH: u64 = 128;
+/// Maximum amount of blocks kept while waiting for their parent to be imported
+const MAX_PENDING_BLOCKS: usize = 256;
+/// Maximum amount of in-flight header and body requests
+const MAX_INFLIGHT_REQUESTS: usize = 64;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BlockGossipState {
+    /// Number of the latest block the peer has, either because we sent or announced it
+    /// or because the peer sent us a block that we validated. Numbers announced by the peer
+    /// aren't trusted, as a made up one would stop us from sending blocks to it.
+    latest_block_sent: u64,
+    /// Ids of the header requests sent to fetch announced blocks
+    requested_headers: HashSet<u64>,
+    /// Headers whose bodies were requested, by request id
+    requested_bodies: HashMap<u64, Vec<BlockHeader>>,
+    /// Received blocks waiting for their parent, by hash. Blocks of competing forks may have
+    /// the same number.
+    pending_blocks: HashMap<H256, Block>,
+}
+
+impl BlockGossipState {
+    pub(crate) fn is_requested_headers(&self, id: u64) -> bool {
+        self.requested_headers.contains(&id)
+    }
+
+    pub(crate) fn is_requested_bodies(&self, id: u64) -> bool {
+        self.requested_bodies.contains_key(&id)
+    }
+
+    fn mark_known(&mut self, block_number: u64) {
+        self.latest_block_sent = self.latest_block_sent.max(block_number);
+    }
+
+    /// Canonical blocks to send in this round, empty if the peer is up to date
+    fn blocks_to_send(&self, latest_block_number: u64) -> RangeInclusive<u64> {
+        let first_block = self
+            .latest_block_sent
+            .saturating_add(1)
+            .max(latest_block_number.saturating_sub(MAX_BLOCKS_PER_ROUND - 1));
+        first_block..=latest_block_number
+    }
+
+    /// Keeps a block whose parent we don't have until it's imported, returning the headers to
+    /// request to get it, as a start and a limit. If the block is ahead of our head we fill the
+    /// gap by number, otherwise its parent is part of a fork and is requested by hash.
+    fn add_pending(
+        &mut self,
+        block: Block,
+        latest_block_number: u64,
+    ) -> Option<(HashOrNumber, u64)> {
+        let block_number = block.header.number;
+        let parent_hash = block.header.parent_hash;
+        if self.pending_blocks.len() < MAX_PENDING_BLOCKS {
+            self.pending_blocks.insert(block.hash(), block);
+        }
+        // The parent is being fetched already
+        if self.pending_blocks.contains_key(&parent_hash) {
+            return None;
+        }
+
+        let first_missing = latest_block_number.saturating_add(1);
+        if block_number > first_missing {
+            // Requests in flight may fill the gap already
+            if !self.requested_headers.is_empty() {
+                return None;
+            }
+            let limit = (block_number - first_missing).min(MAX_BLOCKS_TO_FETCH);
+            Some((HashOrNumber::Number(first_missing), limit))
+        } else if self.requested_headers.len() < MAX_INFLIGHT_REQUESTS {
+            Some((HashOrNumber::Hash(parent_hash), 1))
+        } else {
+            None
+        }
+    }
+
+    /// Takes the pending blocks that were waiting for the given one
+    fn take_children(&mut self, block_hash: H256) -> Vec<Block> {
+        let children: Vec<H256> = self
+            .pending_blocks
+            .iter()
+            .filter(|(_, block)| block.header.parent_hash == block_hash)
+            .map(|(hash, _)| *hash)
+            .collect();
+        children
+            .iter()
+            .filter_map(|hash| self.pending_blocks.remove(hash))
+            .collect()
+    }
+}
+
+fn gossip_state(state: &mut Established) -> Result<&mut BlockGossipState, RLPxError> {
+    state
+        .block_gossip
+        .as_mut()
+        .ok_or(RLPxError::MessageNotHandled(
+            "Block gossip is disabled".to_string(),
+        ))
+}
+
+/// Starts announcing blocks from the current head, the peer is expected to have the
+/// previous ones or to get them by syncing
+pub(crate) async fn init(state: &mut Established) -> Result<(), RLPxError> {
+    let latest_block_number = state.storage.get_latest_block_number().await?;
+    if let Some(gossip) = state.block_gossip.as_mut() {
+        gossip.latest_block_sent = latest_block_number;
+    }
+    Ok(())
+}
+
+/// Sends the blocks added to the canonical chain since the last round.
+/// As with transactions, only about sqrt(peers) get the full block, the rest get the hash.
+pub(crate) async fn send_new_blocks(state: &mut Established) -> Result<(), RLPxError> {
+    let latest_block_number = state.storage.get_latest_block_number().await?;
+    let blocks_to_send = gossip_state(state)?.blocks_to_send(latest_block_number);
+    if blocks_to_send.is_empty() {
+        return Ok(());
+    }
+
+    let peer_count = state.table.lock().await.count_connected_peers();
+    let node_id = state.node.node_id();
+    let total_difficulty = total_difficulty(state)?;
+    let mut announcements = vec![];
+    for block_number in blocks_to_send {
+        let Some(header) = state.storage.get_block_header(block_number)? else {
+            break;
+        };
+        let block_hash = header.hash();
+        if is_direct_broadcast_peer(block_hash, node_id, peer_count) {
+            let body = state.storage.get_block_body(block_number).await?.ok_or(
+                RLPxError::InternalError(
+                    "Block body not found after querying for the block number".to_owned(),
+                ),
+            )?;
+            let block = Block::new(header, body);
+            send(
+                state,
+                Message::NewBlock(NewBlockMessage::new(block, total_difficulty)),
+            )
+            .await?;
+        } else {
+            announcements.push((block_hash, block_number));
+        }
+        gossip_state(state)?.mark_known(block_number);
+    }
+    if !announcements.is_empty() {
+        send(
+            state,
+            Message::NewBlockHashes(NewBlockHashes::new(announcements)),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+pub(crate) async fn handle_new_block(
+    state: &mut Established,
+    msg: NewBlockMessage,
+) -> Result<(), RLPxError> {
+    // Blocks are only accepted with block gossip enabled
+    gossip_state(state)?;
+    log_peer_debug(
+        &state.node,
+        &format!("Received new block {}", msg.block.header.number),
+    );
+    import_block(state, msg.block).await
+}
+
+/// Requests the headers of the announced blocks we don't have yet
+pub(crate) async fn handle_new_block_hashes(
+    state: &mut Established,
+    msg: NewBlockHashes,
+) -> Result<(), RLPxError> {
+    for (block_hash, _) in msg.block_hashes {
+        // The announced number is only trusted once we have the block
+        if let Some(header) = state.storage.get_block_header_by_hash(block_hash)? {
+            gossip_state(state)?.mark_known(header.number);
+            continue;
+        }
+        if gossip_state(state)?.requested_headers.len() >= MAX_INFLIGHT_REQUESTS {
+            log_peer_debug(&state.node, "Too many block requests in flight");
+            break;
+        }
+        let id = random();
+        gossip_state(state)?.requested_headers.insert(id);
+        send(
+            state,
+            Message::GetBlockHeaders(GetBlockHeaders::new(
+                id,
+                HashOrNumber::Hash(block_hash),
+                1,
+                0,
+                false,
+            )),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Handles the response to a header request sent by `handle_new_block_hashes` or by
+/// `import_block` to fill a gap, by requesting the bodies of the received headers
+pub(crate) async fn handle_block_headers(
+    state: &mut Established,
+    msg: BlockHeaders,
+) -> Result<(), RLPxError> {
+    let gossip = gossip_state(state)?;
+    gossip.requested_headers.remove(&msg.id);
+    if msg.block_headers.is_empty() || gossip.requested_bodies.len() >= MAX_INFLIGHT_REQUESTS {
+        return Ok(());
+    }
+    let id = random();
+    let block_hashes = msg.block_headers.iter().map(BlockHeader::hash).collect();
+    gossip.requested_bodies.insert(id, msg.block_headers);
+    send(
+        state,
+        Message::GetBlockBodies(GetBlockBodies::new(id, block_hashes)),
+    )
+    .await
+}
+
+pub(crate) async fn handle_block_bodies(
+    state: &mut Established,
+    msg: BlockBodies,
+) -> Result<(), RLPxError> {
+    let Some(headers) = gossip_state(state)?.requested_bodies.remove(&msg.id) else {
+        return Ok(());
+    };
+    for (header, body) in headers.into_iter().zip(msg.block_bodies) {
+        import_block(state, Block::new(header, body)).await?;
+    }
+    Ok(())
+}
+
+/// Validates and executes the block, making it the head of the chain if it's higher than
+/// the current one. Blocks whose parent is unknown are kept until it's imported, and the
+/// missing blocks are requested to the peer.
+async fn import_block(state: &mut Established, block: Block) -> Result<(), RLPxError> {
+    if let Some(header) = state.storage.get_block_header_by_hash(block.hash())? {
+        gossip_state(state)?.mark_known(header.number);
+        return Ok(());
+    }
+    if state
+        .storage
+        .get_block_header_by_hash(block.header.parent_hash)?
+        .is_none()
+    {
+        let latest_block_number = state.storage.get_latest_block_number().await?;
+        if let Some((start, limit)) = gossip_state(state)?.add_pending(block, latest_block_number) {
+            request_headers(state, start, limit).await?;
+        }
+        return Ok(());
+    }
+
+    // Import the block along with the pending blocks that were waiting for it
+    let mut ready = vec![block];
+    while let Some(block) = ready.pop() {
+        add_block(state, &block).await?;
+        let gossip = gossip_state(state)?;
+        gossip.mark_known(block.header.number);
+        ready.extend(gossip.take_children(block.hash()));
+    }
+    Ok(())
+}
+
+async fn add_block(state: &mut Established, block: &Block) -> Result<(), RLPxError> {
+    let block_number = block.header.number;
+    let block_hash = block.hash();
+    state.blockchain.add_block(block).await.inspect_err(|e| {
+        log_peer_warn(
+            &state.node,
+            &format!("Error adding new block {block_number} with hash {block_hash:?}, error: {e}"),
+        );
+    })?;
+
+    let latest_block_number = state.storage.get_latest_block_number().await?;
+    if block_number > latest_block_number {
+        apply_fork_choice(&state.storage, block_hash, block_hash, block_hash)
+            .await
+            .map_err(|e| {
+                RLPxError::BlockchainError(ChainError::Custom(format!(
+                    "Error applying fork choice for block {block_number} with hash {block_hash:?}, error: {e}"
+                )))
+            })?;
+        // There's no consensus client to tell us we're synced, following the head
+        // of our peers is the closest thing to it
+        state.blockchain.set_synced();
+        info!("Added new block {block_number} with hash {block_hash:?}");
+    }
+    Ok(())
+}
+
+async fn request_headers(
+    state: &mut Established,
+    start: HashOrNumber,
+    limit: u64,
+) -> Result<(), RLPxError> {
+    let id = random();
+    gossip_state(state)?.requested_headers.insert(id);
+    send(
+        state,
+        Message::GetBlockHeaders(GetBlockHeaders::new(id, start, limit, 0, false)),
+    )
+    .await
+}
+
+/// Like in the `Status` message, we use the terminal total difficulty as the chain's
+/// total difficulty since we don't keep track of it
+fn total_difficulty(state: &Established) -> Result<U256, RLPxError> {
+    let chain_config = state.storage.get_chain_config()?;
+    Ok(U256::from(
+        chain_config.terminal_total_difficulty.unwrap_or_default(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethrex_common::types::BlockBody;
+
+    fn block(number: u64, parent_hash: H256, fork: u8) -> Block {
+        let header = BlockHeader {
+            number,
+            parent_hash,
+            extra_data: vec![fork].into(),
+            ..Default::default()
+        };
+        Block::new(header, BlockBody::default())
+    }
+
+    #[test]
+    fn blocks_are_sent_once_and_not_back_to_their_sender() {
+        let mut sender = BlockGossipState {
+            latest_block_sent: 10,
+            ..Default::default()
+        };
+        let mut other_peer = sender.clone();
+
+        // The sender gave us block 11, so only the other peer gets it
+        sender.mark_known(11);
+        assert!(sender.blocks_to_send(11).is_empty());
+        assert_eq!(other_peer.blocks_to_send(11), 11..=11);
+        other_peer.mark_known(11);
+        assert!(other_peer.blocks_to_send(11).is_empty());
+
+        // Peers far behind only get the latest blocks
+        assert_eq!(
+            other_peer.blocks_to_send(100),
+            (100 - MAX_BLOCKS_PER_ROUND + 1)..=100
+        );
+    }
+
+    #[test]
+    fn pending_blocks_fill_gaps_by_number() {
+        let mut gossip = BlockGossipState::default();
+        let parent = block(12, H256::repeat_byte(1), 0);
+        let child = block(13, parent.hash(), 0);
+
+        // Our head is block 10, so block 11 is requested
+        assert_eq!(
+            gossip.add_pending(parent.clone(), 10),
+            Some((HashOrNumber::Number(11), 1))
+        );
+        gossip.requested_headers.insert(1);
+        // The child's parent is pending already
+        assert_eq!(gossip.add_pending(child.clone(), 10), None);
+
+        assert_eq!(
+            gossip.take_children(H256::repeat_byte(1)),
+            vec![parent.clone()]
+        );
+        assert_eq!(gossip.take_children(parent.hash()), vec![child]);
+        assert!(gossip.pending_blocks.is_empty());
+    }
+
+    #[test]
+    fn sibling_blocks_of_a_fork_are_resolved_by_hash() {
+        let mut gossip = BlockGossipState::default();
+        // A fork of our chain, whose head is block 10, that branched off before it
+        let fork_parent = block(10, H256::repeat_byte(1), 1);
+        let sibling = block(11, fork_parent.hash(), 1);
+        let other_sibling = block(11, fork_parent.hash(), 2);
+
+        assert_eq!(
+            gossip.add_pending(sibling.clone(), 10),
+            Some((HashOrNumber::Hash(fork_parent.hash()), 1))
+        );
+        gossip.requested_headers.insert(1);
+        // The missing parent is requested by hash even with other requests in flight
+        assert_eq!(
+            gossip.add_pending(other_sibling.clone(), 10),
+            Some((HashOrNumber::Hash(fork_parent.hash()), 1))
+        );
+
+        // Blocks with the same number don't replace each other
+        let mut children = gossip.take_children(fork_parent.hash());
+        children.sort_by_key(|block| block.header.extra_data.clone());
+        assert_eq!(children, vec![sibling, other_sibling]);
+    }
+}
@@ -3,7 +3,10 @@ use crate::rlpx::{
     utils::{snappy_compress, snappy_decompress},
 };
 use bytes::BufMut;
-use ethrex_common::types::{BlockBody, BlockHash, BlockHeader, BlockNumber};
+use ethrex_common::{
+    U256,
+    types::{Block, BlockBody, BlockHash, BlockHeader, BlockNumber},
+};
 use ethrex_rlp::{
     decode::RLPDecode,
     encode::RLPEncode,
@@ -311,12 +314,90 @@ impl RLPxMessage for BlockBodies {
     }
 }
 
+// https://github.com/ethereum/devp2p/blob/master/caps/eth.md#newblockhashes-0x01
+// Only used on networks without a consensus client, see `block_gossip`
+#[derive(Debug, Clone)]
+pub struct NewBlockHashes {
+    pub block_hashes: Vec<(BlockHash, BlockNumber)>,
+}
+
+impl NewBlockHashes {
+    pub fn new(block_hashes: Vec<(BlockHash, BlockNumber)>) -> Self {
+        Self { block_hashes }
+    }
+}
+
+impl RLPxMessage for NewBlockHashes {
+    const CODE: u8 = 0x01;
+    fn encode(&self, buf: &mut dyn BufMut) -> Result<(), RLPEncodeError> {
+        let mut encoded_data = vec![];
+        self.block_hashes.encode(&mut encoded_data);
+
+        let msg_data = snappy_compress(encoded_data)?;
+        buf.put_slice(&msg_data);
+        Ok(())
+    }
+
+    fn decode(msg_data: &[u8]) -> Result<Self, RLPDecodeError> {
+        let decompressed_data = snappy_decompress(msg_data)?;
+        let block_hashes = Vec::<(BlockHash, BlockNumber)>::decode(&decompressed_data)?;
+
+        Ok(Self::new(block_hashes))
+    }
+}
+
+// https://github.com/ethereum/devp2p/blob/master/caps/eth.md#newblock-0x07
+// Only used on networks without a consensus client, see `block_gossip`
+#[derive(Debug, Clone)]
+pub struct NewBlockMessage {
+    pub block: Block,
+    pub total_difficulty: U256,
+}
+
+impl NewBlockMessage {
+    pub fn new(block: Block, total_difficulty: U256) -> Self {
+        Self {
+            block,
+            total_difficulty,
+        }
+    }
+}
+
+impl RLPxMessage for NewBlockMessage {
+    const CODE: u8 = 0x07;
+    fn encode(&self, buf: &mut dyn BufMut) -> Result<(), RLPEncodeError> {
+        let mut encoded_data = vec![];
+        Encoder::new(&mut encoded_data)
+            .encode_field(&self.block)
+            .encode_field(&self.total_difficulty)
+            .finish();
+
+        let msg_data = snappy_compress(encoded_data)?;
+        buf.put_slice(&msg_data);
+        Ok(())
+    }
+
+    fn decode(msg_data: &[u8]) -> Result<Self, RLPDecodeError> {
+        let decompressed_data = snappy_decompress(msg_data)?;
+        let decoder = Decoder::new(&decompressed_data)?;
+        let (block, decoder): (Block, _) = decoder.decode_field("block")?;
+        let (total_difficulty, _): (U256, _) = decoder.decode_field("td")?;
+
+        Ok(Self::new(block, total_difficulty))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use ethrex_common::types::BlockHash;
+    use ethrex_common::{
+        U256,
+        types::{Block, BlockBody, BlockHash, BlockHeader},
+    };
 
     use crate::rlpx::{
-        eth::blocks::{BlockBodies, GetBlockBodies, GetBlockHeaders},
+        eth::blocks::{
+            BlockBodies, GetBlockBodies, GetBlockHeaders, NewBlockHashes, NewBlockMessage,
+        },
         message::RLPxMessage,
     };
 
@@ -392,4 +473,34 @@ mod tests {
         assert_eq!(decoded.id, 1);
         assert_eq!(decoded.block_bodies, vec![]);
     }
+
+    #[test]
+    fn new_block_hashes_message() {
+        let block_hashes = vec![(BlockHash::from([1; 32]), 1), (BlockHash::from([2; 32]), 2)];
+        let new_block_hashes = NewBlockHashes::new(block_hashes.clone());
+
+        let mut buf = Vec::new();
+        new_block_hashes.encode(&mut buf).unwrap();
+
+        let decoded = NewBlockHashes::decode(&buf).unwrap();
+        assert_eq!(decoded.block_hashes, block_hashes);
+    }
+
+    #[test]
+    fn new_block_message() {
+        let header = BlockHeader {
+            number: 7,
+            gas_limit: 30_000_000,
+            ..Default::default()
+        };
+        let block = Block::new(header, BlockBody::default());
+        let new_block = NewBlockMessage::new(block.clone(), U256::from(17));
+
+        let mut buf = Vec::new();
+        new_block.encode(&mut buf).unwrap();
+
+        let decoded = NewBlockMessage::decode(&buf).unwrap();
+        assert_eq!(decoded.block.hash(), block.hash());
+        assert_eq!(decoded.total_difficulty, U256::from(17));
+    }
 }
@@ -1,8 +1,10 @@
 pub(crate) mod backend;
+pub(crate) mod block_gossip;
 pub(crate) mod blocks;
 mod eth68;
 mod eth69;
 pub(crate) mod receipts;
 pub(crate) mod status;
 pub(crate) mod transactions;
+pub(crate) mod tx_propagation;
 pub(crate) mod update;
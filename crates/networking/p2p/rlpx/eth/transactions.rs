@@ -225,7 +225,7 @@ pub(crate) struct PooledTransactions {
     // id is a u64 chosen by the requesting peer, the responding peer must mirror the value for the response
     // https://github.com/ethereum/devp2p/blob/master/caps/eth.md#protocol-messages
     pub(crate) id: u64,
-    pooled_transactions: Vec<P2PTransaction>,
+    pub(crate) pooled_transactions: Vec<P2PTransaction>,
 }
 
 impl PooledTransactions {
@@ -269,9 +269,16 @@ impl PooledTransactions {
     }
 
     /// Saves every incoming pooled transaction to the mempool.
-    pub async fn handle(self, node: &Node, blockchain: &Blockchain) -> Result<(), MempoolError> {
+    /// Returns the transactions that were accepted, so they can be propagated to other peers.
+    pub async fn handle(
+        self,
+        node: &Node,
+        blockchain: &Blockchain,
+    ) -> Result<Vec<Transaction>, MempoolError> {
+        let mut accepted = Vec::with_capacity(self.pooled_transactions.len());
         for tx in self.pooled_transactions {
             if let P2PTransaction::EIP4844TransactionWithBlobs(itx) = tx {
+                let blob_tx = Transaction::EIP4844Transaction(itx.tx.clone());
                 if let Err(e) = blockchain
                     .add_blob_transaction_to_pool(itx.tx, itx.blobs_bundle)
                     .await
@@ -279,17 +286,19 @@ impl PooledTransactions {
                     log_peer_warn(node, &format!("Error adding transaction: {e}"));
                     continue;
                 }
+                accepted.push(blob_tx);
             } else {
-                let regular_tx = tx
+                let regular_tx: Transaction = tx
                     .try_into()
                     .map_err(|error| MempoolError::StoreError(StoreError::Custom(error)))?;
-                if let Err(e) = blockchain.add_transaction_to_pool(regular_tx).await {
+                if let Err(e) = blockchain.add_transaction_to_pool(regular_tx.clone()).await {
                     log_peer_warn(node, &format!("Error adding transaction: {e}"));
                     continue;
                 }
+                accepted.push(regular_tx);
             }
         }
-        Ok(())
+        Ok(accepted)
     }
 }
 
@@ -0,0 +1,161 @@
+use std::collections::{HashMap, VecDeque};
+
+use ethrex_common::{
+    H256,
+    types::{Transaction, TxType},
+};
+
+/// Maximum amount of transaction hashes remembered per peer, same as geth's `maxKnownTxs`
+pub(crate) const MAX_KNOWN_TXS: usize = 32768;
+
+/// Bounded set of the transaction hashes a peer is known to have, either because it sent them
+/// to us, announced them, or because we already sent or announced them to it.
+/// When full, the least recently seen hash is evicted.
+#[derive(Debug, Clone)]
+pub(crate) struct KnownTransactions {
+    capacity: usize,
+    /// Maps each hash to the tick it was last seen at
+    entries: HashMap<H256, u64>,
+    /// Hashes in the order they were seen, entries whose tick doesn't match the one in
+    /// `entries` are stale (the hash was seen again later) and are skipped on eviction
+    order: VecDeque<(u64, H256)>,
+    tick: u64,
+}
+
+impl Default for KnownTransactions {
+    fn default() -> Self {
+        Self::new(MAX_KNOWN_TXS)
+    }
+}
+
+impl KnownTransactions {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            tick: 0,
+        }
+    }
+
+    pub fn contains(&self, hash: &H256) -> bool {
+        self.entries.contains_key(hash)
+    }
+
+    /// Marks the hash as known, refreshing it if it already was
+    pub fn insert(&mut self, hash: H256) {
+        self.tick += 1;
+        self.entries.insert(hash, self.tick);
+        self.order.push_back((self.tick, hash));
+
+        while self.entries.len() > self.capacity {
+            let Some((tick, oldest)) = self.order.pop_front() else {
+                break;
+            };
+            if self.entries.get(&oldest) == Some(&tick) {
+                self.entries.remove(&oldest);
+            }
+        }
+        // Drop stale entries if refreshes made the queue grow too much
+        if self.order.len() > self.capacity * 2 {
+            let entries = &self.entries;
+            self.order
+                .retain(|(tick, hash)| entries.get(hash) == Some(tick));
+        }
+    }
+
+    pub fn extend(&mut self, hashes: impl IntoIterator<Item = H256>) {
+        for hash in hashes {
+            self.insert(hash);
+        }
+    }
+}
+
+/// Blob transactions are too big to be pushed to peers, they must only be announced
+/// and fetched on demand, as per https://eips.ethereum.org/EIPS/eip-4844#networking
+pub(crate) fn can_be_sent_in_full(tx: &Transaction) -> bool {
+    tx.tx_type() != TxType::EIP4844
+}
+
+/// Decides if the peer with `node_id` is one of the peers `tx_hash` is sent to in full,
+/// the rest of the peers only receive the announcement of its hash.
+///
+/// Like geth, we want each transaction to be pushed to about `sqrt(peer_count)` peers.
+/// Instead of picking them in a single place, every connection decides independently
+/// keeping each peer with probability `1/sqrt(peer_count)`. The decision is derived from
+/// the transaction hash and the node id, so it's uniformly distributed between peers and
+/// stable for the same pair.
+pub(crate) fn is_direct_broadcast_peer(tx_hash: H256, node_id: H256, peer_count: usize) -> bool {
+    if peer_count <= 1 {
+        return true;
+    }
+    let score = (tx_hash ^ node_id).to_low_u64_be() as f64 / u64::MAX as f64;
+    score < 1.0 / (peer_count as f64).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use keccak_hash::keccak;
+
+    #[test]
+    fn known_transactions_evicts_least_recently_seen() {
+        let hash = H256::from_low_u64_be;
+        let mut known = KnownTransactions::new(3);
+        known.extend([hash(1), hash(2), hash(3)]);
+
+        // refresh the first hash so the second one becomes the oldest
+        known.insert(hash(1));
+        known.insert(hash(4));
+
+        assert_eq!(known.entries.len(), 3);
+        assert!(known.contains(&hash(1)));
+        assert!(!known.contains(&hash(2)));
+        assert!(known.contains(&hash(3)));
+        assert!(known.contains(&hash(4)));
+
+        // refreshing many times doesn't grow the set
+        for _ in 0..100 {
+            known.insert(hash(4));
+        }
+        assert_eq!(known.entries.len(), 3);
+        assert!(known.order.len() <= 6);
+        known.insert(hash(5));
+        assert!(!known.contains(&hash(3)));
+        assert!(known.contains(&hash(1)));
+    }
+
+    #[test]
+    fn direct_broadcast_reaches_about_sqrt_of_peers() {
+        let peer_count = 100;
+        // hash small numbers so the bits are spread over the whole hash
+        let peers: Vec<H256> = (0..peer_count)
+            .map(|i| keccak(H256::from_low_u64_be(i)))
+            .collect();
+        let txs: Vec<H256> = (0..1000)
+            .map(|i| keccak(H256::from_low_u64_be(i + peer_count)))
+            .collect();
+
+        let total: usize = txs
+            .iter()
+            .map(|tx| {
+                peers
+                    .iter()
+                    .filter(|peer| is_direct_broadcast_peer(*tx, **peer, peer_count as usize))
+                    .count()
+            })
+            .sum();
+        let average = total as f64 / txs.len() as f64;
+        // sqrt(100) = 10
+        assert!((8.0..12.0).contains(&average), "average was {average}");
+    }
+
+    #[test]
+    fn lone_peer_always_gets_full_transactions() {
+        assert!(is_direct_broadcast_peer(
+            H256::repeat_byte(0xff),
+            H256::zero(),
+            1
+        ));
+    }
+}
@@ -149,6 +149,7 @@ impl RLPxMessage for BatchSealed {
                 blobs,
                 commitments,
                 proofs,
+                ..Default::default()
             },
             commit_tx,
             verify_tx,
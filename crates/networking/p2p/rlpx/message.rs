@@ -2,7 +2,9 @@ use bytes::BufMut;
 use ethrex_rlp::error::{RLPDecodeError, RLPEncodeError};
 use std::fmt::Display;
 
-use super::eth::blocks::{BlockBodies, BlockHeaders, GetBlockBodies, GetBlockHeaders};
+use super::eth::blocks::{
+    BlockBodies, BlockHeaders, GetBlockBodies, GetBlockHeaders, NewBlockHashes, NewBlockMessage,
+};
 use super::eth::receipts::{GetReceipts, Receipts};
 use super::eth::status::StatusMessage;
 use super::eth::transactions::{
@@ -39,11 +41,13 @@ pub(crate) enum Message {
     Status(StatusMessage),
     // eth capability
     // https://github.com/ethereum/devp2p/blob/master/caps/eth.md
+    NewBlockHashes(NewBlockHashes),
     GetBlockHeaders(GetBlockHeaders),
     BlockHeaders(BlockHeaders),
     Transactions(Transactions),
     GetBlockBodies(GetBlockBodies),
     BlockBodies(BlockBodies),
+    NewBlock(NewBlockMessage),
     NewPooledTransactionHashes(NewPooledTransactionHashes),
     GetPooledTransactions(GetPooledTransactions),
     PooledTransactions(PooledTransactions),
@@ -74,11 +78,13 @@ impl Message {
 
             // eth capability
             Message::Status(_) => ETH_CAPABILITY_OFFSET + StatusMessage::CODE,
+            Message::NewBlockHashes(_) => ETH_CAPABILITY_OFFSET + NewBlockHashes::CODE,
             Message::Transactions(_) => ETH_CAPABILITY_OFFSET + Transactions::CODE,
             Message::GetBlockHeaders(_) => ETH_CAPABILITY_OFFSET + GetBlockHeaders::CODE,
             Message::BlockHeaders(_) => ETH_CAPABILITY_OFFSET + BlockHeaders::CODE,
             Message::GetBlockBodies(_) => ETH_CAPABILITY_OFFSET + GetBlockBodies::CODE,
             Message::BlockBodies(_) => ETH_CAPABILITY_OFFSET + BlockBodies::CODE,
+            Message::NewBlock(_) => ETH_CAPABILITY_OFFSET + NewBlockMessage::CODE,
             Message::NewPooledTransactionHashes(_) => {
                 ETH_CAPABILITY_OFFSET + NewPooledTransactionHashes::CODE
             }
@@ -125,6 +131,7 @@ impl Message {
             // eth capability
             match msg_id - ETH_CAPABILITY_OFFSET {
                 StatusMessage::CODE => Ok(Message::Status(StatusMessage::decode(data)?)),
+                NewBlockHashes::CODE => Ok(Message::NewBlockHashes(NewBlockHashes::decode(data)?)),
                 Transactions::CODE => Ok(Message::Transactions(Transactions::decode(data)?)),
                 GetBlockHeaders::CODE => {
                     Ok(Message::GetBlockHeaders(GetBlockHeaders::decode(data)?))
@@ -132,6 +139,7 @@ impl Message {
                 BlockHeaders::CODE => Ok(Message::BlockHeaders(BlockHeaders::decode(data)?)),
                 GetBlockBodies::CODE => Ok(Message::GetBlockBodies(GetBlockBodies::decode(data)?)),
                 BlockBodies::CODE => Ok(Message::BlockBodies(BlockBodies::decode(data)?)),
+                NewBlockMessage::CODE => Ok(Message::NewBlock(NewBlockMessage::decode(data)?)),
                 NewPooledTransactionHashes::CODE => Ok(Message::NewPooledTransactionHashes(
                     NewPooledTransactionHashes::decode(data)?,
                 )),
@@ -189,11 +197,13 @@ impl Message {
             Message::Ping(msg) => msg.encode(buf),
             Message::Pong(msg) => msg.encode(buf),
             Message::Status(msg) => msg.encode(buf),
+            Message::NewBlockHashes(msg) => msg.encode(buf),
             Message::Transactions(msg) => msg.encode(buf),
             Message::GetBlockHeaders(msg) => msg.encode(buf),
             Message::BlockHeaders(msg) => msg.encode(buf),
             Message::GetBlockBodies(msg) => msg.encode(buf),
             Message::BlockBodies(msg) => msg.encode(buf),
+            Message::NewBlock(msg) => msg.encode(buf),
             Message::NewPooledTransactionHashes(msg) => msg.encode(buf),
             Message::GetPooledTransactions(msg) => msg.encode(buf),
             Message::PooledTransactions(msg) => msg.encode(buf),
@@ -224,6 +234,8 @@ impl Display for Message {
             Message::Ping(_) => "p2p:Ping".fmt(f),
             Message::Pong(_) => "p2p:Pong".fmt(f),
             Message::Status(_) => "eth:Status".fmt(f),
+            Message::NewBlockHashes(_) => "eth:NewBlockHashes".fmt(f),
+            Message::NewBlock(_) => "eth:NewBlock".fmt(f),
             Message::GetBlockHeaders(_) => "eth:getBlockHeaders".fmt(f),
             Message::BlockHeaders(_) => "eth:BlockHeaders".fmt(f),
             Message::BlockBodies(_) => "eth:BlockBodies".fmt(f),
@@ -1,12 +1,15 @@
 mod bytecode_fetcher;
 mod fetcher_queue;
+mod progress;
 mod state_healing;
 mod state_sync;
 mod storage_fetcher;
 mod storage_healing;
 mod trie_rebuild;
 
-use crate::peer_handler::{BlockRequestOrder, HASH_MAX, MAX_BLOCK_BODIES_TO_REQUEST, PeerHandler};
+use crate::peer_handler::{
+    BlockRequestOrder, HASH_MAX, MAX_BLOCK_BODIES_TO_REQUEST, PeerHandler, REQUEST_RETRY_ATTEMPTS,
+};
 use bytecode_fetcher::bytecode_fetcher;
 use ethrex_blockchain::{BatchBlockProcessingFailure, Blockchain, error::ChainError};
 use ethrex_common::{
@@ -16,6 +19,8 @@ use ethrex_common::{
 use ethrex_rlp::error::RLPDecodeError;
 use ethrex_storage::{EngineType, STATE_TRIE_SEGMENTS, Store, error::StoreError};
 use ethrex_trie::{Nibbles, Node, TrieDB, TrieError};
+pub(crate) use progress::SYNC_PROGRESS;
+pub use progress::SyncStatus;
 use state_healing::heal_state_trie;
 use state_sync::state_sync;
 use std::{
@@ -142,9 +147,10 @@ impl Syncer {
     /// If the sync fails, no error will be returned but a warning will be emitted
     /// [WARNING] Sync is done optimistically, so headers and bodies may be stored even if their data has not been fully synced if the sync is aborted halfway
     /// [WARNING] Sync is currenlty simplified and will not download bodies + receipts previous to the pivot during snap sync
-    pub async fn start_sync(&mut self, sync_head: H256, store: Store) {
+    /// If `checkpoint` is true the sync head is trusted, see `fetch_checkpoint_headers`
+    pub async fn start_sync(&mut self, sync_head: H256, store: Store, checkpoint: bool) {
         let start_time = Instant::now();
-        match self.sync_cycle(sync_head, store).await {
+        match self.sync_cycle(sync_head, store, checkpoint).await {
             Ok(()) => {
                 info!(
                     "Sync cycle finished, time elapsed: {} secs",
@@ -159,7 +165,12 @@ impl Syncer {
     }
 
     /// Performs the sync cycle described in `start_sync`, returns an error if the sync fails at any given step and aborts all active processes
-    async fn sync_cycle(&mut self, sync_head: H256, store: Store) -> Result<(), SyncError> {
+    async fn sync_cycle(
+        &mut self,
+        sync_head: H256,
+        store: Store,
+        checkpoint: bool,
+    ) -> Result<(), SyncError> {
         // Take picture of the current sync mode, we will update the original value when we need to
         let mut sync_mode = if self.snap_enabled.load(Ordering::Relaxed) {
             SyncMode::Snap
@@ -174,6 +185,7 @@ impl Syncer {
         // This applies only to snap sync—full sync always starts fetching headers
         // from the canonical block, which updates as new block headers are fetched.
         let mut current_head = block_sync_state.get_current_head().await?;
+        SYNC_PROGRESS.start_cycle(store.get_latest_block_number().await?);
         info!(
             "Syncing from current head {:?} to sync_head {:?}",
             current_head, sync_head
@@ -182,99 +194,137 @@ impl Syncer {
             Ok(res) => res,
             Err(e) => return Err(e.into()),
         };
+        if let Some(block) = &pending_block {
+            SYNC_PROGRESS.update_highest_block(block.header.number);
+        }
 
-        loop {
-            debug!("Requesting Block Headers from {current_head}");
-
-            let Some(mut block_headers) = self
-                .peers
-                .request_block_headers(current_head, BlockRequestOrder::OldToNew)
-                .await
-            else {
-                warn!("Sync failed to find target block header, aborting");
+        if checkpoint {
+            let Some(chunks) = self.fetch_checkpoint_headers(sync_head, &store).await? else {
                 return Ok(());
             };
+            let header_count: usize = chunks.iter().map(|chunk| chunk.len).sum();
+            if sync_mode == SyncMode::Snap && header_count < MIN_FULL_BLOCKS {
+                // Too few blocks for a snap sync, switching to full sync
+                debug!(
+                    "Sync head is less than {MIN_FULL_BLOCKS} blocks away, switching to FullSync"
+                );
+                sync_mode = SyncMode::Full;
+                self.snap_enabled.store(false, Ordering::Relaxed);
+                block_sync_state = block_sync_state.into_fullsync().await?;
+            }
+            // The headers are already verified and stored, process them as if they came from peers
+            let chunk_count = chunks.len();
+            for (i, chunk) in chunks.iter().enumerate() {
+                let block_headers = chunk.read_headers(&store)?;
+                block_sync_state
+                    .process_incoming_headers(
+                        block_headers,
+                        i + 1 == chunk_count,
+                        self.blockchain.clone(),
+                        self.peers.clone(),
+                        self.cancel_token.clone(),
+                    )
+                    .await?;
+            }
+        } else {
+            loop {
+                debug!("Requesting Block Headers from {current_head}");
+
+                let Some(mut block_headers) = self
+                    .peers
+                    .request_block_headers(current_head, BlockRequestOrder::OldToNew)
+                    .await
+                else {
+                    warn!("Sync failed to find target block header, aborting");
+                    return Ok(());
+                };
 
-            let (first_block_hash, first_block_number, first_block_parent_hash) =
-                match block_headers.first() {
-                    Some(header) => (header.hash(), header.number, header.parent_hash),
+                let (first_block_hash, first_block_number, first_block_parent_hash) =
+                    match block_headers.first() {
+                        Some(header) => (header.hash(), header.number, header.parent_hash),
+                        None => continue,
+                    };
+                let (last_block_hash, last_block_number) = match block_headers.last() {
+                    Some(header) => (header.hash(), header.number),
                     None => continue,
                 };
-            let (last_block_hash, last_block_number) = match block_headers.last() {
-                Some(header) => (header.hash(), header.number),
-                None => continue,
-            };
-            // TODO(#2126): This is just a temporary solution to avoid a bug where the sync would get stuck
-            // on a loop when the target head is not found, i.e. on a reorg with a side-chain.
-            if first_block_hash == last_block_hash
-                && first_block_hash == current_head
-                && current_head != sync_head
-            {
-                // There is no path to the sync head this goes back until it find a common ancerstor
-                warn!("Sync failed to find target block header, going back to the previous parent");
-                current_head = first_block_parent_hash;
-                continue;
-            }
+                // TODO(#2126): This is just a temporary solution to avoid a bug where the sync would get stuck
+                // on a loop when the target head is not found, i.e. on a reorg with a side-chain.
+                if first_block_hash == last_block_hash
+                    && first_block_hash == current_head
+                    && current_head != sync_head
+                {
+                    // There is no path to the sync head this goes back until it find a common ancerstor
+                    warn!(
+                        "Sync failed to find target block header, going back to the previous parent"
+                    );
+                    current_head = first_block_parent_hash;
+                    continue;
+                }
 
-            debug!(
-                "Received {} block headers| First Number: {} Last Number: {}",
-                block_headers.len(),
-                first_block_number,
-                last_block_number
-            );
+                SYNC_PROGRESS.update_highest_block(last_block_number);
+                debug!(
+                    "Received {} block headers| First Number: {} Last Number: {}",
+                    block_headers.len(),
+                    first_block_number,
+                    last_block_number
+                );
 
-            // If we have a pending block from new_payload request
-            // attach it to the end if it matches the parent_hash of the latest received header
-            if let Some(ref block) = pending_block {
-                if block.header.parent_hash == last_block_hash {
-                    block_headers.push(block.header.clone());
+                // If we have a pending block from new_payload request
+                // attach it to the end if it matches the parent_hash of the latest received header
+                if let Some(ref block) = pending_block {
+                    if block.header.parent_hash == last_block_hash {
+                        block_headers.push(block.header.clone());
+                    }
                 }
-            }
 
-            // Filter out everything after the sync_head
-            let mut sync_head_found = false;
-            if let Some(index) = block_headers
-                .iter()
-                .position(|header| header.hash() == sync_head)
-            {
-                sync_head_found = true;
-                block_headers.drain(index + 1..);
-            }
+                // Filter out everything after the sync_head
+                let mut sync_head_found = false;
+                if let Some(index) = block_headers
+                    .iter()
+                    .position(|header| header.hash() == sync_head)
+                {
+                    sync_head_found = true;
+                    block_headers.drain(index + 1..);
+                }
 
-            // Update current fetch head
-            current_head = last_block_hash;
+                // Update current fetch head
+                current_head = last_block_hash;
 
-            // If the sync head is less than 64 blocks away from our current head switch to full-sync
-            if sync_mode == SyncMode::Snap && sync_head_found {
-                let latest_block_number = store.get_latest_block_number().await?;
-                if last_block_number.saturating_sub(latest_block_number) < MIN_FULL_BLOCKS as u64 {
-                    // Too few blocks for a snap sync, switching to full sync
-                    debug!(
-                        "Sync head is less than {MIN_FULL_BLOCKS} blocks away, switching to FullSync"
-                    );
-                    sync_mode = SyncMode::Full;
-                    self.snap_enabled.store(false, Ordering::Relaxed);
-                    block_sync_state = block_sync_state.into_fullsync().await?;
+                // If the sync head is less than 64 blocks away from our current head switch to full-sync
+                if sync_mode == SyncMode::Snap && sync_head_found {
+                    let latest_block_number = store.get_latest_block_number().await?;
+                    if last_block_number.saturating_sub(latest_block_number)
+                        < MIN_FULL_BLOCKS as u64
+                    {
+                        // Too few blocks for a snap sync, switching to full sync
+                        debug!(
+                            "Sync head is less than {MIN_FULL_BLOCKS} blocks away, switching to FullSync"
+                        );
+                        sync_mode = SyncMode::Full;
+                        self.snap_enabled.store(false, Ordering::Relaxed);
+                        block_sync_state = block_sync_state.into_fullsync().await?;
+                    }
                 }
-            }
 
-            // Discard the first header as we already have it
-            block_headers.remove(0);
-            if !block_headers.is_empty() {
-                block_sync_state
-                    .process_incoming_headers(
-                        block_headers,
-                        sync_head_found,
-                        self.blockchain.clone(),
-                        self.peers.clone(),
-                        self.cancel_token.clone(),
-                    )
-                    .await?;
-            }
+                // Discard the first header as we already have it
+                block_headers.remove(0);
+                if !block_headers.is_empty() {
+                    block_sync_state
+                        .process_incoming_headers(
+                            block_headers,
+                            sync_head_found,
+                            self.blockchain.clone(),
+                            self.peers.clone(),
+                            self.cancel_token.clone(),
+                        )
+                        .await?;
+                }
 
-            if sync_head_found {
-                break;
-            };
+                if sync_head_found {
+                    break;
+                };
+            }
         }
         match sync_mode {
             SyncMode::Snap => {
@@ -318,6 +368,7 @@ impl Syncer {
                     store
                         .forkchoice_update(None, block_number, *hash, None, None)
                         .await?;
+                    SYNC_PROGRESS.set_current_block(block_number);
                 }
                 self.last_snap_pivot = pivot_header.number;
                 // Finished a sync cycle without aborting halfway, clear current checkpoint
@@ -328,9 +379,80 @@ impl Syncer {
             // Full sync stores and executes blocks as it asks for the headers
             SyncMode::Full => {}
         }
+        if checkpoint {
+            // There's no consensus client to tell us we're synced, reaching the trusted
+            // head is the closest thing to it
+            self.blockchain.set_synced();
+        }
         Ok(())
     }
 
+    /// Downloads the headers between our canonical chain and the trusted `checkpoint` hash.
+    /// Instead of going forward from our head, headers are requested backwards from the
+    /// checkpoint, so every header is verified against the parent hash of the one after it
+    /// and peers can't make us follow a chain that doesn't lead to the checkpoint.
+    /// Headers are stored as they arrive and only the chunks they were stored in are kept in
+    /// memory, returns the chunks from oldest to newest, or None if the download was aborted.
+    /// Requests are retried until peers answer, only headers that don't lead to the checkpoint
+    /// or a cancellation abort the download
+    async fn fetch_checkpoint_headers(
+        &self,
+        checkpoint: H256,
+        store: &Store,
+    ) -> Result<Option<Vec<CheckpointChunk>>, SyncError> {
+        let mut chunks = Vec::new();
+        let mut header_count = 0;
+        let mut next_hash = checkpoint;
+        let mut failed_attempts = 0;
+        loop {
+            if self.cancel_token.is_cancelled() {
+                return Ok(None);
+            }
+            debug!("Requesting Block Headers back from {next_hash}");
+            let Some(block_headers) = self
+                .peers
+                .request_block_headers(next_hash, BlockRequestOrder::NewToOld)
+                .await
+            else {
+                // The sync may start along with the node, before it has any peers to ask
+                info!("No peers answered for checkpoint block headers, retrying");
+                continue;
+            };
+            let Some((new_headers, canonical_found)) =
+                verify_checkpoint_headers(store, block_headers, next_hash).await?
+            else {
+                failed_attempts += 1;
+                if failed_attempts >= REQUEST_RETRY_ATTEMPTS {
+                    warn!("Peers returned headers not leading to the checkpoint, aborting");
+                    return Ok(None);
+                }
+                continue;
+            };
+            failed_attempts = 0;
+            if let (Some(newest), Some(oldest)) = (new_headers.first(), new_headers.last()) {
+                if chunks.is_empty() {
+                    SYNC_PROGRESS.update_highest_block(newest.number);
+                }
+                next_hash = oldest.parent_hash;
+                chunks.push(CheckpointChunk {
+                    head: newest.hash(),
+                    len: new_headers.len(),
+                });
+            }
+            header_count += new_headers.len();
+            debug!(
+                "Received {} checkpoint block headers, {header_count} in total",
+                new_headers.len(),
+            );
+            store.add_block_headers(new_headers).await?;
+            if canonical_found {
+                break;
+            }
+        }
+        chunks.reverse();
+        Ok(Some(chunks))
+    }
+
     /// Executes the given blocks and stores them
     /// If sync_head_found is true, they will be executed one by one
     /// If sync_head_found is false, they will be executed in a single batch
@@ -413,6 +535,57 @@ async fn store_receipts(
     Ok(())
 }
 
+/// Checks that headers requested backwards from `next_hash` start at it and are chained to
+/// each other, and returns the ones before our canonical chain is reached along with whether
+/// it was reached. Returns None if the headers don't lead to `next_hash`
+async fn verify_checkpoint_headers(
+    store: &Store,
+    block_headers: Vec<BlockHeader>,
+    next_hash: H256,
+) -> Result<Option<(Vec<BlockHeader>, bool)>, SyncError> {
+    if block_headers.is_empty() {
+        return Ok(None);
+    }
+    let mut expected_hash = next_hash;
+    let mut new_headers = Vec::with_capacity(block_headers.len());
+    for header in block_headers {
+        let block_hash = header.hash();
+        if block_hash != expected_hash {
+            return Ok(None);
+        }
+        if store.get_canonical_block_hash(header.number).await? == Some(block_hash) {
+            return Ok(Some((new_headers, true)));
+        }
+        expected_hash = header.parent_hash;
+        new_headers.push(header);
+    }
+    Ok(Some((new_headers, false)))
+}
+
+/// A chunk of at most `BLOCK_HEADER_LIMIT` checkpoint headers that were verified and stored,
+/// identified by its newest header
+struct CheckpointChunk {
+    head: H256,
+    len: usize,
+}
+
+impl CheckpointChunk {
+    /// Reads the chunk's headers back from the store, from oldest to newest
+    fn read_headers(&self, store: &Store) -> Result<Vec<BlockHeader>, SyncError> {
+        let mut headers = Vec::with_capacity(self.len);
+        let mut block_hash = self.head;
+        for _ in 0..self.len {
+            let header = store
+                .get_block_header_by_hash(block_hash)?
+                .ok_or(SyncError::CorruptDB)?;
+            block_hash = header.parent_hash;
+            headers.push(header);
+        }
+        headers.reverse();
+        Ok(headers)
+    }
+}
+
 /// Persisted State during the Block Sync phase
 enum BlockSyncState {
     Full(FullBlockSyncState),
@@ -599,6 +772,7 @@ impl FullBlockSyncState {
                     None,
                 )
                 .await?;
+            SYNC_PROGRESS.set_current_block(last_block_number);
 
             let execution_time: f64 = execution_start.elapsed().as_millis() as f64 / 1000.0;
             let blocks_per_second = blocks_len as f64 / execution_time;
@@ -847,3 +1021,163 @@ impl<T> From<SendError<T>> for SyncError {
         Self::Send(value.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(number: u64, parent_hash: H256, fork: u8) -> BlockHeader {
+        BlockHeader {
+            number,
+            parent_hash,
+            extra_data: vec![fork].into(),
+            ..Default::default()
+        }
+    }
+
+    /// Headers chained to `parent` up to block `last`, from oldest to newest
+    fn chain(parent: &BlockHeader, last: u64, fork: u8) -> Vec<BlockHeader> {
+        let mut headers: Vec<BlockHeader> = Vec::new();
+        for number in parent.number + 1..=last {
+            let parent_hash = headers.last().unwrap_or(parent).hash();
+            headers.push(header(number, parent_hash, fork));
+        }
+        headers
+    }
+
+    fn hashes(headers: &[BlockHeader]) -> Vec<H256> {
+        headers.iter().map(BlockHeader::hash).collect()
+    }
+
+    /// Store whose canonical chain goes from genesis to block 2
+    async fn store_with_canonical_chain() -> (Store, Vec<BlockHeader>) {
+        let store = Store::new("", EngineType::InMemory).unwrap();
+        let genesis = header(0, H256::zero(), 0);
+        let mut canonical = vec![genesis.clone()];
+        canonical.extend(chain(&genesis, 2, 0));
+        store.add_block_headers(canonical.clone()).await.unwrap();
+        let head = canonical.last().unwrap().hash();
+        let numbers_and_hashes = canonical.iter().map(|h| (h.number, h.hash())).collect();
+        store
+            .forkchoice_update(Some(numbers_and_hashes), 2, head, None, None)
+            .await
+            .unwrap();
+        (store, canonical)
+    }
+
+    #[tokio::test]
+    async fn checkpoint_headers_stop_at_the_canonical_chain() {
+        let (store, canonical) = store_with_canonical_chain().await;
+        let mut new_to_old = canonical.clone();
+        new_to_old.extend(chain(&canonical[2], 5, 0));
+        new_to_old.reverse();
+        let checkpoint = new_to_old[0].hash();
+
+        let (new_headers, canonical_found) =
+            verify_checkpoint_headers(&store, new_to_old.clone(), checkpoint)
+                .await
+                .unwrap()
+                .unwrap();
+
+        assert!(canonical_found);
+        assert_eq!(hashes(&new_headers), hashes(&new_to_old[..3]));
+        // Headers that don't reach the canonical chain yet are all returned
+        let (new_headers, canonical_found) =
+            verify_checkpoint_headers(&store, new_to_old[..2].to_vec(), checkpoint)
+                .await
+                .unwrap()
+                .unwrap();
+        assert!(!canonical_found);
+        assert_eq!(hashes(&new_headers), hashes(&new_to_old[..2]));
+    }
+
+    #[tokio::test]
+    async fn checkpoint_headers_must_lead_to_the_checkpoint() {
+        let (store, canonical) = store_with_canonical_chain().await;
+        let mut new_to_old = chain(&canonical[2], 5, 0);
+        new_to_old.reverse();
+        let mut fork = chain(&canonical[2], 5, 1);
+        fork.reverse();
+        let checkpoint = new_to_old[0].hash();
+
+        // Headers of another chain
+        assert!(
+            verify_checkpoint_headers(&store, fork.clone(), checkpoint)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        // Headers starting at the checkpoint but switching to another chain
+        let mut switched = new_to_old.clone();
+        switched[1] = fork[1].clone();
+        assert!(
+            verify_checkpoint_headers(&store, switched, checkpoint)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            verify_checkpoint_headers(&store, Vec::new(), checkpoint)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn checkpoint_chunks_are_read_back_from_oldest_to_newest() {
+        let (store, canonical) = store_with_canonical_chain().await;
+        let headers = chain(&canonical[2], 6, 0);
+        store.add_block_headers(headers.clone()).await.unwrap();
+
+        let newer_chunk = CheckpointChunk {
+            head: headers[3].hash(),
+            len: 2,
+        };
+        let older_chunk = CheckpointChunk {
+            head: headers[1].hash(),
+            len: 2,
+        };
+
+        assert_eq!(
+            hashes(&older_chunk.read_headers(&store).unwrap()),
+            hashes(&headers[..2])
+        );
+        assert_eq!(
+            hashes(&newer_chunk.read_headers(&store).unwrap()),
+            hashes(&headers[2..])
+        );
+        // Chunks reaching headers that weren't stored mean the store is corrupt
+        let too_long = CheckpointChunk {
+            head: headers[3].hash(),
+            len: 8,
+        };
+        assert!(matches!(
+            too_long.read_headers(&store),
+            Err(SyncError::CorruptDB)
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn checkpoint_headers_are_requested_until_peers_answer() {
+        let (store, canonical) = store_with_canonical_chain().await;
+        let checkpoint = chain(&canonical[2], 3, 0)[0].hash();
+        let cancel_token = CancellationToken::new();
+        // A node that just started and has no peers yet
+        let syncer = Syncer::new(
+            PeerHandler::dummy(),
+            Arc::new(AtomicBool::new(false)),
+            cancel_token.clone(),
+            Arc::new(Blockchain::default_with_store(store.clone())),
+        );
+        let fetch =
+            tokio::spawn(async move { syncer.fetch_checkpoint_headers(checkpoint, &store).await });
+
+        // Each request waits for peers for 30 seconds, the download outlives many of them
+        tokio::time::sleep(Duration::from_secs(10 * 60)).await;
+        assert!(!fetch.is_finished());
+
+        cancel_token.cancel();
+        assert!(fetch.await.unwrap().unwrap().is_none());
+    }
+}
@@ -11,7 +11,7 @@ use tracing::debug;
 
 use crate::peer_handler::PeerHandler;
 
-use super::{BYTECODE_BATCH_SIZE, SyncError, fetcher_queue::run_queue};
+use super::{BYTECODE_BATCH_SIZE, SYNC_PROGRESS, SyncError, fetcher_queue::run_queue};
 
 /// Waits for incoming code hashes from the receiver channel endpoint, queues them, and fetches and stores their bytecodes in batches
 pub(crate) async fn bytecode_fetcher(
@@ -46,6 +46,7 @@ async fn fetch_bytecode_batch(
 ) -> Result<Vec<H256>, SyncError> {
     if let Some(bytecodes) = peers.request_bytecodes(batch.clone()).await {
         debug!("Received {} bytecodes", bytecodes.len());
+        SYNC_PROGRESS.add_bytecodes(bytecodes.len());
         // Store the bytecodes
         for code in bytecodes.into_iter() {
             store.add_account_code(batch.remove(0), code).await?;
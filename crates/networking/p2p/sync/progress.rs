@@ -0,0 +1,169 @@
+//! Progress of the sync process, reported by `eth_syncing` and exported as metrics
+//! There is only one sync process per node, so the progress is kept in a process-wide
+//! static that is updated by the syncer and its fetchers as they advance
+
+use std::sync::{
+    LazyLock,
+    atomic::{AtomicU64, Ordering},
+};
+
+use ethrex_metrics::metrics;
+#[cfg(feature = "metrics")]
+use ethrex_metrics::metrics_sync::METRICS_SYNC;
+
+pub(crate) static SYNC_PROGRESS: LazyLock<SyncProgress> = LazyLock::new(SyncProgress::default);
+
+/// Snapshot of the sync progress
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncStatus {
+    /// Latest block we had when the current sync cycle started
+    pub starting_block: u64,
+    /// Latest block the node has fully processed
+    pub current_block: u64,
+    /// Highest block known to the sync, that of the latest header downloaded
+    pub highest_block: u64,
+    /// Accounts downloaded by snap sync
+    pub synced_accounts: u64,
+    /// Storage slots downloaded by snap sync
+    pub synced_storage_slots: u64,
+    /// Bytecodes downloaded by snap sync
+    pub synced_bytecodes: u64,
+    /// State and storage trie nodes downloaded during snap sync healing
+    pub healed_trie_nodes: u64,
+    /// State and storage trie paths still queued for healing
+    pub healing_pending: u64,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct SyncProgress {
+    starting_block: AtomicU64,
+    current_block: AtomicU64,
+    highest_block: AtomicU64,
+    synced_accounts: AtomicU64,
+    synced_storage_slots: AtomicU64,
+    synced_bytecodes: AtomicU64,
+    healed_trie_nodes: AtomicU64,
+    state_healing_pending: AtomicU64,
+    storage_healing_pending: AtomicU64,
+}
+
+impl SyncProgress {
+    /// Resets the block progress at the start of a sync cycle
+    /// Snap counters are kept as a snap sync may span several cycles
+    pub fn start_cycle(&self, starting_block: u64) {
+        self.starting_block.store(starting_block, Ordering::Relaxed);
+        self.current_block.store(starting_block, Ordering::Relaxed);
+        self.highest_block.store(starting_block, Ordering::Relaxed);
+        metrics!(
+            METRICS_SYNC.set_starting_block(starting_block);
+            METRICS_SYNC.set_current_block(starting_block);
+            METRICS_SYNC.set_highest_block(starting_block);
+        );
+    }
+
+    pub fn set_current_block(&self, block_number: u64) {
+        self.current_block.store(block_number, Ordering::Relaxed);
+        metrics!(METRICS_SYNC.set_current_block(block_number));
+    }
+
+    /// Raises the highest known block, lower values are ignored
+    pub fn update_highest_block(&self, block_number: u64) {
+        self.highest_block
+            .fetch_max(block_number, Ordering::Relaxed);
+        metrics!(METRICS_SYNC.set_highest_block(self.highest_block.load(Ordering::Relaxed)));
+    }
+
+    pub fn add_accounts(&self, count: usize) {
+        self.synced_accounts
+            .fetch_add(count as u64, Ordering::Relaxed);
+        metrics!(METRICS_SYNC.set_synced_accounts(self.synced_accounts.load(Ordering::Relaxed)));
+    }
+
+    pub fn add_storage_slots(&self, count: usize) {
+        self.synced_storage_slots
+            .fetch_add(count as u64, Ordering::Relaxed);
+        metrics!(
+            METRICS_SYNC
+                .set_synced_storage_slots(self.synced_storage_slots.load(Ordering::Relaxed))
+        );
+    }
+
+    pub fn add_bytecodes(&self, count: usize) {
+        self.synced_bytecodes
+            .fetch_add(count as u64, Ordering::Relaxed);
+        metrics!(METRICS_SYNC.set_synced_bytecodes(self.synced_bytecodes.load(Ordering::Relaxed)));
+    }
+
+    pub fn add_healed_nodes(&self, count: usize) {
+        self.healed_trie_nodes
+            .fetch_add(count as u64, Ordering::Relaxed);
+        metrics!(
+            METRICS_SYNC.set_healed_trie_nodes(self.healed_trie_nodes.load(Ordering::Relaxed))
+        );
+    }
+
+    pub fn set_state_healing_pending(&self, pending: usize) {
+        self.state_healing_pending
+            .store(pending as u64, Ordering::Relaxed);
+        metrics!(METRICS_SYNC.set_healing_pending(self.healing_pending()));
+    }
+
+    pub fn set_storage_healing_pending(&self, pending: usize) {
+        self.storage_healing_pending
+            .store(pending as u64, Ordering::Relaxed);
+        metrics!(METRICS_SYNC.set_healing_pending(self.healing_pending()));
+    }
+
+    fn healing_pending(&self) -> u64 {
+        self.state_healing_pending.load(Ordering::Relaxed)
+            + self.storage_healing_pending.load(Ordering::Relaxed)
+    }
+
+    pub fn status(&self) -> SyncStatus {
+        SyncStatus {
+            starting_block: self.starting_block.load(Ordering::Relaxed),
+            current_block: self.current_block.load(Ordering::Relaxed),
+            highest_block: self.highest_block.load(Ordering::Relaxed),
+            synced_accounts: self.synced_accounts.load(Ordering::Relaxed),
+            synced_storage_slots: self.synced_storage_slots.load(Ordering::Relaxed),
+            synced_bytecodes: self.synced_bytecodes.load(Ordering::Relaxed),
+            healed_trie_nodes: self.healed_trie_nodes.load(Ordering::Relaxed),
+            healing_pending: self.healing_pending(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sync_progress_status() {
+        let progress = SyncProgress::default();
+        progress.add_accounts(10);
+        progress.start_cycle(100);
+        progress.update_highest_block(200);
+        progress.update_highest_block(150);
+        progress.set_current_block(120);
+        progress.add_accounts(5);
+        progress.add_storage_slots(7);
+        progress.add_bytecodes(2);
+        progress.add_healed_nodes(3);
+        progress.set_state_healing_pending(4);
+        progress.set_storage_healing_pending(6);
+
+        assert_eq!(
+            progress.status(),
+            SyncStatus {
+                starting_block: 100,
+                current_block: 120,
+                highest_block: 200,
+                synced_accounts: 15,
+                synced_storage_slots: 7,
+                synced_bytecodes: 2,
+                healed_trie_nodes: 3,
+                healing_pending: 10,
+            }
+        );
+    }
+}
@@ -21,7 +21,7 @@ use crate::{
     peer_handler::PeerHandler,
     sync::{
         MAX_CHANNEL_MESSAGES, MAX_PARALLEL_FETCHES, NODE_BATCH_SIZE,
-        SHOW_PROGRESS_INTERVAL_DURATION, bytecode_fetcher, node_missing_children,
+        SHOW_PROGRESS_INTERVAL_DURATION, SYNC_PROGRESS, bytecode_fetcher, node_missing_children,
     },
 };
 
@@ -46,6 +46,7 @@ pub(crate) async fn heal_state_trie(
     paths.push(Nibbles::default());
     let mut last_update = Instant::now();
     while !paths.is_empty() {
+        SYNC_PROGRESS.set_state_healing_pending(paths.len());
         if last_update.elapsed() >= SHOW_PROGRESS_INTERVAL_DURATION {
             last_update = Instant::now();
             info!("State Healing in Progress, pending paths: {}", paths.len());
@@ -79,6 +80,7 @@ pub(crate) async fn heal_state_trie(
         }
     }
     debug!("State Healing stopped, signaling storage healer");
+    SYNC_PROGRESS.set_state_healing_pending(paths.len());
     // Save paths for the next cycle
     if !paths.is_empty() {
         debug!("Caching {} paths for the next cycle", paths.len());
@@ -105,6 +107,7 @@ async fn heal_state_batch(
         .await
     {
         debug!("Received {} state nodes", nodes.len());
+        SYNC_PROGRESS.add_healed_nodes(nodes.len());
         let mut hashed_addresses = vec![];
         let mut code_hashes = vec![];
         // For each fetched node:
@@ -27,7 +27,7 @@ use crate::{
     },
 };
 
-use super::{SHOW_PROGRESS_INTERVAL_DURATION, SyncError};
+use super::{SHOW_PROGRESS_INTERVAL_DURATION, SYNC_PROGRESS, SyncError};
 
 /// Downloads the leaf values of a Block's state trie by requesting snap state from peers
 /// Also downloads the storage tries & bytecodes for each downloaded account
@@ -151,6 +151,7 @@ async fn state_sync_segment(
                 "[Segment {segment_number}]: Received {} account ranges",
                 accounts.len()
             );
+            SYNC_PROGRESS.add_accounts(accounts.len());
             // Update starting hash for next batch
             let last_account_hash = account_hashes
                 .last()
@@ -16,7 +16,7 @@ use tracing::{debug, error};
 use crate::{
     peer_handler::PeerHandler,
     sync::{
-        MAX_CHANNEL_MESSAGES, STORAGE_BATCH_SIZE, fetcher_queue::run_queue,
+        MAX_CHANNEL_MESSAGES, STORAGE_BATCH_SIZE, SYNC_PROGRESS, fetcher_queue::run_queue,
         trie_rebuild::REBUILDER_INCOMPLETE_STORAGE_ROOT,
     },
 };
@@ -109,6 +109,7 @@ async fn fetch_storage_batch(
         .await
     {
         debug!("Received {} storage ranges", keys.len(),);
+        SYNC_PROGRESS.add_storage_slots(keys.iter().map(Vec::len).sum());
         // Handle incomplete ranges
         if incomplete {
             // An incomplete range cannot be empty
@@ -233,6 +234,7 @@ async fn fetch_large_storage(
         )
         .await
     {
+        SYNC_PROGRESS.add_storage_slots(keys.len());
         // Update next batch's start
         let last_key = keys
             .last()
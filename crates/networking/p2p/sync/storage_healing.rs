@@ -20,7 +20,10 @@ use tokio::time::Instant;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, info};
 
-use crate::{peer_handler::PeerHandler, sync::node_missing_children};
+use crate::{
+    peer_handler::PeerHandler,
+    sync::{SYNC_PROGRESS, node_missing_children},
+};
 
 /// Minumum amount of storages to keep in the storage healer queue
 /// More paths will be read from the Store if the amount goes below this value
@@ -62,6 +65,7 @@ pub(crate) async fn storage_healer(
                     .into_iter(),
             );
         }
+        SYNC_PROGRESS.set_storage_healing_pending(pending_paths.values().map(Vec::len).sum());
         // If we have no more pending paths even after reading from the store, and state healing has finished, cut the loop
         if pending_paths.is_empty() && state_healing_ended.load(Ordering::Relaxed) {
             break;
@@ -117,6 +121,7 @@ async fn heal_storage_batch(
         .await
     {
         debug!("Received {} storage nodes", nodes.len());
+        SYNC_PROGRESS.add_healed_nodes(nodes.len());
         // Process the nodes for each account path
         for (acc_path, paths) in batch.iter_mut() {
             let trie = store.open_storage_trie(*acc_path, *EMPTY_TRIE_HASH)?;
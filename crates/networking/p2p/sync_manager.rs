@@ -15,17 +15,19 @@ use tracing::{error, info, warn};
 
 use crate::{
     peer_handler::PeerHandler,
-    sync::{SyncMode, Syncer},
+    sync::{SYNC_PROGRESS, SyncMode, SyncStatus, Syncer},
 };
 
 /// Abstraction to interact with the active sync process without disturbing it
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SyncManager {
     /// This is also held by the Syncer and allows tracking it's latest syncmode
     /// It is a READ_ONLY value, as modifications will disrupt the current active sync progress
     snap_enabled: Arc<AtomicBool>,
     syncer: Arc<Mutex<Syncer>>,
     last_fcu_head: Arc<Mutex<H256>>,
+    /// Trusted block hash to sync to when there is no consensus client, see `sync_to_checkpoint`
+    checkpoint: Arc<Mutex<Option<H256>>>,
     store: Store,
 }
 
@@ -48,6 +50,7 @@ impl SyncManager {
             snap_enabled,
             syncer,
             last_fcu_head: Arc::new(Mutex::new(H256::zero())),
+            checkpoint: Arc::new(Mutex::new(None)),
             store: store.clone(),
         };
         // If the node was in the middle of a sync and then re-started we must resume syncing
@@ -69,6 +72,7 @@ impl SyncManager {
             snap_enabled: Arc::new(AtomicBool::new(false)),
             syncer: Arc::new(Mutex::new(Syncer::dummy())),
             last_fcu_head: Arc::new(Mutex::new(H256::zero())),
+            checkpoint: Arc::new(Mutex::new(None)),
             store: Store::new("temp.db", ethrex_storage::EngineType::InMemory)
                 .expect("Failed to start Storage Engine"),
         }
@@ -82,6 +86,19 @@ impl SyncManager {
         }
     }
 
+    /// Syncs to the given trusted block hash without a consensus client driving the sync
+    /// The header chain is verified backwards from the checkpoint, and the node is marked as
+    /// synced once it's reached. A later fcu head takes over as the sync target
+    pub fn sync_to_checkpoint(&self, checkpoint: H256) {
+        if let Ok(mut current_checkpoint) = self.checkpoint.try_lock() {
+            *current_checkpoint = Some(checkpoint);
+        } else {
+            warn!("Failed to set checkpoint for syncing");
+            return;
+        }
+        self.sync_to_head(checkpoint);
+    }
+
     /// Returns the syncer's current syncmode (either snap or full)
     pub fn sync_mode(&self) -> SyncMode {
         if self.snap_enabled.load(Ordering::Relaxed) {
@@ -112,6 +129,7 @@ impl SyncManager {
         let syncer = self.syncer.clone();
         let store = self.store.clone();
         let sync_head = self.last_fcu_head.clone();
+        let checkpoint = self.checkpoint.clone();
 
         tokio::spawn(async move {
             // If we can't get hold of the syncer, then it means that there is an active sync in process
@@ -133,8 +151,13 @@ impl SyncManager {
                     sleep(Duration::from_secs(5)).await;
                     continue;
                 }
+                let is_checkpoint = checkpoint
+                    .try_lock()
+                    .is_ok_and(|checkpoint| *checkpoint == Some(sync_head));
                 // Start the sync cycle
-                syncer.start_sync(sync_head, store.clone()).await;
+                syncer
+                    .start_sync(sync_head, store.clone(), is_checkpoint)
+                    .await;
                 // Continue to the next sync cycle if we have an ongoing snap sync (aka if we still have snap sync checkpoints stored)
                 if store
                     .get_header_download_checkpoint()
@@ -152,4 +175,9 @@ impl SyncManager {
     pub fn get_last_fcu_head(&self) -> Result<H256, tokio::sync::TryLockError> {
         Ok(*self.last_fcu_head.try_lock()?)
     }
+
+    /// Returns the progress of the current (or latest) sync process
+    pub fn status(&self) -> SyncStatus {
+        SYNC_PROGRESS.status()
+    }
 }
@@ -351,6 +351,20 @@ impl NodeRecord {
         Ok(())
     }
 
+    /// Replaces the advertised `ip` pair, bumping the sequence number if it changed.
+    /// Returns whether the record was updated.
+    pub fn set_ip(&mut self, ip: IpAddr, signer: &SecretKey) -> Result<bool, String> {
+        let encoded_ip: Bytes = ip.encode_to_vec().into();
+        match self.pairs.iter_mut().find(|(k, _)| k == "ip") {
+            Some((_, value)) if *value == encoded_ip => return Ok(false),
+            Some((_, value)) => *value = encoded_ip,
+            None => self.pairs.push(("ip".into(), encoded_ip)),
+        }
+
+        self.update_seq(signer)?;
+        Ok(true)
+    }
+
     fn sign_record(&mut self, signer: &SecretKey) -> Result<H512, String> {
         let digest = &self.get_signature_digest();
         let msg = secp256k1::Message::from_digest_slice(digest)
@@ -448,7 +462,10 @@ mod tests {
     };
     use ethrex_common::H512;
     use secp256k1::SecretKey;
-    use std::{net::SocketAddr, str::FromStr};
+    use std::{
+        net::{IpAddr, Ipv4Addr, SocketAddr},
+        str::FromStr,
+    };
 
     #[test]
     fn parse_node_from_enode_string() {
@@ -523,4 +540,35 @@ mod tests {
 
         assert_eq!(record.enr_url().unwrap(), expected_enr_string);
     }
+
+    #[test]
+    fn set_ip_updates_node_record() {
+        let signer = SecretKey::new(&mut rand::rngs::OsRng);
+        let addr = SocketAddr::from_str("127.0.0.1:30303").unwrap();
+        let node = Node::new(
+            addr.ip(),
+            addr.port(),
+            addr.port(),
+            public_key_from_signing_key(&signer),
+        );
+        let mut record = NodeRecord::from_node(&node, 1, &signer).unwrap();
+
+        // setting the same ip is a no-op
+        assert!(!record.set_ip(addr.ip(), &signer).unwrap());
+        assert_eq!(record.seq, 1);
+
+        let external_ip = IpAddr::from_str("203.0.113.7").unwrap();
+        assert!(record.set_ip(external_ip, &signer).unwrap());
+        assert_eq!(record.seq, 2);
+
+        let pairs = record.decode_pairs();
+        assert_eq!(
+            pairs.ip.map(|ip| IpAddr::from(Ipv4Addr::from_bits(ip))),
+            Some(external_ip)
+        );
+        // the record is still valid and can be parsed back into a node
+        let parsed = Node::from_enr_url(&record.enr_url().unwrap()).unwrap();
+        assert_eq!(parsed.ip, external_ip);
+        assert_eq!(parsed.public_key, node.public_key);
+    }
 }
@@ -2,7 +2,10 @@ use ethrex_common::types::ChainConfig;
 use ethrex_storage::Store;
 use serde::Serialize;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr},
+};
 
 use crate::{rpc::NodeData, utils::RpcErr};
 mod peers;
@@ -31,9 +34,16 @@ enum Protocol {
     Eth(ChainConfig),
 }
 
-pub fn node_info(storage: Store, node_data: &NodeData) -> Result<Value, RpcErr> {
-    let enode_url = node_data.local_p2p_node.enode_url();
-    let enr_url = match node_data.local_node_record.enr_url() {
+pub async fn node_info(storage: Store, node_data: &NodeData) -> Result<Value, RpcErr> {
+    let node_record = node_data.local_node_record.lock().await.clone();
+    // The record holds the external ip, which might have been updated by the p2p layer
+    // since the node was started (i.e. if we are behind a NAT)
+    let mut local_node = node_data.local_p2p_node.clone();
+    if let Some(ip) = node_record.decode_pairs().ip {
+        local_node.ip = IpAddr::from(Ipv4Addr::from_bits(ip));
+    }
+    let enode_url = local_node.enode_url();
+    let enr_url = match node_record.enr_url() {
         Ok(enr) => enr,
         Err(_) => "".into(),
     };
@@ -47,12 +57,12 @@ pub fn node_info(storage: Store, node_data: &NodeData) -> Result<Value, RpcErr>
     let node_info = NodeInfo {
         enode: enode_url,
         enr: enr_url,
-        id: hex::encode(node_data.local_p2p_node.node_id()),
+        id: hex::encode(local_node.node_id()),
         name: node_data.client_version.clone(),
-        ip: node_data.local_p2p_node.ip.to_string(),
+        ip: local_node.ip.to_string(),
         ports: Ports {
-            discovery: node_data.local_p2p_node.udp_port,
-            listener: node_data.local_p2p_node.tcp_port,
+            discovery: local_node.udp_port,
+            listener: local_node.tcp_port,
         },
         protocols,
     };
@@ -2,7 +2,7 @@ use crate::{
     engine::{
         ExchangeCapabilitiesRequest,
         fork_choice::ForkChoiceUpdatedV3,
-        payload::{GetPayloadV4Request, NewPayloadV4Request},
+        payload::{GetPayloadV4Request, GetPayloadV5Request, NewPayloadV4Request},
     },
     types::{
         fork_choice::{ForkChoiceResponse, ForkChoiceState, PayloadAttributesV3},
@@ -128,6 +128,28 @@ impl EngineClient {
         }
     }
 
+    pub async fn engine_get_payload_v5(
+        &self,
+        payload_id: u64,
+    ) -> Result<ExecutionPayloadResponse, EngineClientError> {
+        let request = GetPayloadV5Request { payload_id }.into();
+
+        match self.send_request(request).await {
+            Ok(RpcResponse::Success(result)) => serde_json::from_value(result.result)
+                .map_err(GetPayloadError::SerdeJSONError)
+                .map_err(EngineClientError::from),
+            Ok(RpcResponse::Error(error_response)) => {
+                let error_message = if let Some(data) = error_response.error.data {
+                    format!("{}: {:?}", error_response.error.message, data)
+                } else {
+                    error_response.error.message.to_string()
+                };
+                Err(GetPayloadError::RPCError(error_message).into())
+            }
+            Err(error) => Err(error),
+        }
+    }
+
     pub async fn engine_new_payload_v4(
         &self,
         execution_payload: ExecutionPayload,
@@ -174,6 +196,7 @@ impl EngineClient {
             "engine_exchangeCapabilities".to_owned(),
             "engine_forkchoiceUpdatedV3".to_owned(),
             "engine_getPayloadV4".to_owned(),
+            "engine_getPayloadV5".to_owned(),
             "engine_newPayloadV4".to_owned(),
         ]
     }
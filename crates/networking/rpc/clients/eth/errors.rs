@@ -9,6 +9,8 @@ pub enum EthClientError {
     ReqwestError(#[from] reqwest::Error),
     #[error("eth_gasPrice request error: {0}")]
     GetGasPriceError(#[from] GetGasPriceError),
+    #[error("eth_blobBaseFee request error: {0}")]
+    GetBlobBaseFeeError(#[from] GetBlobBaseFeeError),
     #[error("eth_estimateGas request error: {0}")]
     EstimateGasError(#[from] EstimateGasError),
     #[error("eth_sendRawTransaction request error: {0}")]
@@ -81,6 +83,14 @@ pub enum GetGasPriceError {
     ParseIntError(#[from] std::num::ParseIntError),
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum GetBlobBaseFeeError {
+    #[error("{0}")]
+    SerdeJSONError(#[from] serde_json::Error),
+    #[error("{0}")]
+    RPCError(String),
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum EstimateGasError {
     #[error("{0}")]
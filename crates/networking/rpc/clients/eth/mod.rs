@@ -15,10 +15,10 @@ use crate::{
 };
 use bytes::Bytes;
 use errors::{
-    EstimateGasError, EthClientError, GetBalanceError, GetBlockByHashError, GetBlockByNumberError,
-    GetBlockNumberError, GetCodeError, GetGasPriceError, GetLogsError, GetMaxPriorityFeeError,
-    GetNonceError, GetRawBlockError, GetTransactionByHashError, GetTransactionReceiptError,
-    SendRawTransactionError,
+    EstimateGasError, EthClientError, GetBalanceError, GetBlobBaseFeeError, GetBlockByHashError,
+    GetBlockByNumberError, GetBlockNumberError, GetCodeError, GetGasPriceError, GetLogsError,
+    GetMaxPriorityFeeError, GetNonceError, GetRawBlockError, GetTransactionByHashError,
+    GetTransactionReceiptError, SendRawTransactionError,
 };
 use ethrex_common::{
     Address, H256, U256,
@@ -425,6 +425,19 @@ impl EthClient {
         }
     }
 
+    pub async fn get_blob_base_fee(&self) -> Result<U256, EthClientError> {
+        let request = RpcRequest::new("eth_blobBaseFee", None);
+
+        match self.send_request(request).await? {
+            RpcResponse::Success(result) => serde_json::from_value(result.result)
+                .map_err(GetBlobBaseFeeError::SerdeJSONError)
+                .map_err(EthClientError::from),
+            RpcResponse::Error(error_response) => {
+                Err(GetBlobBaseFeeError::RPCError(error_response.error.message).into())
+            }
+        }
+    }
+
     pub async fn get_gas_price_with_extra(
         &self,
         bump_percent: u64,
@@ -804,6 +817,30 @@ impl EthClient {
         Self::from_hex_string_to_h256_array(&response)
     }
 
+    /// Returns the L1 timestamp by which the privileged transaction must be processed.
+    pub async fn get_privileged_transaction_deadline(
+        &self,
+        common_bridge_address: Address,
+        tx_hash: H256,
+    ) -> Result<u64, EthClientError> {
+        let mut calldata = keccak(b"privilegedTxDeadline(bytes32)")
+            .as_bytes()
+            .get(..4)
+            .ok_or(EthClientError::Custom("Failed to get selector.".to_owned()))?
+            .to_vec();
+        calldata.extend_from_slice(tx_hash.as_bytes());
+
+        let hex_string = self
+            .call(common_bridge_address, calldata.into(), Overrides::default())
+            .await?;
+
+        from_hex_string_to_u256(&hex_string)?
+            .try_into()
+            .map_err(|_| {
+                EthClientError::Custom("Failed to convert from_hex_string_to_u256()".to_owned())
+            })
+    }
+
     pub fn from_hex_string_to_h256_array(hex_string: &str) -> Result<Vec<H256>, EthClientError> {
         let bytes = hex::decode(hex_string.strip_prefix("0x").unwrap_or(hex_string))
             .map_err(|_| EthClientError::Custom("Invalid hex string".to_owned()))?;
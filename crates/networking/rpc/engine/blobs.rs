@@ -1,7 +1,10 @@
 use ethrex_common::{
     H256,
     serde_utils::{self},
-    types::{Blob, Proof, blobs_bundle::kzg_commitment_to_versioned_hash},
+    types::{
+        BLOB_WRAPPER_VERSION_4844, BLOB_WRAPPER_VERSION_7594, Blob, CELLS_PER_EXT_BLOB, Proof,
+        blobs_bundle::kzg_commitment_to_versioned_hash,
+    },
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -14,6 +17,8 @@ use crate::{
 
 // -> https://github.com/ethereum/execution-apis/blob/d41fdf10fabbb73c4d126fb41809785d830acace/src/engine/cancun.md?plain=1#L186
 const GET_BLOBS_V1_REQUEST_MAX_SIZE: usize = 128;
+// -> https://github.com/ethereum/execution-apis/blob/main/src/engine/osaka.md#engine_getblobsv2
+const GET_BLOBS_V2_REQUEST_MAX_SIZE: usize = 128;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BlobsV1Request {
@@ -67,6 +72,10 @@ impl RpcHandler for BlobsV1Request {
         let mut res: Vec<Option<BlobAndProofV1>> = vec![None; self.blob_versioned_hashes.len()];
 
         for blobs_bundle in context.blockchain.mempool.get_blobs_bundle_pool()? {
+            // Bundles with cell proofs can't be served through this endpoint
+            if blobs_bundle.version != BLOB_WRAPPER_VERSION_4844 {
+                continue;
+            }
             // Go over all blobs bundles from the blobs bundle pool.
             let blobs_in_bundle = blobs_bundle.blobs;
             let commitments_in_bundle = blobs_bundle.commitments;
@@ -95,3 +104,90 @@ impl RpcHandler for BlobsV1Request {
         serde_json::to_value(res).map_err(|error| RpcErr::Internal(error.to_string()))
     }
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlobsV2Request {
+    blob_versioned_hashes: Vec<H256>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobAndProofV2 {
+    #[serde(with = "serde_utils::blob")]
+    pub blob: Blob,
+    #[serde(with = "serde_utils::bytes48::vec")]
+    pub proofs: Vec<Proof>,
+}
+
+impl RpcHandler for BlobsV2Request {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = params
+            .as_ref()
+            .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+        if params.len() != 1 {
+            return Err(RpcErr::BadParams("Expected 1 param".to_owned()));
+        };
+        Ok(BlobsV2Request {
+            blob_versioned_hashes: serde_json::from_value(params[0].clone())?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        info!("Received new engine request: Requested Blobs V2");
+        if self.blob_versioned_hashes.len() > GET_BLOBS_V2_REQUEST_MAX_SIZE {
+            return Err(RpcErr::TooLargeRequest);
+        }
+
+        if let Some(current_block_header) = context
+            .storage
+            .get_block_header(context.storage.get_latest_block_number().await?)?
+        {
+            if !context
+                .storage
+                .get_chain_config()?
+                .is_osaka_activated(current_block_header.timestamp)
+            {
+                return Err(RpcErr::UnsuportedFork(
+                    "getBlobsV2 engine request not supported before Osaka".to_string(),
+                ));
+            }
+        };
+
+        let mut res: Vec<Option<BlobAndProofV2>> = vec![None; self.blob_versioned_hashes.len()];
+
+        for blobs_bundle in context.blockchain.mempool.get_blobs_bundle_pool()? {
+            if blobs_bundle.version != BLOB_WRAPPER_VERSION_7594 {
+                continue;
+            }
+            for (i, (commitment, blob)) in blobs_bundle
+                .commitments
+                .iter()
+                .zip(blobs_bundle.blobs.iter())
+                .enumerate()
+            {
+                let current_versioned_hash = kzg_commitment_to_versioned_hash(commitment);
+                let Some(index) = self
+                    .blob_versioned_hashes
+                    .iter()
+                    .position(|&hash| hash == current_versioned_hash)
+                else {
+                    continue;
+                };
+                let Some(proofs) = blobs_bundle
+                    .proofs
+                    .get(i * CELLS_PER_EXT_BLOB..(i + 1) * CELLS_PER_EXT_BLOB)
+                else {
+                    continue;
+                };
+                res[index] = Some(BlobAndProofV2 {
+                    blob: *blob,
+                    proofs: proofs.to_vec(),
+                });
+            }
+        }
+
+        // Unlike V1, a missing blob makes the whole response null
+        let res: Option<Vec<BlobAndProofV2>> = res.into_iter().collect();
+        serde_json::to_value(res).map_err(|error| RpcErr::Internal(error.to_string()))
+    }
+}
@@ -36,6 +36,17 @@ struct SyncingStatusRpc {
     current_block: u64,
     #[serde(with = "serde_utils::u64::hex_str")]
     highest_block: u64,
+    // Snap sync progress, named as in geth
+    #[serde(with = "serde_utils::u64::hex_str")]
+    synced_accounts: u64,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    synced_storage: u64,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    synced_bytecodes: u64,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    healed_trienodes: u64,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    healing_trienodes: u64,
 }
 
 impl RpcHandler for Syncing {
@@ -48,14 +59,20 @@ impl RpcHandler for Syncing {
         if context.blockchain.is_synced() {
             Ok(Value::Bool(!context.blockchain.is_synced()))
         } else {
+            let status = context.syncer.status();
+            // The sync may not have started a cycle yet, fall back to what the store has
+            let current_block = status
+                .current_block
+                .max(context.storage.get_latest_block_number().await?);
             let syncing_status = SyncingStatusRpc {
-                starting_block: context.storage.get_earliest_block_number().await?,
-                current_block: context.storage.get_latest_block_number().await?,
-                highest_block: context
-                    .syncer
-                    .get_last_fcu_head()
-                    .map_err(|error| RpcErr::Internal(error.to_string()))?
-                    .to_low_u64_be(),
+                starting_block: status.starting_block,
+                current_block,
+                highest_block: status.highest_block.max(current_block),
+                synced_accounts: status.synced_accounts,
+                synced_storage: status.synced_storage_slots,
+                synced_bytecodes: status.synced_bytecodes,
+                healed_trienodes: status.healed_trie_nodes,
+                healing_trienodes: status.healing_pending,
             };
             serde_json::to_value(syncing_status)
                 .map_err(|error| RpcErr::Internal(error.to_string()))
@@ -2,8 +2,8 @@ use ethrex_blockchain::payload::calc_gas_limit;
 use ethrex_common::{
     constants::GAS_PER_BLOB,
     types::{
-        Block, BlockHeader, ELASTICITY_MULTIPLIER, Transaction, calc_excess_blob_gas,
-        calculate_base_fee_per_blob_gas, calculate_base_fee_per_gas,
+        Block, BlockHeader, ELASTICITY_MULTIPLIER, Fork, ForkBlobSchedule, Transaction,
+        calc_excess_blob_gas, calculate_base_fee_per_blob_gas, calculate_base_fee_per_gas,
     },
 };
 use serde::Serialize;
@@ -147,16 +147,13 @@ impl RpcHandler for FeeHistoryRequest {
             blob_gas_used_ratio[idx] = blob_gas_used_r;
 
             if block_number == end_block {
-                let blob_target = config
-                    .get_fork_blob_schedule(header.timestamp)
-                    .map(|schedule| schedule.target)
-                    .unwrap_or_default();
+                let blob_schedule = config.get_fork_blob_schedule(header.timestamp);
 
                 (base_fee_per_gas[idx + 1], base_fee_per_blob_gas[idx + 1]) =
                     project_next_block_base_fee_values(
                         &header,
-                        base_fee_update_fraction,
-                        blob_target,
+                        blob_schedule,
+                        config.get_fork(header.timestamp),
                     );
             }
             if !self.reward_percentiles.is_empty() {
@@ -189,8 +186,8 @@ impl RpcHandler for FeeHistoryRequest {
 // Project base_fee_per_gas and base_fee_per_blob_gas of next block, from provided block
 fn project_next_block_base_fee_values(
     header: &BlockHeader,
-    base_fee_update_fraction: u64,
-    blob_target: u32,
+    blob_schedule: Option<ForkBlobSchedule>,
+    fork: Fork,
 ) -> (u64, u64) {
     // NOTE: Given that this client supports the Paris fork and later versions, we are sure that the next block
     // will have the London update active, so the base fee calculation makes sense
@@ -205,13 +202,13 @@ fn project_next_block_base_fee_values(
         ELASTICITY_MULTIPLIER,
     )
     .unwrap_or_default();
-    let next_excess_blob_gas = calc_excess_blob_gas(
-        header.excess_blob_gas.unwrap_or_default(),
-        header.blob_gas_used.unwrap_or_default(),
-        blob_target,
-    );
-    let base_fee_per_blob =
-        calculate_base_fee_per_blob_gas(next_excess_blob_gas, base_fee_update_fraction);
+    // Blocks before Cancun have no blob fee
+    let base_fee_per_blob = blob_schedule
+        .map(|schedule| {
+            let next_excess_blob_gas = calc_excess_blob_gas(header, schedule, fork);
+            calculate_base_fee_per_blob_gas(next_excess_blob_gas, schedule.base_fee_update_fraction)
+        })
+        .unwrap_or_default();
     (base_fee_per_gas, base_fee_per_blob)
 }
 
@@ -21,7 +21,7 @@ pub use eth::{
     filter::{ActiveFilters, clean_outdated_filters},
     gas_price::GasPrice,
     gas_tip_estimator::GasTipEstimator,
-    transaction::EstimateGasRequest,
+    transaction::{EstimateGasRequest, GetTransactionReceiptRequest},
 };
 pub use rpc::{
     NodeData, RpcApiContext, RpcHandler, RpcRequestWrapper, map_debug_requests, map_eth_requests,
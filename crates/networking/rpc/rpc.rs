@@ -2,13 +2,13 @@ use crate::authentication::authenticate;
 use crate::debug::execution_witness::ExecutionWitnessRequest;
 use crate::engine::{
     ExchangeCapabilitiesRequest,
-    blobs::BlobsV1Request,
+    blobs::{BlobsV1Request, BlobsV2Request},
     exchange_transition_config::ExchangeTransitionConfigV1Req,
     fork_choice::{ForkChoiceUpdatedV1, ForkChoiceUpdatedV2, ForkChoiceUpdatedV3},
     payload::{
         GetPayloadBodiesByHashV1Request, GetPayloadBodiesByRangeV1Request, GetPayloadV1Request,
-        GetPayloadV2Request, GetPayloadV3Request, GetPayloadV4Request, NewPayloadV1Request,
-        NewPayloadV2Request, NewPayloadV3Request, NewPayloadV4Request,
+        GetPayloadV2Request, GetPayloadV3Request, GetPayloadV4Request, GetPayloadV5Request,
+        NewPayloadV1Request, NewPayloadV2Request, NewPayloadV3Request, NewPayloadV4Request,
     },
 };
 use crate::eth::{
@@ -33,7 +33,9 @@ use crate::eth::{
         GetTransactionByHashRequest, GetTransactionReceiptRequest,
     },
 };
-use crate::tracing::{TraceBlockByNumberRequest, TraceTransactionRequest};
+use crate::tracing::{
+    TraceBlockByNumberRequest, TraceBlockStateAccessRequest, TraceTransactionRequest,
+};
 use crate::types::transaction::SendRawTransactionRequest;
 use crate::utils::{
     RpcErr, RpcErrorMetadata, RpcErrorResponse, RpcNamespace, RpcRequest, RpcRequestId,
@@ -89,7 +91,7 @@ pub struct RpcApiContext {
 pub struct NodeData {
     pub jwt_secret: Bytes,
     pub local_p2p_node: Node,
-    pub local_node_record: NodeRecord,
+    pub local_node_record: Arc<TokioMutex<NodeRecord>>,
     pub client_version: String,
 }
 
@@ -121,7 +123,7 @@ pub async fn start_api(
     blockchain: Arc<Blockchain>,
     jwt_secret: Bytes,
     local_p2p_node: Node,
-    local_node_record: NodeRecord,
+    local_node_record: Arc<TokioMutex<NodeRecord>>,
     syncer: SyncManager,
     peer_handler: PeerHandler,
     client_version: String,
@@ -263,7 +265,7 @@ pub async fn handle_authrpc_request(
 pub async fn map_http_requests(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
     match req.namespace() {
         Ok(RpcNamespace::Eth) => map_eth_requests(req, context).await,
-        Ok(RpcNamespace::Admin) => map_admin_requests(req, context),
+        Ok(RpcNamespace::Admin) => map_admin_requests(req, context).await,
         Ok(RpcNamespace::Debug) => map_debug_requests(req, context).await,
         Ok(RpcNamespace::Web3) => map_web3_requests(req, context),
         Ok(RpcNamespace::Net) => map_net_requests(req, context).await,
@@ -347,6 +349,7 @@ pub async fn map_debug_requests(req: &RpcRequest, context: RpcApiContext) -> Res
         "debug_executionWitness" => ExecutionWitnessRequest::call(req, context).await,
         "debug_traceTransaction" => TraceTransactionRequest::call(req, context).await,
         "debug_traceBlockByNumber" => TraceBlockByNumberRequest::call(req, context).await,
+        "debug_traceBlockStateAccess" => TraceBlockStateAccessRequest::call(req, context).await,
         unknown_debug_method => Err(RpcErr::MethodNotFound(unknown_debug_method.to_owned())),
     }
 }
@@ -367,6 +370,7 @@ pub async fn map_engine_requests(
         "engine_exchangeTransitionConfigurationV1" => {
             ExchangeTransitionConfigV1Req::call(req, context).await
         }
+        "engine_getPayloadV5" => GetPayloadV5Request::call(req, context).await,
         "engine_getPayloadV4" => GetPayloadV4Request::call(req, context).await,
         "engine_getPayloadV3" => GetPayloadV3Request::call(req, context).await,
         "engine_getPayloadV2" => GetPayloadV2Request::call(req, context).await,
@@ -378,13 +382,14 @@ pub async fn map_engine_requests(
             GetPayloadBodiesByRangeV1Request::call(req, context).await
         }
         "engine_getBlobsV1" => BlobsV1Request::call(req, context).await,
+        "engine_getBlobsV2" => BlobsV2Request::call(req, context).await,
         unknown_engine_method => Err(RpcErr::MethodNotFound(unknown_engine_method.to_owned())),
     }
 }
 
-pub fn map_admin_requests(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
+pub async fn map_admin_requests(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
     match req.method.as_str() {
-        "admin_nodeInfo" => admin::node_info(context.storage, &context.node_data),
+        "admin_nodeInfo" => admin::node_info(context.storage, &context.node_data).await,
         "admin_peers" => admin::peers(&context),
         unknown_admin_method => Err(RpcErr::MethodNotFound(unknown_admin_method.to_owned())),
     }
@@ -468,7 +473,13 @@ mod tests {
         let context = default_context_with_storage(storage).await;
         let local_p2p_node = context.node_data.local_p2p_node.clone();
 
-        let enr_url = context.node_data.local_node_record.enr_url().unwrap();
+        let enr_url = context
+            .node_data
+            .local_node_record
+            .lock()
+            .await
+            .enr_url()
+            .unwrap();
         let result = map_http_requests(&request, context).await;
         let rpc_response = rpc_response(request.id, result).unwrap();
         let blob_schedule = serde_json::json!({
@@ -1,6 +1,7 @@
-use std::time::Duration;
+use std::{collections::BTreeMap, time::Duration};
 
-use ethrex_common::{serde_utils, tracing::CallTrace, types::BlockNumber};
+use ethrex_common::{serde_utils, types::BlockNumber};
+use ethrex_vm::tracing::{NativeTracer, StateAccess, TraceResult};
 use keccak_hash::H256;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -22,6 +23,11 @@ pub struct TraceBlockByNumberRequest {
     trace_config: TraceConfig,
 }
 
+pub struct TraceBlockStateAccessRequest {
+    number: BlockNumber,
+    config: StateAccessTraceConfig,
+}
+
 #[derive(Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 struct TraceConfig {
@@ -36,11 +42,24 @@ struct TraceConfig {
     reexec: Option<u32>,
 }
 
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct StateAccessTraceConfig {
+    #[serde(default, with = "serde_utils::duration::opt")]
+    timeout: Option<Duration>,
+    #[serde(default)]
+    reexec: Option<u32>,
+}
+
 #[derive(Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 enum TracerType {
     #[default]
     CallTracer,
+    #[serde(rename = "4byteTracer")]
+    FourByteTracer,
+    GasProfiler,
+    MuxTracer,
 }
 
 #[derive(Deserialize, Default)]
@@ -52,6 +71,48 @@ struct CallTracerConfig {
     with_log: bool,
 }
 
+impl TraceConfig {
+    /// Builds the tracer given by the config, parsing its tracer config now that we know its type
+    fn native_tracer(&self) -> Result<NativeTracer, RpcErr> {
+        native_tracer(&self.tracer, self.tracer_config.as_ref())
+    }
+}
+
+fn native_tracer(tracer: &TracerType, config: Option<&Value>) -> Result<NativeTracer, RpcErr> {
+    Ok(match tracer {
+        TracerType::CallTracer => {
+            let config = if let Some(value) = config {
+                serde_json::from_value(value.clone())?
+            } else {
+                CallTracerConfig::default()
+            };
+            NativeTracer::Call {
+                only_top_call: config.only_top_call,
+                with_log: config.with_log,
+            }
+        }
+        TracerType::FourByteTracer => NativeTracer::FourByte,
+        TracerType::GasProfiler => NativeTracer::GasProfiler,
+        TracerType::MuxTracer => {
+            // The config maps the name of each tracer to its own config
+            let configs: BTreeMap<String, Value> = if let Some(value) = config {
+                serde_json::from_value(value.clone())?
+            } else {
+                BTreeMap::new()
+            };
+            let mut tracers = Vec::new();
+            for (name, config) in configs {
+                let tracer: TracerType = serde_json::from_value(Value::String(name.clone()))?;
+                if let TracerType::MuxTracer = tracer {
+                    return Err(RpcErr::BadParams("muxTracer can't be nested".to_owned()));
+                }
+                tracers.push((name, native_tracer(&tracer, Some(&config))?));
+            }
+            NativeTracer::Mux(tracers)
+        }
+    })
+}
+
 type BlockTrace<TxTrace> = Vec<BlockTraceComponent<TxTrace>>;
 
 #[derive(Serialize)]
@@ -96,29 +157,17 @@ impl RpcHandler for TraceTransactionRequest {
     ) -> Result<serde_json::Value, crate::utils::RpcErr> {
         let reexec = self.trace_config.reexec.unwrap_or(DEFAULT_REEXEC);
         let timeout = self.trace_config.timeout.unwrap_or(DEFAULT_TIMEOUT);
-        // This match will make more sense once we support other tracers
-        match self.trace_config.tracer {
-            TracerType::CallTracer => {
-                // Parse tracer config now that we know the type
-                let config = if let Some(value) = &self.trace_config.tracer_config {
-                    serde_json::from_value(value.clone())?
-                } else {
-                    CallTracerConfig::default()
-                };
-                let call_trace = context
-                    .blockchain
-                    .trace_transaction_calls(
-                        self.tx_hash,
-                        reexec,
-                        timeout,
-                        config.only_top_call,
-                        config.with_log,
-                    )
-                    .await
-                    .map_err(|err| RpcErr::Internal(err.to_string()))?;
-                Ok(serde_json::to_value(call_trace)?)
-            }
-        }
+        let trace = context
+            .blockchain
+            .trace_transaction(
+                self.tx_hash,
+                reexec,
+                timeout,
+                self.trace_config.native_tracer()?,
+            )
+            .await
+            .map_err(|err| RpcErr::Internal(err.to_string()))?;
+        Ok(serde_json::to_value(trace)?)
     }
 }
 
@@ -153,31 +202,57 @@ impl RpcHandler for TraceBlockByNumberRequest {
             .ok_or(RpcErr::Internal("Block not Found".to_string()))?;
         let reexec = self.trace_config.reexec.unwrap_or(DEFAULT_REEXEC);
         let timeout = self.trace_config.timeout.unwrap_or(DEFAULT_TIMEOUT);
-        // This match will make more sense once we support other tracers
-        match self.trace_config.tracer {
-            TracerType::CallTracer => {
-                // Parse tracer config now that we know the type
-                let config = if let Some(value) = &self.trace_config.tracer_config {
-                    serde_json::from_value(value.clone())?
-                } else {
-                    CallTracerConfig::default()
-                };
-                let call_traces = context
-                    .blockchain
-                    .trace_block_calls(
-                        block,
-                        reexec,
-                        timeout,
-                        config.only_top_call,
-                        config.with_log,
-                    )
-                    .await
-                    .map_err(|err| RpcErr::Internal(err.to_string()))?;
-                // We need to show transactions from newest to oldest
-                let block_trace: BlockTrace<CallTrace> =
-                    call_traces.into_iter().rev().map(Into::into).collect();
-                Ok(serde_json::to_value(block_trace)?)
-            }
-        }
+        let traces = context
+            .blockchain
+            .trace_block(block, reexec, timeout, self.trace_config.native_tracer()?)
+            .await
+            .map_err(|err| RpcErr::Internal(err.to_string()))?;
+        // We need to show transactions from newest to oldest
+        let block_trace: BlockTrace<TraceResult> =
+            traces.into_iter().rev().map(Into::into).collect();
+        Ok(serde_json::to_value(block_trace)?)
+    }
+}
+
+impl RpcHandler for TraceBlockStateAccessRequest {
+    fn parse(params: &Option<Vec<serde_json::Value>>) -> Result<Self, RpcErr> {
+        let params = params
+            .as_ref()
+            .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+        if params.len() != 1 && params.len() != 2 {
+            return Err(RpcErr::BadParams("Expected 1 or 2 params".to_owned()));
+        };
+        let config = if params.len() == 2 {
+            serde_json::from_value(params[1].clone())?
+        } else {
+            StateAccessTraceConfig::default()
+        };
+
+        Ok(TraceBlockStateAccessRequest {
+            number: serde_json::from_value(params[0].clone())?,
+            config,
+        })
+    }
+
+    async fn handle(
+        &self,
+        context: crate::rpc::RpcApiContext,
+    ) -> Result<serde_json::Value, crate::utils::RpcErr> {
+        let block = context
+            .storage
+            .get_block_by_number(self.number)
+            .await?
+            .ok_or(RpcErr::Internal("Block not Found".to_string()))?;
+        let reexec = self.config.reexec.unwrap_or(DEFAULT_REEXEC);
+        let timeout = self.config.timeout.unwrap_or(DEFAULT_TIMEOUT);
+        let state_accesses = context
+            .blockchain
+            .trace_block_state_access(block, reexec, timeout)
+            .await
+            .map_err(|err| RpcErr::Internal(err.to_string()))?;
+        // Unlike call traces, state accesses are shown in block order as each one depends on the previous ones
+        let block_trace: BlockTrace<StateAccess> =
+            state_accesses.into_iter().map(Into::into).collect();
+        Ok(serde_json::to_value(block_trace)?)
     }
 }
@@ -350,7 +350,7 @@ pub mod test_utils {
             blockchain,
             jwt_secret,
             local_p2p_node,
-            example_local_node_record(),
+            Arc::new(TokioMutex::new(example_local_node_record())),
             SyncManager::dummy(),
             PeerHandler::dummy(),
             "ethrex/test".to_string(),
@@ -370,7 +370,7 @@ pub mod test_utils {
             node_data: NodeData {
                 jwt_secret: Default::default(),
                 local_p2p_node: example_p2p_node(),
-                local_node_record: example_local_node_record(),
+                local_node_record: Arc::new(TokioMutex::new(example_local_node_record())),
                 client_version: "ethrex/test".to_string(),
             },
             gas_tip_estimator: Arc::new(TokioMutex::new(GasTipEstimator::new())),
@@ -1,4 +1,5 @@
 pub mod db;
+mod parallel;
 mod tracing;
 
 use super::BlockExecutionResult;
@@ -12,8 +13,8 @@ use ethrex_common::{
     Address, H256, U256,
     types::{
         AccessList, AccountUpdate, AuthorizationTuple, Block, BlockHeader, EIP1559Transaction,
-        EIP7702Transaction, Fork, GWEI_TO_WEI, GenericTransaction, INITIAL_BASE_FEE, Receipt,
-        Transaction, TxKind, Withdrawal, requests::Requests,
+        EIP7702Transaction, Fork, GWEI_TO_WEI, GenericTransaction, INITIAL_BASE_FEE, L1FeeConfig,
+        Receipt, Transaction, TxKind, Withdrawal, requests::Requests,
     },
 };
 use ethrex_levm::EVMConfig;
@@ -31,6 +32,7 @@ use std::cmp::min;
 
 /// The struct implements the following functions:
 /// [LEVM::execute_block]
+/// [LEVM::execute_block_parallel]
 /// [LEVM::execute_tx]
 /// [LEVM::get_state_transitions]
 /// [LEVM::process_withdrawals]
@@ -64,6 +66,16 @@ impl LEVM {
             receipts.push(receipt);
         }
 
+        Self::finalize_block(block, db, vm_type, receipts)
+    }
+
+    /// Processes the withdrawals and extracts the requests once all the block transactions were executed.
+    pub fn finalize_block(
+        block: &Block,
+        db: &mut GeneralizedDatabase,
+        vm_type: VMType,
+        receipts: Vec<Receipt>,
+    ) -> Result<BlockExecutionResult, EvmError> {
         if let Some(withdrawals) = &block.body.withdrawals {
             Self::process_withdrawals(db, withdrawals)?;
         }
@@ -79,7 +91,7 @@ impl LEVM {
         Ok(BlockExecutionResult { receipts, requests })
     }
 
-    fn setup_env(
+    pub fn setup_env(
         tx: &Transaction,
         tx_sender: Address,
         block_header: &BlockHeader,
@@ -115,6 +127,7 @@ impl LEVM {
             block_gas_limit: block_header.gas_limit,
             difficulty: block_header.difficulty,
             is_privileged: matches!(tx, Transaction::PrivilegedL2Transaction(_)),
+            l1_fee_config: L1FeeConfig::from_extra_data(&block_header.extra_data),
         };
 
         Ok(env)
@@ -532,6 +545,7 @@ fn env_from_generic(
         block_gas_limit: header.gas_limit,
         difficulty: header.difficulty,
         is_privileged: false,
+        l1_fee_config: L1FeeConfig::from_extra_data(&header.extra_data),
     })
 }
 
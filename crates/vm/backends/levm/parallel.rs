@@ -0,0 +1,360 @@
+//! Optimistic parallel block execution, in the spirit of Block-STM.
+//!
+//! After the system calls run, every transaction of the block is speculatively executed on a
+//! worker thread against the state left by them, recording which accounts and storage slots it
+//! read. Transactions are then committed in block order: a speculation is only committed if none
+//! of the keys it read were written by a transaction committed before it, otherwise the
+//! transaction is re-executed sequentially on top of the committed state. This makes the result
+//! identical to the one of [LEVM::execute_block].
+//!
+//! Every transaction credits the coinbase with its priority fee, which would make each of them
+//! conflict with all the previous ones. To avoid that, a coinbase read that happens only after the
+//! execution finished (i.e. for the fee payment) isn't recorded, and the coinbase balance change
+//! of such transactions is applied as a delta on commit.
+
+use super::LEVM;
+use crate::EvmError;
+use crate::backends::BlockExecutionResult;
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    types::{AccountInfo, Block, BlockHeader, ChainConfig, Receipt, Transaction},
+};
+use ethrex_levm::{
+    db::{
+        Database,
+        gen_db::{CacheDB, GeneralizedDatabase},
+    },
+    errors::{ContextResult, DatabaseError, ExecutionReport, InternalError, TxResult, VMError},
+    hooks::{backup_hook::BackupHook, hook::Hook},
+    tracing::LevmCallTracer,
+    vm::{VM, VMType},
+};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashSet},
+    rc::Rc,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+/// State of the block right before its first transaction, shared by all the speculative executions.
+struct Snapshot {
+    store: Arc<dyn Database>,
+    accounts: CacheDB,
+    codes: BTreeMap<H256, Bytes>,
+    destroyed_accounts: HashSet<Address>,
+}
+
+/// Keys read by a speculative execution.
+#[derive(Default)]
+struct ReadSet {
+    accounts: HashSet<Address>,
+    slots: HashSet<(Address, H256)>,
+}
+
+/// Keys written by the transactions committed so far.
+#[derive(Default)]
+struct WriteSet {
+    accounts: HashSet<Address>,
+    slots: HashSet<(Address, H256)>,
+    destroyed_accounts: HashSet<Address>,
+}
+
+impl WriteSet {
+    fn conflicts_with(&self, reads: &ReadSet) -> bool {
+        reads
+            .accounts
+            .iter()
+            .any(|address| self.accounts.contains(address))
+            || reads.slots.iter().any(|(address, key)| {
+                self.destroyed_accounts.contains(address) || self.slots.contains(&(*address, *key))
+            })
+    }
+}
+
+/// Database used by a single speculative execution, it serves the snapshot and records every read.
+struct SpeculativeView {
+    snapshot: Arc<Snapshot>,
+    coinbase: Address,
+    /// Set once the transaction execution finished, from then on coinbase reads are only for the fee payment.
+    paying_fees: AtomicBool,
+    reads: Mutex<ReadSet>,
+}
+
+impl SpeculativeView {
+    fn new(snapshot: Arc<Snapshot>, coinbase: Address) -> Self {
+        Self {
+            snapshot,
+            coinbase,
+            paying_fees: AtomicBool::new(false),
+            reads: Mutex::new(ReadSet::default()),
+        }
+    }
+
+    fn reads(&self) -> std::sync::MutexGuard<'_, ReadSet> {
+        self.reads
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn take_reads(&self) -> ReadSet {
+        std::mem::take(&mut *self.reads())
+    }
+}
+
+impl Database for SpeculativeView {
+    fn get_account_info(&self, address: Address) -> Result<AccountInfo, DatabaseError> {
+        if address != self.coinbase || !self.paying_fees.load(Ordering::Relaxed) {
+            self.reads().accounts.insert(address);
+        }
+        match self.snapshot.accounts.get(&address) {
+            Some(account) => Ok(account.info.clone()),
+            None => self.snapshot.store.get_account_info(address),
+        }
+    }
+
+    fn get_storage_value(&self, address: Address, key: H256) -> Result<U256, DatabaseError> {
+        self.reads().slots.insert((address, key));
+        if let Some(value) = self
+            .snapshot
+            .accounts
+            .get(&address)
+            .and_then(|account| account.storage.get(&key))
+        {
+            return Ok(*value);
+        }
+        if self.snapshot.destroyed_accounts.contains(&address) {
+            return Ok(U256::zero());
+        }
+        self.snapshot.store.get_storage_value(address, key)
+    }
+
+    fn get_block_hash(&self, block_number: u64) -> Result<H256, DatabaseError> {
+        self.snapshot.store.get_block_hash(block_number)
+    }
+
+    fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError> {
+        self.snapshot.store.get_chain_config()
+    }
+
+    fn get_account_code(&self, code_hash: H256) -> Result<Bytes, DatabaseError> {
+        match self.snapshot.codes.get(&code_hash) {
+            Some(code) => Ok(code.clone()),
+            None => self.snapshot.store.get_account_code(code_hash),
+        }
+    }
+}
+
+/// Runs before the default hook's finalization, flagging the start of the fee payment.
+struct FeePaymentHook(Arc<SpeculativeView>);
+
+impl Hook for FeePaymentHook {
+    fn prepare_execution(&mut self, _vm: &mut VM<'_>) -> Result<(), VMError> {
+        Ok(())
+    }
+
+    fn finalize_execution(
+        &mut self,
+        _vm: &mut VM<'_>,
+        _report: &mut ContextResult,
+    ) -> Result<(), VMError> {
+        self.0.paying_fees.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+struct Speculation {
+    report: ExecutionReport,
+    db: GeneralizedDatabase,
+    reads: ReadSet,
+}
+
+impl LEVM {
+    /// Executes the block like [LEVM::execute_block], but running its transactions optimistically in parallel.
+    pub fn execute_block_parallel(
+        block: &Block,
+        db: &mut GeneralizedDatabase,
+        vm_type: VMType,
+    ) -> Result<BlockExecutionResult, EvmError> {
+        // L2 hooks handle fees differently, so only L1 blocks are executed in parallel
+        if let VMType::L2 = vm_type {
+            return Self::execute_block(block, db, vm_type);
+        }
+
+        Self::prepare_block(block, db, vm_type)?;
+
+        let transactions = block.body.get_transactions_with_sender().map_err(|error| {
+            EvmError::Transaction(format!("Couldn't recover addresses with error: {error}"))
+        })?;
+
+        let snapshot = Arc::new(Snapshot {
+            store: db.store.clone(),
+            accounts: db.current_accounts_state.clone(),
+            codes: db.codes.clone(),
+            destroyed_accounts: db.destroyed_accounts.clone(),
+        });
+
+        let mut speculations: Vec<Option<Speculation>> = transactions
+            .par_iter()
+            .map(|(tx, tx_sender)| {
+                speculate(tx, *tx_sender, &block.header, snapshot.clone(), vm_type)
+            })
+            .collect();
+
+        let coinbase = block.header.coinbase;
+        let mut writes = WriteSet::default();
+        let mut receipts = Vec::new();
+        let mut cumulative_gas_used = 0;
+
+        for ((tx, tx_sender), speculation) in transactions.iter().zip(speculations.iter_mut()) {
+            let report = match speculation.take() {
+                Some(speculation) if !writes.conflicts_with(&speculation.reads) => {
+                    commit_speculation(&speculation, db, coinbase, &mut writes)?;
+                    speculation.report
+                }
+                // Either the speculation failed or it read stale state, so the transaction is re-executed
+                _ => execute_tx_recording_writes(
+                    tx,
+                    *tx_sender,
+                    &block.header,
+                    db,
+                    vm_type,
+                    &mut writes,
+                )?,
+            };
+
+            cumulative_gas_used += report.gas_used;
+            let receipt = Receipt::new(
+                tx.tx_type(),
+                matches!(report.result, TxResult::Success),
+                cumulative_gas_used,
+                report.logs,
+            );
+
+            receipts.push(receipt);
+        }
+
+        Self::finalize_block(block, db, vm_type, receipts)
+    }
+}
+
+/// Executes the transaction over the snapshot, returning `None` if the execution failed.
+/// Failures are not final since they may be caused by stale state, the transaction is re-executed in that case.
+fn speculate(
+    tx: &Transaction,
+    tx_sender: Address,
+    block_header: &BlockHeader,
+    snapshot: Arc<Snapshot>,
+    vm_type: VMType,
+) -> Option<Speculation> {
+    let view = Arc::new(SpeculativeView::new(snapshot, block_header.coinbase));
+    let mut db = GeneralizedDatabase::new(view.clone());
+
+    let env = LEVM::setup_env(tx, tx_sender, block_header, &mut db).ok()?;
+    let mut vm = VM::new(env, &mut db, tx, LevmCallTracer::disabled(), vm_type).ok()?;
+    vm.hooks
+        .insert(0, Rc::new(RefCell::new(FeePaymentHook(view.clone()))));
+    let report = vm.execute().ok()?;
+
+    Some(Speculation {
+        report,
+        db,
+        reads: view.take_reads(),
+    })
+}
+
+/// Applies the changes made by a speculative execution to the block state.
+fn commit_speculation(
+    speculation: &Speculation,
+    db: &mut GeneralizedDatabase,
+    coinbase: Address,
+    writes: &mut WriteSet,
+) -> Result<(), EvmError> {
+    let spec_db = &speculation.db;
+
+    for (address, account) in &spec_db.current_accounts_state {
+        let initial = spec_db
+            .initial_accounts_state
+            .get(address)
+            .ok_or_else(|| InternalError::msg("Speculative account missing from initial state"))?;
+
+        // The transaction only credited its fee, which doesn't depend on the coinbase state
+        if *address == coinbase && !speculation.reads.accounts.contains(&coinbase) {
+            let fee = account
+                .info
+                .balance
+                .checked_sub(initial.info.balance)
+                .ok_or(InternalError::Underflow)?;
+            let coinbase_account = db.get_account_mut(coinbase)?;
+            coinbase_account.info.balance = coinbase_account
+                .info
+                .balance
+                .checked_add(fee)
+                .ok_or(InternalError::Overflow)?;
+            writes.accounts.insert(coinbase);
+            continue;
+        }
+
+        if spec_db.destroyed_accounts.contains(address) {
+            *db.get_account_mut(*address)? = account.clone();
+            db.destroyed_accounts.insert(*address);
+            writes.accounts.insert(*address);
+            writes.destroyed_accounts.insert(*address);
+            continue;
+        }
+
+        if account.info != initial.info {
+            db.get_account_mut(*address)?.info = account.info.clone();
+            writes.accounts.insert(*address);
+        }
+
+        for (key, value) in &account.storage {
+            if initial.storage.get(key) == Some(value) {
+                continue;
+            }
+            // Make sure the original value is cached so the state transitions are computed correctly
+            db.get_storage_value(*address, *key)?;
+            db.get_account_mut(*address)?.storage.insert(*key, *value);
+            writes.slots.insert((*address, *key));
+        }
+    }
+
+    for (code_hash, code) in &spec_db.codes {
+        db.codes.entry(*code_hash).or_insert_with(|| code.clone());
+    }
+
+    Ok(())
+}
+
+/// Executes the transaction on top of the committed state, as the sequential execution would.
+fn execute_tx_recording_writes(
+    tx: &Transaction,
+    tx_sender: Address,
+    block_header: &BlockHeader,
+    db: &mut GeneralizedDatabase,
+    vm_type: VMType,
+    writes: &mut WriteSet,
+) -> Result<ExecutionReport, EvmError> {
+    let env = LEVM::setup_env(tx, tx_sender, block_header, db)?;
+    let mut vm = VM::new(env, db, tx, LevmCallTracer::disabled(), vm_type)?;
+    vm.hooks.push(Rc::new(RefCell::new(BackupHook::default())));
+    let report = vm.execute()?;
+
+    // The backup holds every account and slot the transaction may have modified
+    let backup = db.get_tx_backup()?;
+    writes.accounts.extend(backup.original_accounts_info.keys());
+    for (address, slots) in backup.original_account_storage_slots {
+        writes
+            .slots
+            .extend(slots.into_keys().map(|key| (address, key)));
+    }
+    writes
+        .destroyed_accounts
+        .extend(db.destroyed_accounts.iter().copied());
+
+    Ok(report)
+}
@@ -1,9 +1,17 @@
-use ethrex_common::types::{Block, Transaction};
+use std::{cell::RefCell, rc::Rc};
+
+use ethrex_common::types::{Block, GenericTransaction, Transaction};
 use ethrex_common::{tracing::CallTrace, types::BlockHeader};
+use ethrex_levm::db::state_access::StateAccess;
+use ethrex_levm::errors::VMError;
+use ethrex_levm::native_tracers::{FourByteTracer, GasProfiler, MuxTracer};
+use ethrex_levm::tracing::Tracer;
 use ethrex_levm::vm::VMType;
 use ethrex_levm::{db::gen_db::GeneralizedDatabase, tracing::LevmCallTracer, vm::VM};
 
-use crate::{EvmError, backends::levm::LEVM};
+use super::{adjust_disabled_base_fee, env_from_generic, vm_from_generic};
+use crate::tracing::{NativeTracer, TraceResult};
+use crate::{EvmError, ExecutionResult, backends::levm::LEVM};
 
 impl LEVM {
     /// Execute all transactions of the block up until a certain transaction specified in `stop_index`.
@@ -73,4 +81,152 @@ impl LEVM {
         // We only return the top call because a transaction only has one call with subcalls
         Ok(vec![callframe])
     }
+
+    /// Run transaction with the given tracer, which may be a mux of several of them.
+    pub fn trace_tx_native(
+        db: &mut GeneralizedDatabase,
+        block_header: &BlockHeader,
+        tx: &Transaction,
+        tracer: &NativeTracer,
+        vm_type: VMType,
+    ) -> Result<TraceResult, EvmError> {
+        let env = Self::setup_env(
+            tx,
+            tx.sender().map_err(|error| {
+                EvmError::Transaction(format!("Couldn't recover addresses with error: {error}"))
+            })?,
+            block_header,
+            db,
+        )?;
+
+        let mut call_tracer = LevmCallTracer::disabled();
+        let mut mux = MuxTracer::default();
+        let handle = TracerHandle::attach(tracer, &mut call_tracer, &mut mux)?;
+
+        let mut vm = VM::new(env, db, tx, call_tracer, vm_type)?;
+        if !mux.is_empty() {
+            vm.set_tracer(Rc::new(RefCell::new(mux)));
+        }
+
+        vm.execute()?;
+
+        handle.result(&mut vm)
+    }
+
+    /// Run transaction recording the accounts and storage slots it accesses.
+    pub fn trace_tx_state_access(
+        db: &mut GeneralizedDatabase,
+        block_header: &BlockHeader,
+        tx: &Transaction,
+        vm_type: VMType,
+    ) -> Result<StateAccess, EvmError> {
+        let env = Self::setup_env(
+            tx,
+            tx.sender().map_err(|error| {
+                EvmError::Transaction(format!("Couldn't recover addresses with error: {error}"))
+            })?,
+            block_header,
+            db,
+        )?;
+
+        db.enable_state_access_recording();
+        let result = VM::new(env, db, tx, LevmCallTracer::disabled(), vm_type)
+            .and_then(|mut vm| vm.execute());
+        // Recording is only enabled for this transaction
+        db.tx_reads = None;
+
+        result?
+            .state_access
+            .ok_or(EvmError::Custom("State access wasn't recorded".to_string()))
+    }
+
+    /// Simulate a transaction like [LEVM::simulate_tx_from_generic] with the given tracer attached.
+    pub fn simulate_tx_from_generic_with_tracer(
+        tx: &GenericTransaction,
+        block_header: &BlockHeader,
+        db: &mut GeneralizedDatabase,
+        tracer: Rc<RefCell<dyn Tracer>>,
+        vm_type: VMType,
+    ) -> Result<ExecutionResult, EvmError> {
+        let mut env = env_from_generic(tx, block_header, db)?;
+
+        env.block_gas_limit = u64::MAX; // disable block gas limit
+
+        adjust_disabled_base_fee(&mut env);
+
+        let mut vm = vm_from_generic(tx, env, db, vm_type)?;
+        vm.set_tracer(tracer);
+
+        vm.execute()
+            .map(|value| value.into())
+            .map_err(VMError::into)
+    }
+}
+
+/// Keeps track of the tracers attached to the VM for a [NativeTracer], to collect their results
+/// after the execution.
+enum TracerHandle {
+    Call,
+    FourByte(Rc<RefCell<FourByteTracer>>),
+    GasProfiler(Rc<RefCell<GasProfiler>>),
+    Mux(Vec<(String, TracerHandle)>),
+}
+
+impl TracerHandle {
+    /// Sets up the tracers needed by `tracer`. The call tracer is built into the VM, so it's set up
+    /// in `call_tracer`, while the rest are added to `mux`.
+    fn attach(
+        tracer: &NativeTracer,
+        call_tracer: &mut LevmCallTracer,
+        mux: &mut MuxTracer,
+    ) -> Result<Self, EvmError> {
+        match tracer {
+            NativeTracer::Call {
+                only_top_call,
+                with_log,
+            } => {
+                if call_tracer.active {
+                    return Err(EvmError::Custom(
+                        "Only one call tracer can be run at once".to_string(),
+                    ));
+                }
+                *call_tracer = LevmCallTracer::new(*only_top_call, *with_log);
+                Ok(Self::Call)
+            }
+            NativeTracer::FourByte => {
+                let tracer = Rc::new(RefCell::new(FourByteTracer::default()));
+                mux.push(tracer.clone());
+                Ok(Self::FourByte(tracer))
+            }
+            NativeTracer::GasProfiler => {
+                let tracer = Rc::new(RefCell::new(GasProfiler::default()));
+                mux.push(tracer.clone());
+                Ok(Self::GasProfiler(tracer))
+            }
+            NativeTracer::Mux(tracers) => tracers
+                .iter()
+                .map(|(name, tracer)| Ok((name.clone(), Self::attach(tracer, call_tracer, mux)?)))
+                .collect::<Result<_, EvmError>>()
+                .map(Self::Mux),
+        }
+    }
+
+    fn result(self, vm: &mut VM<'_>) -> Result<TraceResult, EvmError> {
+        Ok(match self {
+            // We only return the top call because a transaction only has one call with subcalls
+            Self::Call => TraceResult::Call(vec![vm.get_trace_result()?]),
+            Self::FourByte(tracer) => {
+                TraceResult::FourByte(std::mem::take(&mut tracer.borrow_mut().selectors))
+            }
+            Self::GasProfiler(tracer) => {
+                TraceResult::GasProfile(std::mem::take(&mut tracer.borrow_mut().profile))
+            }
+            Self::Mux(handles) => TraceResult::Mux(
+                handles
+                    .into_iter()
+                    .map(|(name, handle)| Ok((name, handle.result(vm)?)))
+                    .collect::<Result<_, EvmError>>()?,
+            ),
+        })
+    }
 }
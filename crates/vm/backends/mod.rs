@@ -6,12 +6,12 @@ use crate::db::{DynVmDatabase, VmDatabase};
 use crate::errors::EvmError;
 use crate::execution_result::ExecutionResult;
 use crate::helpers::{SpecId, fork_to_spec_id, spec_id};
-use ethrex_common::Address;
 use ethrex_common::types::requests::Requests;
 use ethrex_common::types::{
     AccessList, AccountUpdate, Block, BlockHeader, Fork, GenericTransaction, Receipt, Transaction,
     Withdrawal,
 };
+use ethrex_common::{Address, U256, constants::EMPTY_KECCACK_HASH};
 pub use ethrex_levm::call_frame::CallFrameBackup;
 use ethrex_levm::db::Database as LevmDatabase;
 use ethrex_levm::db::gen_db::GeneralizedDatabase;
@@ -131,6 +131,19 @@ impl Evm {
         }
     }
 
+    /// Same as [Evm::execute_block] but running the transactions optimistically in parallel.
+    /// Only L1 LEVM blocks are executed in parallel, the rest falls back to sequential execution.
+    #[instrument(level = "trace", name = "Parallel block execution", skip_all)]
+    pub fn execute_block_parallel(
+        &mut self,
+        block: &Block,
+    ) -> Result<BlockExecutionResult, EvmError> {
+        match self {
+            Evm::REVM { state } => REVM::execute_block(block, state),
+            Evm::LEVM { db, vm_type } => LEVM::execute_block_parallel(block, db, *vm_type),
+        }
+    }
+
     /// Wraps [REVM::execute_tx] and [LEVM::execute_tx].
     /// The output is `(Receipt, u64)` == (transaction_receipt, gas_used).
     #[allow(clippy::too_many_arguments)]
@@ -163,6 +176,27 @@ impl Evm {
 
                 Ok((receipt, execution_result.gas_used()))
             }
+            Evm::LEVM { .. } => {
+                let (receipt, gas_used, _) =
+                    self.execute_tx_with_l1_fee(tx, block_header, remaining_gas, sender)?;
+                Ok((receipt, gas_used))
+            }
+        }
+    }
+
+    /// Same as [Evm::execute_tx], also returning the L1 data fee paid by the transaction.
+    /// Only supported by LEVM.
+    pub fn execute_tx_with_l1_fee(
+        &mut self,
+        tx: &Transaction,
+        block_header: &BlockHeader,
+        remaining_gas: &mut u64,
+        sender: Address,
+    ) -> Result<(Receipt, u64, U256), EvmError> {
+        match self {
+            Evm::REVM { .. } => Err(EvmError::InvalidEVM(
+                "L1 data fees are not supported in REVM".to_string(),
+            )),
             Evm::LEVM { db, vm_type } => {
                 let execution_report = LEVM::execute_tx(tx, sender, block_header, db, *vm_type)?;
 
@@ -175,7 +209,7 @@ impl Evm {
                     execution_report.logs.clone(),
                 );
 
-                Ok((receipt, execution_report.gas_used))
+                Ok((receipt, execution_report.gas_used, execution_report.l1_fee))
             }
         }
     }
@@ -276,6 +310,21 @@ impl Evm {
         }
     }
 
+    /// Removes the code of an account from the cached state, like a state override, so that
+    /// transactions can be simulated as sent by it.
+    /// Only supported by LEVM.
+    pub fn clear_code(&mut self, address: Address) -> Result<(), EvmError> {
+        match self {
+            Evm::REVM { .. } => Err(EvmError::Custom(
+                "Clearing code is only supported by LEVM".to_string(),
+            )),
+            Evm::LEVM { db, .. } => {
+                db.get_account_mut(address)?.info.code_hash = *EMPTY_KECCACK_HASH;
+                Ok(())
+            }
+        }
+    }
+
     pub fn create_access_list(
         &mut self,
         tx: &GenericTransaction,
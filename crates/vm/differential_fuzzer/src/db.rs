@@ -0,0 +1,109 @@
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    constants::EMPTY_KECCACK_HASH,
+    types::{AccountInfo, AccountUpdate, ChainConfig, code_hash},
+};
+use ethrex_vm::{EvmError, VmDatabase};
+use std::collections::BTreeMap;
+
+/// In-memory state, both engines are run over their own copy of it.
+#[derive(Debug, Clone, Default)]
+pub struct FuzzDatabase {
+    pub chain_config: ChainConfig,
+    pub accounts: BTreeMap<Address, AccountInfo>,
+    pub storage: BTreeMap<Address, BTreeMap<H256, U256>>,
+    pub codes: BTreeMap<H256, Bytes>,
+}
+
+impl FuzzDatabase {
+    pub fn new(chain_config: ChainConfig) -> Self {
+        Self {
+            chain_config,
+            ..Default::default()
+        }
+    }
+
+    pub fn insert_account(
+        &mut self,
+        address: Address,
+        balance: U256,
+        nonce: u64,
+        code: Bytes,
+        storage: BTreeMap<H256, U256>,
+    ) {
+        let code_hash = code_hash(&code);
+        self.codes.insert(code_hash, code);
+        self.accounts.insert(
+            address,
+            AccountInfo {
+                code_hash,
+                balance,
+                nonce,
+            },
+        );
+        if !storage.is_empty() {
+            self.storage.insert(address, storage);
+        }
+    }
+
+    /// Applies the updates the same way the store does, so that the resulting states can be compared.
+    /// A removed account that comes with info was created again, so it keeps the new info and
+    /// storage but none of its previous storage.
+    pub fn apply_account_updates(&mut self, account_updates: &[AccountUpdate]) {
+        for update in account_updates {
+            if update.removed {
+                self.accounts.remove(&update.address);
+                self.storage.remove(&update.address);
+            }
+            if let Some(info) = &update.info {
+                self.accounts.insert(update.address, info.clone());
+                if let Some(code) = &update.code {
+                    self.codes.insert(info.code_hash, code.clone());
+                }
+            }
+            let storage = self.storage.entry(update.address).or_default();
+            for (key, value) in &update.added_storage {
+                if value.is_zero() {
+                    storage.remove(key);
+                } else {
+                    storage.insert(*key, *value);
+                }
+            }
+        }
+        self.storage.retain(|_, storage| !storage.is_empty());
+    }
+}
+
+impl VmDatabase for FuzzDatabase {
+    fn get_account_info(&self, address: Address) -> Result<Option<AccountInfo>, EvmError> {
+        Ok(self.accounts.get(&address).cloned())
+    }
+
+    fn get_storage_slot(&self, address: Address, key: H256) -> Result<Option<U256>, EvmError> {
+        Ok(self
+            .storage
+            .get(&address)
+            .and_then(|storage| storage.get(&key))
+            .copied())
+    }
+
+    fn get_block_hash(&self, block_number: u64) -> Result<H256, EvmError> {
+        // Any deterministic value works, as both engines see the same one
+        Ok(H256::from_low_u64_be(block_number))
+    }
+
+    fn get_chain_config(&self) -> Result<ChainConfig, EvmError> {
+        Ok(self.chain_config)
+    }
+
+    fn get_account_code(&self, code_hash: H256) -> Result<Bytes, EvmError> {
+        if code_hash == *EMPTY_KECCACK_HASH {
+            return Ok(Bytes::new());
+        }
+        self.codes
+            .get(&code_hash)
+            .cloned()
+            .ok_or_else(|| EvmError::DB(format!("Missing code for hash {code_hash:#x}")))
+    }
+}
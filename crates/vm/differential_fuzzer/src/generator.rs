@@ -0,0 +1,308 @@
+use crate::db::FuzzDatabase;
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    types::{
+        BlockHeader, ChainConfig, EIP1559Transaction, EIP2930Transaction, LegacyTransaction,
+        Transaction, TxKind,
+    },
+};
+use ethrex_levm::opcodes::Opcode;
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+use std::collections::{BTreeMap, HashMap};
+
+const CHAIN_ID: u64 = 1;
+const BASE_FEE: u64 = 7;
+const BLOCK_GAS_LIMIT: u64 = 30_000_000;
+const COINBASE: u64 = 0xc0ffee;
+const FIRST_SENDER: u64 = 0x1000;
+const FIRST_CONTRACT: u64 = 0x2000;
+/// Highest precompile address, calls to them are generated as well.
+const LAST_PRECOMPILE: u64 = 0x11;
+/// Amount of values pushed before each generated opcode, enough for the ones taking the most
+/// arguments (CALL and CALLCODE).
+const OPCODE_ARGUMENTS: usize = 7;
+
+#[derive(Debug, Clone)]
+pub struct GeneratorConfig {
+    pub senders: usize,
+    pub contracts: usize,
+    pub transactions: usize,
+    /// Amount of opcodes of each generated contract.
+    pub code_size: usize,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self {
+            senders: 4,
+            contracts: 8,
+            transactions: 16,
+            code_size: 48,
+        }
+    }
+}
+
+/// A pre-state along with a block of transactions to execute over it.
+#[derive(Debug, Clone)]
+pub struct FuzzCase {
+    pub seed: u64,
+    pub pre_state: FuzzDatabase,
+    pub header: BlockHeader,
+    /// Transactions along with their senders, they are not signed.
+    pub transactions: Vec<(Transaction, Address)>,
+}
+
+/// Chain config with every fork up to Osaka active from genesis.
+pub fn chain_config() -> ChainConfig {
+    ChainConfig {
+        chain_id: CHAIN_ID,
+        homestead_block: Some(0),
+        eip150_block: Some(0),
+        eip155_block: Some(0),
+        eip158_block: Some(0),
+        byzantium_block: Some(0),
+        constantinople_block: Some(0),
+        petersburg_block: Some(0),
+        istanbul_block: Some(0),
+        muir_glacier_block: Some(0),
+        berlin_block: Some(0),
+        london_block: Some(0),
+        arrow_glacier_block: Some(0),
+        gray_glacier_block: Some(0),
+        merge_netsplit_block: Some(0),
+        shanghai_time: Some(0),
+        cancun_time: Some(0),
+        prague_time: Some(0),
+        osaka_time: Some(0),
+        terminal_total_difficulty: Some(0),
+        terminal_total_difficulty_passed: true,
+        ..Default::default()
+    }
+}
+
+/// Generates a random case, the same seed always generates the same case.
+pub fn generate_case(seed: u64, config: &GeneratorConfig) -> FuzzCase {
+    let mut generator = Generator {
+        rng: StdRng::seed_from_u64(seed),
+        senders: (0..config.senders)
+            .map(|i| Address::from_low_u64_be(FIRST_SENDER + i as u64))
+            .collect(),
+        contracts: (0..config.contracts)
+            .map(|i| Address::from_low_u64_be(FIRST_CONTRACT + i as u64))
+            .collect(),
+        opcodes: (0..=u8::MAX)
+            .filter(|byte| Opcode::from(*byte) != Opcode::INVALID)
+            .collect(),
+    };
+
+    let mut pre_state = FuzzDatabase::new(chain_config());
+    for sender in &generator.senders {
+        let balance = U256::from(generator.rng.gen_range(1..=u64::MAX)) * U256::from(1_000_000);
+        pre_state.insert_account(*sender, balance, 0, Bytes::new(), BTreeMap::new());
+    }
+    for contract in generator.contracts.clone() {
+        let code = generator.code(config.code_size);
+        let storage = (0..generator.rng.gen_range(0..4))
+            .map(|_| {
+                (
+                    H256::from_low_u64_be(generator.rng.gen_range(0..8)),
+                    U256::from(generator.rng.gen_range(1..=u64::MAX)),
+                )
+            })
+            .collect();
+        let balance = U256::from(generator.rng.gen_range(0..1_000_000u64));
+        pre_state.insert_account(contract, balance, 1, code, storage);
+    }
+
+    let header = BlockHeader {
+        number: 1,
+        coinbase: Address::from_low_u64_be(COINBASE),
+        gas_limit: BLOCK_GAS_LIMIT,
+        timestamp: 12,
+        prev_randao: H256(generator.rng.r#gen()),
+        base_fee_per_gas: Some(BASE_FEE),
+        blob_gas_used: Some(0),
+        excess_blob_gas: Some(0),
+        ..Default::default()
+    };
+
+    let mut nonces: HashMap<Address, u64> = HashMap::new();
+    let transactions = (0..config.transactions)
+        .map(|_| generator.transaction(&mut nonces, config.code_size))
+        .collect();
+
+    FuzzCase {
+        seed,
+        pre_state,
+        header,
+        transactions,
+    }
+}
+
+struct Generator {
+    rng: StdRng,
+    senders: Vec<Address>,
+    contracts: Vec<Address>,
+    /// Every byte decoding to a defined opcode.
+    opcodes: Vec<u8>,
+}
+
+impl Generator {
+    fn address(&mut self) -> Address {
+        match self.rng.gen_range(0..4) {
+            0 => Address::from_low_u64_be(self.rng.gen_range(1..=LAST_PRECOMPILE)),
+            1 => *self
+                .senders
+                .choose(&mut self.rng)
+                .unwrap_or(&Address::zero()),
+            2 => Address(self.rng.r#gen()),
+            _ => *self
+                .contracts
+                .choose(&mut self.rng)
+                .unwrap_or(&Address::zero()),
+        }
+    }
+
+    /// Pushes a value likely to be meaningful as an opcode argument: small numbers for offsets and
+    /// sizes, known addresses, or arbitrary words.
+    fn push_value(&mut self, code: &mut Vec<u8>) {
+        match self.rng.gen_range(0..6) {
+            0..=2 => {
+                code.push(Opcode::PUSH1.into());
+                code.push(self.rng.gen_range(0..=64));
+            }
+            3 => {
+                code.push(Opcode::PUSH20.into());
+                code.extend_from_slice(self.address().as_bytes());
+            }
+            4 => {
+                code.push(Opcode::PUSH2.into());
+                code.extend_from_slice(&self.rng.gen_range(0..=u16::MAX).to_be_bytes());
+            }
+            _ => {
+                code.push(Opcode::PUSH32.into());
+                code.extend_from_slice(H256(self.rng.r#gen()).as_bytes());
+            }
+        }
+    }
+
+    /// Random bytecode made of `size` opcodes, each one preceded by pushes of its arguments so
+    /// that executions usually go further than the first opcode.
+    fn code(&mut self, size: usize) -> Bytes {
+        let mut code = Vec::new();
+        for _ in 0..size {
+            let opcode = *self.opcodes.choose(&mut self.rng).unwrap_or(&0);
+            let push_size = opcode.wrapping_sub(Opcode::PUSH0.into());
+            if (1..=32).contains(&push_size) {
+                code.push(opcode);
+                code.extend((0..push_size).map(|_| self.rng.r#gen::<u8>()));
+                continue;
+            }
+            for _ in 0..OPCODE_ARGUMENTS {
+                self.push_value(&mut code);
+            }
+            code.push(opcode);
+        }
+        Bytes::from(code)
+    }
+
+    /// Init code running random bytecode and then returning another random bytecode as the
+    /// deployed code.
+    fn init_code(&mut self, size: usize) -> Bytes {
+        let runtime = self.code(size);
+        let mut code = self.code(size / 4).to_vec();
+        let mut offset = 0;
+        for chunk in runtime.chunks(32) {
+            let mut word = [0; 32];
+            word[..chunk.len()].copy_from_slice(chunk);
+            code.push(Opcode::PUSH32.into());
+            code.extend_from_slice(&word);
+            code.push(Opcode::PUSH2.into());
+            code.extend_from_slice(&(offset as u16).to_be_bytes());
+            code.push(Opcode::MSTORE.into());
+            offset += 32;
+        }
+        code.push(Opcode::PUSH2.into());
+        code.extend_from_slice(&(runtime.len() as u16).to_be_bytes());
+        code.extend_from_slice(&[Opcode::PUSH1.into(), 0, Opcode::RETURN.into()]);
+        Bytes::from(code)
+    }
+
+    fn transaction(
+        &mut self,
+        nonces: &mut HashMap<Address, u64>,
+        code_size: usize,
+    ) -> (Transaction, Address) {
+        let sender = *self
+            .senders
+            .choose(&mut self.rng)
+            .unwrap_or(&Address::zero());
+        let nonce = nonces.entry(sender).or_default();
+        // Sometimes use a wrong nonce, both engines must reject the transaction
+        let tx_nonce = if self.rng.gen_ratio(1, 32) {
+            nonce.wrapping_add(1)
+        } else {
+            *nonce += 1;
+            *nonce - 1
+        };
+
+        let (to, data) = if self.rng.gen_ratio(1, 8) {
+            (TxKind::Create, self.init_code(code_size))
+        } else {
+            let length = self.rng.gen_range(0..=68);
+            let data: Vec<u8> = (0..length).map(|_| self.rng.r#gen()).collect();
+            (TxKind::Call(self.address()), Bytes::from(data))
+        };
+        let gas_limit = self.rng.gen_range(21_000..=1_000_000);
+        let value = U256::from(self.rng.gen_range(0..1_000_000u64));
+        let gas_price = BASE_FEE + self.rng.gen_range(0..10);
+        let access_list = if self.rng.gen_bool(0.5) {
+            vec![(
+                self.address(),
+                (0..self.rng.gen_range(0..3))
+                    .map(|_| H256::from_low_u64_be(self.rng.gen_range(0..8)))
+                    .collect(),
+            )]
+        } else {
+            Vec::new()
+        };
+
+        let tx = match self.rng.gen_range(0..3) {
+            0 => Transaction::LegacyTransaction(LegacyTransaction {
+                nonce: tx_nonce,
+                gas_price,
+                gas: gas_limit,
+                to,
+                value,
+                data,
+                ..Default::default()
+            }),
+            1 => Transaction::EIP2930Transaction(EIP2930Transaction {
+                chain_id: CHAIN_ID,
+                nonce: tx_nonce,
+                gas_price,
+                gas_limit,
+                to,
+                value,
+                data,
+                access_list,
+                ..Default::default()
+            }),
+            _ => Transaction::EIP1559Transaction(EIP1559Transaction {
+                chain_id: CHAIN_ID,
+                nonce: tx_nonce,
+                max_priority_fee_per_gas: self.rng.gen_range(0..10),
+                max_fee_per_gas: gas_price,
+                gas_limit,
+                to,
+                value,
+                data,
+                access_list,
+                ..Default::default()
+            }),
+        };
+
+        (tx, sender)
+    }
+}
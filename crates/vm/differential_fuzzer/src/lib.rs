@@ -0,0 +1,7 @@
+//! Differential fuzzer running random transactions through both LEVM and REVM over identical
+//! pre-states, reporting every divergence in their outcomes, receipts, gas usage and resulting
+//! account updates.
+
+pub mod db;
+pub mod generator;
+pub mod runner;
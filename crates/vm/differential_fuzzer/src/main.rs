@@ -0,0 +1,80 @@
+use clap::Parser;
+use differential_fuzzer::{
+    generator::{GeneratorConfig, generate_case},
+    runner::run_case,
+};
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(about = "Runs random transactions through both LEVM and REVM and reports any divergence")]
+struct Cli {
+    #[arg(
+        long,
+        help = "Seed of the first case, a random one is used if not given"
+    )]
+    seed: Option<u64>,
+
+    #[arg(long, default_value_t = 1000, help = "Amount of cases to run")]
+    cases: u64,
+
+    #[arg(long, default_value_t = 16, help = "Transactions per case")]
+    transactions: usize,
+
+    #[arg(long, default_value_t = 4, help = "Accounts sending transactions")]
+    senders: usize,
+
+    #[arg(long, default_value_t = 8, help = "Contracts with random code")]
+    contracts: usize,
+
+    #[arg(long, default_value_t = 48, help = "Opcodes of each random contract")]
+    code_size: usize,
+
+    #[arg(long, action = clap::ArgAction::SetTrue, help = "Keep running after the first divergent case")]
+    keep_going: bool,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let config = GeneratorConfig {
+        senders: cli.senders,
+        contracts: cli.contracts,
+        transactions: cli.transactions,
+        code_size: cli.code_size,
+    };
+    let first_seed = cli.seed.unwrap_or_else(rand::random);
+    println!(
+        "Running {} cases starting from seed {first_seed}",
+        cli.cases
+    );
+
+    let mut divergent_cases = 0;
+    for seed in (0..cli.cases).map(|i| first_seed.wrapping_add(i)) {
+        let case = generate_case(seed, &config);
+        let divergences = match run_case(&case) {
+            Ok(divergences) => divergences,
+            Err(error) => {
+                eprintln!("Seed {seed}: execution failed: {error}");
+                return ExitCode::FAILURE;
+            }
+        };
+        if divergences.is_empty() {
+            continue;
+        }
+
+        divergent_cases += 1;
+        println!("Seed {seed}: {} divergences", divergences.len());
+        for divergence in divergences {
+            println!("  {divergence}");
+        }
+        if !cli.keep_going {
+            break;
+        }
+    }
+
+    if divergent_cases > 0 {
+        println!("{divergent_cases} divergent cases, rerun them with --seed <seed> --cases 1");
+        return ExitCode::FAILURE;
+    }
+    println!("No divergences found");
+    ExitCode::SUCCESS
+}
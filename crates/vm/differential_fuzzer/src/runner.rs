@@ -0,0 +1,386 @@
+use crate::{db::FuzzDatabase, generator::FuzzCase};
+use ethrex_common::{
+    Address, H256, U256,
+    types::{AccountInfo, AccountUpdate, Receipt},
+};
+use ethrex_vm::{Evm, EvmEngine, EvmError};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+/// Outcome of a transaction: its receipt and gas used, or the reason it was rejected.
+pub type TxOutcome = Result<(Receipt, u64), String>;
+
+/// Result of executing a [FuzzCase] with a single engine.
+#[derive(Debug)]
+pub struct Execution {
+    pub outcomes: Vec<TxOutcome>,
+    /// The account updates of the execution, see [merge_account_updates].
+    pub account_updates: BTreeMap<Address, AccountUpdate>,
+    /// The pre-state with the account updates of the execution applied.
+    pub post_state: FuzzDatabase,
+}
+
+/// Difference found between the LEVM and REVM executions of a case.
+#[derive(Debug)]
+pub enum Divergence {
+    /// One engine rejected the transaction while the other one executed it.
+    Validity {
+        tx_index: usize,
+        levm: TxOutcome,
+        revm: TxOutcome,
+    },
+    Receipt {
+        tx_index: usize,
+        levm: Receipt,
+        revm: Receipt,
+    },
+    GasUsed {
+        tx_index: usize,
+        levm: u64,
+        revm: u64,
+    },
+    AccountUpdate {
+        address: Address,
+        levm: Option<AccountUpdate>,
+        revm: Option<AccountUpdate>,
+    },
+    Account {
+        address: Address,
+        levm: Option<AccountInfo>,
+        revm: Option<AccountInfo>,
+    },
+    Storage {
+        address: Address,
+        key: H256,
+        levm: U256,
+        revm: U256,
+    },
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Divergence::Validity {
+                tx_index,
+                levm,
+                revm,
+            } => write!(
+                f,
+                "tx {tx_index}: outcome differs\n  levm: {levm:?}\n  revm: {revm:?}"
+            ),
+            Divergence::Receipt {
+                tx_index,
+                levm,
+                revm,
+            } => write!(
+                f,
+                "tx {tx_index}: receipt differs\n  levm: {levm:?}\n  revm: {revm:?}"
+            ),
+            Divergence::GasUsed {
+                tx_index,
+                levm,
+                revm,
+            } => write!(
+                f,
+                "tx {tx_index}: gas used differs, levm: {levm}, revm: {revm}"
+            ),
+            Divergence::AccountUpdate {
+                address,
+                levm,
+                revm,
+            } => write!(
+                f,
+                "account update of {address:#x} differs\n  levm: {levm:?}\n  revm: {revm:?}"
+            ),
+            Divergence::Account {
+                address,
+                levm,
+                revm,
+            } => write!(
+                f,
+                "account {address:#x} differs\n  levm: {levm:?}\n  revm: {revm:?}"
+            ),
+            Divergence::Storage {
+                address,
+                key,
+                levm,
+                revm,
+            } => write!(
+                f,
+                "storage {key:#x} of {address:#x} differs, levm: {levm}, revm: {revm}"
+            ),
+        }
+    }
+}
+
+/// Executes the case's transactions one after the other with the given engine.
+/// Rejected transactions don't stop the execution, they are just recorded as such.
+pub fn execute_case(case: &FuzzCase, engine: EvmEngine) -> Result<Execution, EvmError> {
+    let mut evm = Evm::new_for_l1(engine, case.pre_state.clone());
+    let mut remaining_gas = case.header.gas_limit;
+
+    let outcomes = case
+        .transactions
+        .iter()
+        .map(|(tx, sender)| {
+            evm.execute_tx(tx, &case.header, &mut remaining_gas, *sender)
+                .map_err(|error| error.to_string())
+        })
+        .collect();
+
+    let account_updates = evm.get_state_transitions()?;
+    let mut post_state = case.pre_state.clone();
+    post_state.apply_account_updates(&account_updates);
+
+    Ok(Execution {
+        outcomes,
+        account_updates: merge_account_updates(&account_updates),
+        post_state,
+    })
+}
+
+/// Merges the updates of each account into a single one, as engines may split them differently.
+/// An account removed and created again ends up as a removed account with the info, code and
+/// storage it was created with.
+pub fn merge_account_updates(
+    account_updates: &[AccountUpdate],
+) -> BTreeMap<Address, AccountUpdate> {
+    let mut merged_updates: BTreeMap<Address, AccountUpdate> = BTreeMap::new();
+    for update in account_updates {
+        let merged = merged_updates
+            .entry(update.address)
+            .or_insert_with(|| AccountUpdate::new(update.address));
+        if update.removed {
+            *merged = AccountUpdate::removed(update.address);
+        }
+        if let Some(info) = &update.info {
+            merged.info = Some(info.clone());
+        }
+        if let Some(code) = &update.code {
+            merged.code = Some(code.clone());
+        }
+        merged.added_storage.extend(&update.added_storage);
+    }
+    merged_updates
+}
+
+/// Runs the case through both LEVM and REVM, returning every difference between their executions.
+pub fn run_case(case: &FuzzCase) -> Result<Vec<Divergence>, EvmError> {
+    let levm = execute_case(case, EvmEngine::LEVM)?;
+    let revm = execute_case(case, EvmEngine::REVM)?;
+    Ok(diff_executions(levm, revm))
+}
+
+pub fn diff_executions(levm: Execution, revm: Execution) -> Vec<Divergence> {
+    let mut divergences = Vec::new();
+
+    for (tx_index, (levm_outcome, revm_outcome)) in
+        levm.outcomes.into_iter().zip(revm.outcomes).enumerate()
+    {
+        match (levm_outcome, revm_outcome) {
+            // Both engines rejected the transaction, their errors aren't expected to match
+            (Err(_), Err(_)) => {}
+            (Ok((levm_receipt, levm_gas)), Ok((revm_receipt, revm_gas))) => {
+                if levm_receipt != revm_receipt {
+                    divergences.push(Divergence::Receipt {
+                        tx_index,
+                        levm: levm_receipt,
+                        revm: revm_receipt,
+                    });
+                }
+                if levm_gas != revm_gas {
+                    divergences.push(Divergence::GasUsed {
+                        tx_index,
+                        levm: levm_gas,
+                        revm: revm_gas,
+                    });
+                }
+            }
+            (levm, revm) => divergences.push(Divergence::Validity {
+                tx_index,
+                levm,
+                revm,
+            }),
+        }
+    }
+
+    let addresses: BTreeSet<&Address> = levm
+        .account_updates
+        .keys()
+        .chain(revm.account_updates.keys())
+        .collect();
+    for address in addresses {
+        let levm_update = levm.account_updates.get(address);
+        let revm_update = revm.account_updates.get(address);
+        if levm_update != revm_update {
+            divergences.push(Divergence::AccountUpdate {
+                address: *address,
+                levm: levm_update.cloned(),
+                revm: revm_update.cloned(),
+            });
+        }
+    }
+
+    let (levm, revm) = (levm.post_state, revm.post_state);
+    let addresses: BTreeSet<&Address> = levm.accounts.keys().chain(revm.accounts.keys()).collect();
+    for address in addresses {
+        let levm_account = levm.accounts.get(address);
+        let revm_account = revm.accounts.get(address);
+        if levm_account != revm_account {
+            divergences.push(Divergence::Account {
+                address: *address,
+                levm: levm_account.cloned(),
+                revm: revm_account.cloned(),
+            });
+        }
+    }
+
+    let addresses: BTreeSet<&Address> = levm.storage.keys().chain(revm.storage.keys()).collect();
+    for address in addresses {
+        let levm_storage = levm.storage.get(address);
+        let revm_storage = revm.storage.get(address);
+        let keys: BTreeSet<&H256> = levm_storage
+            .into_iter()
+            .chain(revm_storage)
+            .flat_map(|storage| storage.keys())
+            .collect();
+        for key in keys {
+            let levm_value = levm_storage
+                .and_then(|storage| storage.get(key))
+                .copied()
+                .unwrap_or_default();
+            let revm_value = revm_storage
+                .and_then(|storage| storage.get(key))
+                .copied()
+                .unwrap_or_default();
+            if levm_value != revm_value {
+                divergences.push(Divergence::Storage {
+                    address: *address,
+                    key: *key,
+                    levm: levm_value,
+                    revm: revm_value,
+                });
+            }
+        }
+    }
+
+    divergences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{GeneratorConfig, generate_case};
+    use bytes::Bytes;
+
+    #[test]
+    fn same_seed_generates_same_case() {
+        let config = GeneratorConfig::default();
+        let case = generate_case(7, &config);
+        let other = generate_case(7, &config);
+
+        assert_eq!(case.transactions, other.transactions);
+        assert_eq!(case.pre_state.accounts, other.pre_state.accounts);
+    }
+
+    #[test]
+    fn diff_reports_post_state_differences() {
+        let case = generate_case(7, &GeneratorConfig::default());
+        let address = Address::from_low_u64_be(0x2000);
+        let key = H256::from_low_u64_be(0x100);
+
+        let levm = Execution {
+            outcomes: vec![Err("nonce too high".to_string())],
+            account_updates: BTreeMap::new(),
+            post_state: case.pre_state.clone(),
+        };
+        let mut revm = Execution {
+            outcomes: vec![Err("Nonce mismatch".to_string())],
+            account_updates: BTreeMap::new(),
+            post_state: case.pre_state.clone(),
+        };
+        revm.post_state
+            .storage
+            .entry(address)
+            .or_default()
+            .insert(key, U256::one());
+
+        let divergences = diff_executions(levm, revm);
+        assert!(matches!(
+            divergences.as_slice(),
+            [Divergence::Storage { levm, revm, .. }] if levm.is_zero() && *revm == U256::one()
+        ));
+    }
+
+    #[test]
+    fn recreated_accounts_keep_their_new_state() {
+        let address = Address::from_low_u64_be(0x2000);
+        let old_key = H256::from_low_u64_be(1);
+        let new_key = H256::from_low_u64_be(2);
+        let info = AccountInfo {
+            nonce: 1,
+            ..Default::default()
+        };
+        let mut state = FuzzDatabase::default();
+        state.insert_account(
+            address,
+            U256::one(),
+            1,
+            Bytes::new(),
+            BTreeMap::from([(old_key, U256::one())]),
+        );
+        let recreated = AccountUpdate {
+            info: Some(info.clone()),
+            added_storage: BTreeMap::from([(new_key, U256::one())]),
+            ..AccountUpdate::new(address)
+        };
+        // LEVM removes the account and then creates it in another update
+        let split = merge_account_updates(&[AccountUpdate::removed(address), recreated.clone()]);
+        let single = merge_account_updates(&[AccountUpdate {
+            removed: true,
+            ..recreated
+        }]);
+        assert_eq!(split, single);
+
+        state.apply_account_updates(&split.into_values().collect::<Vec<_>>());
+        assert_eq!(state.accounts.get(&address), Some(&info));
+        assert_eq!(
+            state.storage.get(&address),
+            Some(&BTreeMap::from([(new_key, U256::one())]))
+        );
+    }
+
+    #[test]
+    fn diff_reports_account_update_differences() {
+        let case = generate_case(7, &GeneratorConfig::default());
+        let address = Address::from_low_u64_be(0x2000);
+        let update = AccountUpdate {
+            added_storage: BTreeMap::from([(H256::zero(), U256::zero())]),
+            ..AccountUpdate::new(address)
+        };
+
+        let levm = Execution {
+            outcomes: Vec::new(),
+            account_updates: BTreeMap::from([(address, update)]),
+            post_state: case.pre_state.clone(),
+        };
+        let revm = Execution {
+            outcomes: Vec::new(),
+            account_updates: BTreeMap::new(),
+            post_state: case.pre_state.clone(),
+        };
+
+        // Writing a zero to an empty slot leaves the same state, but not the same updates
+        let divergences = diff_executions(levm, revm);
+        assert!(matches!(
+            divergences.as_slice(),
+            [Divergence::AccountUpdate {
+                levm: Some(_),
+                revm: None,
+                ..
+            }]
+        ));
+    }
+}
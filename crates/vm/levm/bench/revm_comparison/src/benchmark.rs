@@ -1,22 +1,28 @@
-use revm_comparison::{levm_bench::run_with_levm, revm_bench::run_with_revm};
+use revm_comparison::{
+    levm_bench::{run_with_levm, run_with_levm_without_code_cache},
+    revm_bench::run_with_revm,
+};
 use sha3::{Digest, Keccak256};
 use std::{fs::File, io::Read};
 
 enum VM {
     Revm,
     Levm,
+    LevmNoCodeCache,
 }
 
 const DEFAULT_REPETITIONS: u64 = 10;
 const DEFAULT_ITERATIONS: u64 = 100;
 
 fn main() {
-    let usage = "usage: benchmark [revm/levm] [bench_name] (#repetitions) (#iterations)";
+    let usage =
+        "usage: benchmark [revm/levm/levm-no-code-cache] [bench_name] (#repetitions) (#iterations)";
 
     let vm = std::env::args().nth(1).expect(usage);
     let vm = match vm.as_str() {
         "levm" => VM::Levm,
         "revm" => VM::Revm,
+        "levm-no-code-cache" => VM::LevmNoCodeCache,
         _ => {
             eprintln!("{usage}");
             std::process::exit(1);
@@ -43,6 +49,7 @@ fn main() {
     match vm {
         VM::Levm => run_with_levm(&bytecode, runs, &calldata),
         VM::Revm => run_with_revm(&bytecode, runs, &calldata),
+        VM::LevmNoCodeCache => run_with_levm_without_code_cache(&bytecode, runs, &calldata),
     }
 }
 
@@ -5,6 +5,7 @@ use ethrex_common::{
     Address, U256,
     types::{Account, EIP1559Transaction, Transaction, TxKind},
 };
+use ethrex_levm::code_cache::CODE_CACHE;
 use ethrex_levm::errors::VMError;
 use ethrex_levm::{
     Environment,
@@ -24,6 +25,16 @@ const SENDER_ADDRESS: u64 = 0x100;
 const CONTRACT_ADDRESS: u64 = 0x42;
 
 pub fn run_with_levm(contract_code: &str, runs: u64, calldata: &str) {
+    run(contract_code, runs, calldata, false)
+}
+
+/// Same as [run_with_levm], but emptying the bytecode analysis cache before every run so that the
+/// contract is analyzed each time, as it was before the cache existed.
+pub fn run_with_levm_without_code_cache(contract_code: &str, runs: u64, calldata: &str) {
+    run(contract_code, runs, calldata, true)
+}
+
+fn run(contract_code: &str, runs: u64, calldata: &str, clear_code_cache: bool) {
     let bytecode = Bytes::from(hex::decode(contract_code).unwrap());
     let calldata = Bytes::from(hex::decode(calldata).unwrap());
 
@@ -31,10 +42,16 @@ pub fn run_with_levm(contract_code: &str, runs: u64, calldata: &str) {
 
     // when using stateful execute() we have to use nonce when instantiating the vm. Otherwise use 0.
     for _nonce in 0..runs - 1 {
+        if clear_code_cache {
+            CODE_CACHE.clear();
+        }
         let mut vm = init_vm(&mut db, 0, calldata.clone()).unwrap();
         let tx_report = black_box(vm.stateless_execute().unwrap());
         assert!(tx_report.is_success());
     }
+    if clear_code_cache {
+        CODE_CACHE.clear();
+    }
     let mut vm = init_vm(&mut db, 0, calldata.clone()).unwrap();
     let tx_report = black_box(vm.stateless_execute().unwrap());
 
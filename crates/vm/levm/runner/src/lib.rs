@@ -1 +1,2 @@
 pub mod input;
+pub mod t8n;
@@ -1,5 +1,5 @@
 use bytes::Bytes;
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
 use env_logger::Env;
 use ethrex_blockchain::vm::StoreVmDatabase;
 use ethrex_common::{
@@ -19,11 +19,15 @@ use ethrex_vm::DynVmDatabase;
 use log::{debug, error, info};
 use num_bigint::BigUint;
 use num_traits::Num;
-use runner::input::{InputAccount, InputTransaction, RunnerInput};
+use runner::{
+    input::{InputAccount, InputTransaction, RunnerInput},
+    t8n::{self, T8nInput, parse_fork, parse_txs_rlp},
+};
 use std::{collections::BTreeMap, io::Write};
 use std::{
     fs::{self, File},
     io::BufReader,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 
@@ -46,6 +50,69 @@ struct Cli {
         help = "Converts mnemonics file into a bytecode file"
     )]
     emit_bytes: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[command(about = "Executes a block of transactions over a pre-state, like geth's `evm t8n`")]
+    T8n(T8nArgs),
+}
+
+#[derive(Args)]
+struct T8nArgs {
+    #[arg(
+        long = "input.alloc",
+        default_value = "alloc.json",
+        help = "Pre-state alloc file"
+    )]
+    input_alloc: PathBuf,
+
+    #[arg(
+        long = "input.env",
+        default_value = "env.json",
+        help = "Block environment file"
+    )]
+    input_env: PathBuf,
+
+    #[arg(
+        long = "input.txs",
+        default_value = "txs.rlp",
+        help = "Hex encoded RLP list of signed transactions"
+    )]
+    input_txs: PathBuf,
+
+    #[arg(
+        long = "output.basedir",
+        default_value = ".",
+        help = "Directory where the outputs and traces are written"
+    )]
+    output_basedir: PathBuf,
+
+    #[arg(
+        long = "output.result",
+        default_value = "result.json",
+        help = "Result file, relative to the basedir"
+    )]
+    output_result: PathBuf,
+
+    #[arg(
+        long = "output.alloc",
+        default_value = "alloc.json",
+        help = "Post-state alloc file, relative to the basedir"
+    )]
+    output_alloc: PathBuf,
+
+    #[arg(long = "state.fork", default_value = "Prague", value_parser = parse_fork, help = "Fork to execute the block with, Paris or later")]
+    fork: ethrex_common::types::Fork,
+
+    #[arg(long = "state.chainid", default_value_t = 1)]
+    chain_id: u64,
+
+    #[arg(long, action = clap::ArgAction::SetTrue, help = "Write an EIP-3155 trace of each transaction to the basedir")]
+    trace: bool,
 }
 
 fn main() {
@@ -56,6 +123,11 @@ fn main() {
         .format(|buf, record| writeln!(buf, "{}", record.args()))
         .init();
 
+    if let Some(Command::T8n(args)) = cli.command {
+        run_t8n(args);
+        return;
+    }
+
     // Subcommand for just converting mnemonics to bytecode without executing
     if let Some(mnemonics_path) = cli.emit_bytes {
         let file_content =
@@ -200,6 +272,46 @@ fn main() {
     );
 }
 
+fn run_t8n(args: T8nArgs) {
+    let read = |path: &Path| {
+        fs::read_to_string(path)
+            .unwrap_or_else(|_| panic!("Failed to read input file '{}'", path.display()))
+    };
+    let alloc = serde_json::from_str(&read(&args.input_alloc)).expect("Failed to parse alloc");
+    let env = serde_json::from_str(&read(&args.input_env)).expect("Failed to parse env");
+    let txs = parse_txs_rlp(&read(&args.input_txs)).expect("Failed to parse transactions");
+
+    let output = t8n::run(T8nInput {
+        alloc,
+        env,
+        txs,
+        fork: args.fork,
+        chain_id: args.chain_id,
+        trace_dir: args.trace.then(|| args.output_basedir.clone()),
+    })
+    .expect("Failed to execute transactions");
+
+    for rejected in &output.result.rejected {
+        info!(
+            "Rejected transaction {}: {}",
+            rejected.index, rejected.error
+        );
+    }
+    let write = |path: &Path, contents: String| {
+        let path = args.output_basedir.join(path);
+        fs::write(&path, contents)
+            .unwrap_or_else(|_| panic!("Failed to write output file '{}'", path.display()));
+    };
+    write(
+        &args.output_result,
+        serde_json::to_string_pretty(&output.result).expect("Failed to serialize result"),
+    );
+    write(
+        &args.output_alloc,
+        serde_json::to_string_pretty(&output.alloc).expect("Failed to serialize alloc"),
+    );
+}
+
 /// Prints on screen difference between initial state and current one.
 fn compare_initial_and_current_accounts(
     initial_accounts: BTreeMap<Address, LevmAccount>,
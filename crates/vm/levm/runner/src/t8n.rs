@@ -0,0 +1,757 @@
+//! State transition tool with the interface of geth's `evm t8n`: executes a block of transactions
+//! over a pre-state, given as `alloc.json`, `env.json` and `txs.rlp`, and outputs the post-state
+//! alloc along with a `result.json` holding the roots, receipts and rejected transactions.
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, Bloom, H160, H256, U256,
+    constants::{EMPTY_KECCACK_HASH, GAS_PER_BLOB},
+    serde_utils,
+    types::{
+        AccountInfo, AccountUpdate, Block, BlockBody, BlockHeader, ChainConfig,
+        ELASTICITY_MULTIPLIER, Fork, Genesis, GenesisAccount, Log, Receipt, Transaction, TxKind,
+        Withdrawal, bloom_from_logs, calc_excess_blob_gas, calculate_base_fee_per_gas, code_hash,
+        compute_receipts_root, compute_transactions_root, compute_withdrawals_root,
+        requests::{EncodedRequests, compute_requests_hash},
+    },
+};
+use ethrex_levm::{
+    db::{Database, gen_db::GeneralizedDatabase},
+    errors::{DatabaseError, ExecutionReport},
+    native_tracers::Eip3155Tracer,
+    tracing::{LevmCallTracer, Tracer},
+    vm::{VM, VMType},
+};
+use ethrex_rlp::encode::RLPEncode;
+use ethrex_vm::{EvmError, backends::levm::LEVM, create_contract_address};
+use keccak_hash::keccak;
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    rc::Rc,
+    str::FromStr,
+    sync::Arc,
+};
+
+pub type Alloc = BTreeMap<Address, GenesisAccount>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum T8nError {
+    #[error("Unsupported fork {0:?}, only Paris and later are supported")]
+    UnsupportedFork(Fork),
+    #[error("Invalid env: {0}")]
+    InvalidEnv(String),
+    #[error(transparent)]
+    Evm(#[from] EvmError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Block environment, in the `env.json` format.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct T8nEnv {
+    pub current_coinbase: Address,
+    #[serde(deserialize_with = "serde_utils::u64::deser_hex_or_dec_str")]
+    pub current_gas_limit: u64,
+    #[serde(deserialize_with = "serde_utils::u64::deser_hex_or_dec_str")]
+    pub current_number: u64,
+    #[serde(deserialize_with = "serde_utils::u64::deser_hex_or_dec_str")]
+    pub current_timestamp: u64,
+    #[serde(default, deserialize_with = "serde_utils::u256::deser_hex_str_opt")]
+    pub current_difficulty: Option<U256>,
+    #[serde(default)]
+    pub current_random: Option<H256>,
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub current_base_fee: Option<u64>,
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub parent_base_fee: Option<u64>,
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub parent_gas_used: Option<u64>,
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub parent_gas_limit: Option<u64>,
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub current_excess_blob_gas: Option<u64>,
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub parent_excess_blob_gas: Option<u64>,
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub parent_blob_gas_used: Option<u64>,
+    #[serde(default)]
+    pub parent_beacon_block_root: Option<H256>,
+    #[serde(default)]
+    pub withdrawals: Option<Vec<Withdrawal>>,
+    /// Hashes of previous blocks, keyed by their number either in hex or decimal.
+    #[serde(default)]
+    pub block_hashes: BTreeMap<String, H256>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct T8nLog {
+    pub address: Address,
+    pub topics: Vec<H256>,
+    #[serde(with = "serde_utils::bytes")]
+    pub data: Bytes,
+}
+
+impl From<Log> for T8nLog {
+    fn from(log: Log) -> Self {
+        Self {
+            address: log.address,
+            topics: log.topics,
+            data: log.data,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct T8nReceipt {
+    #[serde(rename = "type", with = "serde_utils::u64::hex_str")]
+    pub tx_type: u64,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub status: u64,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub cumulative_gas_used: u64,
+    pub logs_bloom: Bloom,
+    pub logs: Vec<T8nLog>,
+    pub transaction_hash: H256,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract_address: Option<Address>,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub gas_used: u64,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub transaction_index: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RejectedTx {
+    pub index: usize,
+    pub error: String,
+}
+
+/// Output of the execution, in the `result.json` format.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct T8nResult {
+    pub state_root: H256,
+    pub tx_root: H256,
+    pub receipts_root: H256,
+    pub logs_hash: H256,
+    pub logs_bloom: Bloom,
+    pub receipts: Vec<T8nReceipt>,
+    pub rejected: Vec<RejectedTx>,
+    pub current_difficulty: U256,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub gas_used: u64,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "serde_utils::u64::hex_str_opt"
+    )]
+    pub current_base_fee: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub withdrawals_root: Option<H256>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "serde_utils::u64::hex_str_opt"
+    )]
+    pub current_excess_blob_gas: Option<u64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "serde_utils::u64::hex_str_opt"
+    )]
+    pub blob_gas_used: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requests_hash: Option<H256>,
+    /// Hex encoded requests of each type, omitting the types without requests.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requests: Option<Vec<String>>,
+}
+
+pub struct T8nInput {
+    pub alloc: Alloc,
+    pub env: T8nEnv,
+    pub txs: Vec<Transaction>,
+    pub fork: Fork,
+    pub chain_id: u64,
+    /// If set, an EIP-3155 trace of each executed transaction is written to a `trace-<index>-<hash>.jsonl` file in this directory.
+    pub trace_dir: Option<PathBuf>,
+}
+
+pub struct T8nOutput {
+    pub result: T8nResult,
+    pub alloc: Alloc,
+}
+
+/// Chain config with every fork up to the given one active from genesis.
+pub fn chain_config(fork: Fork, chain_id: u64) -> Result<ChainConfig, T8nError> {
+    if fork < Fork::Paris {
+        return Err(T8nError::UnsupportedFork(fork));
+    }
+    let activation_time = |activation_fork: Fork| (fork >= activation_fork).then_some(0);
+
+    Ok(ChainConfig {
+        chain_id,
+        homestead_block: Some(0),
+        eip150_block: Some(0),
+        eip155_block: Some(0),
+        eip158_block: Some(0),
+        byzantium_block: Some(0),
+        constantinople_block: Some(0),
+        petersburg_block: Some(0),
+        istanbul_block: Some(0),
+        muir_glacier_block: Some(0),
+        berlin_block: Some(0),
+        london_block: Some(0),
+        arrow_glacier_block: Some(0),
+        gray_glacier_block: Some(0),
+        merge_netsplit_block: Some(0),
+        terminal_total_difficulty: Some(0),
+        terminal_total_difficulty_passed: true,
+        shanghai_time: activation_time(Fork::Shanghai),
+        cancun_time: activation_time(Fork::Cancun),
+        prague_time: activation_time(Fork::Prague),
+        osaka_time: activation_time(Fork::Osaka),
+        deposit_contract_address: H160::from_str("0x00000000219ab540356cbb839cbe05303d7705fa")
+            .unwrap_or_default(),
+        ..Default::default()
+    })
+}
+
+/// Parses the name of a fork, accepting geth's `Merge` for Paris.
+pub fn parse_fork(name: &str) -> Result<Fork, String> {
+    let name = if name == "Merge" { "Paris" } else { name };
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .map_err(|_| format!("Unknown fork {name}"))
+}
+
+/// Parses the contents of a `txs.rlp` file: the hex encoded RLP list of transactions, optionally
+/// as a JSON string.
+pub fn parse_txs_rlp(contents: &str) -> Result<Vec<Transaction>, String> {
+    let hex = contents.trim().trim_matches('"').trim_start_matches("0x");
+    let rlp = hex::decode(hex).map_err(|error| format!("Invalid txs hex: {error}"))?;
+    ethrex_rlp::decode::RLPDecode::decode(&rlp).map_err(|error| format!("Invalid txs RLP: {error}"))
+}
+
+/// Pre-state given by the alloc, along with the chain config and previous block hashes.
+struct AllocDatabase {
+    chain_config: ChainConfig,
+    alloc: Alloc,
+    codes: BTreeMap<H256, Bytes>,
+    block_hashes: BTreeMap<u64, H256>,
+}
+
+impl Database for AllocDatabase {
+    fn get_account_info(&self, address: Address) -> Result<AccountInfo, DatabaseError> {
+        Ok(match self.alloc.get(&address) {
+            Some(account) => AccountInfo {
+                code_hash: code_hash(&account.code),
+                balance: account.balance,
+                nonce: account.nonce,
+            },
+            None => AccountInfo {
+                code_hash: *EMPTY_KECCACK_HASH,
+                ..Default::default()
+            },
+        })
+    }
+
+    fn get_storage_value(&self, address: Address, key: H256) -> Result<U256, DatabaseError> {
+        Ok(self
+            .alloc
+            .get(&address)
+            .and_then(|account| account.storage.get(&U256::from_big_endian(key.as_bytes())))
+            .copied()
+            .unwrap_or_default())
+    }
+
+    fn get_block_hash(&self, block_number: u64) -> Result<H256, DatabaseError> {
+        Ok(self
+            .block_hashes
+            .get(&block_number)
+            .copied()
+            .unwrap_or_default())
+    }
+
+    fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError> {
+        Ok(self.chain_config)
+    }
+
+    fn get_account_code(&self, code_hash: H256) -> Result<Bytes, DatabaseError> {
+        if code_hash == *EMPTY_KECCACK_HASH {
+            return Ok(Bytes::new());
+        }
+        self.codes
+            .get(&code_hash)
+            .cloned()
+            .ok_or_else(|| DatabaseError::Custom(format!("Missing code for hash {code_hash:#x}")))
+    }
+}
+
+impl T8nEnv {
+    fn block_hashes(&self) -> Result<BTreeMap<u64, H256>, T8nError> {
+        self.block_hashes
+            .iter()
+            .map(|(number, hash)| {
+                let parsed = match number.strip_prefix("0x") {
+                    Some(hex) => u64::from_str_radix(hex, 16),
+                    None => number.parse(),
+                };
+                parsed
+                    .map(|number| (number, *hash))
+                    .map_err(|_| T8nError::InvalidEnv(format!("Invalid block number {number}")))
+            })
+            .collect()
+    }
+
+    fn header(&self, fork: Fork, chain_config: &ChainConfig) -> Result<BlockHeader, T8nError> {
+        let base_fee_per_gas = match (
+            self.current_base_fee,
+            self.parent_base_fee,
+            self.parent_gas_used,
+            self.parent_gas_limit,
+        ) {
+            (Some(base_fee), ..) => base_fee,
+            (None, Some(parent_base_fee), Some(parent_gas_used), Some(parent_gas_limit)) => {
+                calculate_base_fee_per_gas(
+                    self.current_gas_limit,
+                    parent_gas_limit,
+                    parent_gas_used,
+                    parent_base_fee,
+                    ELASTICITY_MULTIPLIER,
+                )
+                .ok_or(T8nError::InvalidEnv("Invalid gas limit".to_string()))?
+            }
+            _ => {
+                return Err(T8nError::InvalidEnv(
+                    "Either currentBaseFee or the parent gas values are required".to_string(),
+                ));
+            }
+        };
+
+        let excess_blob_gas = if fork < Fork::Cancun {
+            None
+        } else if let Some(excess_blob_gas) = self.current_excess_blob_gas {
+            Some(excess_blob_gas)
+        } else {
+            let schedule = chain_config
+                .get_fork_blob_schedule(self.current_timestamp)
+                .ok_or(T8nError::InvalidEnv("Missing blob schedule".to_string()))?;
+            let parent = BlockHeader {
+                excess_blob_gas: self.parent_excess_blob_gas,
+                blob_gas_used: self.parent_blob_gas_used,
+                base_fee_per_gas: self.parent_base_fee,
+                ..Default::default()
+            };
+            Some(calc_excess_blob_gas(&parent, schedule, fork))
+        };
+
+        Ok(BlockHeader {
+            coinbase: self.current_coinbase,
+            number: self.current_number,
+            gas_limit: self.current_gas_limit,
+            timestamp: self.current_timestamp,
+            difficulty: self.current_difficulty.unwrap_or_default(),
+            prev_randao: self.current_random.unwrap_or_default(),
+            base_fee_per_gas: Some(base_fee_per_gas),
+            excess_blob_gas,
+            blob_gas_used: (fork >= Fork::Cancun).then_some(0),
+            parent_beacon_block_root: self
+                .parent_beacon_block_root
+                .filter(|_| fork >= Fork::Cancun),
+            ..Default::default()
+        })
+    }
+}
+
+/// Executes the transactions over the pre-state, rejecting the ones that are invalid.
+pub fn run(input: T8nInput) -> Result<T8nOutput, T8nError> {
+    let chain_config = chain_config(input.fork, input.chain_id)?;
+    let header = input.env.header(input.fork, &chain_config)?;
+    let withdrawals =
+        (input.fork >= Fork::Shanghai).then(|| input.env.withdrawals.clone().unwrap_or_default());
+    let block = Block::new(
+        header,
+        BlockBody {
+            transactions: Vec::new(),
+            ommers: Vec::new(),
+            withdrawals,
+        },
+    );
+
+    let store = AllocDatabase {
+        chain_config,
+        codes: input
+            .alloc
+            .values()
+            .map(|account| (code_hash(&account.code), account.code.clone()))
+            .collect(),
+        alloc: input.alloc.clone(),
+        block_hashes: input.env.block_hashes()?,
+    };
+    let mut db = GeneralizedDatabase::new(Arc::new(store));
+
+    LEVM::prepare_block(&block, &mut db, VMType::L1)?;
+
+    let mut receipts = Vec::new();
+    let mut t8n_receipts = Vec::new();
+    let mut included_txs = Vec::new();
+    let mut rejected = Vec::new();
+    let mut gas_used: u64 = 0;
+    let mut blob_gas_used: u64 = 0;
+
+    for (index, tx) in input.txs.iter().enumerate() {
+        let sender = match tx.sender() {
+            Ok(sender) => sender,
+            Err(error) => {
+                rejected.push(RejectedTx {
+                    index,
+                    error: format!("Invalid signature: {error}"),
+                });
+                continue;
+            }
+        };
+        if gas_used.saturating_add(tx.gas_limit()) > block.header.gas_limit {
+            rejected.push(RejectedTx {
+                index,
+                error: "Gas limit reached".to_string(),
+            });
+            continue;
+        }
+
+        let tracer = input
+            .trace_dir
+            .as_ref()
+            .map(|trace_dir| trace_file_tracer(trace_dir, index, tx));
+        let dyn_tracer = tracer
+            .clone()
+            .map(|tracer| -> Rc<RefCell<dyn Tracer>> { tracer });
+        let report = match execute_tx(tx, sender, &block.header, &mut db, dyn_tracer) {
+            Ok(report) => report,
+            Err(error) => {
+                rejected.push(RejectedTx {
+                    index,
+                    error: error.to_string(),
+                });
+                continue;
+            }
+        };
+
+        if let Some(tracer) = tracer {
+            let state_root = intermediate_state_root(&input.alloc, &db)?;
+            tracer.borrow_mut().finish(state_root, report.is_success());
+        }
+
+        gas_used = gas_used.saturating_add(report.gas_used);
+        blob_gas_used = blob_gas_used.saturating_add(
+            u64::from(GAS_PER_BLOB).saturating_mul(tx.blob_versioned_hashes().len() as u64),
+        );
+        let receipt = Receipt::new(
+            tx.tx_type(),
+            report.is_success(),
+            gas_used,
+            report.logs.clone(),
+        );
+        let contract_address = match tx.to() {
+            TxKind::Create => Some(create_contract_address(sender, tx.nonce())),
+            TxKind::Call(_) => None,
+        };
+        t8n_receipts.push(T8nReceipt {
+            tx_type: u8::from(tx.tx_type()).into(),
+            status: report.is_success().into(),
+            cumulative_gas_used: gas_used,
+            logs_bloom: bloom_from_logs(&report.logs),
+            logs: report.logs.into_iter().map(Into::into).collect(),
+            transaction_hash: tx.hash(),
+            contract_address,
+            gas_used: report.gas_used,
+            transaction_index: included_txs.len() as u64,
+        });
+        receipts.push(receipt);
+        included_txs.push(tx.clone());
+    }
+
+    let execution_result = LEVM::finalize_block(&block, &mut db, VMType::L1, receipts)?;
+    let account_updates = LEVM::get_state_transitions(&mut db)?;
+    let mut alloc = input.alloc;
+    apply_account_updates(&mut alloc, account_updates);
+
+    let logs: Vec<Log> = execution_result
+        .receipts
+        .iter()
+        .flat_map(|receipt| receipt.logs.clone())
+        .collect();
+    let requests: Option<Vec<EncodedRequests>> = (input.fork >= Fork::Prague).then(|| {
+        execution_result
+            .requests
+            .iter()
+            .map(|requests| requests.encode())
+            .collect()
+    });
+    let state_root = Genesis {
+        alloc: alloc.clone(),
+        ..Default::default()
+    }
+    .compute_state_root();
+
+    let result = T8nResult {
+        state_root,
+        tx_root: compute_transactions_root(&included_txs),
+        receipts_root: compute_receipts_root(&execution_result.receipts),
+        logs_hash: keccak(logs.encode_to_vec()),
+        logs_bloom: bloom_from_logs(&logs),
+        receipts: t8n_receipts,
+        rejected,
+        current_difficulty: block.header.difficulty,
+        gas_used,
+        current_base_fee: block.header.base_fee_per_gas,
+        withdrawals_root: block
+            .body
+            .withdrawals
+            .as_ref()
+            .map(|withdrawals| compute_withdrawals_root(withdrawals)),
+        current_excess_blob_gas: block.header.excess_blob_gas,
+        blob_gas_used: block.header.blob_gas_used.map(|_| blob_gas_used),
+        requests_hash: requests
+            .as_ref()
+            .map(|requests| compute_requests_hash(requests)),
+        requests: requests.map(|requests| {
+            requests
+                .into_iter()
+                .filter(|request| !request.is_empty())
+                .map(|request| format!("0x{:x}", request.0))
+                .collect()
+        }),
+    };
+
+    Ok(T8nOutput { result, alloc })
+}
+
+fn trace_file_tracer(
+    trace_dir: &Path,
+    index: usize,
+    tx: &Transaction,
+) -> Rc<RefCell<Eip3155Tracer>> {
+    let writer = TraceFile {
+        path: trace_dir.join(format!("trace-{index}-{:#x}.jsonl", tx.hash())),
+        file: None,
+    };
+    Rc::new(RefCell::new(Eip3155Tracer::new(Box::new(writer))))
+}
+
+/// Trace file that is only created on its first write, so transactions rejected before their
+/// execution starts don't leave empty traces behind.
+struct TraceFile {
+    path: PathBuf,
+    file: Option<BufWriter<File>>,
+}
+
+impl Write for TraceFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let file = match self.file.take() {
+            Some(file) => file,
+            None => BufWriter::new(File::create(&self.path)?),
+        };
+        self.file.insert(file).write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.file {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// State root after the transactions executed so far, for the summary of their traces.
+fn intermediate_state_root(alloc: &Alloc, db: &GeneralizedDatabase) -> Result<H256, T8nError> {
+    // Getting the state transitions clears the database caches, which the next transactions need
+    let account_updates = LEVM::get_state_transitions(&mut db.clone())?;
+    let mut alloc = alloc.clone();
+    apply_account_updates(&mut alloc, account_updates);
+    Ok(Genesis {
+        alloc,
+        ..Default::default()
+    }
+    .compute_state_root())
+}
+
+fn execute_tx(
+    tx: &Transaction,
+    sender: Address,
+    header: &BlockHeader,
+    db: &mut GeneralizedDatabase,
+    tracer: Option<Rc<RefCell<dyn Tracer>>>,
+) -> Result<ExecutionReport, EvmError> {
+    let env = LEVM::setup_env(tx, sender, header, db)?;
+    let mut vm = VM::new(env, db, tx, LevmCallTracer::disabled(), VMType::L1)?;
+    if let Some(tracer) = tracer {
+        vm.set_tracer(tracer);
+    }
+    Ok(vm.execute()?)
+}
+
+/// Applies the updates to the alloc, the same way the store applies them to the state trie.
+fn apply_account_updates(alloc: &mut Alloc, account_updates: Vec<AccountUpdate>) {
+    for update in account_updates {
+        if update.removed {
+            alloc.remove(&update.address);
+            continue;
+        }
+        let account = alloc.entry(update.address).or_insert(GenesisAccount {
+            code: Bytes::new(),
+            storage: Default::default(),
+            balance: U256::zero(),
+            nonce: 0,
+        });
+        if let Some(info) = update.info {
+            account.balance = info.balance;
+            account.nonce = info.nonce;
+        }
+        if let Some(code) = update.code {
+            account.code = code;
+        }
+        for (key, value) in update.added_storage {
+            let key = U256::from_big_endian(key.as_bytes());
+            if value.is_zero() {
+                account.storage.remove(&key);
+            } else {
+                account.storage.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethrex_common::types::{EIP1559Transaction, TxType};
+    use ethrex_rlp::encode::PayloadRLPEncode;
+    use secp256k1::{Message, SECP256K1, SecretKey};
+
+    #[test]
+    fn parses_forks_with_geth_names() {
+        assert_eq!(parse_fork("Merge"), Ok(Fork::Paris));
+        assert_eq!(parse_fork("Prague"), Ok(Fork::Prague));
+        assert!(parse_fork("Frontier2").is_err());
+    }
+
+    fn sign(mut tx: EIP1559Transaction, key: &SecretKey) -> Transaction {
+        let mut payload = vec![TxType::EIP1559 as u8];
+        payload.append(&mut tx.encode_payload_to_vec());
+        let message = Message::from_digest(keccak(payload).0);
+        let (recovery_id, signature) = SECP256K1
+            .sign_ecdsa_recoverable(&message, key)
+            .serialize_compact();
+        tx.signature_r = U256::from_big_endian(&signature[..32]);
+        tx.signature_s = U256::from_big_endian(&signature[32..]);
+        tx.signature_y_parity = recovery_id.to_i32() != 0;
+        Transaction::EIP1559Transaction(tx)
+    }
+
+    fn account(balance: u64) -> GenesisAccount {
+        GenesisAccount {
+            code: Bytes::new(),
+            storage: Default::default(),
+            balance: U256::from(balance),
+            nonce: 0,
+        }
+    }
+
+    #[test]
+    fn value_transfer_updates_alloc_and_rejects_invalid_txs() {
+        let key = SecretKey::from_slice(&[1; 32]).unwrap();
+        let sender = Address::from(keccak(
+            &key.public_key(SECP256K1).serialize_uncompressed()[1..],
+        ));
+        let recipient = Address::from_low_u64_be(0x200);
+        let coinbase = Address::from_low_u64_be(0xc0ffee);
+        let transfer = EIP1559Transaction {
+            chain_id: 1,
+            nonce: 0,
+            max_priority_fee_per_gas: 1,
+            max_fee_per_gas: 10,
+            gas_limit: 21_000,
+            to: TxKind::Call(recipient),
+            value: U256::from(1_000),
+            ..Default::default()
+        };
+        let txs = vec![
+            // Not signed, so its sender can't be recovered
+            Transaction::EIP1559Transaction(Default::default()),
+            sign(transfer.clone(), &key),
+            // Its nonce was already used by the transfer
+            sign(transfer, &key),
+        ];
+        let env = T8nEnv {
+            current_coinbase: coinbase,
+            current_gas_limit: 30_000_000,
+            current_number: 1,
+            current_timestamp: 12,
+            current_base_fee: Some(7),
+            ..Default::default()
+        };
+        let trace_dir = std::env::temp_dir().join(format!("t8n-traces-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&trace_dir);
+        std::fs::create_dir_all(&trace_dir).unwrap();
+
+        let output = run(T8nInput {
+            alloc: Alloc::from([(sender, account(1_000_000_000))]),
+            env,
+            txs: txs.clone(),
+            fork: Fork::Shanghai,
+            chain_id: 1,
+            trace_dir: Some(trace_dir.clone()),
+        })
+        .unwrap();
+
+        let rejected: Vec<usize> = output.result.rejected.iter().map(|tx| tx.index).collect();
+        assert_eq!(rejected, vec![0, 2]);
+        let [receipt] = output.result.receipts.as_slice() else {
+            panic!("expected a single receipt");
+        };
+        assert_eq!(receipt.status, 1);
+        assert_eq!(receipt.gas_used, 21_000);
+        assert_eq!(receipt.transaction_hash, txs[1].hash());
+        assert_eq!(output.result.gas_used, 21_000);
+
+        // The sender pays the effective gas price of 7 + 1, of which the coinbase gets the tip
+        let mut sender_account = account(1_000_000_000 - 1_000 - 21_000 * 8);
+        sender_account.nonce = 1;
+        let expected_alloc = Alloc::from([
+            (sender, sender_account),
+            (recipient, account(1_000)),
+            (coinbase, account(21_000)),
+        ]);
+        assert_eq!(output.alloc, expected_alloc);
+        let state_root = Genesis {
+            alloc: expected_alloc,
+            ..Default::default()
+        }
+        .compute_state_root();
+        assert_eq!(output.result.state_root, state_root);
+        assert_eq!(output.result.tx_root, compute_transactions_root(&txs[1..2]));
+        assert_eq!(
+            output.result.receipts_root,
+            compute_receipts_root(&[Receipt::new(TxType::EIP1559, true, 21_000, Vec::new())])
+        );
+
+        // Only the executed transaction is traced, and its summary holds the state root after it
+        let trace_files: Vec<_> = std::fs::read_dir(&trace_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        let trace_file = format!("trace-1-{:#x}.jsonl", txs[1].hash());
+        assert_eq!(trace_files, vec![std::ffi::OsString::from(&trace_file)]);
+        let trace = std::fs::read_to_string(trace_dir.join(trace_file)).unwrap();
+        let summary: serde_json::Value =
+            serde_json::from_str(trace.lines().last().unwrap()).unwrap();
+        assert_eq!(summary["stateRoot"], format!("{state_root:#x}"));
+        assert_eq!(summary["pass"], true);
+        std::fs::remove_dir_all(trace_dir).unwrap();
+    }
+}
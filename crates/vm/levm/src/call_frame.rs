@@ -1,9 +1,10 @@
 use crate::{
     account::LevmAccount,
+    code_cache::AnalyzedCode,
     constants::STACK_LIMIT,
     errors::{ExceptionalHalt, InternalError, VMError},
     memory::Memory,
-    utils::{JumpTargetFilter, restore_cache_state},
+    utils::restore_cache_state,
     vm::VM,
 };
 use bytes::Bytes;
@@ -12,6 +13,7 @@ use keccak_hash::H256;
 use std::{
     collections::{BTreeMap, HashMap},
     fmt,
+    sync::Arc,
 };
 
 #[derive(Clone, PartialEq, Eq)]
@@ -229,9 +231,9 @@ pub struct CallFrame {
     pub is_static: bool,
     /// Call stack current depth
     pub depth: usize,
-    /// Lazy blacklist of jump targets. Contains all offsets of 0x5B (JUMPDEST) in literals (after
-    /// push instructions).
-    pub jump_target_filter: JumpTargetFilter,
+    /// Analysis of the bytecode, holding its valid jump targets and its padded form. Shared with
+    /// every other call frame running the same code.
+    pub code: Arc<AnalyzedCode>,
     /// This is set to true if the function that created this callframe is CREATE or CREATE2
     pub is_create: bool,
     /// Everytime we want to write an account during execution of a callframe we store the pre-write state so that we can restore if it reverts
@@ -289,7 +291,7 @@ impl CallFrame {
         msg_sender: Address,
         to: Address,
         code_address: Address,
-        bytecode: Bytes,
+        code: Arc<AnalyzedCode>,
         msg_value: U256,
         calldata: Bytes,
         is_static: bool,
@@ -309,12 +311,12 @@ impl CallFrame {
             msg_sender,
             to,
             code_address,
-            bytecode: bytecode.clone(),
+            bytecode: code.bytecode.clone(),
             msg_value,
             calldata,
             is_static,
             depth,
-            jump_target_filter: JumpTargetFilter::new(bytecode),
+            code,
             should_transfer_value,
             is_create,
             ret_offset,
@@ -352,9 +354,9 @@ impl CallFrame {
         Ok(())
     }
 
-    pub fn set_code(&mut self, code: Bytes) -> Result<(), VMError> {
-        self.jump_target_filter = JumpTargetFilter::new(code.clone());
-        self.bytecode = code;
+    pub fn set_code(&mut self, code: Arc<AnalyzedCode>) -> Result<(), VMError> {
+        self.bytecode = code.bytecode.clone();
+        self.code = code;
         Ok(())
     }
 }
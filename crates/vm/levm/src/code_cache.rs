@@ -0,0 +1,221 @@
+use crate::opcodes::Opcode;
+use bytes::Bytes;
+use ethrex_common::H256;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, LazyLock, Mutex, MutexGuard, PoisonError},
+};
+
+/// Amount of zero (STOP) bytes appended to the bytecode, enough for the immediate of a trailing
+/// `PUSH32` plus the opcode following it.
+pub const CODE_PADDING: usize = 33;
+
+/// Maximum amount of bytes held by [CODE_CACHE], the oldest entries are evicted past it.
+pub const CODE_CACHE_MAX_SIZE: usize = 64 * 1024 * 1024;
+
+/// Process-wide cache of analyzed bytecode, shared by every VM across transactions and blocks.
+pub static CODE_CACHE: LazyLock<CodeCache> = LazyLock::new(|| CodeCache::new(CODE_CACHE_MAX_SIZE));
+
+/// Bytecode analyzed once, so that it can be executed by many call frames without redoing the work.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnalyzedCode {
+    /// The original bytecode.
+    pub bytecode: Bytes,
+    /// The bytecode followed by [CODE_PADDING] zero bytes, so that push immediates running past
+    /// the end of the code are read as zeroes without any bounds checks. Shares its buffer with
+    /// `bytecode`.
+    pub padded: Bytes,
+    /// Bitmap of the offsets holding a `JUMPDEST` opcode, i.e. not part of a push immediate.
+    jump_dests: Vec<u8>,
+}
+
+impl AnalyzedCode {
+    pub fn new(bytecode: Bytes) -> Self {
+        let length = bytecode.len();
+
+        let mut padded = Vec::with_capacity(length.saturating_add(CODE_PADDING));
+        padded.extend_from_slice(&bytecode);
+        padded.resize(length.saturating_add(CODE_PADDING), 0);
+        let padded = Bytes::from(padded);
+
+        Self {
+            bytecode: padded.slice(..length),
+            jump_dests: analyze_jump_dests(&bytecode),
+            padded,
+        }
+    }
+
+    /// Checks whether the offset holds a `JUMPDEST` opcode that is a valid jump target.
+    // Neither the division nor the shift can overflow.
+    #[expect(clippy::arithmetic_side_effects)]
+    pub fn is_valid_jump_dest(&self, offset: usize) -> bool {
+        self.jump_dests
+            .get(offset / 8)
+            .is_some_and(|byte| byte & (1 << (offset % 8)) != 0)
+    }
+
+    /// Amount of bytes held by the analysis, used to bound the cache size.
+    pub fn size(&self) -> usize {
+        self.padded.len().saturating_add(self.jump_dests.len())
+    }
+}
+
+/// Walks the bytecode skipping push immediates, marking the offsets of every `JUMPDEST` found.
+// It is not realistic to expect a bytecode offset to overflow an `usize`, and the `as` conversions
+// can't fail.
+#[expect(clippy::arithmetic_side_effects, clippy::as_conversions)]
+fn analyze_jump_dests(bytecode: &[u8]) -> Vec<u8> {
+    let mut jump_dests = vec![0; bytecode.len().div_ceil(8)];
+
+    let mut offset = 0;
+    while let Some(&value) = bytecode.get(offset) {
+        let opcode = Opcode::from(value);
+        if opcode == Opcode::JUMPDEST {
+            if let Some(byte) = jump_dests.get_mut(offset / 8) {
+                *byte |= 1 << (offset % 8);
+            }
+        } else if (Opcode::PUSH1..=Opcode::PUSH32).contains(&opcode) {
+            offset += value as usize - Opcode::PUSH0 as usize;
+        }
+        offset += 1;
+    }
+
+    jump_dests
+}
+
+/// Size-bounded map from code hash to analyzed bytecode. Entries are evicted in insertion order.
+#[derive(Debug)]
+pub struct CodeCache {
+    max_size: usize,
+    inner: Mutex<CodeCacheInner>,
+}
+
+#[derive(Debug, Default)]
+struct CodeCacheInner {
+    entries: HashMap<H256, Arc<AnalyzedCode>>,
+    /// Code hashes in insertion order, the front one is the next to be evicted.
+    order: VecDeque<H256>,
+    /// Sum of the sizes of all the entries.
+    size: usize,
+}
+
+impl CodeCache {
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            max_size,
+            inner: Mutex::new(CodeCacheInner::default()),
+        }
+    }
+
+    fn inner(&self) -> MutexGuard<'_, CodeCacheInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn get(&self, code_hash: &H256) -> Option<Arc<AnalyzedCode>> {
+        self.inner().entries.get(code_hash).cloned()
+    }
+
+    /// Returns the analyzed code for the hash, analyzing the bytecode returned by `get_bytecode`
+    /// if it isn't cached yet.
+    pub fn get_or_analyze<E>(
+        &self,
+        code_hash: H256,
+        get_bytecode: impl FnOnce() -> Result<Bytes, E>,
+    ) -> Result<Arc<AnalyzedCode>, E> {
+        if let Some(code) = self.get(&code_hash) {
+            return Ok(code);
+        }
+        // The analysis is done without holding the lock, so it doesn't block other threads
+        let code = Arc::new(AnalyzedCode::new(get_bytecode()?));
+        self.insert(code_hash, code.clone());
+        Ok(code)
+    }
+
+    pub fn insert(&self, code_hash: H256, code: Arc<AnalyzedCode>) {
+        let code_size = code.size();
+        if code_size > self.max_size {
+            return;
+        }
+
+        let mut inner = self.inner();
+        if inner.entries.contains_key(&code_hash) {
+            return;
+        }
+        while inner.size.saturating_add(code_size) > self.max_size {
+            let Some(evicted) = inner.order.pop_front() else {
+                break;
+            };
+            if let Some(evicted) = inner.entries.remove(&evicted) {
+                inner.size = inner.size.saturating_sub(evicted.size());
+            }
+        }
+        inner.entries.insert(code_hash, code);
+        inner.order.push_back(code_hash);
+        inner.size = inner.size.saturating_add(code_size);
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sum of the sizes of all the cached entries, in bytes.
+    pub fn size(&self) -> usize {
+        self.inner().size
+    }
+
+    pub fn clear(&self) {
+        *self.inner() = CodeCacheInner::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::arithmetic_side_effects)]
+    use super::*;
+
+    #[test]
+    fn jump_dests_skip_push_immediates() {
+        // JUMPDEST, PUSH2 0x5b5b, JUMPDEST, PUSH1 (truncated)
+        let code = AnalyzedCode::new(Bytes::from(vec![0x5b, 0x61, 0x5b, 0x5b, 0x5b, 0x60]));
+
+        assert!(code.is_valid_jump_dest(0));
+        assert!(!code.is_valid_jump_dest(2));
+        assert!(!code.is_valid_jump_dest(3));
+        assert!(code.is_valid_jump_dest(4));
+        assert!(!code.is_valid_jump_dest(5));
+        assert!(!code.is_valid_jump_dest(100));
+    }
+
+    #[test]
+    fn padded_code_reads_zeroes_past_the_end() {
+        let code = AnalyzedCode::new(Bytes::from(vec![0x7f, 0x01]));
+
+        assert_eq!(code.bytecode.as_ref(), &[0x7f, 0x01]);
+        assert_eq!(code.padded.len(), 2 + CODE_PADDING);
+        assert!(code.padded.iter().skip(2).all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn cache_evicts_oldest_entries_past_max_size() {
+        let entry_size = AnalyzedCode::new(Bytes::from(vec![0; 8])).size();
+        let cache = CodeCache::new(entry_size * 2);
+
+        for i in 0..3u64 {
+            let code = cache
+                .get_or_analyze(H256::from_low_u64_be(i), || {
+                    Ok::<_, ()>(Bytes::from(vec![0; 8]))
+                })
+                .unwrap();
+            assert_eq!(code.bytecode.len(), 8);
+        }
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.size() <= entry_size * 2);
+        assert!(cache.get(&H256::from_low_u64_be(0)).is_none());
+        assert!(cache.get(&H256::from_low_u64_be(2)).is_some());
+    }
+}
@@ -23,6 +23,9 @@ pub const SYS_CALL_GAS_LIMIT: u64 = 30000000;
 // Transaction costs in gas
 pub const TX_BASE_COST: u64 = 21000;
 
+// https://eips.ethereum.org/EIPS/eip-7825
+pub const TX_MAX_GAS_LIMIT_OSAKA: u64 = 1 << 24;
+
 pub const MAX_CODE_SIZE: u64 = 0x6000;
 pub const INIT_CODE_MAX_SIZE: usize = 49152;
 
@@ -10,8 +10,10 @@ use keccak_hash::H256;
 use keccak_hash::keccak;
 
 use super::Database;
+use super::state_access::ReadSet;
 use crate::account::LevmAccount;
 use crate::call_frame::CallFrameBackup;
+use crate::code_cache::{AnalyzedCode, CODE_CACHE};
 use crate::errors::InternalError;
 use crate::errors::VMError;
 use crate::utils::account_to_levm_account;
@@ -33,6 +35,9 @@ pub struct GeneralizedDatabase {
     /// Used in get_state_transitions for edge case in which account is destroyed and re-created afterwards
     /// In that scenario we want to remove the previous storage of the account but we still want the account to exist.
     pub destroyed_accounts: HashSet<Address>,
+    /// Keys read by the current transaction, only recorded when enabled with
+    /// [GeneralizedDatabase::enable_state_access_recording].
+    pub tx_reads: Option<ReadSet>,
 }
 
 impl GeneralizedDatabase {
@@ -44,6 +49,7 @@ impl GeneralizedDatabase {
             tx_backup: None,
             destroyed_accounts: HashSet::new(),
             codes: BTreeMap::new(),
+            tx_reads: None,
         }
     }
 
@@ -67,6 +73,19 @@ impl GeneralizedDatabase {
             tx_backup: None,
             destroyed_accounts: HashSet::new(),
             codes,
+            tx_reads: None,
+        }
+    }
+
+    /// Makes every transaction executed from now on report the accounts and storage slots it read
+    /// and wrote, see [ExecutionReport::state_access](crate::errors::ExecutionReport::state_access).
+    pub fn enable_state_access_recording(&mut self) {
+        self.tx_reads.get_or_insert_default();
+    }
+
+    pub fn record_slot_read(&mut self, address: Address, key: H256) {
+        if let Some(reads) = &mut self.tx_reads {
+            reads.record_slot(address, key);
         }
     }
 
@@ -74,6 +93,9 @@ impl GeneralizedDatabase {
     /// Loads account
     /// If it's the first time it's loaded store it in `initial_accounts_state` and also cache it in `current_accounts_state` for making changes to it
     fn load_account(&mut self, address: Address) -> Result<&mut LevmAccount, InternalError> {
+        if let Some(reads) = &mut self.tx_reads {
+            reads.record_account(address);
+        }
         match self.current_accounts_state.entry(address) {
             Entry::Occupied(entry) => Ok(entry.into_mut()),
             Entry::Vacant(entry) => {
@@ -115,6 +137,32 @@ impl GeneralizedDatabase {
         self.get_code(code_hash)
     }
 
+    /// Gets the analyzed code of an account, going through the process-wide [CODE_CACHE] so that
+    /// the analysis is reused across transactions and blocks.
+    pub fn get_analyzed_account_code(
+        &mut self,
+        address: Address,
+    ) -> Result<Arc<AnalyzedCode>, InternalError> {
+        let code_hash = self.get_account(address)?.info.code_hash;
+        CODE_CACHE.get_or_analyze(code_hash, || self.get_code(code_hash).cloned())
+    }
+
+    /// Gets the value of a storage slot, loading the account and the slot if they aren't cached yet.
+    /// Warning: Use directly only if outside of the EVM, otherwise use `vm.get_storage_value`.
+    pub fn get_storage_value(
+        &mut self,
+        address: Address,
+        key: H256,
+    ) -> Result<U256, InternalError> {
+        self.record_slot_read(address, key);
+        if let Some(value) = self.load_account(address)?.storage.get(&key) {
+            return Ok(*value);
+        }
+        let value = self.get_value_from_database(address, key)?;
+        self.load_account(address)?.storage.insert(key, value);
+        Ok(value)
+    }
+
     /// Gets storage slot from Database, storing in initial_accounts_state for efficiency when getting AccountUpdates.
     fn get_value_from_database(
         &mut self,
@@ -405,6 +453,7 @@ impl<'a> VM<'a> {
         address: Address,
         key: H256,
     ) -> Result<U256, InternalError> {
+        self.db.record_slot_read(address, key);
         if let Some(account) = self.db.current_accounts_state.get(&address) {
             if let Some(value) = account.storage.get(&key) {
                 return Ok(*value);
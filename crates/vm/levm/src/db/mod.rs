@@ -6,6 +6,7 @@ use ethrex_common::{
 };
 
 pub mod gen_db;
+pub mod state_access;
 
 pub trait Database: Send + Sync {
     fn get_account_info(&self, address: Address) -> Result<AccountInfo, DatabaseError>;
@@ -0,0 +1,161 @@
+use crate::{call_frame::CallFrameBackup, errors::InternalError};
+use ethrex_common::{Address, H256, U256, types::AccountInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::gen_db::CacheDB;
+
+/// Keys read by the transaction being executed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReadSet {
+    pub accounts: BTreeSet<Address>,
+    pub storage: BTreeMap<Address, BTreeSet<H256>>,
+}
+
+impl ReadSet {
+    pub fn record_account(&mut self, address: Address) {
+        self.accounts.insert(address);
+    }
+
+    pub fn record_slot(&mut self, address: Address, key: H256) {
+        self.storage.entry(address).or_default().insert(key);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountDiff {
+    pub before: AccountInfo,
+    pub after: AccountInfo,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageDiff {
+    pub before: U256,
+    pub after: U256,
+}
+
+/// Accounts and storage slots read and written by a transaction, along with the values of the
+/// written ones before and after it.
+///
+/// Writes undone by a reverted call are not included, while writes that set a value equal to
+/// the previous one are.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateAccess {
+    pub read_accounts: BTreeSet<Address>,
+    pub read_storage: BTreeMap<Address, BTreeSet<H256>>,
+    pub written_accounts: BTreeMap<Address, AccountDiff>,
+    pub written_storage: BTreeMap<Address, BTreeMap<H256, StorageDiff>>,
+}
+
+impl StateAccess {
+    /// Builds the state access of a transaction from the keys it read and its backup, which holds
+    /// the original value of everything it wrote. `state` is the state right after it.
+    pub fn new(
+        reads: ReadSet,
+        tx_backup: &CallFrameBackup,
+        state: &CacheDB,
+    ) -> Result<Self, InternalError> {
+        let mut written_accounts = BTreeMap::new();
+        for (address, original) in &tx_backup.original_accounts_info {
+            let account = state.get(address).ok_or(InternalError::AccountNotFound)?;
+            written_accounts.insert(
+                *address,
+                AccountDiff {
+                    before: original.info.clone(),
+                    after: account.info.clone(),
+                },
+            );
+        }
+
+        let mut written_storage: BTreeMap<Address, BTreeMap<H256, StorageDiff>> = BTreeMap::new();
+        for (address, slots) in &tx_backup.original_account_storage_slots {
+            let account = state.get(address).ok_or(InternalError::AccountNotFound)?;
+            let diffs = written_storage.entry(*address).or_default();
+            for (key, before) in slots {
+                diffs.insert(
+                    *key,
+                    StorageDiff {
+                        before: *before,
+                        after: account.storage.get(key).copied().unwrap_or_default(),
+                    },
+                );
+            }
+        }
+
+        Ok(Self {
+            read_accounts: reads.accounts,
+            read_storage: reads.storage,
+            written_accounts,
+            written_storage,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+    use super::*;
+    use crate::account::LevmAccount;
+
+    fn account_info(balance: u64, nonce: u64) -> AccountInfo {
+        AccountInfo {
+            code_hash: H256::zero(),
+            balance: U256::from(balance),
+            nonce,
+        }
+    }
+
+    #[test]
+    fn state_access_diffs_written_keys_against_backup() {
+        let sender = Address::from_low_u64_be(1);
+        let contract = Address::from_low_u64_be(2);
+        let key = H256::from_low_u64_be(3);
+
+        let mut reads = ReadSet::default();
+        reads.record_account(sender);
+        reads.record_account(contract);
+        reads.record_slot(contract, key);
+        reads.record_slot(contract, H256::from_low_u64_be(4));
+
+        let mut tx_backup = CallFrameBackup::default();
+        tx_backup
+            .original_accounts_info
+            .insert(sender, LevmAccount::from(account_info(100, 0)));
+        tx_backup
+            .original_account_storage_slots
+            .entry(contract)
+            .or_default()
+            .insert(key, U256::from(7));
+
+        let mut state = CacheDB::new();
+        state.insert(sender, LevmAccount::from(account_info(90, 1)));
+        let mut contract_account = LevmAccount::from(account_info(0, 1));
+        contract_account.storage.insert(key, U256::from(8));
+        state.insert(contract, contract_account);
+
+        let state_access = StateAccess::new(reads, &tx_backup, &state).unwrap();
+
+        assert_eq!(state_access.read_accounts.len(), 2);
+        assert_eq!(state_access.read_storage[&contract].len(), 2);
+        assert_eq!(
+            state_access.written_accounts,
+            BTreeMap::from([(
+                sender,
+                AccountDiff {
+                    before: account_info(100, 0),
+                    after: account_info(90, 1),
+                }
+            )])
+        );
+        assert_eq!(
+            state_access.written_storage[&contract][&key],
+            StorageDiff {
+                before: U256::from(7),
+                after: U256::from(8),
+            }
+        );
+    }
+}
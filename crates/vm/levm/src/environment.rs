@@ -1,6 +1,6 @@
 use ethrex_common::{
     Address, H256, U256,
-    types::{BlockHeader, ChainConfig, Fork, ForkBlobSchedule},
+    types::{BlockHeader, ChainConfig, Fork, ForkBlobSchedule, L1FeeConfig},
 };
 
 use crate::constants::{
@@ -38,6 +38,8 @@ pub struct Environment {
     pub tx_nonce: u64,
     pub block_gas_limit: u64,
     pub is_privileged: bool,
+    /// L1 data fee parameters of the L2 block, only used by the L2 hook.
+    pub l1_fee_config: Option<L1FeeConfig>,
 }
 
 /// This struct holds special configuration variables specific to the
@@ -1,3 +1,4 @@
+use crate::db::state_access::StateAccess;
 use bytes::Bytes;
 use derive_more::derive::Display;
 use ethrex_common::{Address, U256, types::Log};
@@ -97,6 +98,11 @@ pub enum TxValidationError {
         block_gas_limit: u64,
         tx_gas_limit: u64,
     },
+    #[error("Transaction gas limit {tx_gas_limit} exceeds the maximum allowed of {max_gas_limit}")]
+    TxMaxGasLimitExceeded {
+        tx_gas_limit: u64,
+        max_gas_limit: u64,
+    },
     #[error("Insufficient max fee per gas")]
     InsufficientMaxFeePerGas,
     #[error(
@@ -220,6 +226,12 @@ pub struct ExecutionReport {
     pub gas_refunded: u64,
     pub output: Bytes,
     pub logs: Vec<Log>,
+    /// Accounts and storage slots read and written by the transaction, only present when
+    /// recording was enabled with
+    /// [GeneralizedDatabase::enable_state_access_recording](crate::db::gen_db::GeneralizedDatabase::enable_state_access_recording).
+    pub state_access: Option<StateAccess>,
+    /// L1 data fee paid by the sender, always zero outside of the L2.
+    pub l1_fee: U256,
 }
 
 impl ExecutionReport {
@@ -39,6 +39,7 @@ pub const BYTE: u64 = 3;
 pub const SHL: u64 = 3;
 pub const SHR: u64 = 3;
 pub const SAR: u64 = 3;
+pub const CLZ: u64 = 5;
 pub const KECCAK25_STATIC: u64 = 30;
 pub const KECCAK25_DYNAMIC_BASE: u64 = 6;
 pub const CALLDATALOAD: u64 = 3;
@@ -194,6 +195,11 @@ pub const IDENTITY_DYNAMIC_BASE: u64 = 3;
 pub const MODEXP_STATIC_COST: u64 = 200;
 pub const MODEXP_DYNAMIC_BASE: u64 = 200;
 pub const MODEXP_DYNAMIC_QUOTIENT: u64 = 3;
+pub const MODEXP_STATIC_COST_OSAKA: u64 = 500;
+pub const MODEXP_EXPONENT_FACTOR: u64 = 8;
+pub const MODEXP_EXPONENT_FACTOR_OSAKA: u64 = 16;
+
+pub const P256VERIFY_COST: u64 = 6900;
 
 pub const ECADD_COST: u64 = 150;
 pub const ECMUL_COST: u64 = 6000;
@@ -838,6 +844,7 @@ pub fn modexp(
     base_size: usize,
     exponent_size: usize,
     modulus_size: usize,
+    fork: Fork,
 ) -> Result<u64, VMError> {
     let base_size: u64 = base_size
         .try_into()
@@ -852,10 +859,25 @@ pub fn modexp(
     let max_length = base_size.max(modulus_size);
 
     //https://eips.ethereum.org/EIPS/eip-2565
+    //https://eips.ethereum.org/EIPS/eip-7883 from Osaka
 
     let words = (max_length.checked_add(7).ok_or(OutOfGas)?) / 8;
-    let multiplication_complexity = words.checked_pow(2).ok_or(OutOfGas)?;
+    let multiplication_complexity = if fork < Fork::Osaka {
+        words.checked_pow(2).ok_or(OutOfGas)?
+    } else if max_length <= 32 {
+        16
+    } else {
+        words
+            .checked_pow(2)
+            .and_then(|complexity| complexity.checked_mul(2))
+            .ok_or(OutOfGas)?
+    };
 
+    let exponent_factor = if fork >= Fork::Osaka {
+        MODEXP_EXPONENT_FACTOR_OSAKA
+    } else {
+        MODEXP_EXPONENT_FACTOR
+    };
     let calculate_iteration_count =
         if exponent_size <= 32 && *exponent_first_32_bytes != Natural::ZERO {
             exponent_first_32_bytes
@@ -866,7 +888,7 @@ pub fn modexp(
             let extra_size = (exponent_size
                 .checked_sub(32)
                 .ok_or(InternalError::Underflow)?)
-            .checked_mul(8)
+            .checked_mul(exponent_factor)
             .ok_or(OutOfGas)?;
             extra_size
                 .checked_add(exponent_first_32_bytes.significant_bits().max(1))
@@ -878,12 +900,14 @@ pub fn modexp(
         }
         .max(1);
 
-    let cost = MODEXP_STATIC_COST.max(
-        multiplication_complexity
-            .checked_mul(calculate_iteration_count)
-            .ok_or(OutOfGas)?
-            / MODEXP_DYNAMIC_QUOTIENT,
-    );
+    let dynamic_cost = multiplication_complexity
+        .checked_mul(calculate_iteration_count)
+        .ok_or(OutOfGas)?;
+    let cost = if fork >= Fork::Osaka {
+        MODEXP_STATIC_COST_OSAKA.max(dynamic_cost)
+    } else {
+        MODEXP_STATIC_COST.max(dynamic_cost / MODEXP_DYNAMIC_QUOTIENT)
+    };
     Ok(cost)
 }
 
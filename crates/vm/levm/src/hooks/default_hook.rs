@@ -1,5 +1,6 @@
 use crate::{
     account::LevmAccount,
+    code_cache::AnalyzedCode,
     constants::*,
     errors::{ContextResult, InternalError, TxValidationError, VMError},
     gas_cost::{self, STANDARD_TOKEN_COST, TOTAL_COST_FLOOR_PER_TOKEN},
@@ -11,7 +12,7 @@ use crate::{
 use bytes::Bytes;
 use ethrex_common::{Address, U256, types::Fork};
 
-use std::cmp::max;
+use std::{cmp::max, sync::Arc};
 
 pub const MAX_REFUND_QUOTIENT: u64 = 5;
 
@@ -96,6 +97,11 @@ impl Hook for DefaultHook {
         // (10) GAS_ALLOWANCE_EXCEEDED
         validate_gas_allowance(vm)?;
 
+        // GAS_LIMIT_EXCEEDS_MAXIMUM (EIP-7825)
+        if vm.env.config.fork >= Fork::Osaka {
+            validate_tx_max_gas_limit(vm)?;
+        }
+
         // Transaction is type 3 if tx_max_fee_per_blob_gas is Some
         if vm.env.tx_max_fee_per_blob_gas.is_some() {
             validate_4844_tx(vm)?;
@@ -396,6 +402,16 @@ pub fn validate_gas_allowance(vm: &mut VM<'_>) -> Result<(), TxValidationError>
     Ok(())
 }
 
+pub fn validate_tx_max_gas_limit(vm: &mut VM<'_>) -> Result<(), TxValidationError> {
+    if vm.env.gas_limit > TX_MAX_GAS_LIMIT_OSAKA {
+        return Err(TxValidationError::TxMaxGasLimitExceeded {
+            tx_gas_limit: vm.env.gas_limit,
+            max_gas_limit: TX_MAX_GAS_LIMIT_OSAKA,
+        });
+    }
+    Ok(())
+}
+
 pub fn validate_sender_balance(vm: &mut VM<'_>, sender_balance: U256) -> Result<(), VMError> {
     // Up front cost is the maximum amount of wei that a user is willing to pay for. Gaslimit * gasprice + value + blob_gas_cost
     let value = vm.current_call_frame.msg_value;
@@ -472,7 +488,10 @@ pub fn set_bytecode_and_code_address(vm: &mut VM<'_>) -> Result<(), VMError> {
     let (bytecode, code_address) = if vm.is_create()? {
         // Here bytecode is the calldata and the code_address is just the created contract address.
         let calldata = std::mem::take(&mut vm.current_call_frame.calldata);
-        (calldata, vm.current_call_frame.to)
+        (
+            Arc::new(AnalyzedCode::new(calldata)),
+            vm.current_call_frame.to,
+        )
     } else {
         // Here bytecode and code_address could be either from the account or from the delegated account.
         let to = vm.current_call_frame.to;
@@ -1,23 +1,47 @@
 use crate::{
-    errors::{ContextResult, InternalError},
+    call_frame::CallFrameBackup,
+    code_cache::AnalyzedCode,
+    errors::{ContextResult, ExceptionalHalt, InternalError, TxResult, VMError},
     hooks::{DefaultHook, default_hook, hook::Hook},
     opcodes::Opcode,
     vm::VM,
 };
 
-use ethrex_common::{Address, H160, U256};
+use bytes::Bytes;
+use ethrex_common::{Address, H160, U256, types::L1FeeConfig};
+use std::{collections::BTreeSet, sync::Arc};
 
 pub const COMMON_BRIDGE_L2_ADDRESS: Address = H160([
     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
     0x00, 0x00, 0xff, 0xff,
 ]);
 
-pub struct L2Hook {}
+// Sizes of the fields of an account diff in the state diff posted to L1, see `AccountStateDiff::encode`.
+/// type (u8) + address (Address)
+const ACCOUNT_DIFF_HEADER_LEN: u64 = 1 + 20;
+const BALANCE_DIFF_LEN: u64 = 32;
+const NONCE_DIFF_LEN: u64 = 2;
+/// storage_len (u16)
+const STORAGE_DIFF_HEADER_LEN: u64 = 2;
+/// key (H256) + value (U256)
+const STORAGE_SLOT_DIFF_LEN: u64 = 32 + 32;
+/// bytecode_len (u16)
+const BYTECODE_DIFF_HEADER_LEN: u64 = 2;
+
+#[derive(Default)]
+pub struct L2Hook {
+    /// Changes made by `prepare_execution` (nonce increment, upfront gas cost, value transfer...)
+    /// are cleared from the callframe backup before execution, but they are part of the state diff
+    /// the transaction pays the L1 fee for.
+    pre_execution_backup: CallFrameBackup,
+}
 
 impl Hook for L2Hook {
     fn prepare_execution(&mut self, vm: &mut VM<'_>) -> Result<(), crate::errors::VMError> {
         if !vm.env.is_privileged {
-            return DefaultHook.prepare_execution(vm);
+            DefaultHook.prepare_execution(vm)?;
+            self.pre_execution_backup = vm.current_call_frame.call_frame_backup.clone();
+            return Ok(());
         }
 
         let sender_address = vm.env.origin;
@@ -89,8 +113,9 @@ impl Hook for L2Hook {
             // If the transaction failed some validation, but it must still be included
             // To prevent it from taking effect, we force it to revert
             vm.current_call_frame.msg_value = U256::zero();
-            vm.current_call_frame
-                .set_code(vec![Opcode::INVALID.into()].into())?;
+            vm.current_call_frame.set_code(Arc::new(AnalyzedCode::new(
+                vec![Opcode::INVALID.into()].into(),
+            )))?;
             return Ok(());
         }
 
@@ -107,7 +132,10 @@ impl Hook for L2Hook {
         ctx_result: &mut ContextResult,
     ) -> Result<(), crate::errors::VMError> {
         if !vm.env.is_privileged {
-            return DefaultHook.finalize_execution(vm, ctx_result);
+            return match vm.env.l1_fee_config {
+                Some(l1_fee_config) => self.finalize_with_l1_fee(vm, ctx_result, l1_fee_config),
+                None => DefaultHook.finalize_execution(vm, ctx_result),
+            };
         }
 
         if !ctx_result.is_success() && vm.env.origin != COMMON_BRIDGE_L2_ADDRESS {
@@ -121,3 +149,159 @@ impl Hook for L2Hook {
         Ok(())
     }
 }
+
+impl L2Hook {
+    /// Same as `DefaultHook::finalize_execution`, but the sender also pays for the L1 blob space
+    /// its state diff takes. The L1 fee is charged as extra gas at the transaction's gas price, so
+    /// it's covered by the gas limit and reflected in the receipt and in gas estimations.
+    /// If the gas limit can't cover it, the transaction reverts consuming all of its gas.
+    fn finalize_with_l1_fee(
+        &mut self,
+        vm: &mut VM<'_>,
+        ctx_result: &mut ContextResult,
+        l1_fee_config: L1FeeConfig,
+    ) -> Result<(), VMError> {
+        if !ctx_result.is_success() {
+            default_hook::undo_value_transfer(vm)?;
+        }
+
+        let mut gas_refunded = default_hook::compute_gas_refunded(vm, ctx_result)?;
+        let mut execution_gas_used =
+            default_hook::compute_actual_gas_used(vm, gas_refunded, ctx_result.gas_used)?;
+        let mut l1_gas = self.l1_gas(vm, &l1_fee_config)?;
+
+        if execution_gas_used.saturating_add(l1_gas) > vm.env.gas_limit {
+            // Revert the execution like an out of gas error would, the remaining gas
+            // goes towards the L1 fee of the changes made before execution
+            if ctx_result.is_success() {
+                vm.restore_cache_state()?;
+                default_hook::undo_value_transfer(vm)?;
+            }
+            vm.substate.logs.clear();
+            vm.substate.selfdestruct_set.clear();
+            ctx_result.result = TxResult::Revert(ExceptionalHalt::OutOfGas.into());
+            ctx_result.output = Bytes::new();
+
+            gas_refunded = 0;
+            l1_gas = self
+                .l1_gas(vm, &l1_fee_config)?
+                .min(vm.env.gas_limit.saturating_sub(ctx_result.gas_used));
+            execution_gas_used = vm
+                .env
+                .gas_limit
+                .checked_sub(l1_gas)
+                .ok_or(InternalError::Underflow)?;
+        }
+
+        let actual_gas_used = execution_gas_used
+            .checked_add(l1_gas)
+            .ok_or(InternalError::Overflow)?;
+        default_hook::refund_sender(vm, ctx_result, gas_refunded, actual_gas_used)?;
+
+        default_hook::pay_coinbase(vm, execution_gas_used)?;
+
+        let l1_fee = vm
+            .env
+            .gas_price
+            .checked_mul(U256::from(l1_gas))
+            .ok_or(InternalError::Overflow)?;
+        vm.increase_account_balance(l1_fee_config.l1_fee_vault, l1_fee)?;
+        vm.substate.l1_fee = l1_fee;
+
+        default_hook::delete_self_destruct_accounts(vm)?;
+
+        Ok(())
+    }
+
+    /// Gas needed to pay the L1 fee of the transaction at its gas price.
+    /// Calls simulated without a gas price can't be charged.
+    fn l1_gas(&self, vm: &mut VM<'_>, l1_fee_config: &L1FeeConfig) -> Result<u64, VMError> {
+        if vm.env.gas_price.is_zero() {
+            return Ok(0);
+        }
+        let excluded = [vm.env.coinbase, l1_fee_config.l1_fee_vault];
+        let l1_fee = l1_fee_config.l1_fee(self.state_diff_size(vm, &excluded)?);
+
+        let (l1_gas, remainder) = l1_fee.div_mod(vm.env.gas_price);
+        let l1_gas = if remainder.is_zero() {
+            l1_gas
+        } else {
+            l1_gas.saturating_add(U256::one())
+        };
+        // A fee that doesn't fit in a u64 can't be paid by any gas limit anyway
+        Ok(l1_gas.try_into().unwrap_or(u64::MAX))
+    }
+
+    /// Estimates how many bytes the transaction adds to the state diff posted to L1, comparing the
+    /// current state with the one before the transaction.
+    /// The coinbase and the L1 fee vault are left out since they change in every block anyway.
+    fn state_diff_size(&self, vm: &mut VM<'_>, excluded: &[Address]) -> Result<u64, VMError> {
+        let mut tx_backup = vm.current_call_frame.call_frame_backup.clone();
+        tx_backup.extend(self.pre_execution_backup.clone());
+
+        let addresses: BTreeSet<Address> = tx_backup
+            .original_accounts_info
+            .keys()
+            .chain(tx_backup.original_account_storage_slots.keys())
+            .filter(|address| !excluded.contains(address))
+            .copied()
+            .collect();
+
+        let mut size: u64 = 0;
+        for address in addresses {
+            let current = vm
+                .db
+                .current_accounts_state
+                .get(&address)
+                .ok_or(InternalError::AccountNotFound)?;
+            let mut account_size: u64 = 0;
+            let mut new_code_hash = None;
+
+            if let Some(original) = tx_backup.original_accounts_info.get(&address) {
+                if current.info.balance != original.info.balance {
+                    account_size = account_size.saturating_add(BALANCE_DIFF_LEN);
+                }
+                if current.info.nonce != original.info.nonce {
+                    account_size = account_size.saturating_add(NONCE_DIFF_LEN);
+                }
+                if current.info.code_hash != original.info.code_hash {
+                    new_code_hash = Some(current.info.code_hash);
+                }
+            }
+
+            if let Some(original_slots) = tx_backup.original_account_storage_slots.get(&address) {
+                let changed_slots: u64 = original_slots
+                    .iter()
+                    .filter(|(key, value)| current.storage.get(*key) != Some(*value))
+                    .count()
+                    .try_into()
+                    .map_err(|_| InternalError::TypeConversion)?;
+                if changed_slots > 0 {
+                    account_size = account_size
+                        .saturating_add(STORAGE_DIFF_HEADER_LEN)
+                        .saturating_add(changed_slots.saturating_mul(STORAGE_SLOT_DIFF_LEN));
+                }
+            }
+
+            if let Some(code_hash) = new_code_hash {
+                let code_len: u64 = vm
+                    .db
+                    .get_code(code_hash)?
+                    .len()
+                    .try_into()
+                    .map_err(|_| InternalError::TypeConversion)?;
+                account_size = account_size
+                    .saturating_add(BYTECODE_DIFF_HEADER_LEN)
+                    .saturating_add(code_len);
+            }
+
+            if account_size > 0 {
+                size = size
+                    .saturating_add(ACCOUNT_DIFF_HEADER_LEN)
+                    .saturating_add(account_size);
+            }
+        }
+
+        Ok(size)
+    }
+}
@@ -1,4 +1,5 @@
 pub mod call_frame;
+pub mod code_cache;
 pub mod constants;
 pub mod db;
 pub mod debug;
@@ -8,6 +9,7 @@ pub mod execution_handlers;
 pub mod gas_cost;
 pub mod hooks;
 pub mod memory;
+pub mod native_tracers;
 pub mod opcode_handlers;
 pub mod opcodes;
 pub mod precompiles;
@@ -0,0 +1,785 @@
+//! Native implementations of geth's `4byteTracer` and `muxTracer`, a gas profiler, an EIP-3155
+//! tracer and an ERC-7562 validation rules tracer, built on top of the [Tracer] callbacks.
+
+use crate::{
+    errors::{ExceptionalHalt, OpcodeResult, VMError},
+    gas_cost::CALL_POSITIVE_VALUE_STIPEND,
+    opcodes::Opcode,
+    tracing::Tracer,
+    utils::{address_to_word, word_to_address},
+    vm::VM,
+};
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256, constants::EMPTY_KECCACK_HASH, tracing::CallType, types::Log,
+};
+use serde::Serialize;
+use std::{cell::RefCell, collections::BTreeMap, fmt::Write, io, rc::Rc};
+
+/// Geth's 4byteTracer (https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers#4byte-tracer)
+///
+/// Counts the function selectors of the calls made by the transaction, keyed by `<selector>-<calldata size>`,
+/// where the size doesn't include the selector. Creations and calls to precompiles are ignored.
+#[derive(Debug, Default)]
+pub struct FourByteTracer {
+    pub selectors: BTreeMap<String, u64>,
+}
+
+impl Tracer for FourByteTracer {
+    fn call_enter(
+        &mut self,
+        vm: &VM<'_>,
+        call_type: CallType,
+        _from: Address,
+        to: Address,
+        _value: U256,
+        _gas: u64,
+        input: &Bytes,
+    ) {
+        if matches!(call_type, CallType::CREATE | CallType::CREATE2) || vm.is_precompile(&to) {
+            return;
+        }
+        let Some(selector) = input.get(..4) else {
+            return;
+        };
+
+        let mut key = String::from("0x");
+        for byte in selector {
+            let _ = write!(key, "{byte:02x}");
+        }
+        let _ = write!(key, "-{}", input.len().saturating_sub(4));
+
+        let count = self.selectors.entry(key).or_default();
+        *count = count.saturating_add(1);
+    }
+}
+
+/// Amount of times an opcode was executed and the gas it consumed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpcodeGas {
+    pub count: u64,
+    pub gas: u64,
+}
+
+/// Gas consumed by the opcodes executed in a transaction, aggregated per opcode and per contract.
+///
+/// The gas forwarded to a sub-context (including the call stipend) is accounted for by the opcodes
+/// executed in it, not by the CALL* or CREATE* opcode creating it. Intrinsic gas and refunds are not
+/// included.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasProfile {
+    pub total_gas: u64,
+    /// Keyed by opcode name.
+    pub opcodes: BTreeMap<String, OpcodeGas>,
+    /// Keyed by the address of the executed code, which differs from the account's one for DELEGATECALL and CALLCODE.
+    pub contracts: BTreeMap<Address, u64>,
+}
+
+/// Builds a [GasProfile] of the executed transaction.
+#[derive(Debug, Default)]
+pub struct GasProfiler {
+    pub profile: GasProfile,
+    /// State of the call frame right before the opcode being executed.
+    step: Option<Step>,
+}
+
+#[derive(Debug)]
+struct Step {
+    depth: usize,
+    gas_remaining: u64,
+    code_address: Address,
+    /// Gas given to the callee of a value transfer on top of the gas sent by the caller.
+    stipend: u64,
+}
+
+impl Tracer for GasProfiler {
+    fn step(&mut self, vm: &VM<'_>, opcode: Opcode) {
+        let call_frame = &vm.current_call_frame;
+        let transfers_value = matches!(opcode, Opcode::CALL | Opcode::CALLCODE)
+            && call_frame.stack.get(2).is_ok_and(|value| !value.is_zero());
+        self.step = Some(Step {
+            depth: call_frame.depth,
+            gas_remaining: call_frame.gas_remaining,
+            code_address: call_frame.code_address,
+            stipend: if transfers_value {
+                CALL_POSITIVE_VALUE_STIPEND
+            } else {
+                0
+            },
+        });
+    }
+
+    fn step_end(&mut self, vm: &VM<'_>, opcode: Opcode, result: &Result<OpcodeResult, VMError>) {
+        let Some(step) = self.step.take() else {
+            return;
+        };
+
+        let gas = if result
+            .as_ref()
+            .is_err_and(|error| !error.is_revert_opcode())
+        {
+            // Exceptional halts consume all the gas left in the context
+            step.gas_remaining
+        } else {
+            let call_frame = &vm.current_call_frame;
+            let gas_remaining = if call_frame.depth > step.depth {
+                // The opcode created a sub-context, which is now the current one. The gas sent to it
+                // is left out, it will be accounted by its own opcodes.
+                let Some(parent) = vm.call_frames.last() else {
+                    return;
+                };
+                parent.gas_remaining.saturating_add(call_frame.gas_limit)
+            } else {
+                call_frame.gas_remaining
+            };
+            // The stipend is part of the gas the callee gets or gives back, but the caller never
+            // paid for it, so it's added back to the cost of the call
+            step.gas_remaining
+                .saturating_sub(gas_remaining)
+                .saturating_add(step.stipend)
+        };
+
+        let profile = &mut self.profile;
+        profile.total_gas = profile.total_gas.saturating_add(gas);
+
+        let opcode_gas = profile.opcodes.entry(format!("{opcode:?}")).or_default();
+        opcode_gas.count = opcode_gas.count.saturating_add(1);
+        opcode_gas.gas = opcode_gas.gas.saturating_add(gas);
+
+        let contract_gas = profile.contracts.entry(step.code_address).or_default();
+        *contract_gas = contract_gas.saturating_add(gas);
+    }
+}
+
+/// Geth's muxTracer (https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers#mux-tracer)
+///
+/// Forwards every callback to each of its tracers, so that they all run over the same execution.
+#[derive(Default)]
+pub struct MuxTracer {
+    tracers: Vec<Rc<RefCell<dyn Tracer>>>,
+}
+
+impl MuxTracer {
+    pub fn new(tracers: Vec<Rc<RefCell<dyn Tracer>>>) -> Self {
+        Self { tracers }
+    }
+
+    pub fn push(&mut self, tracer: Rc<RefCell<dyn Tracer>>) {
+        self.tracers.push(tracer);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tracers.is_empty()
+    }
+}
+
+impl Tracer for MuxTracer {
+    fn step(&mut self, vm: &VM<'_>, opcode: Opcode) {
+        for tracer in &self.tracers {
+            tracer.borrow_mut().step(vm, opcode);
+        }
+    }
+
+    fn step_end(&mut self, vm: &VM<'_>, opcode: Opcode, result: &Result<OpcodeResult, VMError>) {
+        for tracer in &self.tracers {
+            tracer.borrow_mut().step_end(vm, opcode, result);
+        }
+    }
+
+    fn call_enter(
+        &mut self,
+        vm: &VM<'_>,
+        call_type: CallType,
+        from: Address,
+        to: Address,
+        value: U256,
+        gas: u64,
+        input: &Bytes,
+    ) {
+        for tracer in &self.tracers {
+            tracer
+                .borrow_mut()
+                .call_enter(vm, call_type, from, to, value, gas, input);
+        }
+    }
+
+    fn call_exit(&mut self, vm: &VM<'_>, gas_used: u64, output: &Bytes, error: Option<&str>) {
+        for tracer in &self.tracers {
+            tracer.borrow_mut().call_exit(vm, gas_used, output, error);
+        }
+    }
+
+    fn log(&mut self, vm: &VM<'_>, log: &Log) {
+        for tracer in &self.tracers {
+            tracer.borrow_mut().log(vm, log);
+        }
+    }
+
+    fn storage_read(&mut self, vm: &VM<'_>, address: Address, key: H256, value: U256) {
+        for tracer in &self.tracers {
+            tracer.borrow_mut().storage_read(vm, address, key, value);
+        }
+    }
+
+    fn storage_write(
+        &mut self,
+        vm: &VM<'_>,
+        address: Address,
+        key: H256,
+        previous_value: U256,
+        new_value: U256,
+    ) {
+        for tracer in &self.tracers {
+            tracer
+                .borrow_mut()
+                .storage_write(vm, address, key, previous_value, new_value);
+        }
+    }
+
+    fn selfdestruct(&mut self, vm: &VM<'_>, address: Address, beneficiary: Address, balance: U256) {
+        for tracer in &self.tracers {
+            tracer
+                .borrow_mut()
+                .selfdestruct(vm, address, beneficiary, balance);
+        }
+    }
+}
+
+/// EIP-3155 (https://eips.ethereum.org/EIPS/eip-3155) tracer, writing a JSON line for each executed
+/// opcode. The summary line needs the post-state root, which is only known once the transaction's
+/// changes are applied, so it is written by [Eip3155Tracer::finish].
+///
+/// Write errors are ignored, as the VM has no way of handling them.
+pub struct Eip3155Tracer {
+    writer: Box<dyn io::Write>,
+    /// Line of the opcode being executed, written once its gas cost is known.
+    step: Option<Eip3155Step>,
+    /// Amount of contexts entered and not exited yet.
+    depth: usize,
+    /// Summary of the transaction once its top context exits, missing the state root.
+    summary: Option<Eip3155Summary>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Eip3155Step {
+    pc: usize,
+    op: u8,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    gas: u64,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    gas_cost: u64,
+    mem_size: usize,
+    /// From bottom to top.
+    stack: Vec<U256>,
+    /// Starts at 1 for the top context.
+    depth: usize,
+    refund: u64,
+    op_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Eip3155Summary {
+    state_root: H256,
+    #[serde(with = "serde_utils::bytes")]
+    output: Bytes,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    gas_used: u64,
+    pass: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl Eip3155Tracer {
+    pub fn new(writer: Box<dyn io::Write>) -> Self {
+        Self {
+            writer,
+            step: None,
+            depth: 0,
+            summary: None,
+        }
+    }
+
+    /// Writes the summary line of the traced transaction, given the state root after it and
+    /// whether it passed (e.g. a state test matched its expected post-state). Nothing is written
+    /// if the transaction didn't execute.
+    pub fn finish(&mut self, state_root: H256, pass: bool) {
+        if let Some(mut summary) = self.summary.take() {
+            summary.state_root = state_root;
+            summary.pass = pass;
+            self.write_line(&summary);
+        }
+        let _ = self.writer.flush();
+    }
+
+    fn write_line(&mut self, line: &impl Serialize) {
+        if serde_json::to_writer(&mut self.writer, line).is_ok() {
+            let _ = self.writer.write_all(b"\n");
+        }
+    }
+}
+
+impl Tracer for Eip3155Tracer {
+    fn step(&mut self, vm: &VM<'_>, opcode: Opcode) {
+        let call_frame = &vm.current_call_frame;
+        let stack = call_frame
+            .stack
+            .values
+            .get(call_frame.stack.offset..)
+            .unwrap_or_default()
+            .iter()
+            .rev()
+            .copied()
+            .collect();
+
+        self.step = Some(Eip3155Step {
+            pc: call_frame.pc,
+            op: opcode.into(),
+            gas: call_frame.gas_remaining,
+            gas_cost: 0,
+            mem_size: call_frame.memory.len,
+            stack,
+            depth: call_frame.depth.saturating_add(1),
+            refund: vm.substate.refunded_gas,
+            op_name: format!("{opcode:?}"),
+            error: None,
+        });
+    }
+
+    fn step_end(&mut self, vm: &VM<'_>, _opcode: Opcode, result: &Result<OpcodeResult, VMError>) {
+        let Some(mut step) = self.step.take() else {
+            return;
+        };
+
+        let call_frame = &vm.current_call_frame;
+        let gas_remaining = if call_frame.depth.saturating_add(1) > step.depth {
+            // The opcode created a sub-context, the gas sent to it is part of its cost
+            vm.call_frames
+                .last()
+                .map_or(call_frame.gas_remaining, |parent| parent.gas_remaining)
+        } else {
+            call_frame.gas_remaining
+        };
+        step.gas_cost = step.gas.saturating_sub(gas_remaining);
+        if let Err(error) = result {
+            step.error = Some(error.to_string());
+        }
+
+        self.write_line(&step);
+    }
+
+    fn call_enter(
+        &mut self,
+        _vm: &VM<'_>,
+        _call_type: CallType,
+        _from: Address,
+        _to: Address,
+        _value: U256,
+        _gas: u64,
+        _input: &Bytes,
+    ) {
+        self.depth = self.depth.saturating_add(1);
+    }
+
+    fn call_exit(&mut self, _vm: &VM<'_>, gas_used: u64, output: &Bytes, error: Option<&str>) {
+        self.depth = self.depth.saturating_sub(1);
+        if self.depth == 0 {
+            self.summary = Some(Eip3155Summary {
+                state_root: H256::zero(),
+                output: output.clone(),
+                gas_used,
+                pass: false,
+                error: error.map(str::to_string),
+            });
+        }
+    }
+}
+
+/// Selector of the v0.7 account's `validateUserOp(PackedUserOperation,bytes32,uint256)`.
+const VALIDATE_USER_OP_SELECTOR: [u8; 4] = [0x19, 0x82, 0x2f, 0x7c];
+
+/// Selector of the v0.7 paymaster's
+/// `validatePaymasterUserOp(PackedUserOperation,bytes32,uint256)`.
+const VALIDATE_PAYMASTER_USER_OP_SELECTOR: [u8; 4] = [0x52, 0xb7, 0x51, 0x2c];
+
+/// Selector of the sender creator's `createSender(bytes)`, through which the entry point calls
+/// the factory.
+const CREATE_SENDER_SELECTOR: [u8; 4] = [0x57, 0x0e, 0x1a, 0x36];
+
+/// Selector of the entry point's `depositTo(address)`, the only function validation code may call
+/// on it.
+const DEPOSIT_TO_SELECTOR: [u8; 4] = [0xb7, 0x60, 0xfa, 0xf9];
+
+/// Max offset from the base slot of a mapping entry keyed by the sender that is still associated
+/// with it, e.g. to access the fields of a struct.
+const MAX_ASSOCIATED_SLOT_OFFSET: u64 = 128;
+
+/// Checks the ERC-7562 (https://eips.ethereum.org/EIPS/eip-7562) validation rules over the
+/// validation of a UserOperation in a simulated `handleOps`, and measures the gas used by each of
+/// its phases.
+///
+/// The operation is the one of `sender`, other operations in the bundle are ignored. The rules are
+/// checked while the entry point deploys the sender through the factory, calls the sender's
+/// `validateUserOp` and calls the paymaster's `validatePaymasterUserOp`. Every entity is treated as
+/// unstaked, so all of them are bound by the same rules. The broken rules are collected in
+/// `violations`.
+#[derive(Debug)]
+pub struct Erc7562Tracer {
+    pub entry_point: Address,
+    pub sender: Address,
+    pub violations: Vec<String>,
+    /// Gas used by the factory deploying the sender, once it returns.
+    pub factory_gas_used: Option<u64>,
+    /// Gas used by the sender's `validateUserOp`, once it returns.
+    pub validation_gas_used: Option<u64>,
+    /// Gas used by the paymaster's `validatePaymasterUserOp`, once it returns.
+    pub paymaster_validation_gas_used: Option<u64>,
+    /// Gas used by the call to the sender executing the operation, once it returns.
+    pub execution_gas_used: Option<u64>,
+    /// Input of the entry point's call to `createSender` with the operation's init code.
+    create_sender_input: Option<Vec<u8>>,
+    paymaster: Option<Address>,
+    /// Results of KECCAK256 over data starting with the sender, i.e. the base slots of the
+    /// mapping entries keyed by it.
+    sender_keys: Vec<U256>,
+    /// Amount of contexts entered and not exited yet.
+    depth: usize,
+    /// Call from the entry point to one of the operation's entities being executed, with the
+    /// depth it was entered at.
+    entity_call: Option<(Phase, usize)>,
+    /// Set once the factory used its CREATE2.
+    sender_created: bool,
+    /// Set when the last opcode was GAS, which can only be followed by a CALL*.
+    after_gas: bool,
+    /// Operand of the opcode being executed that's checked once it's done.
+    pending: Option<PendingCheck>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Factory,
+    Account,
+    Paymaster,
+    Execution,
+}
+
+#[derive(Debug)]
+enum PendingCheck {
+    /// Memory offset of a KECCAK256 input that might start with the sender.
+    Keccak(usize),
+    /// Account whose code is accessed with an EXTCODE* opcode.
+    ExtCode(Address),
+}
+
+impl Erc7562Tracer {
+    /// `init_code` is the operation's factory followed by its factory data, if it has a factory.
+    pub fn new(
+        entry_point: Address,
+        sender: Address,
+        init_code: Option<&[u8]>,
+        paymaster: Option<Address>,
+    ) -> Self {
+        // createSender(bytes) with the init code as its only argument
+        let create_sender_input = init_code.map(|init_code| {
+            let mut input = CREATE_SENDER_SELECTOR.to_vec();
+            input.extend_from_slice(&U256::from(32).to_big_endian());
+            input.extend_from_slice(&U256::from(init_code.len()).to_big_endian());
+            input.extend_from_slice(init_code);
+            input
+        });
+        Self {
+            entry_point,
+            sender,
+            violations: Vec::new(),
+            factory_gas_used: None,
+            validation_gas_used: None,
+            paymaster_validation_gas_used: None,
+            execution_gas_used: None,
+            create_sender_input,
+            paymaster,
+            sender_keys: Vec::new(),
+            depth: 0,
+            entity_call: None,
+            sender_created: false,
+            after_gas: false,
+            pending: None,
+        }
+    }
+
+    fn phase(&self) -> Option<Phase> {
+        self.entity_call.map(|(phase, _)| phase)
+    }
+
+    fn is_validating(&self) -> bool {
+        matches!(
+            self.phase(),
+            Some(Phase::Factory | Phase::Account | Phase::Paymaster)
+        )
+    }
+
+    /// Phase of the operation started by a call from the entry point, if any.
+    fn entered_phase(&self, to: Address, input: &Bytes) -> Option<Phase> {
+        let account_validated = self.validation_gas_used.is_some();
+        if !account_validated
+            && self
+                .create_sender_input
+                .as_ref()
+                .is_some_and(|create_sender_input| input.starts_with(create_sender_input))
+        {
+            Some(Phase::Factory)
+        } else if to == self.sender && input.starts_with(&VALIDATE_USER_OP_SELECTOR) {
+            (!account_validated).then_some(Phase::Account)
+        } else if to == self.sender {
+            account_validated.then_some(Phase::Execution)
+        } else if self.paymaster == Some(to)
+            && input.starts_with(&VALIDATE_PAYMASTER_USER_OP_SELECTOR)
+        {
+            (account_validated && self.paymaster_validation_gas_used.is_none())
+                .then_some(Phase::Paymaster)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the storage slot is associated with the sender: either the sender's address itself
+    /// or close after the base slot of a mapping entry keyed by it.
+    fn is_associated_slot(&self, slot: U256) -> bool {
+        slot == address_to_word(self.sender)
+            || self.sender_keys.iter().any(|base| {
+                slot.checked_sub(*base)
+                    .is_some_and(|offset| offset <= U256::from(MAX_ASSOCIATED_SLOT_OFFSET))
+            })
+    }
+
+    /// OP-041: accessing the code of an account that doesn't have any is banned, as it could be
+    /// deployed after the validation.
+    fn check_has_code(&mut self, vm: &VM<'_>, address: Address) {
+        if address == self.sender || vm.is_precompile(&address) {
+            return;
+        }
+        if vm
+            .db
+            .current_accounts_state
+            .get(&address)
+            .is_some_and(|account| account.info.code_hash == *EMPTY_KECCACK_HASH)
+        {
+            self.violations.push(format!(
+                "OP-041: access to {address:#x}, which doesn't have code"
+            ));
+        }
+    }
+
+    fn check_storage_access(&mut self, address: Address, key: H256) {
+        if !self.is_validating() || address == self.sender {
+            return;
+        }
+        if !self.is_associated_slot(U256::from_big_endian(key.as_bytes())) {
+            self.violations.push(format!(
+                "STO-021: access to slot {key:#x} of {address:#x}, not associated with the sender"
+            ));
+        } else if self.create_sender_input.is_some() {
+            // The sender's slots in other contracts could be shared with operations deploying
+            // the same sender
+            self.violations.push(format!(
+                "STO-022: access to slot {key:#x} of {address:#x} while the sender is deployed by an unstaked factory"
+            ));
+        }
+    }
+}
+
+impl Tracer for Erc7562Tracer {
+    fn step(&mut self, vm: &VM<'_>, opcode: Opcode) {
+        let after_gas = std::mem::take(&mut self.after_gas);
+        if !self.is_validating() {
+            return;
+        }
+
+        let is_call = matches!(
+            opcode,
+            Opcode::CALL | Opcode::CALLCODE | Opcode::DELEGATECALL | Opcode::STATICCALL
+        );
+        if after_gas && !is_call {
+            self.violations
+                .push("OP-012: GAS is only allowed right before a CALL*".to_string());
+        }
+
+        let stack = &vm.current_call_frame.stack;
+        match opcode {
+            Opcode::GAS => self.after_gas = true,
+            Opcode::ORIGIN
+            | Opcode::GASPRICE
+            | Opcode::BLOCKHASH
+            | Opcode::COINBASE
+            | Opcode::TIMESTAMP
+            | Opcode::NUMBER
+            | Opcode::PREVRANDAO
+            | Opcode::GASLIMIT
+            | Opcode::BASEFEE
+            | Opcode::BLOBHASH
+            | Opcode::BLOBBASEFEE
+            | Opcode::BALANCE
+            | Opcode::SELFBALANCE
+            | Opcode::CREATE
+            | Opcode::SELFDESTRUCT
+            | Opcode::INVALID => {
+                self.violations
+                    .push(format!("OP-011: {opcode:?} is banned during validation"));
+            }
+            // Only allowed once, for the factory to deploy the sender
+            Opcode::CREATE2 => {
+                if self.phase() == Some(Phase::Factory) && !self.sender_created {
+                    self.sender_created = true;
+                } else {
+                    self.violations.push(
+                        "OP-031: CREATE2 is only allowed once, for the factory to deploy the sender"
+                            .to_string(),
+                    );
+                }
+            }
+            Opcode::KECCAK256 => {
+                let (Ok(offset), Ok(size)) = (stack.get(0), stack.get(1)) else {
+                    return;
+                };
+                if *size >= U256::from(32) {
+                    self.pending = usize::try_from(*offset).ok().map(PendingCheck::Keccak);
+                }
+            }
+            Opcode::EXTCODESIZE | Opcode::EXTCODEHASH | Opcode::EXTCODECOPY => {
+                if let Ok(address) = stack.get(0) {
+                    self.pending = Some(PendingCheck::ExtCode(word_to_address(*address)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn step_end(&mut self, vm: &VM<'_>, _opcode: Opcode, result: &Result<OpcodeResult, VMError>) {
+        let Some(pending) = self.pending.take() else {
+            return;
+        };
+        if result.is_err() {
+            return;
+        }
+
+        match pending {
+            PendingCheck::Keccak(offset) => {
+                let call_frame = &vm.current_call_frame;
+                // The memory was already expanded by the opcode, so loading from a copy of it
+                // doesn't change it
+                let mut memory = call_frame.memory.clone();
+                let starts_with_sender = memory
+                    .load_range(offset, 32)
+                    .is_ok_and(|input| input.as_slice() == H256::from(self.sender).as_bytes());
+                if let (true, Ok(hash)) = (starts_with_sender, call_frame.stack.get(0)) {
+                    self.sender_keys.push(*hash);
+                }
+            }
+            PendingCheck::ExtCode(address) => self.check_has_code(vm, address),
+        }
+    }
+
+    fn call_enter(
+        &mut self,
+        vm: &VM<'_>,
+        call_type: CallType,
+        from: Address,
+        to: Address,
+        value: U256,
+        _gas: u64,
+        input: &Bytes,
+    ) {
+        self.depth = self.depth.saturating_add(1);
+
+        if self.entity_call.is_none() {
+            if from == self.entry_point {
+                self.entity_call = self
+                    .entered_phase(to, input)
+                    .map(|phase| (phase, self.depth));
+            }
+            return;
+        }
+        if !self.is_validating() {
+            return;
+        }
+
+        if to == self.entry_point {
+            if input
+                .get(..4)
+                .is_some_and(|selector| selector != DEPOSIT_TO_SELECTOR)
+            {
+                self.violations
+                    .push("OP-052: only depositTo can be called on the entry point".to_string());
+            }
+            return;
+        }
+        if matches!(call_type, CallType::CALL | CallType::CALLCODE) && !value.is_zero() {
+            self.violations.push(format!(
+                "OP-061: call to {to:#x} with value, which can only be sent to the entry point"
+            ));
+        }
+        // Created accounts don't have code yet
+        if !matches!(call_type, CallType::CREATE | CallType::CREATE2) {
+            self.check_has_code(vm, to);
+        }
+    }
+
+    fn call_exit(&mut self, _vm: &VM<'_>, gas_used: u64, output: &Bytes, error: Option<&str>) {
+        if self.is_validating() && error == Some(ExceptionalHalt::OutOfGas.to_string().as_str()) {
+            self.violations
+                .push("OP-020: validation ran out of gas".to_string());
+        }
+
+        if let Some((phase, _)) = self.entity_call.take_if(|(_, depth)| *depth == self.depth) {
+            match phase {
+                Phase::Factory => self.factory_gas_used = Some(gas_used),
+                Phase::Account => self.validation_gas_used = Some(gas_used),
+                Phase::Paymaster => {
+                    // The context is passed to postOp, which is only allowed for staked paymasters
+                    if error.is_none() && has_paymaster_context(output) {
+                        self.violations.push(
+                            "EREP-050: unstaked paymasters must return an empty context"
+                                .to_string(),
+                        );
+                    }
+                    self.paymaster_validation_gas_used = Some(gas_used);
+                }
+                Phase::Execution => self.execution_gas_used = Some(gas_used),
+            }
+        }
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    fn storage_read(&mut self, _vm: &VM<'_>, address: Address, key: H256, _value: U256) {
+        self.check_storage_access(address, key);
+    }
+
+    fn storage_write(
+        &mut self,
+        _vm: &VM<'_>,
+        address: Address,
+        key: H256,
+        _previous_value: U256,
+        _new_value: U256,
+    ) {
+        self.check_storage_access(address, key);
+    }
+}
+
+/// Whether the `(bytes context, uint256 validationData)` returned by `validatePaymasterUserOp`
+/// has a non-empty context.
+fn has_paymaster_context(output: &Bytes) -> bool {
+    let word = |offset: usize| {
+        output
+            .get(offset..offset.checked_add(32)?)
+            .map(U256::from_big_endian)
+    };
+    word(0)
+        .and_then(|offset| usize::try_from(offset).ok())
+        .and_then(word)
+        .is_some_and(|length| !length.is_zero())
+}
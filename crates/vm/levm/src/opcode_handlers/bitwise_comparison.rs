@@ -1,13 +1,13 @@
 use crate::{
     constants::WORD_SIZE,
-    errors::{InternalError, OpcodeResult, VMError},
+    errors::{ExceptionalHalt, InternalError, OpcodeResult, VMError},
     gas_cost,
     vm::VM,
 };
-use ethrex_common::U256;
+use ethrex_common::{U256, types::Fork};
 
-// Comparison and Bitwise Logic Operations (14)
-// Opcodes: LT, GT, SLT, SGT, EQ, ISZERO, AND, OR, XOR, NOT, BYTE, SHL, SHR, SAR
+// Comparison and Bitwise Logic Operations (15)
+// Opcodes: LT, GT, SLT, SGT, EQ, ISZERO, AND, OR, XOR, NOT, BYTE, SHL, SHR, SAR, CLZ
 
 impl<'a> VM<'a> {
     // LT operation
@@ -222,6 +222,24 @@ impl<'a> VM<'a> {
 
         Ok(OpcodeResult::Continue { pc_increment: 1 })
     }
+
+    // CLZ operation (count leading zeros)
+    pub fn op_clz(&mut self) -> Result<OpcodeResult, VMError> {
+        // [EIP-7939] - CLZ is only available from OSAKA
+        if self.env.config.fork < Fork::Osaka {
+            return Err(ExceptionalHalt::InvalidOpcode.into());
+        }
+        let current_call_frame = &mut self.current_call_frame;
+        current_call_frame.increase_consumed_gas(gas_cost::CLZ)?;
+        let value = current_call_frame.stack.pop1()?;
+
+        // Returns 256 for zero
+        current_call_frame
+            .stack
+            .push1(U256::from(value.leading_zeros()))?;
+
+        Ok(OpcodeResult::Continue { pc_increment: 1 })
+    }
 }
 
 /// Instead of using unsafe <<, uses checked_mul n times, replicating n shifts.
@@ -43,6 +43,7 @@ impl<'a> VM<'a> {
         };
 
         self.tracer.log(&log)?;
+        self.with_tracer(|tracer, vm| tracer.log(vm, &log));
 
         self.substate.logs.push(log);
 
@@ -4,7 +4,6 @@ use crate::{
     errors::{ExceptionalHalt, InternalError, OpcodeResult, VMError},
     gas_cost::{self, SSTORE_STIPEND},
     memory::calculate_memory_size,
-    opcodes::Opcode,
     utils::u256_to_usize,
     vm::VM,
 };
@@ -153,6 +152,7 @@ impl<'a> VM<'a> {
         let storage_slot_key = u256_to_h256(storage_slot_key);
 
         let (value, storage_slot_was_cold) = self.access_storage_slot(address, storage_slot_key)?;
+        self.with_tracer(|tracer, vm| tracer.storage_read(vm, address, storage_slot_key, value));
 
         let current_call_frame = &mut self.current_call_frame;
 
@@ -239,6 +239,9 @@ impl<'a> VM<'a> {
         if new_storage_slot_value != current_value {
             self.update_account_storage(to, key, new_storage_slot_value, current_value)?;
         }
+        self.with_tracer(|tracer, vm| {
+            tracer.storage_write(vm, to, key, current_value, new_storage_slot_value)
+        });
 
         Ok(OpcodeResult::Continue { pc_increment: 1 })
     }
@@ -307,17 +310,10 @@ impl<'a> VM<'a> {
         Ok(OpcodeResult::Continue { pc_increment: 0 })
     }
 
-    /// Check if the jump destination is valid by:
-    ///   - Checking that the byte at the requested target PC is a JUMPDEST (0x5B).
-    ///   - Ensuring the byte is not blacklisted. In other words, the 0x5B value is not part of a
-    ///     constant associated with a push instruction.
-    fn target_address_is_valid(call_frame: &mut CallFrame, jump_address: usize) -> bool {
-        #[expect(clippy::as_conversions)]
-        call_frame.bytecode.get(jump_address).is_some_and(|value| {
-            // It's a constant, therefore the conversion cannot fail.
-            *value == Opcode::JUMPDEST as u8
-                && !call_frame.jump_target_filter.is_blacklisted(jump_address)
-        })
+    /// Check if the jump destination is valid, i.e. the byte at the requested target PC is a
+    /// JUMPDEST (0x5B) that is not part of a constant associated with a push instruction.
+    fn target_address_is_valid(call_frame: &CallFrame, jump_address: usize) -> bool {
+        call_frame.code.is_valid_jump_dest(jump_address)
     }
 
     /// JUMP* family (`JUMP` and `JUMP` ATTOW [DEC 2024]) helper
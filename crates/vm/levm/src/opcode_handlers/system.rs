@@ -1,5 +1,6 @@
 use crate::{
     call_frame::CallFrame,
+    code_cache::AnalyzedCode,
     constants::{FAIL, INIT_CODE_MAX_SIZE, SUCCESS},
     errors::{ContextResult, ExceptionalHalt, InternalError, OpcodeResult, TxResult, VMError},
     gas_cost::{self, max_message_call_gas},
@@ -12,6 +13,7 @@ use ethrex_common::tracing::CallType::{
     self, CALL, CALLCODE, DELEGATECALL, SELFDESTRUCT, STATICCALL,
 };
 use ethrex_common::{Address, U256, types::Fork};
+use std::sync::Arc;
 
 // System Operations (10)
 // Opcodes: CREATE, CALL, CALLCODE, RETURN, DELEGATECALL, CREATE2, STATICCALL, REVERT, INVALID, SELFDESTRUCT
@@ -62,7 +64,7 @@ impl<'a> VM<'a> {
         }
 
         // CHECK EIP7702
-        let (is_delegation_7702, eip7702_gas_consumed, code_address, bytecode) =
+        let (is_delegation_7702, eip7702_gas_consumed, code_address, code) =
             eip7702_get_code(self.db, &mut self.substate, callee)?;
 
         // GAS
@@ -103,6 +105,9 @@ impl<'a> VM<'a> {
         let data = self.get_calldata(args_offset, args_size)?;
 
         self.tracer.enter(CALL, from, to, value, gas_limit, &data);
+        self.with_tracer(|tracer, vm| {
+            tracer.call_enter(vm, CALL, from, to, value, gas_limit, &data)
+        });
 
         self.generic_call(
             gas_limit,
@@ -115,7 +120,7 @@ impl<'a> VM<'a> {
             data,
             return_data_offset,
             return_data_size,
-            bytecode,
+            code,
             is_delegation_7702,
         )
     }
@@ -161,7 +166,7 @@ impl<'a> VM<'a> {
         };
 
         // CHECK EIP7702
-        let (is_delegation_7702, eip7702_gas_consumed, code_address, bytecode) =
+        let (is_delegation_7702, eip7702_gas_consumed, code_address, code) =
             eip7702_get_code(self.db, &mut self.substate, address)?;
         // GAS
         let (new_memory_size, gas_left, _account_is_empty, address_was_cold) = self
@@ -201,6 +206,9 @@ impl<'a> VM<'a> {
 
         self.tracer
             .enter(CALLCODE, from, code_address, value, gas_limit, &data);
+        self.with_tracer(|tracer, vm| {
+            tracer.call_enter(vm, CALLCODE, from, code_address, value, gas_limit, &data)
+        });
 
         self.generic_call(
             gas_limit,
@@ -213,7 +221,7 @@ impl<'a> VM<'a> {
             data,
             return_data_offset,
             return_data_size,
-            bytecode,
+            code,
             is_delegation_7702,
         )
     }
@@ -277,7 +285,7 @@ impl<'a> VM<'a> {
         };
 
         // CHECK EIP7702
-        let (is_delegation_7702, eip7702_gas_consumed, code_address, bytecode) =
+        let (is_delegation_7702, eip7702_gas_consumed, code_address, code) =
             eip7702_get_code(self.db, &mut self.substate, address)?;
 
         // GAS
@@ -319,6 +327,9 @@ impl<'a> VM<'a> {
         // In this trace the `from` is the current contract, we don't want the `from` to be, for example, the EOA that sent the transaction
         self.tracer
             .enter(DELEGATECALL, to, code_address, value, gas_limit, &data);
+        self.with_tracer(|tracer, vm| {
+            tracer.call_enter(vm, DELEGATECALL, to, code_address, value, gas_limit, &data)
+        });
 
         self.generic_call(
             gas_limit,
@@ -331,7 +342,7 @@ impl<'a> VM<'a> {
             data,
             return_data_offset,
             return_data_size,
-            bytecode,
+            code,
             is_delegation_7702,
         )
     }
@@ -374,7 +385,7 @@ impl<'a> VM<'a> {
         };
 
         // CHECK EIP7702
-        let (is_delegation_7702, eip7702_gas_consumed, _, bytecode) =
+        let (is_delegation_7702, eip7702_gas_consumed, _, code) =
             eip7702_get_code(self.db, &mut self.substate, address)?;
 
         // GAS
@@ -414,6 +425,9 @@ impl<'a> VM<'a> {
 
         self.tracer
             .enter(STATICCALL, from, to, value, gas_limit, &data);
+        self.with_tracer(|tracer, vm| {
+            tracer.call_enter(vm, STATICCALL, from, to, value, gas_limit, &data)
+        });
 
         self.generic_call(
             gas_limit,
@@ -426,7 +440,7 @@ impl<'a> VM<'a> {
             data,
             return_data_offset,
             return_data_size,
-            bytecode,
+            code,
             is_delegation_7702,
         )
     }
@@ -576,6 +590,7 @@ impl<'a> VM<'a> {
             .enter(SELFDESTRUCT, to, beneficiary, balance, 0, &Bytes::new());
 
         self.tracer.exit_early(0, None)?;
+        self.with_tracer(|tracer, vm| tracer.selfdestruct(vm, to, beneficiary, balance));
 
         Ok(OpcodeResult::Halt)
     }
@@ -637,6 +652,17 @@ impl<'a> VM<'a> {
         };
         self.tracer
             .enter(call_type, deployer, new_address, value, gas_limit, &code);
+        self.with_tracer(|tracer, vm| {
+            tracer.call_enter(
+                vm,
+                call_type,
+                deployer,
+                new_address,
+                value,
+                gas_limit,
+                &code,
+            )
+        });
 
         let new_depth = self
             .current_call_frame
@@ -669,6 +695,9 @@ impl<'a> VM<'a> {
             self.current_call_frame.stack.push1(FAIL)?;
             self.tracer
                 .exit_early(gas_limit, Some("CreateAccExists".to_string()))?;
+            self.with_tracer(|tracer, vm| {
+                tracer.call_exit(vm, gas_limit, &Bytes::new(), Some("CreateAccExists"))
+            });
             return Ok(OpcodeResult::Continue { pc_increment: 1 });
         }
 
@@ -681,7 +710,8 @@ impl<'a> VM<'a> {
             deployer,
             new_address,
             new_address,
-            code,
+            // Init code isn't stored in any account, so it is analyzed without going through the cache
+            Arc::new(AnalyzedCode::new(code)),
             value,
             Bytes::new(),
             false,
@@ -727,7 +757,7 @@ impl<'a> VM<'a> {
         calldata: Bytes,
         ret_offset: usize,
         ret_size: usize,
-        bytecode: Bytes,
+        code: Arc<AnalyzedCode>,
         is_delegation_7702: bool,
     ) -> Result<OpcodeResult, VMError> {
         // Clear callframe subreturn data
@@ -761,6 +791,7 @@ impl<'a> VM<'a> {
                 &calldata,
                 gas_limit,
                 &mut gas_remaining,
+                self.env.config.fork,
             )?;
 
             let call_frame = &mut self.current_call_frame;
@@ -806,6 +837,7 @@ impl<'a> VM<'a> {
             }
 
             self.tracer.exit_context(&ctx_result, false)?;
+            self.trace_call_exit(&ctx_result);
         } else {
             let mut stack = self.stack_pool.pop().unwrap_or_default();
             stack.clear();
@@ -816,7 +848,7 @@ impl<'a> VM<'a> {
                 msg_sender,
                 to,
                 code_address,
-                bytecode,
+                code,
                 value,
                 calldata,
                 is_static,
@@ -926,6 +958,7 @@ impl<'a> VM<'a> {
         };
 
         self.tracer.exit_context(ctx_result, false)?;
+        self.trace_call_exit(ctx_result);
 
         let mut stack = executed_call_frame.stack;
         stack.clear();
@@ -977,6 +1010,7 @@ impl<'a> VM<'a> {
         };
 
         self.tracer.exit_context(ctx_result, false)?;
+        self.trace_call_exit(ctx_result);
 
         let mut stack = executed_call_frame.stack;
         stack.clear();
@@ -1035,6 +1069,7 @@ impl<'a> VM<'a> {
             .ok_or(InternalError::Overflow)?;
         callframe.stack.push1(FAIL)?; // It's the same as revert for CREATE
 
+        self.with_tracer(|tracer, vm| tracer.call_exit(vm, 0, &Bytes::new(), Some(&reason)));
         self.tracer.exit_early(0, Some(reason))?;
         Ok(())
     }
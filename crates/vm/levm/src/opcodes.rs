@@ -35,6 +35,7 @@ pub enum Opcode {
     SHL = 0x1B,
     SHR = 0x1C,
     SAR = 0x1D,
+    CLZ = 0x1E,
 
     // KECCAK256
     KECCAK256 = 0x20,
@@ -193,6 +194,7 @@ impl From<u8> for Opcode {
             table[0x1B] = Opcode::SHL;
             table[0x1C] = Opcode::SHR;
             table[0x1D] = Opcode::SAR;
+            table[0x1E] = Opcode::CLZ;
             table[0x02] = Opcode::MUL;
             table[0x03] = Opcode::SUB;
             table[0x04] = Opcode::DIV;
@@ -512,6 +514,7 @@ impl<'a> VM<'a> {
         opcode_table[Opcode::SHL as usize] = OpCodeFn(VM::op_shl);
         opcode_table[Opcode::SHR as usize] = OpCodeFn(VM::op_shr);
         opcode_table[Opcode::SAR as usize] = OpCodeFn(VM::op_sar);
+        opcode_table[Opcode::CLZ as usize] = OpCodeFn(VM::op_clz);
         opcode_table[Opcode::TLOAD as usize] = OpCodeFn(VM::op_tload);
         opcode_table[Opcode::TSTORE as usize] = OpCodeFn(VM::op_tstore);
         opcode_table[Opcode::SELFBALANCE as usize] = OpCodeFn(VM::op_selfbalance);
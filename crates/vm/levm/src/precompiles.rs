@@ -38,6 +38,14 @@ use lambdaworks_math::{
 use malachite::base::num::arithmetic::traits::ModPow as _;
 use malachite::base::num::basic::traits::Zero as _;
 use malachite::{Natural, base::num::conversion::traits::*};
+use p256::{
+    EncodedPoint, FieldElement as P256FieldElement, NistP256,
+    ecdsa::{
+        Signature as P256Signature, VerifyingKey as P256VerifyingKey,
+        signature::hazmat::PrehashVerifier,
+    },
+    elliptic_curve::{Curve, bigint::U256 as P256Uint, ff::PrimeField},
+};
 use sha3::Digest;
 use std::ops::Mul;
 
@@ -48,7 +56,8 @@ use crate::{
         self, BLAKE2F_ROUND_COST, BLS12_381_G1_K_DISCOUNT, BLS12_381_G1ADD_COST,
         BLS12_381_G2_K_DISCOUNT, BLS12_381_G2ADD_COST, BLS12_381_MAP_FP_TO_G1_COST,
         BLS12_381_MAP_FP2_TO_G2_COST, ECADD_COST, ECMUL_COST, ECRECOVER_COST, G1_MUL_COST,
-        G2_MUL_COST, MODEXP_STATIC_COST, POINT_EVALUATION_COST,
+        G2_MUL_COST, MODEXP_STATIC_COST, MODEXP_STATIC_COST_OSAKA, P256VERIFY_COST,
+        POINT_EVALUATION_COST,
     },
 };
 
@@ -120,6 +129,10 @@ pub const BLS12_MAP_FP2_TO_G2_ADDRESS: H160 = H160([
     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
     0x00, 0x00, 0x00, 0x11,
 ]);
+pub const P256VERIFY_ADDRESS: H160 = H160([
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x01, 0x00,
+]);
 
 pub const PRECOMPILES: [H160; 10] = [
     ECRECOVER_ADDRESS,
@@ -180,6 +193,22 @@ const FP2_ZERO_MAPPED_TO_G2: [u8; 256] = [
 pub const G1_POINT_AT_INFINITY: [u8; 128] = [0_u8; 128];
 pub const G2_POINT_AT_INFINITY: [u8; 256] = [0_u8; 256];
 
+/// [EIP-7823] - Upper bound for each of the base, exponent and modulus lengths from Osaka
+pub const MODEXP_MAX_INPUT_FIELD_SIZE: usize = 1024;
+
+pub const P256VERIFY_INPUT_LENGTH: usize = 160;
+
+// Secp256r1 curve parameters
+// See https://neuromancer.sk/std/secg/secp256r1
+const P256_P: P256Uint = P256Uint::from_be_hex(P256FieldElement::MODULUS);
+const P256_N: P256Uint = NistP256::ORDER;
+const P256_A: P256FieldElement = P256FieldElement::from_u64(3).neg();
+const P256_B_UINT: P256Uint =
+    P256Uint::from_be_hex("5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b");
+lazy_static::lazy_static! {
+    static ref P256_B: P256FieldElement = P256FieldElement::from_uint(P256_B_UINT).unwrap();
+}
+
 pub fn is_precompile(address: &Address, fork: Fork) -> bool {
     // Cancun specs is the only one that allows point evaluation precompile
     if *address == POINT_EVALUATION_ADDRESS && fork < Fork::Cancun {
@@ -190,6 +219,10 @@ pub fn is_precompile(address: &Address, fork: Fork) -> bool {
     if PRECOMPILES_POST_CANCUN.contains(address) && fork < Fork::Prague {
         return false;
     }
+    // https://eips.ethereum.org/EIPS/eip-7951
+    if *address == P256VERIFY_ADDRESS {
+        return fork >= Fork::Osaka;
+    }
 
     PRECOMPILES.contains(address) || PRECOMPILES_POST_CANCUN.contains(address)
 }
@@ -198,13 +231,14 @@ pub fn execute_precompile(
     address: Address,
     calldata: &Bytes,
     gas_remaining: &mut u64,
+    fork: Fork,
 ) -> Result<Bytes, VMError> {
     let result = match address {
         address if address == ECRECOVER_ADDRESS => ecrecover(calldata, gas_remaining)?,
         address if address == IDENTITY_ADDRESS => identity(calldata, gas_remaining)?,
         address if address == SHA2_256_ADDRESS => sha2_256(calldata, gas_remaining)?,
         address if address == RIPEMD_160_ADDRESS => ripemd_160(calldata, gas_remaining)?,
-        address if address == MODEXP_ADDRESS => modexp(calldata, gas_remaining)?,
+        address if address == MODEXP_ADDRESS => modexp(calldata, gas_remaining, fork)?,
         address if address == ECADD_ADDRESS => ecadd(calldata, gas_remaining)?,
         address if address == ECMUL_ADDRESS => ecmul(calldata, gas_remaining)?,
         address if address == ECPAIRING_ADDRESS => ecpairing(calldata, gas_remaining)?,
@@ -225,6 +259,7 @@ pub fn execute_precompile(
         address if address == BLS12_MAP_FP2_TO_G2_ADDRESS => {
             bls12_map_fp2_tp_g2(calldata, gas_remaining)?
         }
+        address if address == P256VERIFY_ADDRESS => p_256_verify(calldata, gas_remaining)?,
         _ => return Err(InternalError::InvalidPrecompileAddress.into()),
     };
 
@@ -359,7 +394,7 @@ pub fn ripemd_160(calldata: &Bytes, gas_remaining: &mut u64) -> Result<Bytes, VM
 }
 
 /// Returns the result of the module-exponentiation operation
-pub fn modexp(calldata: &Bytes, gas_remaining: &mut u64) -> Result<Bytes, VMError> {
+pub fn modexp(calldata: &Bytes, gas_remaining: &mut u64, fork: Fork) -> Result<Bytes, VMError> {
     // If calldata does not reach the required length, we should fill the rest with zeros
     let calldata = fill_with_zeros(calldata, 96);
 
@@ -367,9 +402,23 @@ pub fn modexp(calldata: &Bytes, gas_remaining: &mut u64) -> Result<Bytes, VMErro
     let exponent_size = U256::from_big_endian(calldata.get(32..64).ok_or(InternalError::Slicing)?);
     let modulus_size = U256::from_big_endian(calldata.get(64..96).ok_or(InternalError::Slicing)?);
 
+    // [EIP-7823] - From Osaka inputs over the size limit are invalid
+    if fork >= Fork::Osaka
+        && [base_size, exponent_size, modulus_size]
+            .iter()
+            .any(|size| *size > U256::from(MODEXP_MAX_INPUT_FIELD_SIZE))
+    {
+        return Err(PrecompileError::ParsingInputError.into());
+    }
+
     if base_size == U256::zero() && modulus_size == U256::zero() {
         // On Berlin or newer there is a floor cost for the modexp precompile
-        increase_precompile_consumed_gas(MODEXP_STATIC_COST, gas_remaining)?;
+        let static_cost = if fork >= Fork::Osaka {
+            MODEXP_STATIC_COST_OSAKA
+        } else {
+            MODEXP_STATIC_COST
+        };
+        increase_precompile_consumed_gas(static_cost, gas_remaining)?;
 
         return Ok(Bytes::new());
     }
@@ -412,7 +461,7 @@ pub fn modexp(calldata: &Bytes, gas_remaining: &mut u64) -> Result<Bytes, VMErro
     )
     .ok_or(InternalError::TypeConversion)?;
 
-    let gas_cost = gas_cost::modexp(&exp_first_32, base_size, exponent_size, modulus_size)?;
+    let gas_cost = gas_cost::modexp(&exp_first_32, base_size, exponent_size, modulus_size, fork)?;
 
     increase_precompile_consumed_gas(gas_cost, gas_remaining)?;
 
@@ -1205,6 +1254,107 @@ pub fn bls12_map_fp2_tp_g2(calldata: &Bytes, gas_remaining: &mut u64) -> Result<
     Ok(Bytes::from(padded_result))
 }
 
+/// Signature verification in the “secp256r1” elliptic curve, available from Osaka
+/// Implemented following https://eips.ethereum.org/EIPS/eip-7951, which differs from the
+/// RIP-7212 precompile of L2 in its gas cost and in that inputs of any length other than
+/// 160 bytes are invalid, instead of being padded.
+/// If the verification succeeds, returns 1 in a 32-bit big-endian format.
+/// If the verification fails, returns an empty `Bytes` object.
+pub fn p_256_verify(calldata: &Bytes, gas_remaining: &mut u64) -> Result<Bytes, VMError> {
+    increase_precompile_consumed_gas(P256VERIFY_COST, gas_remaining)?;
+
+    if calldata.len() != P256VERIFY_INPUT_LENGTH {
+        return Ok(Bytes::new());
+    }
+
+    verify_p256_signature(calldata)
+}
+
+/// Verifies the signature in the first 160 bytes of `calldata`, which are the message hash,
+/// the r and s values of the signature, and the x and y coordinates of the public key.
+/// Implemented following https://github.com/ethereum/RIPs/blob/89474e2b9dbd066fac9446c8cd280651bda35849/RIPS/rip-7212.md?plain=1#L1.
+pub(crate) fn verify_p256_signature(calldata: &[u8]) -> Result<Bytes, VMError> {
+    // Parse parameters
+    let message_hash = calldata
+        .get(0..32)
+        .ok_or(PrecompileError::ParsingInputError)?;
+    let r = calldata
+        .get(32..64)
+        .ok_or(PrecompileError::ParsingInputError)?;
+    let s = calldata
+        .get(64..96)
+        .ok_or(PrecompileError::ParsingInputError)?;
+    let x = calldata
+        .get(96..128)
+        .ok_or(PrecompileError::ParsingInputError)?;
+    let y = calldata
+        .get(128..160)
+        .ok_or(PrecompileError::ParsingInputError)?;
+
+    if !validate_p256_parameters(r, s, x, y)? {
+        return Ok(Bytes::new());
+    }
+
+    // Build verifier
+    let Ok(verifier) = P256VerifyingKey::from_encoded_point(
+        &EncodedPoint::from_affine_coordinates(x.into(), y.into(), false),
+    ) else {
+        return Ok(Bytes::new());
+    };
+
+    // Build signature
+    let r: [u8; 32] = r.try_into().map_err(|_| InternalError::Slicing)?;
+    let s: [u8; 32] = s.try_into().map_err(|_| InternalError::Slicing)?;
+
+    let Ok(signature) = P256Signature::from_scalars(r, s) else {
+        return Ok(Bytes::new());
+    };
+
+    // Verify message signature
+    let success = verifier.verify_prehash(message_hash, &signature).is_ok();
+
+    // If the verification succeeds, returns 1 in a 32-bit big-endian format.
+    // If the verification fails, returns an empty `Bytes` object.
+    if success {
+        let mut result = [0; 32];
+        result[31] = 1;
+        Ok(Bytes::from(result.to_vec()))
+    } else {
+        Ok(Bytes::new())
+    }
+}
+
+/// Following https://github.com/ethereum/RIPs/blob/89474e2b9dbd066fac9446c8cd280651bda35849/RIPS/rip-7212.md?plain=1#L86
+fn validate_p256_parameters(r: &[u8], s: &[u8], x: &[u8], y: &[u8]) -> Result<bool, VMError> {
+    let [r, s, x, y] = [r, s, x, y].map(P256Uint::from_be_slice);
+
+    // Verify that the r and s values are in (0, n) (exclusive)
+    if r == P256Uint::ZERO || r >= P256_N || s == P256Uint::ZERO || s >= P256_N {
+        return Ok(false);
+    }
+
+    // Verify that both x and y are in [0, p) (inclusive 0, exclusive p)
+    if x >= P256_P || y >= P256_P {
+        return Ok(false);
+    }
+
+    // Verify that the point formed by (x, y) is on the curve
+    let x: Option<P256FieldElement> = P256FieldElement::from_uint(x).into();
+    let y: Option<P256FieldElement> = P256FieldElement::from_uint(y).into();
+
+    let (Some(x), Some(y)) = (x, y) else {
+        return Err(InternalError::Slicing.into());
+    };
+
+    // Curve equation: `y² = x³ + ax + b`
+    let a_x = P256_A.multiply(&x);
+    if y.square() == x.pow_vartime(&[3u64]).add(&a_x).add(&P256_B) {
+        return Ok(true);
+    }
+
+    Ok(false)
+}
+
 /// coordinate raw bytes should have a len of 64
 #[expect(clippy::indexing_slicing, reason = "bounds checked at start")]
 #[inline]
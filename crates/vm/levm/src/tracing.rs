@@ -1,13 +1,79 @@
 use crate::{
-    errors::{ContextResult, InternalError, TxResult, VMError},
+    errors::{ContextResult, InternalError, OpcodeResult, TxResult, VMError},
+    opcodes::Opcode,
     vm::VM,
 };
 use bytes::Bytes;
 use ethrex_common::{
-    Address, U256,
+    Address, H256, U256,
     tracing::{CallLog, CallTraceFrame, CallType},
     types::Log,
 };
+use std::{cell::RefCell, rc::Rc};
+
+/// Callbacks invoked by the VM while executing a transaction, for writing custom tracers
+/// (coverage, gas profiling, taint analysis...) outside of LEVM.
+///
+/// Every callback has an empty default implementation, so tracers only implement the ones they
+/// need. A tracer is attached with [VM::set_tracer]; when there's none, the VM doesn't do any work
+/// for them besides checking that.
+///
+/// The VM is passed to every callback to inspect its state, e.g. the pc, stack, memory and gas
+/// of `vm.current_call_frame`.
+pub trait Tracer {
+    /// Called right before executing an opcode.
+    fn step(&mut self, _vm: &VM<'_>, _opcode: Opcode) {}
+
+    /// Called right after executing an opcode, before the pc is advanced.
+    fn step_end(&mut self, _vm: &VM<'_>, _opcode: Opcode, _result: &Result<OpcodeResult, VMError>) {
+    }
+
+    /// Called when entering a new context, either the top one of the transaction or one created
+    /// by a CALL* or CREATE* opcode.
+    #[allow(clippy::too_many_arguments)]
+    fn call_enter(
+        &mut self,
+        _vm: &VM<'_>,
+        _call_type: CallType,
+        _from: Address,
+        _to: Address,
+        _value: U256,
+        _gas: u64,
+        _input: &Bytes,
+    ) {
+    }
+
+    /// Called when exiting the context of the last [Tracer::call_enter], `error` is set if it
+    /// didn't succeed.
+    fn call_exit(&mut self, _vm: &VM<'_>, _gas_used: u64, _output: &Bytes, _error: Option<&str>) {}
+
+    /// Called when a LOG* opcode emits a log. Logs of contexts that revert are discarded later.
+    fn log(&mut self, _vm: &VM<'_>, _log: &Log) {}
+
+    /// Called when SLOAD reads a storage slot.
+    fn storage_read(&mut self, _vm: &VM<'_>, _address: Address, _key: H256, _value: U256) {}
+
+    /// Called when SSTORE writes a storage slot, even if the value doesn't change.
+    fn storage_write(
+        &mut self,
+        _vm: &VM<'_>,
+        _address: Address,
+        _key: H256,
+        _previous_value: U256,
+        _new_value: U256,
+    ) {
+    }
+
+    /// Called when SELFDESTRUCT is executed, `balance` is the amount sent to the beneficiary.
+    fn selfdestruct(
+        &mut self,
+        _vm: &VM<'_>,
+        _address: Address,
+        _beneficiary: Address,
+        _balance: U256,
+    ) {
+    }
+}
 
 /// Geth's callTracer (https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers)
 /// Use `LevmCallTracer::disabled()` when tracing is not wanted.
@@ -193,6 +259,36 @@ fn clear_reverted_logs(callframe: &mut CallTraceFrame) {
 }
 
 impl<'a> VM<'a> {
+    /// Attaches a custom tracer, replacing the previous one if any. The caller keeps a reference
+    /// to it for reading its results after the execution.
+    pub fn set_tracer(&mut self, tracer: Rc<RefCell<dyn Tracer>>) {
+        self.custom_tracer = Some(tracer);
+    }
+
+    /// Runs the callback on the custom tracer, if there's one.
+    #[inline(always)]
+    pub(crate) fn with_tracer(&self, callback: impl FnOnce(&mut dyn Tracer, &Self)) {
+        if let Some(tracer) = &self.custom_tracer {
+            callback(&mut *tracer.borrow_mut(), self);
+        }
+    }
+
+    /// Notifies the custom tracer of the exit of a context, see [Tracer::call_exit].
+    pub(crate) fn trace_call_exit(&self, ctx_result: &ContextResult) {
+        self.with_tracer(|tracer, vm| {
+            let error = match &ctx_result.result {
+                TxResult::Success => None,
+                TxResult::Revert(error) => Some(error.to_string()),
+            };
+            tracer.call_exit(
+                vm,
+                ctx_result.gas_used,
+                &ctx_result.output,
+                error.as_deref(),
+            );
+        });
+    }
+
     /// This method is intended to be accessed after transaction execution
     pub fn get_trace_result(&mut self) -> Result<CallTraceFrame, VMError> {
         self.tracer
@@ -2,6 +2,7 @@ use crate::{
     EVMConfig, Environment,
     account::{AccountStatus, LevmAccount},
     call_frame::CallFrameBackup,
+    code_cache::AnalyzedCode,
     constants::*,
     db::gen_db::GeneralizedDatabase,
     errors::{ExceptionalHalt, InternalError, TxValidationError, VMError},
@@ -11,14 +12,14 @@ use crate::{
         TOTAL_COST_FLOOR_PER_TOKEN, WARM_ADDRESS_ACCESS_COST, fake_exponential,
     },
     l2_precompiles,
-    opcodes::Opcode,
     precompiles::{
-        self, SIZE_PRECOMPILES_CANCUN, SIZE_PRECOMPILES_PRAGUE, SIZE_PRECOMPILES_PRE_CANCUN,
+        self, P256VERIFY_ADDRESS, SIZE_PRECOMPILES_CANCUN, SIZE_PRECOMPILES_PRAGUE,
+        SIZE_PRECOMPILES_PRE_CANCUN,
     },
     vm::{Substate, VM, VMType},
 };
 use ExceptionalHalt::OutOfGas;
-use bytes::{Bytes, buf::IntoIter};
+use bytes::Bytes;
 use ethrex_common::{
     Address, H256, U256,
     types::{Account, Fork, Transaction, tx_fields::*},
@@ -35,7 +36,7 @@ use secp256k1::{
 use sha3::{Digest, Keccak256};
 use std::{
     collections::{BTreeMap, BTreeSet, HashMap, HashSet},
-    iter::Enumerate,
+    sync::Arc,
 };
 pub type Storage = HashMap<U256, H256>;
 
@@ -97,69 +98,6 @@ pub fn calculate_create2_address(
     Ok(generated_address)
 }
 
-/// # Filter for jump target offsets.
-///
-/// Used to filter which program offsets are not valid jump targets. Implemented as a sorted list of
-/// offsets of bytes `0x5B` (`JUMPDEST`) within push constants.
-#[derive(Debug)]
-pub struct JumpTargetFilter {
-    /// The list of invalid jump target offsets.
-    filter: Vec<usize>,
-    /// The last processed offset, plus one.
-    offset: usize,
-
-    /// Program bytecode iterator.
-    iter: Enumerate<IntoIter<Bytes>>,
-    /// Number of bytes remaining to process from the last push instruction.
-    partial: usize,
-}
-
-impl JumpTargetFilter {
-    /// Create an empty `JumpTargetFilter`.
-    pub fn new(bytecode: Bytes) -> Self {
-        Self {
-            filter: Vec::new(),
-            offset: 0,
-
-            iter: bytecode.into_iter().enumerate(),
-            partial: 0,
-        }
-    }
-
-    /// Check whether a target jump address is blacklisted or not.
-    ///
-    /// This method may potentially grow the filter if the requested address is out of range.
-    pub fn is_blacklisted(&mut self, address: usize) -> bool {
-        if let Some(delta) = address.checked_sub(self.offset) {
-            // It is not realistic to expect a bytecode offset to overflow an `usize`.
-            #[expect(clippy::arithmetic_side_effects)]
-            for (offset, value) in (&mut self.iter).take(delta + 1) {
-                match self.partial.checked_sub(1) {
-                    None => {
-                        // Neither the `as` conversions nor the subtraction can fail here.
-                        #[expect(clippy::as_conversions)]
-                        if (Opcode::PUSH1..=Opcode::PUSH32).contains(&Opcode::from(value)) {
-                            self.partial = value as usize - Opcode::PUSH0 as usize;
-                        }
-                    }
-                    Some(partial) => {
-                        self.partial = partial;
-
-                        #[expect(clippy::as_conversions)]
-                        if value == Opcode::JUMPDEST as u8 {
-                            self.filter.push(offset);
-                        }
-                    }
-                }
-            }
-
-            self.filter.last() == Some(&address)
-        } else {
-            self.filter.binary_search(&address).is_ok()
-        }
-    }
-}
-
 // ================== Backup related functions =======================
 
 /// Restore the state of the cache to the state it in the callframe backup.
@@ -357,7 +295,7 @@ pub fn eip7702_get_code(
     db: &mut GeneralizedDatabase,
     accrued_substate: &mut Substate,
     address: Address,
-) -> Result<(bool, u64, Address, Bytes), VMError> {
+) -> Result<(bool, u64, Address, Arc<AnalyzedCode>), VMError> {
     // Address is the delgated address
     let bytecode = db.get_account_code(address)?;
 
@@ -366,7 +304,7 @@ pub fn eip7702_get_code(
     // return the same address given
     // return the bytecode of the given address
     if !code_has_delegation(bytecode)? {
-        return Ok((false, 0, address, bytecode.clone()));
+        return Ok((false, 0, address, db.get_analyzed_account_code(address)?));
     }
 
     // Here the address has a delegation code
@@ -380,9 +318,9 @@ pub fn eip7702_get_code(
         COLD_ADDRESS_ACCESS_COST
     };
 
-    let authorized_bytecode = db.get_account_code(auth_address)?.clone();
+    let authorized_code = db.get_analyzed_account_code(auth_address)?;
 
-    Ok((true, access_cost, auth_address, authorized_bytecode))
+    Ok((true, access_cost, auth_address, authorized_code))
 }
 
 impl<'a> VM<'a> {
@@ -615,6 +553,10 @@ impl<'a> VM<'a> {
         for i in 1..=max_precompile_address {
             initial_accessed_addresses.insert(Address::from_low_u64_be(i));
         }
+        // [EIP-7951] - P256VERIFY is outside of the range of the other precompiles
+        if self.env.config.fork >= Fork::Osaka {
+            initial_accessed_addresses.insert(P256VERIFY_ADDRESS);
+        }
 
         // Add access lists contents to accessed accounts and accessed storage slots.
         for (address, keys) in self.tx.access_list().clone() {
@@ -634,6 +576,7 @@ impl<'a> VM<'a> {
             refunded_gas: 0,
             transient_storage: HashMap::new(),
             logs: Vec::new(),
+            l1_fee: U256::zero(),
         };
 
         Ok(())
@@ -1,7 +1,11 @@
 use crate::{
     TransientStorage,
     call_frame::{CallFrame, Stack},
-    db::gen_db::GeneralizedDatabase,
+    code_cache::AnalyzedCode,
+    db::{
+        gen_db::GeneralizedDatabase,
+        state_access::{ReadSet, StateAccess},
+    },
     debug::DebugMode,
     environment::Environment,
     errors::{ContextResult, ExecutionReport, InternalError, OpcodeResult, VMError},
@@ -11,10 +15,12 @@ use crate::{
     },
     l2_precompiles,
     memory::Memory,
+    opcodes::Opcode,
     precompiles::{
-        self, SIZE_PRECOMPILES_CANCUN, SIZE_PRECOMPILES_PRAGUE, SIZE_PRECOMPILES_PRE_CANCUN,
+        self, P256VERIFY_ADDRESS, SIZE_PRECOMPILES_CANCUN, SIZE_PRECOMPILES_PRAGUE,
+        SIZE_PRECOMPILES_PRE_CANCUN,
     },
-    tracing::LevmCallTracer,
+    tracing::{LevmCallTracer, Tracer},
 };
 use bytes::Bytes;
 use ethrex_common::{
@@ -26,6 +32,7 @@ use std::{
     cell::RefCell,
     collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     rc::Rc,
+    sync::Arc,
 };
 
 pub type Storage = HashMap<U256, H256>;
@@ -47,6 +54,8 @@ pub struct Substate {
     pub refunded_gas: u64,
     pub transient_storage: TransientStorage,
     pub logs: Vec<Log>,
+    /// L1 data fee charged to the sender, only set by the L2 hook.
+    pub l1_fee: U256,
 }
 
 pub struct VM<'a> {
@@ -64,6 +73,8 @@ pub struct VM<'a> {
     pub storage_original_values: BTreeMap<(Address, H256), U256>,
     /// When enabled, it "logs" relevant information during execution
     pub tracer: LevmCallTracer,
+    /// Custom tracer notified of every step, call, log and storage access. See [Tracer].
+    pub custom_tracer: Option<Rc<RefCell<dyn Tracer>>>,
     /// Mode for printing some useful stuff, only used in development!
     pub debug_mode: DebugMode,
     /// A pool of stacks to avoid reallocating too much when creating new call frames.
@@ -80,6 +91,10 @@ impl<'a> VM<'a> {
         vm_type: VMType,
     ) -> Result<Self, VMError> {
         db.tx_backup = None; // If BackupHook is enabled, it will contain backup at the end of tx execution.
+        let record_state_access = db.tx_reads.is_some();
+        if let Some(reads) = &mut db.tx_reads {
+            *reads = ReadSet::default();
+        }
 
         let mut substate = Substate::initialize(&env, tx)?;
 
@@ -94,6 +109,7 @@ impl<'a> VM<'a> {
             substate_backups: Vec::new(),
             storage_original_values: BTreeMap::new(),
             tracer,
+            custom_tracer: None,
             debug_mode: DebugMode::disabled(),
             stack_pool: Vec::new(),
             vm_type,
@@ -101,7 +117,7 @@ impl<'a> VM<'a> {
                 env.origin,
                 callee,
                 Address::default(), // Will be assigned at the end of prepare_execution
-                Bytes::new(),       // Will be assigned at the end of prepare_execution
+                Arc::new(AnalyzedCode::default()), // Will be assigned at the end of prepare_execution
                 tx.value(),
                 tx.data().clone(),
                 false,
@@ -131,6 +147,11 @@ impl<'a> VM<'a> {
             vm.tx.data(),
         );
 
+        if record_state_access {
+            // The backup of the whole transaction holds the original values of everything it wrote
+            vm.add_hook(BackupHook::default());
+        }
+
         #[cfg(feature = "debug")]
         {
             // Enable debug mode for printing in Solidity contracts.
@@ -156,6 +177,23 @@ impl<'a> VM<'a> {
         // We want to apply these changes even if the Tx reverts. E.g. Incrementing sender nonce
         self.current_call_frame.call_frame_backup.clear();
 
+        self.with_tracer(|tracer, vm| {
+            let call_type = if vm.tx.is_contract_creation() {
+                CallType::CREATE
+            } else {
+                CallType::CALL
+            };
+            tracer.call_enter(
+                vm,
+                call_type,
+                vm.env.origin,
+                vm.current_call_frame.to,
+                vm.tx.value(),
+                vm.env.gas_limit,
+                vm.tx.data(),
+            );
+        });
+
         if self.is_create()? {
             // Create contract, reverting the Tx if address is already occupied.
             if let Some(context_result) = self.handle_create_transaction()? {
@@ -176,6 +214,7 @@ impl<'a> VM<'a> {
     pub fn run_execution(&mut self) -> Result<ContextResult, VMError> {
         if self.is_precompile(&self.current_call_frame.to) {
             let vm_type = self.vm_type;
+            let fork = self.env.config.fork;
             let call_frame = &mut self.current_call_frame;
 
             return Self::execute_precompile(
@@ -184,17 +223,22 @@ impl<'a> VM<'a> {
                 &call_frame.calldata,
                 call_frame.gas_limit,
                 &mut call_frame.gas_remaining,
+                fork,
             );
         }
 
         loop {
             let opcode = self.current_call_frame.next_opcode();
 
+            self.with_tracer(|tracer, vm| tracer.step(vm, Opcode::from(opcode)));
+
             // Call the opcode, using the opcode function lookup table.
             // Indexing will not panic as all the opcode values fit within the table.
             #[allow(clippy::indexing_slicing, clippy::as_conversions)]
             let op_result = VM::OPCODE_TABLE[opcode as usize].call(self);
 
+            self.with_tracer(|tracer, vm| tracer.step_end(vm, Opcode::from(opcode), &op_result));
+
             let result = match op_result {
                 Ok(OpcodeResult::Continue { pc_increment }) => {
                     self.increment_pc_by(pc_increment)?;
@@ -223,6 +267,7 @@ impl<'a> VM<'a> {
         calldata: &Bytes,
         gas_limit: u64,
         gas_remaining: &mut u64,
+        fork: Fork,
     ) -> Result<ContextResult, VMError> {
         let execute_precompile = match vm_type {
             VMType::L1 => precompiles::execute_precompile,
@@ -230,7 +275,7 @@ impl<'a> VM<'a> {
         };
 
         Self::handle_precompile_result(
-            execute_precompile(code_address, calldata, gas_remaining),
+            execute_precompile(code_address, calldata, gas_remaining, fork),
             gas_limit,
             *gas_remaining,
         )
@@ -269,6 +314,16 @@ impl<'a> VM<'a> {
         }
 
         self.tracer.exit_context(&ctx_result, true)?;
+        self.trace_call_exit(&ctx_result);
+
+        let state_access = match self.db.tx_reads.as_mut() {
+            Some(reads) => Some(StateAccess::new(
+                std::mem::take(reads),
+                &self.db.get_tx_backup()?,
+                &self.db.current_accounts_state,
+            )?),
+            None => None,
+        };
 
         let report = ExecutionReport {
             result: ctx_result.result.clone(),
@@ -276,6 +331,8 @@ impl<'a> VM<'a> {
             gas_refunded: self.substate.refunded_gas,
             output: std::mem::take(&mut ctx_result.output),
             logs: self.substate.logs.clone(),
+            state_access,
+            l1_fee: self.substate.l1_fee,
         };
 
         Ok(report)
@@ -307,6 +364,10 @@ impl Substate {
         for i in 1..=max_precompile_address {
             initial_accessed_addresses.insert(Address::from_low_u64_be(i));
         }
+        // [EIP-7951] - P256VERIFY is outside of the range of the other precompiles
+        if env.config.fork >= Fork::Osaka {
+            initial_accessed_addresses.insert(P256VERIFY_ADDRESS);
+        }
 
         // Add access lists contents to accessed accounts and accessed storage slots.
         for (address, keys) in tx.access_list().clone() {
@@ -326,6 +387,7 @@ impl Substate {
             refunded_gas: 0,
             transient_storage: HashMap::new(),
             logs: Vec::new(),
+            l1_fee: U256::zero(),
         };
 
         Ok(substate)
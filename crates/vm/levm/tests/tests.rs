@@ -1,9 +1,11 @@
+#![allow(clippy::arithmetic_side_effects)]
 #![allow(clippy::indexing_slicing)]
 #![allow(clippy::unwrap_used)]
 
 use bytes::Bytes;
+use ethrex_common::types::Fork;
 use ethrex_levm::l2_precompiles::p_256_verify;
-use ethrex_levm::precompiles::bls12_pairing_check;
+use ethrex_levm::precompiles::{self, bls12_pairing_check, modexp};
 
 #[test]
 fn pairing_infinity() {
@@ -74,5 +76,592 @@ fn p_256_verify_test() {
             "Gas assertion failed on test: {}.",
             test.name
         );
+
+        // The L1 precompile (EIP-7951) returns the same output but charges a flat cost.
+        let mut remaining_gas = initial_remaining_gas;
+        let result = precompiles::p_256_verify(&calldata, &mut remaining_gas).unwrap();
+        assert_eq!(
+            result, expected_result,
+            "L1 result failed on: {}.",
+            test.name
+        );
+        assert_eq!(initial_remaining_gas - remaining_gas, 6900);
+    }
+}
+
+fn modexp_calldata(base: &[u8], exponent: &[u8], modulus: &[u8]) -> Bytes {
+    let mut calldata = Vec::new();
+    for len in [base.len(), exponent.len(), modulus.len()] {
+        let mut word = [0u8; 32];
+        word[24..].copy_from_slice(&u64::try_from(len).unwrap().to_be_bytes());
+        calldata.extend_from_slice(&word);
     }
+    calldata.extend_from_slice(base);
+    calldata.extend_from_slice(exponent);
+    calldata.extend_from_slice(modulus);
+    Bytes::from(calldata)
+}
+
+#[test]
+fn modexp_osaka_pricing() {
+    // 3^5 mod 7 = 5
+    let calldata = modexp_calldata(&[3], &[5], &[7]);
+
+    let mut remaining_gas = 10000;
+    let result = modexp(&calldata, &mut remaining_gas, Fork::Prague).unwrap();
+    assert_eq!(result, Bytes::from(vec![5]));
+    assert_eq!(10000 - remaining_gas, 200);
+
+    let mut remaining_gas = 10000;
+    let result = modexp(&calldata, &mut remaining_gas, Fork::Osaka).unwrap();
+    assert_eq!(result, Bytes::from(vec![5]));
+    assert_eq!(10000 - remaining_gas, 500);
+}
+
+#[test]
+fn modexp_osaka_input_size_limit() {
+    let calldata = modexp_calldata(&[1; 1025], &[1], &[7]);
+
+    let mut remaining_gas = 10_000_000;
+    assert!(modexp(&calldata, &mut remaining_gas, Fork::Prague).is_ok());
+
+    let mut remaining_gas = 10_000_000;
+    assert!(modexp(&calldata, &mut remaining_gas, Fork::Osaka).is_err());
+}
+
+use ethrex_common::{
+    Address, H256, U256,
+    tracing::CallType,
+    types::{
+        Account, AccountInfo, ChainConfig, EIP1559Transaction, L1FeeConfig, Transaction, TxKind,
+        code_hash,
+    },
+};
+use ethrex_levm::{
+    EVMConfig, Environment,
+    db::{Database, gen_db::GeneralizedDatabase},
+    errors::DatabaseError,
+    native_tracers::{Erc7562Tracer, FourByteTracer, GasProfiler, MuxTracer, OpcodeGas},
+    tracing::{LevmCallTracer, Tracer},
+    vm::{VM, VMType},
+};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc, sync::Arc};
+
+/// Database without any account, every account used by a test must be in the initial cache.
+struct EmptyDatabase;
+
+impl Database for EmptyDatabase {
+    fn get_account_info(&self, _address: Address) -> Result<AccountInfo, DatabaseError> {
+        Ok(AccountInfo {
+            code_hash: code_hash(&Bytes::new()),
+            balance: U256::zero(),
+            nonce: 0,
+        })
+    }
+
+    fn get_storage_value(&self, _address: Address, _key: H256) -> Result<U256, DatabaseError> {
+        Ok(U256::zero())
+    }
+
+    fn get_block_hash(&self, _block_number: u64) -> Result<H256, DatabaseError> {
+        Ok(H256::zero())
+    }
+
+    fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError> {
+        Ok(ChainConfig::default())
+    }
+
+    fn get_account_code(&self, _code_hash: H256) -> Result<Bytes, DatabaseError> {
+        Ok(Bytes::new())
+    }
+}
+
+const SENDER: u64 = 0x100;
+const CONTRACT: u64 = 0x42;
+
+fn db_with_contract(code: Bytes) -> GeneralizedDatabase {
+    db_with_funded_contract(code, U256::zero())
+}
+
+fn db_with_funded_contract(code: Bytes, balance: U256) -> GeneralizedDatabase {
+    let accounts = BTreeMap::from([
+        (
+            Address::from_low_u64_be(SENDER),
+            Account::new(U256::MAX, Bytes::new(), 0, BTreeMap::new()),
+        ),
+        (
+            Address::from_low_u64_be(CONTRACT),
+            Account::new(balance, code, 0, BTreeMap::new()),
+        ),
+    ]);
+    GeneralizedDatabase::new_with_account_state(Arc::new(EmptyDatabase), accounts)
+}
+
+fn call_contract<'a>(db: &'a mut GeneralizedDatabase) -> VM<'a> {
+    call_contract_with_data(db, Bytes::new())
+}
+
+fn call_contract_with_data<'a>(db: &'a mut GeneralizedDatabase, data: Bytes) -> VM<'a> {
+    call_contract_on_fork(db, data, Fork::Prague)
+}
+
+fn call_contract_on_fork<'a>(db: &'a mut GeneralizedDatabase, data: Bytes, fork: Fork) -> VM<'a> {
+    let env = Environment {
+        origin: Address::from_low_u64_be(SENDER),
+        gas_limit: 1_000_000,
+        block_gas_limit: 30_000_000,
+        config: EVMConfig::new(fork, EVMConfig::canonical_values(fork)),
+        ..Default::default()
+    };
+    let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+        to: TxKind::Call(Address::from_low_u64_be(CONTRACT)),
+        gas_limit: 1_000_000,
+        data,
+        ..Default::default()
+    });
+    VM::new(env, db, &tx, LevmCallTracer::disabled(), VMType::L1).unwrap()
+}
+
+/// Code returning CLZ of `value`
+fn clz_code(value: U256) -> Bytes {
+    // PUSH32 value CLZ PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+    let mut code = vec![0x7f];
+    code.extend_from_slice(&value.to_big_endian());
+    code.extend_from_slice(&[0x1e, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3]);
+    Bytes::from(code)
+}
+
+#[test]
+fn clz_counts_leading_zeros_from_osaka() {
+    for (value, leading_zeros) in [
+        (U256::zero(), 256),
+        (U256::one(), 255),
+        (U256([0, 0, 0, 0x8000_0000_0000_0000]), 0),
+    ] {
+        let mut db = db_with_contract(clz_code(value));
+        let report = call_contract_on_fork(&mut db, Bytes::new(), Fork::Osaka)
+            .execute()
+            .unwrap();
+        assert!(report.is_success());
+        assert_eq!(
+            U256::from_big_endian(&report.output),
+            U256::from(leading_zeros)
+        );
+    }
+
+    // Before Osaka CLZ is an invalid opcode
+    let mut db = db_with_contract(clz_code(U256::one()));
+    let report = call_contract_on_fork(&mut db, Bytes::new(), Fork::Prague)
+        .execute()
+        .unwrap();
+    assert!(!report.is_success());
+}
+
+#[derive(Default)]
+struct RecordingTracer {
+    steps: Vec<u8>,
+    steps_ended: usize,
+    calls: Vec<CallType>,
+    exits: usize,
+    storage_reads: Vec<(H256, U256)>,
+    storage_writes: Vec<(H256, U256, U256)>,
+}
+
+impl Tracer for RecordingTracer {
+    fn step(&mut self, _vm: &VM<'_>, opcode: ethrex_levm::opcodes::Opcode) {
+        self.steps.push(opcode.into());
+    }
+
+    fn step_end(
+        &mut self,
+        _vm: &VM<'_>,
+        _opcode: ethrex_levm::opcodes::Opcode,
+        _result: &Result<ethrex_levm::errors::OpcodeResult, ethrex_levm::errors::VMError>,
+    ) {
+        self.steps_ended += 1;
+    }
+
+    fn call_enter(
+        &mut self,
+        _vm: &VM<'_>,
+        call_type: CallType,
+        _from: Address,
+        _to: Address,
+        _value: U256,
+        _gas: u64,
+        _input: &Bytes,
+    ) {
+        self.calls.push(call_type);
+    }
+
+    fn call_exit(&mut self, _vm: &VM<'_>, _gas_used: u64, _output: &Bytes, error: Option<&str>) {
+        assert!(error.is_none());
+        self.exits += 1;
+    }
+
+    fn storage_read(&mut self, _vm: &VM<'_>, _address: Address, key: H256, value: U256) {
+        self.storage_reads.push((key, value));
+    }
+
+    fn storage_write(
+        &mut self,
+        _vm: &VM<'_>,
+        _address: Address,
+        key: H256,
+        previous_value: U256,
+        new_value: U256,
+    ) {
+        self.storage_writes.push((key, previous_value, new_value));
+    }
+}
+
+#[test]
+fn custom_tracer_is_notified_of_steps_calls_and_storage_accesses() {
+    // PUSH1 0x2a PUSH1 0x00 SSTORE PUSH1 0x00 SLOAD POP STOP
+    let code = Bytes::from(vec![
+        0x60, 0x2a, 0x60, 0x00, 0x55, 0x60, 0x00, 0x54, 0x50, 0x00,
+    ]);
+    let mut db = db_with_contract(code);
+    let mut vm = call_contract(&mut db);
+
+    let tracer = Rc::new(RefCell::new(RecordingTracer::default()));
+    vm.set_tracer(tracer.clone());
+    let report = vm.execute().unwrap();
+    assert!(report.is_success());
+
+    let tracer = tracer.borrow();
+    assert_eq!(tracer.steps, vec![0x60, 0x60, 0x55, 0x60, 0x54, 0x50, 0x00]);
+    assert_eq!(tracer.steps_ended, tracer.steps.len());
+    assert!(matches!(tracer.calls.as_slice(), [CallType::CALL]));
+    assert_eq!(tracer.exits, 1);
+    assert_eq!(
+        tracer.storage_writes,
+        vec![(H256::zero(), U256::zero(), U256::from(0x2a))]
+    );
+    assert_eq!(tracer.storage_reads, vec![(H256::zero(), U256::from(0x2a))]);
+}
+
+#[test]
+fn mux_tracer_runs_four_byte_tracer_and_gas_profiler() {
+    // PUSH1 0x01 PUSH1 0x00 SSTORE STOP
+    let code = Bytes::from(vec![0x60, 0x01, 0x60, 0x00, 0x55, 0x00]);
+    let mut db = db_with_contract(code);
+    let mut vm = call_contract_with_data(&mut db, Bytes::from(vec![0xaa, 0xbb, 0xcc, 0xdd, 0x01]));
+
+    let four_byte = Rc::new(RefCell::new(FourByteTracer::default()));
+    let gas_profiler = Rc::new(RefCell::new(GasProfiler::default()));
+    vm.set_tracer(Rc::new(RefCell::new(MuxTracer::new(vec![
+        four_byte.clone(),
+        gas_profiler.clone(),
+    ]))));
+    let report = vm.execute().unwrap();
+    assert!(report.is_success());
+
+    assert_eq!(
+        four_byte.borrow().selectors,
+        BTreeMap::from([("0xaabbccdd-1".to_string(), 1)])
+    );
+
+    let profile = &gas_profiler.borrow().profile;
+    // Two PUSH1 and a cold SSTORE setting a zero slot
+    assert_eq!(profile.total_gas, 3 + 3 + 22_100);
+    assert_eq!(profile.opcodes["PUSH1"], OpcodeGas { count: 2, gas: 6 });
+    assert_eq!(
+        profile.opcodes["SSTORE"],
+        OpcodeGas {
+            count: 1,
+            gas: 22_100
+        }
+    );
+    assert_eq!(profile.opcodes["STOP"], OpcodeGas { count: 1, gas: 0 });
+    assert_eq!(
+        profile.contracts,
+        BTreeMap::from([(Address::from_low_u64_be(CONTRACT), 22_106)])
+    );
+}
+
+#[test]
+fn gas_profiler_charges_value_transfers_and_failed_opcodes() {
+    // PUSH1 0x00 PUSH1 0x00 PUSH1 0x00 PUSH1 0x00 PUSH1 0x01 PUSH2 0x1234 PUSH1 0x00 CALL POP STOP
+    let code = Bytes::from(vec![
+        0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x01, 0x61, 0x12, 0x34, 0x60, 0x00,
+        0xf1, 0x50, 0x00,
+    ]);
+    let mut db = db_with_funded_contract(code, U256::one());
+    let mut vm = call_contract(&mut db);
+    let gas_profiler = Rc::new(RefCell::new(GasProfiler::default()));
+    vm.set_tracer(gas_profiler.clone());
+    let report = vm.execute().unwrap();
+    assert!(report.is_success());
+
+    // A cold account access, a value transfer and a new account, the 2300 gas stipend sent to the
+    // callee isn't taken out of it
+    let profile = &gas_profiler.borrow().profile;
+    assert_eq!(
+        profile.opcodes["CALL"],
+        OpcodeGas {
+            count: 1,
+            gas: 2_600 + 9_000 + 25_000
+        }
+    );
+
+    // PUSH1 0x00 JUMP
+    let code = Bytes::from(vec![0x60, 0x00, 0x56]);
+    let mut db = db_with_contract(code);
+    let mut vm = call_contract(&mut db);
+    let gas_profiler = Rc::new(RefCell::new(GasProfiler::default()));
+    vm.set_tracer(gas_profiler.clone());
+    let report = vm.execute().unwrap();
+    assert!(!report.is_success());
+
+    // The invalid jump consumes all the gas left
+    let profile = &gas_profiler.borrow().profile;
+    assert_eq!(profile.total_gas, report.gas_used - 21_000);
+    assert_eq!(
+        profile.opcodes["JUMP"],
+        OpcodeGas {
+            count: 1,
+            gas: profile.total_gas - 3
+        }
+    );
+}
+
+#[test]
+fn erc7562_tracer_checks_the_rules_during_validation() {
+    const TOKEN: u64 = 0x43;
+    const ACCOUNT: u64 = 0x44;
+    // Entry point calling ACCOUNT's validateUserOp: TIMESTAMP POP,
+    // PUSH4 <selector> PUSH1 0xe0 SHL PUSH1 0x00 MSTORE, then CALL(GAS, ACCOUNT, 0, 0, 4, 0, 0) POP STOP
+    let entry_point_code = Bytes::from(vec![
+        0x42, 0x50, 0x63, 0x19, 0x82, 0x2f, 0x7c, 0x60, 0xe0, 0x1b, 0x60, 0x00, 0x52, 0x60, 0x00,
+        0x60, 0x00, 0x60, 0x04, 0x60, 0x00, 0x60, 0x00, 0x60, 0x44, 0x5a, 0xf1, 0x50, 0x00,
+    ]);
+    // TIMESTAMP POP, then CALL(GAS, TOKEN, 0, 0, 0, 0, 0) POP STOP
+    let account_code = Bytes::from(vec![
+        0x42, 0x50, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x43, 0x5a,
+        0xf1, 0x50, 0x00,
+    ]);
+    // Reads the slot of a mapping entry keyed by the caller, then slot 5:
+    // CALLER PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 KECCAK256 SLOAD POP PUSH1 0x05 SLOAD POP STOP
+    let token_code = Bytes::from(vec![
+        0x33, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0x20, 0x54, 0x50, 0x60, 0x05, 0x54, 0x50,
+        0x00,
+    ]);
+    let accounts = BTreeMap::from([
+        (
+            Address::from_low_u64_be(SENDER),
+            Account::new(U256::MAX, Bytes::new(), 0, BTreeMap::new()),
+        ),
+        (
+            Address::from_low_u64_be(CONTRACT),
+            Account::new(U256::zero(), entry_point_code, 0, BTreeMap::new()),
+        ),
+        (
+            Address::from_low_u64_be(ACCOUNT),
+            Account::new(U256::zero(), account_code, 0, BTreeMap::new()),
+        ),
+        (
+            Address::from_low_u64_be(TOKEN),
+            Account::new(U256::zero(), token_code, 0, BTreeMap::new()),
+        ),
+    ]);
+    let mut db = GeneralizedDatabase::new_with_account_state(Arc::new(EmptyDatabase), accounts);
+    let mut vm = call_contract(&mut db);
+
+    let tracer = Rc::new(RefCell::new(Erc7562Tracer::new(
+        Address::from_low_u64_be(CONTRACT),
+        Address::from_low_u64_be(ACCOUNT),
+        None,
+        None,
+    )));
+    vm.set_tracer(tracer.clone());
+    let report = vm.execute().unwrap();
+    assert!(report.is_success());
+
+    let tracer = tracer.borrow();
+    // The entry point's TIMESTAMP isn't part of the validation
+    assert_eq!(tracer.violations.len(), 2, "{:?}", tracer.violations);
+    assert_eq!(
+        tracer.violations[0],
+        "OP-011: TIMESTAMP is banned during validation"
+    );
+    // The mapping entry keyed by the account is associated with it, slot 5 isn't
+    assert!(tracer.violations[1].starts_with("STO-021"));
+    assert!(tracer.violations[1].ends_with(&format!(
+        "{:#x}, not associated with the sender",
+        Address::from_low_u64_be(TOKEN)
+    )));
+    assert!(tracer.validation_gas_used.is_some());
+    assert_eq!(tracer.execution_gas_used, None);
+}
+
+#[test]
+fn erc7562_tracer_checks_the_factory_and_the_paymaster() {
+    const TOKEN: u64 = 0x43;
+    const ACCOUNT: u64 = 0x44;
+    const SENDER_CREATOR: u64 = 0x45;
+    const FACTORY: u64 = 0x46;
+    const PAYMASTER: u64 = 0x47;
+    // createSender(bytes) with the factory as the init code
+    let mut create_sender_input = vec![0x57, 0x0e, 0x1a, 0x36];
+    create_sender_input.extend_from_slice(H256::from_low_u64_be(32).as_bytes());
+    create_sender_input.extend_from_slice(H256::from_low_u64_be(20).as_bytes());
+    create_sender_input.extend_from_slice(Address::from_low_u64_be(FACTORY).as_bytes());
+    // Entry point copying the createSender input appended to its code and calling
+    // SENDER_CREATOR with it: PUSH1 88 PUSH1 75 PUSH1 0x00 CODECOPY,
+    // CALL(GAS, SENDER_CREATOR, 0, 0, 88, 0, 0) POP, then calling ACCOUNT's validateUserOp:
+    // PUSH4 <selector> PUSH1 0xe0 SHL PUSH1 0x00 MSTORE CALL(GAS, ACCOUNT, 0, 0, 4, 0, 0) POP,
+    // then PAYMASTER's validatePaymasterUserOp the same way and STOP
+    let mut entry_point_code = vec![
+        0x60, 0x58, 0x60, 0x4b, 0x60, 0x00, 0x39, 0x60, 0x00, 0x60, 0x00, 0x60, 0x58, 0x60, 0x00,
+        0x60, 0x00, 0x60, 0x45, 0x5a, 0xf1, 0x50, 0x63, 0x19, 0x82, 0x2f, 0x7c, 0x60, 0xe0, 0x1b,
+        0x60, 0x00, 0x52, 0x60, 0x00, 0x60, 0x00, 0x60, 0x04, 0x60, 0x00, 0x60, 0x00, 0x60, 0x44,
+        0x5a, 0xf1, 0x50, 0x63, 0x52, 0xb7, 0x51, 0x2c, 0x60, 0xe0, 0x1b, 0x60, 0x00, 0x52, 0x60,
+        0x00, 0x60, 0x00, 0x60, 0x04, 0x60, 0x00, 0x60, 0x00, 0x60, 0x47, 0x5a, 0xf1, 0x50, 0x00,
+    ];
+    entry_point_code.extend(create_sender_input);
+    // CALL(GAS, FACTORY, 0, 0, 0, 0, 0) POP STOP
+    let sender_creator_code = Bytes::from(vec![
+        0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x46, 0x5a, 0xf1, 0x50,
+        0x00,
+    ]);
+    // CREATE2(0, 0, 0, 0) POP, then CREATE2(0, 0, 0, 1) POP STOP
+    let factory_code = Bytes::from(vec![
+        0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0xf5, 0x50, 0x60, 0x01, 0x60, 0x00, 0x60,
+        0x00, 0x60, 0x00, 0xf5, 0x50, 0x00,
+    ]);
+    // CALL(GAS, TOKEN, 0, 0, 0, 0, 0) POP STOP
+    let account_code = Bytes::from(vec![
+        0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x43, 0x5a, 0xf1, 0x50,
+        0x00,
+    ]);
+    // Reads the slot of a mapping entry keyed by the caller:
+    // CALLER PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 KECCAK256 SLOAD POP STOP
+    let token_code = Bytes::from(vec![
+        0x33, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0x20, 0x54, 0x50, 0x00,
+    ]);
+    // Returns a one byte context: PUSH1 0x40 PUSH1 0x00 MSTORE PUSH1 0x01 PUSH1 0x40 MSTORE,
+    // then RETURN(0, 0x60)
+    let paymaster_code = Bytes::from(vec![
+        0x60, 0x40, 0x60, 0x00, 0x52, 0x60, 0x01, 0x60, 0x40, 0x52, 0x60, 0x60, 0x60, 0x00, 0xf3,
+    ]);
+    let contract = |code: Bytes| Account::new(U256::zero(), code, 0, BTreeMap::new());
+    let accounts = BTreeMap::from([
+        (
+            Address::from_low_u64_be(SENDER),
+            Account::new(U256::MAX, Bytes::new(), 0, BTreeMap::new()),
+        ),
+        (
+            Address::from_low_u64_be(CONTRACT),
+            contract(Bytes::from(entry_point_code)),
+        ),
+        (
+            Address::from_low_u64_be(SENDER_CREATOR),
+            contract(sender_creator_code),
+        ),
+        (Address::from_low_u64_be(FACTORY), contract(factory_code)),
+        (Address::from_low_u64_be(ACCOUNT), contract(account_code)),
+        (Address::from_low_u64_be(TOKEN), contract(token_code)),
+        (
+            Address::from_low_u64_be(PAYMASTER),
+            contract(paymaster_code),
+        ),
+    ]);
+    let mut db = GeneralizedDatabase::new_with_account_state(Arc::new(EmptyDatabase), accounts);
+    let mut vm = call_contract(&mut db);
+
+    let tracer = Rc::new(RefCell::new(Erc7562Tracer::new(
+        Address::from_low_u64_be(CONTRACT),
+        Address::from_low_u64_be(ACCOUNT),
+        Some(Address::from_low_u64_be(FACTORY).as_bytes()),
+        Some(Address::from_low_u64_be(PAYMASTER)),
+    )));
+    vm.set_tracer(tracer.clone());
+    let report = vm.execute().unwrap();
+    assert!(report.is_success());
+
+    let tracer = tracer.borrow();
+    // The factory's first CREATE2 deploys the sender, the second one is banned
+    assert_eq!(tracer.violations.len(), 3, "{:?}", tracer.violations);
+    assert!(tracer.violations[0].starts_with("OP-031"));
+    // Slots associated with a sender that's being deployed can't be accessed in other contracts
+    assert!(tracer.violations[1].starts_with("STO-022"));
+    assert_eq!(
+        tracer.violations[2],
+        "EREP-050: unstaked paymasters must return an empty context"
+    );
+    assert!(tracer.factory_gas_used.is_some());
+    assert!(tracer.validation_gas_used.is_some());
+    assert!(tracer.paymaster_validation_gas_used.is_some());
+    assert_eq!(tracer.execution_gas_used, None);
+}
+
+const L1_FEE_VAULT: u64 = 0xfee;
+
+fn call_contract_on_l2(db: &mut GeneralizedDatabase, gas_limit: u64) -> VM<'_> {
+    let env = Environment {
+        origin: Address::from_low_u64_be(SENDER),
+        gas_limit,
+        block_gas_limit: 30_000_000,
+        gas_price: U256::one(),
+        config: EVMConfig::new(Fork::Prague, EVMConfig::canonical_values(Fork::Prague)),
+        l1_fee_config: Some(L1FeeConfig {
+            l1_fee_vault: Address::from_low_u64_be(L1_FEE_VAULT),
+            l1_fee_per_blob_gas: 1000,
+        }),
+        ..Default::default()
+    };
+    let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+        to: TxKind::Call(Address::from_low_u64_be(CONTRACT)),
+        gas_limit,
+        ..Default::default()
+    });
+    VM::new(env, db, &tx, LevmCallTracer::disabled(), VMType::L2).unwrap()
+}
+
+#[test]
+fn l2_transactions_pay_the_l1_fee_of_their_state_diff() {
+    // PUSH1 0x01 PUSH1 0x00 SSTORE STOP
+    let code = Bytes::from(vec![0x60, 0x01, 0x60, 0x00, 0x55, 0x00]);
+    let mut db = db_with_contract(code);
+    let report = call_contract_on_l2(&mut db, 1_000_000).execute().unwrap();
+    assert!(report.is_success());
+
+    // The sender's balance and nonce (21 + 32 + 2 bytes) plus one storage slot of the
+    // contract (21 + 2 + 64 bytes), each byte costing 32/31 blob gas at 1000 wei
+    let l1_gas = 146_581;
+    assert_eq!(report.l1_fee, U256::from(l1_gas));
+    assert_eq!(report.gas_used, 21_000 + 3 + 3 + 22_100 + l1_gas);
+    assert_eq!(
+        db.get_account(Address::from_low_u64_be(L1_FEE_VAULT))
+            .unwrap()
+            .info
+            .balance,
+        report.l1_fee
+    );
+}
+
+#[test]
+fn l2_transactions_that_cant_pay_the_l1_fee_revert() {
+    // PUSH1 0x01 PUSH1 0x00 SSTORE STOP
+    let code = Bytes::from(vec![0x60, 0x01, 0x60, 0x00, 0x55, 0x00]);
+    let mut db = db_with_contract(code);
+    let report = call_contract_on_l2(&mut db, 60_000).execute().unwrap();
+    assert!(!report.is_success());
+
+    // All the gas is consumed, what's left after execution goes towards the L1 fee
+    assert_eq!(report.gas_used, 60_000);
+    assert_eq!(
+        report.l1_fee,
+        U256::from(60_000 - (21_000 + 3 + 3 + 22_100))
+    );
+    assert_eq!(
+        db.get_account(Address::from_low_u64_be(CONTRACT))
+            .unwrap()
+            .storage
+            .get(&H256::zero())
+            .copied()
+            .unwrap_or_default(),
+        U256::zero()
+    );
 }
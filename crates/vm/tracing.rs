@@ -1,14 +1,45 @@
 use ethrex_common::tracing::CallTrace;
-use ethrex_common::types::Block;
+use ethrex_common::types::{Block, BlockHeader, GenericTransaction};
+pub use ethrex_levm::db::state_access::{AccountDiff, StateAccess, StorageDiff};
+pub use ethrex_levm::native_tracers::{Erc7562Tracer, GasProfile, OpcodeGas};
+pub use ethrex_levm::tracing::Tracer;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
 
 use crate::backends::levm::LEVM;
-use crate::{Evm, EvmError, backends::revm::REVM};
+use crate::{Evm, EvmError, ExecutionResult, backends::revm::REVM};
+
+/// Tracers that can be run over the re-execution of a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NativeTracer {
+    /// Geth's callTracer.
+    Call { only_top_call: bool, with_log: bool },
+    /// Geth's 4byteTracer, only supported by LEVM.
+    FourByte,
+    /// Per-opcode and per-contract gas profile, only supported by LEVM.
+    GasProfiler,
+    /// Geth's muxTracer, runs each tracer over the same execution and outputs their results keyed
+    /// by the given names. Only supported by LEVM.
+    Mux(Vec<(String, NativeTracer)>),
+}
+
+/// Output of a [NativeTracer], serialized as the output of the equivalent geth tracer.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum TraceResult {
+    Call(CallTrace),
+    FourByte(BTreeMap<String, u64>),
+    GasProfile(GasProfile),
+    Mux(BTreeMap<String, TraceResult>),
+}
 
 impl Evm {
     /// Runs a single tx with the call tracer and outputs its trace
     /// Asumes that the received state already contains changes from previous blocks and other
     /// transactions within its block
-    /// Wraps [REVM::trace_tx_calls], does not currenlty have levm support.
+    /// Wraps [REVM::trace_tx_calls] and [LEVM::trace_tx_calls].
     pub fn trace_tx_calls(
         &mut self,
         block: &Block,
@@ -34,10 +65,92 @@ impl Evm {
         }
     }
 
+    /// Runs a single tx with the given tracer and outputs its result
+    /// Asumes that the received state already contains changes from previous blocks and other
+    /// transactions within its block
+    /// REVM only supports the call tracer.
+    pub fn trace_tx(
+        &mut self,
+        block: &Block,
+        tx_index: usize,
+        tracer: &NativeTracer,
+    ) -> Result<TraceResult, EvmError> {
+        let tx = block
+            .body
+            .transactions
+            .get(tx_index)
+            .ok_or(EvmError::Custom(
+                "Missing Transaction for Trace".to_string(),
+            ))?;
+
+        match self {
+            Evm::REVM { state } => match tracer {
+                NativeTracer::Call {
+                    only_top_call,
+                    with_log,
+                } => REVM::trace_tx_calls(&block.header, tx, state, *only_top_call, *with_log)
+                    .map(TraceResult::Call),
+                _ => Err(EvmError::Custom(
+                    "REVM only supports the call tracer".to_string(),
+                )),
+            },
+            Evm::LEVM { db, vm_type } => {
+                LEVM::trace_tx_native(db, &block.header, tx, tracer, *vm_type)
+            }
+        }
+    }
+
+    /// Runs a single tx recording the accounts and storage slots it read and wrote, along with the
+    /// values of the written ones before and after it
+    /// Asumes that the received state already contains changes from previous blocks and other
+    /// transactions within its block
+    /// Only supported by LEVM.
+    pub fn trace_tx_state_access(
+        &mut self,
+        block: &Block,
+        tx_index: usize,
+    ) -> Result<StateAccess, EvmError> {
+        let tx = block
+            .body
+            .transactions
+            .get(tx_index)
+            .ok_or(EvmError::Custom(
+                "Missing Transaction for Trace".to_string(),
+            ))?;
+
+        match self {
+            Evm::REVM { .. } => Err(EvmError::Custom(
+                "State access tracing is only supported by LEVM".to_string(),
+            )),
+            Evm::LEVM { db, vm_type } => {
+                LEVM::trace_tx_state_access(db, &block.header, tx, *vm_type)
+            }
+        }
+    }
+
+    /// Simulates a tx like [Evm::simulate_tx_from_generic] with a custom tracer attached, which
+    /// the caller keeps a reference to for reading its results
+    /// Only supported by LEVM.
+    pub fn simulate_tx_with_tracer(
+        &mut self,
+        tx: &GenericTransaction,
+        header: &BlockHeader,
+        tracer: Rc<RefCell<dyn Tracer>>,
+    ) -> Result<ExecutionResult, EvmError> {
+        match self {
+            Evm::REVM { .. } => Err(EvmError::Custom(
+                "Custom tracers are only supported by LEVM".to_string(),
+            )),
+            Evm::LEVM { db, vm_type } => {
+                LEVM::simulate_tx_from_generic_with_tracer(tx, header, db, tracer, *vm_type)
+            }
+        }
+    }
+
     /// Reruns the given block, saving the changes on the state, doesn't output any results or receipts
     /// If the optional argument `stop_index` is set, the run will stop just before executing the transaction at that index
     /// and won't process the withdrawals afterwards
-    /// Wraps [REVM::rerun_block], does not currenlty have levm support.
+    /// Wraps [REVM::rerun_block] and [LEVM::rerun_block].
     pub fn rerun_block(
         &mut self,
         block: &Block,